@@ -0,0 +1,53 @@
+# bash completion for moli
+# Install: copy to /usr/share/bash-completion/completions/moli, or run
+#   source <(moli completions bash)
+
+_moli() {
+    local cur prev
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    case "$prev" in
+        --config | --out | -o | --check-config)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return
+            ;;
+        --date)
+            COMPREPLY=($(compgen -W "$(date +%Y-%m-%d)" -- "$cur"))
+            return
+            ;;
+        --format)
+            COMPREPLY=($(compgen -W "%Y-%m-%d" -- "$cur"))
+            return
+            ;;
+        --color)
+            COMPREPLY=($(compgen -W "auto always never" -- "$cur"))
+            return
+            ;;
+        completions)
+            COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
+            return
+            ;;
+    esac
+
+    local words
+    case "${COMP_WORDS[1]}" in
+        --print) words="--color --no-color --json --markdown" ;;
+        convert) words="--lunar --json --stdin --strict" ;;
+        term) words="--next --json" ;;
+        holidays) words="--json" ;;
+        export-ics) words="--out" ;;
+        --init-config) words="--force" ;;
+        completions) words="" ;;
+        *)
+            if [[ $COMP_CWORD -eq 1 ]]; then
+                words="convert term holidays export-ics completions --print --init-config --check-config --date --config --no-alt-screen --inline --pick --format --help --version"
+            else
+                words="--date --config --no-alt-screen --inline --pick --format"
+            fi
+            ;;
+    esac
+    COMPREPLY=($(compgen -W "$words" -- "$cur"))
+}
+
+complete -F _moli moli
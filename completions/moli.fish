@@ -0,0 +1,41 @@
+# fish completion for moli
+# Install: copy to ~/.config/fish/completions/moli.fish, or run
+#   moli completions fish | source
+
+set -l commands convert term holidays export-ics completions --print --init-config --check-config
+set -l no_command "not __fish_seen_subcommand_from $commands"
+
+complete -c moli -f
+
+# Subcommands
+complete -c moli -n $no_command -a convert -d 查询公历或农历日期
+complete -c moli -n $no_command -a term -d 列出一年的二十四节气
+complete -c moli -n $no_command -a holidays -d 列出当年全部节假日
+complete -c moli -n $no_command -a export-ics -d '导出当年节假日为 ICS 日历'
+complete -c moli -n $no_command -a completions -d '输出 shell 补全脚本'
+complete -c moli -n $no_command -l print -d 输出月历后退出
+complete -c moli -n $no_command -l init-config -d 生成带注释的默认配置文件
+complete -c moli -n $no_command -l check-config -r -F -d 检查配置文件
+
+# Options of the interactive calendar
+complete -c moli -n $no_command -l date -x -a '(date +%Y-%m-%d)' -d '以指定日期作为“今天”启动'
+complete -c moli -n $no_command -l config -r -F -d 使用指定的键位配置文件
+complete -c moli -n $no_command -l no-alt-screen -d 不切换到备用屏幕
+complete -c moli -n $no_command -l inline -d 在提示符下方的若干行内绘制
+complete -c moli -n $no_command -l pick -d 作为日期选择器运行，输出选中的日期
+complete -c moli -n $no_command -l format -x -a '%Y-%m-%d' -d '--pick 输出日期的 strftime 格式'
+complete -c moli -n $no_command -s h -l help -d 显示说明
+complete -c moli -n $no_command -s V -l version -d 显示版本号
+
+# Options of each subcommand
+complete -c moli -n '__fish_seen_subcommand_from --print' -l color -x -a 'auto always never' -d 何时着色
+complete -c moli -n '__fish_seen_subcommand_from --print' -l no-color -d 不输出颜色
+complete -c moli -n '__fish_seen_subcommand_from --print' -l markdown -d '输出 Markdown 表格'
+complete -c moli -n '__fish_seen_subcommand_from --print convert term holidays' -l json -d '输出 JSON'
+complete -c moli -n '__fish_seen_subcommand_from convert' -s l -l lunar -d 输入为农历日期
+complete -c moli -n '__fish_seen_subcommand_from convert' -l stdin -d 从标准输入逐行读取日期
+complete -c moli -n '__fish_seen_subcommand_from convert' -l strict -d 遇到无法转换的行即停止
+complete -c moli -n '__fish_seen_subcommand_from term' -s n -l next -d 只显示下一个节气
+complete -c moli -n '__fish_seen_subcommand_from export-ics' -s o -l out -r -F -d 输出文件
+complete -c moli -n '__fish_seen_subcommand_from --init-config' -s f -l force -d 覆盖已有文件
+complete -c moli -n '__fish_seen_subcommand_from completions' -x -a 'bash zsh fish'
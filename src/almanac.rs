@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+
+use crate::lunar;
+
+/// Auspicious (宜) and inauspicious (忌) activities of a day
+#[derive(Clone, Debug, Default)]
+pub struct Almanac {
+    pub yi: Vec<&'static str>,
+    pub ji: Vec<&'static str>,
+}
+
+/// Customary 宜 lists of the twelve day officers (建除十二神), in order from 建
+const OFFICER_YI: [&[&str]; 12] = [
+    &["出行", "上任", "求财", "拜访"],         // 建
+    &["扫舍", "沐浴", "求医", "祭祀"],         // 除
+    &["祭祀", "祈福", "开市", "纳财"],         // 满
+    &["修路", "涂泥", "平治道途"],             // 平
+    &["嫁娶", "纳畜", "订盟", "入学"],         // 定
+    &["捕捉", "祭祀", "栽种"],                 // 执
+    &["求医", "破屋", "拆卸"],                 // 破
+    &["祭祀", "安床", "纳财"],                 // 危
+    &["嫁娶", "开市", "入学", "上任", "出行"], // 成
+    &["纳财", "收藏", "捕捉", "纳畜"],         // 收
+    &["开市", "出行", "上任", "求嗣", "入宅"], // 开
+    &["筑堤", "补垣", "安葬"],                 // 闭
+];
+
+/// Customary 忌 lists of the twelve day officers, in order from 建
+const OFFICER_JI: [&[&str]; 12] = [
+    &["动土", "开仓", "安葬"],         // 建
+    &["嫁娶", "远行", "开市"],         // 除
+    &["栽种", "上任", "服药"],         // 满
+    &["祈福", "嫁娶", "开渠"],         // 平
+    &["诉讼", "出行", "栽种"],         // 定
+    &["移徙", "开市", "出行"],         // 执
+    &["嫁娶", "开市", "出行", "动土"], // 破
+    &["登高", "乘船", "出行"],         // 危
+    &["诉讼"],                         // 成
+    &["出行", "安葬", "放债"],         // 收
+    &["安葬", "动土"],                 // 开
+    &["开市", "出行", "求医", "上任"], // 闭
+];
+
+/// Derive the day's 宜/忌 from its day officer
+/// The officer counts from 建 on the day whose branch matches the lunar month's branch
+/// (正月 is 寅月, 二月 卯月, …), a simplification of the solar-term-based rule
+/// Dates outside the lunar table get an empty almanac
+pub fn almanac_for(date: NaiveDate) -> Almanac {
+    let Some(info) = lunar::solar_to_lunar(date) else {
+        return Almanac::default();
+    };
+    let month_branch = (info.date.month as usize + 1) % 12;
+    let day_branch = lunar::gan_zhi_day_index(date) % 12;
+    let officer = (day_branch + 12 - month_branch) % 12;
+    Almanac {
+        yi: OFFICER_YI[officer].to_vec(),
+        ji: OFFICER_JI[officer].to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn almanac(year: i32, month: u32, day: u32) -> (Vec<&'static str>, Vec<&'static str>) {
+        let almanac = almanac_for(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+        (almanac.yi, almanac.ji)
+    }
+
+    /// Pinned so a refactor can't quietly change the derivation
+    #[test]
+    fn almanac_of_known_dates() {
+        // 正月初一 2025 is a 戊戌 day in 寅月: 成
+        assert_eq!(
+            almanac(2025, 1, 29),
+            (vec!["嫁娶", "开市", "入学", "上任", "出行"], vec!["诉讼"])
+        );
+        assert_eq!(
+            almanac(2024, 2, 10),
+            (
+                vec!["祭祀", "祈福", "开市", "纳财"],
+                vec!["栽种", "上任", "服药"]
+            )
+        );
+        assert_eq!(
+            almanac(2025, 10, 6),
+            (
+                vec!["筑堤", "补垣", "安葬"],
+                vec!["开市", "出行", "求医", "上任"]
+            )
+        );
+        assert_eq!(
+            almanac(2023, 3, 22),
+            (
+                vec!["出行", "上任", "求财", "拜访"],
+                vec!["动土", "开仓", "安葬"]
+            )
+        );
+    }
+
+    #[test]
+    fn dates_outside_the_lunar_table_are_empty() {
+        assert_eq!(almanac(1899, 12, 31), (vec![], vec![]));
+    }
+}
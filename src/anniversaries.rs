@@ -0,0 +1,238 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+
+/// Day a February 29th anniversary is kept on in common years
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeapDayRule {
+    /// The last day of February
+    #[default]
+    Feb28,
+    /// The day after February 28th
+    Mar1,
+}
+
+/// A Gregorian anniversary declared in the config file: yearly on `month`/`day`, or once on `date`
+#[derive(Clone, Debug, Deserialize)]
+pub struct Anniversary {
+    pub name: String,
+    #[serde(default)]
+    pub month: Option<u32>,
+    #[serde(default)]
+    pub day: Option<u32>,
+    /// A one-off date, given instead of `month` and `day`
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+}
+
+impl Anniversary {
+    /// Why the entry can't be used, or None when it can
+    pub fn problem(&self) -> Option<&'static str> {
+        if self.name.trim().is_empty() {
+            return Some("the name is empty");
+        }
+        match (self.date, self.month, self.day) {
+            (Some(_), None, None) => None,
+            (Some(_), _, _) => Some("give either date or month and day, not both"),
+            // 2000 is a leap year, so February 29th passes
+            (None, Some(month), Some(day)) => NaiveDate::from_ymd_opt(2000, month, day)
+                .is_none()
+                .then_some("month and day do not form a date"),
+            (None, _, _) => Some("it needs a date or both month and day"),
+        }
+    }
+
+    /// The date it falls on in a Gregorian year, if any
+    /// A yearly February 29th moves to the day `leap_day` names in common years
+    pub fn in_year(&self, year: i32, leap_day: LeapDayRule) -> Option<NaiveDate> {
+        if let Some(date) = self.date {
+            return (date.year() == year).then_some(date);
+        }
+        let (month, day) = (self.month?, self.day?);
+        NaiveDate::from_ymd_opt(year, month, day).or_else(|| {
+            if (month, day) != (2, 29) {
+                return None;
+            }
+            match leap_day {
+                LeapDayRule::Feb28 => NaiveDate::from_ymd_opt(year, 2, 28),
+                LeapDayRule::Mar1 => NaiveDate::from_ymd_opt(year, 3, 1),
+            }
+        })
+    }
+}
+
+/// Names of the anniversaries falling on the date
+pub fn anniversaries_on(
+    anniversaries: &[Anniversary],
+    date: NaiveDate,
+    leap_day: LeapDayRule,
+) -> Vec<String> {
+    anniversaries
+        .iter()
+        .filter(|anniversary| anniversary.in_year(date.year(), leap_day) == Some(date))
+        .map(|anniversary| anniversary.name.clone())
+        .collect()
+}
+
+/// One date per anniversary as seen from `from`: the next occurrence of a yearly one, the date
+/// of a one-off one even when it has passed
+/// Coming dates go first, nearest first, then past ones, most recent first
+pub fn upcoming_anniversaries(
+    anniversaries: &[Anniversary],
+    from: NaiveDate,
+    count: usize,
+    leap_day: LeapDayRule,
+) -> Vec<(NaiveDate, String)> {
+    let mut upcoming: Vec<(NaiveDate, String)> = anniversaries
+        .iter()
+        .filter_map(|anniversary| {
+            let date = match anniversary.date {
+                Some(date) => date,
+                None => (from.year()..=from.year() + 1)
+                    .filter_map(|year| anniversary.in_year(year, leap_day))
+                    .find(|date| *date >= from)?,
+            };
+            Some((date, anniversary.name.clone()))
+        })
+        .collect();
+    upcoming.sort_by_key(|(date, _)| (*date < from, (*date - from).num_days().abs()));
+    upcoming.truncate(count);
+    upcoming
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn yearly(name: &str, month: u32, day: u32) -> Anniversary {
+        Anniversary {
+            name: name.to_string(),
+            month: Some(month),
+            day: Some(day),
+            date: None,
+        }
+    }
+
+    fn once(name: &str, date: NaiveDate) -> Anniversary {
+        Anniversary {
+            name: name.to_string(),
+            month: None,
+            day: None,
+            date: Some(date),
+        }
+    }
+
+    #[test]
+    fn leap_day_anniversaries_follow_the_rule_in_common_years() {
+        let leap = yearly("闰日", 2, 29);
+        for rule in [LeapDayRule::Feb28, LeapDayRule::Mar1] {
+            assert_eq!(leap.in_year(2024, rule), Some(date(2024, 2, 29)));
+            assert_eq!(leap.in_year(2000, rule), Some(date(2000, 2, 29)));
+        }
+        assert_eq!(
+            leap.in_year(2025, LeapDayRule::Feb28),
+            Some(date(2025, 2, 28))
+        );
+        assert_eq!(
+            leap.in_year(2025, LeapDayRule::Mar1),
+            Some(date(2025, 3, 1))
+        );
+        // 1900 and 2100 skip the leap day
+        assert_eq!(
+            leap.in_year(2100, LeapDayRule::Mar1),
+            Some(date(2100, 3, 1))
+        );
+        assert_eq!(
+            leap.in_year(1900, LeapDayRule::Feb28),
+            Some(date(1900, 2, 28))
+        );
+        // Only February 29th moves; a one-off date only shows in its own year
+        assert_eq!(yearly("x", 4, 31).in_year(2025, LeapDayRule::Mar1), None);
+        let deadline = once("截止", date(2025, 9, 30));
+        assert_eq!(
+            deadline.in_year(2025, LeapDayRule::Feb28),
+            Some(date(2025, 9, 30))
+        );
+        assert_eq!(deadline.in_year(2026, LeapDayRule::Feb28), None);
+        let list = [leap, yearly("生日", 2, 28)];
+        assert_eq!(
+            anniversaries_on(&list, date(2025, 2, 28), LeapDayRule::Feb28),
+            ["闰日", "生日"]
+        );
+        assert_eq!(
+            anniversaries_on(&list, date(2025, 2, 28), LeapDayRule::Mar1),
+            ["生日"]
+        );
+    }
+
+    #[test]
+    fn upcoming_puts_coming_dates_first_and_wraps_the_year() {
+        let list = [
+            yearly("结婚纪念日", 6, 18),
+            yearly("闰日", 2, 29),
+            once("项目截止", date(2025, 9, 30)),
+            once("入职", date(2025, 3, 1)),
+        ];
+        let names = |from, count, rule| {
+            upcoming_anniversaries(&list, from, count, rule)
+                .into_iter()
+                .map(|(date, name)| format!("{date} {name}"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(date(2025, 6, 18), 4, LeapDayRule::Feb28),
+            [
+                "2025-06-18 结婚纪念日",
+                "2025-09-30 项目截止",
+                "2026-02-28 闰日",
+                "2025-03-01 入职",
+            ]
+        );
+        // Once 2027's stand-in day has passed, the next one is the real leap day of 2028
+        assert_eq!(
+            names(date(2027, 3, 2), 2, LeapDayRule::Mar1),
+            ["2027-06-18 结婚纪念日", "2028-02-29 闰日"]
+        );
+        assert_eq!(
+            names(date(2025, 10, 1), 1, LeapDayRule::Feb28),
+            ["2026-02-28 闰日"]
+        );
+    }
+
+    #[test]
+    fn entries_need_a_name_and_a_real_date() {
+        assert_eq!(yearly("闰日", 2, 29).problem(), None);
+        assert_eq!(once("截止", date(2025, 9, 30)).problem(), None);
+        assert_eq!(yearly(" ", 6, 18).problem(), Some("the name is empty"));
+        assert_eq!(
+            yearly("x", 2, 30).problem(),
+            Some("month and day do not form a date")
+        );
+        let mut both = once("x", date(2025, 9, 30));
+        both.month = Some(9);
+        assert_eq!(
+            both.problem(),
+            Some("give either date or month and day, not both")
+        );
+        both.date = None;
+        assert_eq!(
+            both.problem(),
+            Some("it needs a date or both month and day")
+        );
+        let app: AppConfig = toml::from_str(
+            "anniversary_leap_day = \"mar1\"\nanniversaries = [\n  { name = \"结婚纪念日\", month = 6, day = 18 },\n  { name = \"项目截止\", date = \"2025-09-30\" },\n]\n",
+        )
+        .unwrap();
+        assert_eq!(app.anniversary_leap_day, LeapDayRule::Mar1);
+        assert_eq!(
+            app.anniversaries[0].in_year(2026, LeapDayRule::Mar1),
+            Some(date(2026, 6, 18))
+        );
+        assert_eq!(app.anniversaries[1].date, Some(date(2025, 9, 30)));
+    }
+}
@@ -1,10 +1,34 @@
-use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime};
+use std::{
+    collections::HashMap,
+    fs,
+    time::{Duration as StdDuration, Instant},
+};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
 
-use crate::lunar;
+use crate::{
+    anniversaries, bazi, birthdays,
+    config::{
+        Action, AmbiguousWidth, AppConfig, CellDetail, DETAILS_WIDTH_RANGE, JumpTarget, LabelKind,
+        LabelPriority, PagingPolicy, TodayMarker, WeekStart, WeekendDays,
+    },
+    countdowns::{Countdown, CountdownStore},
+    events::EventStore,
+    export,
+    i18n::{self, Locale, Msg},
+    lunar,
+    notes::NoteStore,
+    schedule::{DayArrangement, HolidaySchedule},
+    session::Session,
+    theme::{ASCII_GLYPHS, Theme},
+};
 
 /// Holiday categories
 /// Distinguishes between statutory, traditional, and other holiday types
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HolidayCategory {
     Statutory,
     Traditional,
@@ -12,18 +36,24 @@ pub enum HolidayCategory {
 }
 
 impl HolidayCategory {
-    pub fn label(self) -> &'static str {
-        match self {
-            HolidayCategory::Statutory => "法定节假日",
-            HolidayCategory::Traditional => "传统节日",
-            HolidayCategory::OtherTraditional => "民俗节日",
+    pub fn label(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (HolidayCategory::Statutory, Locale::Zh) => "法定节假日",
+            (HolidayCategory::Traditional, Locale::Zh) => "传统节日",
+            (HolidayCategory::OtherTraditional, Locale::Zh) => "民俗节日",
+            (HolidayCategory::Statutory, Locale::ZhHant) => "法定節假日",
+            (HolidayCategory::Traditional, Locale::ZhHant) => "傳統節日",
+            (HolidayCategory::OtherTraditional, Locale::ZhHant) => "民俗節日",
+            (HolidayCategory::Statutory, Locale::En) => "Public holiday",
+            (HolidayCategory::Traditional, Locale::En) => "Traditional festival",
+            (HolidayCategory::OtherTraditional, Locale::En) => "Folk festival",
         }
     }
 }
 
 /// Detailed holiday information
 /// Includes the holiday name, category, and a short note
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
 pub struct HolidayInfo {
     pub name: &'static str,
     pub category: HolidayCategory,
@@ -42,7 +72,7 @@ struct SolarHoliday {
 const HOLIDAY_SPRING_FESTIVAL: HolidayInfo = HolidayInfo {
     name: "春节",
     category: HolidayCategory::Statutory,
-    note: "农历正月初一 · 放假4天（除夕至初三）",
+    note: "农历正月初一 · 辞旧迎新",
 };
 const HOLIDAY_SPRING_EVE: HolidayInfo = HolidayInfo {
     name: "除夕",
@@ -52,32 +82,32 @@ const HOLIDAY_SPRING_EVE: HolidayInfo = HolidayInfo {
 const HOLIDAY_NEW_YEAR: HolidayInfo = HolidayInfo {
     name: "元旦",
     category: HolidayCategory::Statutory,
-    note: "公历1月1日 · 放假1天",
+    note: "公历1月1日 · 新年伊始",
 };
 const HOLIDAY_LABOR_DAY: HolidayInfo = HolidayInfo {
     name: "劳动节",
     category: HolidayCategory::Statutory,
-    note: "公历5月1日 · 放假2天",
+    note: "公历5月1日 · 国际劳动节",
 };
 const HOLIDAY_DRAGON_BOAT: HolidayInfo = HolidayInfo {
     name: "端午节",
     category: HolidayCategory::Statutory,
-    note: "农历五月初五 · 放假1天",
+    note: "农历五月初五 · 赛龙舟吃粽子",
 };
 const HOLIDAY_MID_AUTUMN: HolidayInfo = HolidayInfo {
     name: "中秋节",
     category: HolidayCategory::Statutory,
-    note: "农历八月十五 · 放假1天",
+    note: "农历八月十五 · 赏月团圆",
 };
 const HOLIDAY_NATIONAL_DAY: HolidayInfo = HolidayInfo {
     name: "国庆节",
     category: HolidayCategory::Statutory,
-    note: "公历10月1日至3日 · 放假3天",
+    note: "公历10月1日 · 国庆",
 };
 const HOLIDAY_QINGMING: HolidayInfo = HolidayInfo {
     name: "清明节",
     category: HolidayCategory::Statutory,
-    note: "清明时节 · 踏青祭祖 · 放假1天",
+    note: "清明时节 · 踏青祭祖",
 };
 const HOLIDAY_LANTERN: HolidayInfo = HolidayInfo {
     name: "元宵节",
@@ -144,38 +174,284 @@ const SOLAR_TERM_OFFSETS: [i64; 24] = [
 ];
 
 const SOLAR_TERM_BASE_YEAR: i32 = 1900;
-const SOLAR_TERM_MIN_YEAR: i32 = 1900;
-const SOLAR_TERM_MAX_YEAR: i32 = 2100;
+/// Years the solar term formula covers
+pub const SOLAR_TERM_MIN_YEAR: i32 = 1900;
+pub const SOLAR_TERM_MAX_YEAR: i32 = 2100;
 const SOLAR_TERM_YEAR_MS: f64 = 31_556_925_974.7;
+/// Largest accepted count prefix; bigger counts already exceed the supported range
+const MAX_COUNT: u32 = 99_999;
+/// How long a transient message stays in the status line
+const MESSAGE_DURATION: StdDuration = StdDuration::from_secs(3);
+/// Confirmed jump inputs remembered for recall with Up/Down
+const JUMP_HISTORY_LEN: usize = 20;
+/// Longest note accepted by the note editor
+const MAX_NOTE_CHARS: usize = 200;
+/// Longest query of the note and festival searches; a longer one could match no note anyway
+const MAX_QUERY_CHARS: usize = MAX_NOTE_CHARS;
+/// Number of upcoming holidays listed in the side panel
+const UPCOMING_HOLIDAY_COUNT: usize = 5;
+/// Number of upcoming lunar birthdays listed in the side panel
+const UPCOMING_BIRTHDAY_COUNT: usize = 3;
+/// Number of anniversaries listed in the side panel
+const UPCOMING_ANNIVERSARY_COUNT: usize = 3;
+/// Days searched for the next occurrence of a named date, a little over the longest lunar year
+const NAMED_DATE_SCAN_DAYS: usize = 400;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct DayCell {
     pub date: NaiveDate,
     pub is_current_month: bool,
     pub is_today: bool,
     pub is_selected: bool,
+    /// One of the configured weekend days
+    pub is_weekend: bool,
     pub lunar: Option<lunar::LunarInfo>,
-    pub holiday: Option<HolidayInfo>,
+    /// All holidays falling on this date, ordered by priority
+    pub holidays: Vec<HolidayInfo>,
     pub solar_term: Option<&'static str>,
+    /// Lunar festival such as "小年", which may also be listed among `holidays`
+    pub festival: Option<&'static str>,
+    /// Official day off (休) or makeup workday (班)
+    pub arrangement: Option<DayArrangement>,
+    /// Whether a vim-style mark points at this date
+    pub is_marked: bool,
+    /// Whether the date lies between the visual anchor and the selection
+    pub in_range: bool,
+    /// Whether the user attached a note to this date
+    pub has_note: bool,
+    /// Whether a configured or pinned countdown targets this date
+    pub is_countdown: bool,
+    /// Summaries of imported calendar events on this date
+    pub events: Vec<String>,
+    /// Names of the configured lunar birthdays on this date
+    pub birthdays: Vec<String>,
+    /// Names of the configured Gregorian anniversaries on this date
+    pub anniversaries: Vec<String>,
+}
+
+impl DayCell {
+    /// Label shown under the date number, with where it came from
+    /// The first source in `priority` that this day has wins
+    pub fn label(&self, priority: &LabelPriority, locale: Locale) -> Option<(String, LabelSource)> {
+        priority.order().iter().find_map(|kind| match kind {
+            LabelKind::Holiday => self.holidays.first().map(|info| {
+                let name = i18n::name(info.name, locale).to_string();
+                (name, LabelSource::Holiday(info.category))
+            }),
+            LabelKind::Birthday => self
+                .birthdays
+                .first()
+                .map(|name| (name.clone(), LabelSource::Birthday)),
+            LabelKind::SolarTerm => self
+                .solar_term
+                .map(|name| (i18n::name(name, locale).to_string(), LabelSource::SolarTerm)),
+            LabelKind::Festival => self
+                .festival
+                .map(|name| (i18n::name(name, locale).to_string(), LabelSource::Festival)),
+            LabelKind::Lunar => self
+                .lunar
+                .map(|info| (i18n::lunar_cell_day(&info, locale), LabelSource::Lunar)),
+        })
+    }
+}
+
+/// Where a month cell's label came from, which decides its color
+#[derive(Clone, Copy, Debug)]
+pub enum LabelSource {
+    Holiday(HolidayCategory),
+    Birthday,
+    SolarTerm,
+    Festival,
+    Lunar,
+}
+
+/// Which layout the main area renders
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewMode {
+    Month,
+    Week,
+    Year,
+}
+
+/// Screen area of the last frame that reacts to a left click
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HitZone {
+    pub area: Rect,
+    pub target: ClickTarget,
+}
+
+/// What clicking a hit zone does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickTarget {
+    /// Select the date, paging the view when it lies in another month
+    Day(NaiveDate),
+    /// Run the action as if its key was pressed
+    Action(Action),
+}
+
+/// Transient feedback for the status line
+pub struct StatusMessage {
+    pub text: String,
+    pub level: MessageLevel,
+    expires_at: Instant,
+}
+
+/// Severity of a status message, which picks its color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    Warn,
+    Error,
 }
 
 pub struct App {
     today: NaiveDate,
+    /// Last date read from the clock, used to notice midnight
+    clock_date: NaiveDate,
+    /// Double-hour at the last tick, shown in today's details
+    shi_chen: String,
+    /// Whether the screen is out of date and needs drawing
+    dirty: bool,
+    view_mode: ViewMode,
+    config: AppConfig,
+    /// Colors resolved from the configured preset and overrides
+    theme: Theme,
+    config_warnings: Vec<String>,
     view_year: i32,
     view_month: u32,
     selected_day: u32,
+    /// Day of month last picked explicitly, restored when paging allows
+    preferred_day: u32,
+    /// Vim-style count typed before an action
+    pending_count: Option<u32>,
+    /// Flips the automatic single/three-month layout choice
+    tri_month_toggled: bool,
     jump_prompt: Option<JumpPrompt>,
+    /// Inputs that jumped successfully, oldest first and without duplicates
+    jump_history: Vec<String>,
+    date_diff: Option<DateDiffPrompt>,
+    /// Four pillars popup for the selected date
+    bazi: Option<BaziPopup>,
+    /// Single-line editor for the selected date's note
+    note_prompt: Option<String>,
+    note_search: Option<NoteSearch>,
+    festival_search: Option<FestivalSearch>,
+    holiday_list: Option<HolidayList>,
+    /// First row shown in the open keymap overlay
+    keymap_help: Option<usize>,
+    /// Whether the details column beside the calendar is collapsed
+    details_hidden: bool,
+    /// Highlighted row of the month agenda panel while it is open
+    agenda: Option<usize>,
+    /// Waiting for the mark letter after `m` or `'`
+    mark_pending: Option<MarkOperation>,
+    marks: HashMap<char, NaiveDate>,
+    /// Details panel scroll offset and the date it applies to
+    details_scroll: (NaiveDate, u16),
+    /// Fixed end of the visual range; the selection is the moving end
+    visual_anchor: Option<NaiveDate>,
+    /// Transient feedback shown in the status line until it expires
+    message: Option<StatusMessage>,
+    /// Upcoming holidays counted from today, computed once per day
+    upcoming: Vec<(NaiveDate, HolidayInfo)>,
+    /// Next occurrence of each lunar birthday, computed once per day
+    upcoming_birthdays: Vec<(NaiveDate, String)>,
+    /// Next or one-off date of each anniversary, computed once per day
+    upcoming_anniversaries: Vec<(NaiveDate, String)>,
+    /// Next date of the `countdown` festival counted from today, computed once per day
+    countdown: Option<NaiveDate>,
+    /// Solar terms and day counts of a month, kept until the view moves to another month
+    month_cache: MonthCache,
+    schedule: HolidaySchedule,
+    notes: NoteStore,
+    /// Countdowns pinned while running; the configured ones live in `config`
+    countdowns: CountdownStore,
+    events: EventStore,
+    /// Clickable areas recorded while drawing the last frame
+    hit_zones: Vec<HitZone>,
+    /// Running as a date picker (`--pick`); holds the chosen date once confirmed
+    pick: Option<Option<NaiveDate>>,
 }
 
 impl App {
-    pub fn new() -> Self {
-        let today = Local::now().date_naive();
+    pub fn new(
+        schedule: HolidaySchedule,
+        notes: NoteStore,
+        countdowns: CountdownStore,
+        events: EventStore,
+        config: AppConfig,
+    ) -> Self {
+        Self::with_today(
+            schedule,
+            notes,
+            countdowns,
+            events,
+            config,
+            Local::now().date_naive(),
+        )
+    }
+
+    /// Start with `today` pinned to the given date instead of the clock
+    pub fn with_today(
+        schedule: HolidaySchedule,
+        notes: NoteStore,
+        countdowns: CountdownStore,
+        events: EventStore,
+        config: AppConfig,
+        today: NaiveDate,
+    ) -> Self {
         Self {
             today,
+            clock_date: Local::now().date_naive(),
+            shi_chen: lunar::shi_chen(Local::now().time()),
+            dirty: true,
+            view_mode: ViewMode::Month,
             view_year: today.year(),
             view_month: today.month(),
             selected_day: today.day(),
+            preferred_day: today.day(),
+            pending_count: None,
+            tri_month_toggled: false,
             jump_prompt: None,
+            jump_history: Vec::new(),
+            date_diff: None,
+            bazi: None,
+            note_prompt: None,
+            note_search: None,
+            holiday_list: None,
+            festival_search: None,
+            keymap_help: None,
+            details_hidden: false,
+            agenda: None,
+            mark_pending: None,
+            marks: HashMap::new(),
+            details_scroll: (today, 0),
+            visual_anchor: None,
+            message: None,
+            upcoming: upcoming_holidays(today, UPCOMING_HOLIDAY_COUNT),
+            upcoming_birthdays: birthdays::upcoming_birthdays(
+                &config.lunar_birthdays,
+                today,
+                UPCOMING_BIRTHDAY_COUNT,
+            ),
+            upcoming_anniversaries: anniversaries::upcoming_anniversaries(
+                &config.anniversaries,
+                today,
+                UPCOMING_ANNIVERSARY_COUNT,
+                config.anniversary_leap_day,
+            ),
+            countdown: next_countdown(&config, today),
+            month_cache: MonthCache::default(),
+            theme: resolve_theme(&config),
+            config_warnings: Vec::new(),
+            config,
+            schedule,
+            notes,
+            countdowns,
+            events,
+            hit_zones: Vec::new(),
+            pick: None,
         }
     }
 
@@ -191,46 +467,555 @@ impl App {
         self.today
     }
 
+    /// Catch up with the clock between inputs, marking the screen dirty only when something
+    /// time-based changed: today rolled over, the double-hour turned or a message expired
+    pub fn tick(&mut self) {
+        if self.refresh_today() {
+            self.dirty = true;
+        }
+        let shi_chen = lunar::shi_chen(Local::now().time());
+        if shi_chen != self.shi_chen {
+            self.shi_chen = shi_chen;
+            self.dirty = true;
+        }
+        if self
+            .message
+            .as_ref()
+            .is_some_and(|message| Instant::now() >= message.expires_at)
+        {
+            self.message = None;
+            self.dirty = true;
+        }
+    }
+
+    /// Double-hour as of the last tick, so drawing never reads the clock itself
+    pub fn shi_chen(&self) -> &str {
+        &self.shi_chen
+    }
+
+    /// Ask for a redraw, e.g. after input or a resize
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether a redraw is due, clearing the flag
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Advance "today" when the clock passes midnight; returns whether it changed
+    /// A date pinned with `with_today` moves forward by the same number of days
+    pub fn refresh_today(&mut self) -> bool {
+        self.advance_clock(Local::now().date_naive())
+    }
+
+    /// Catch "today" up with the clock now reading `now`
+    fn advance_clock(&mut self, now: NaiveDate) -> bool {
+        let elapsed = now - self.clock_date;
+        self.clock_date = now;
+        match self.today.checked_add_signed(elapsed) {
+            Some(today) => self.set_today(today),
+            None => false,
+        }
+    }
+
+    /// Move "today" to the given date, recomputing the upcoming holidays and anniversaries
+    fn set_today(&mut self, today: NaiveDate) -> bool {
+        if today == self.today {
+            return false;
+        }
+        self.today = today;
+        self.upcoming = upcoming_holidays(today, UPCOMING_HOLIDAY_COUNT);
+        self.upcoming_birthdays = birthdays::upcoming_birthdays(
+            &self.config.lunar_birthdays,
+            today,
+            UPCOMING_BIRTHDAY_COUNT,
+        );
+        self.upcoming_anniversaries = anniversaries::upcoming_anniversaries(
+            &self.config.anniversaries,
+            today,
+            UPCOMING_ANNIVERSARY_COUNT,
+            self.config.anniversary_leap_day,
+        );
+        self.countdown = next_countdown(&self.config, today);
+        true
+    }
+
+    /// Official holiday arrangements used for notes and 休/班 badges
+    pub fn schedule(&self) -> &HolidaySchedule {
+        &self.schedule
+    }
+
+    /// The countdown festival's name and its next date on or after today
+    pub fn countdown(&self) -> Option<(&str, NaiveDate)> {
+        self.countdown
+            .map(|date| (self.config.countdown.as_str(), date))
+    }
+
+    /// The viewed month's solar terms in date order, empty outside the term tables
+    pub fn month_solar_terms(&mut self) -> &[(&'static str, NaiveDate)] {
+        self.refresh_month_cache();
+        &self.month_cache.terms
+    }
+
+    /// Workdays, weekend days and statutory days off of the viewed month
+    pub fn month_stats(&mut self) -> MonthStats {
+        self.refresh_month_cache();
+        self.month_cache.stats
+    }
+
+    /// Recompute the month data once the view has moved to another month
+    fn refresh_month_cache(&mut self) {
+        let month = (self.view_year, self.view_month);
+        if self.month_cache.month != Some(month) {
+            self.month_cache = MonthCache {
+                month: Some(month),
+                terms: terms_in_month(month.0, month.1),
+                stats: self.count_month_stats(month.0, month.1),
+            };
+        }
+    }
+
+    /// Holidays coming up from today, nearest first
+    pub fn upcoming_holidays(&self) -> &[(NaiveDate, HolidayInfo)] {
+        &self.upcoming
+    }
+
+    /// Lunar birthdays coming up from today, nearest first
+    pub fn upcoming_birthdays(&self) -> &[(NaiveDate, String)] {
+        &self.upcoming_birthdays
+    }
+
+    /// Anniversaries coming up from today, nearest first, then past one-off ones
+    pub fn upcoming_anniversaries(&self) -> &[(NaiveDate, String)] {
+        &self.upcoming_anniversaries
+    }
+
     pub fn selected_date(&self) -> NaiveDate {
         NaiveDate::from_ymd_opt(self.view_year, self.view_month, self.selected_day)
             .expect("invalid selected date")
     }
 
+    /// Whether the last session is saved on exit and reopened on the next start
+    pub fn restores_session(&self) -> bool {
+        self.config.restore_session
+    }
+
+    /// The view state to save for the next start
+    pub fn session(&self) -> Session {
+        Session {
+            selected: self.selected_date(),
+            view: self.view_mode,
+            show_help_bar: self.config.show_help_bar,
+            show_details: !self.details_hidden,
+            show_cell_labels: self.config.show_cell_labels,
+            tri_month: self.tri_month_toggled,
+        }
+    }
+
+    /// Reopen a saved session, keeping the selection within the supported years
+    pub fn restore_session(&mut self, session: Session) {
+        self.select_date(
+            session
+                .selected
+                .clamp(min_supported_date(), max_supported_date()),
+        );
+        self.view_mode = session.view;
+        self.config.show_help_bar = session.show_help_bar;
+        self.details_hidden = !session.show_details;
+        self.config.show_cell_labels = session.show_cell_labels;
+        self.tri_month_toggled = session.tri_month;
+    }
+
+    /// Run as a date picker, where confirming the selection ends the session
+    pub fn start_pick(&mut self) {
+        self.pick = Some(None);
+    }
+
+    pub fn pick_mode(&self) -> bool {
+        self.pick.is_some()
+    }
+
+    /// Take the selected date as the pick; returns false outside pick mode, where there is
+    /// nothing to confirm
+    pub fn confirm_pick(&mut self) -> bool {
+        if self.pick.is_none() {
+            return false;
+        }
+        self.pick = Some(Some(self.selected_date()));
+        true
+    }
+
+    /// The date confirmed in pick mode, if any
+    pub fn picked(&self) -> Option<NaiveDate> {
+        self.pick.flatten()
+    }
+
+    /// Whether to ask the terminal for kitty keyboard protocol reports
+    pub fn kitty_keyboard(&self) -> bool {
+        self.config.kitty_keyboard
+    }
+
+    pub fn view_mode(&self) -> ViewMode {
+        self.view_mode
+    }
+
+    /// Order in which a month cell picks its label
+    pub fn label_priority(&self) -> &LabelPriority {
+        &self.config.label_priority
+    }
+
+    /// How many labels a month cell shows under its date
+    pub fn cell_detail(&self) -> CellDetail {
+        self.config.cell_detail
+    }
+
+    pub fn today_marker(&self) -> &TodayMarker {
+        &self.config.today_marker
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.config.locale
+    }
+
+    /// The validated `date_format`, safe to hand to `NaiveDate::format`
+    pub fn date_format(&self) -> &str {
+        &self.config.date_format
+    }
+
+    /// `date` in the configured `date_format`, e.g. "2025-06-01"
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(&self.config.date_format).to_string()
+    }
+
+    /// The month in the configured `month_format`, or None to keep the locale's own title
+    pub fn format_month(&self, year: i32, month: u32) -> Option<String> {
+        let format = self.config.month_format.as_deref()?;
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        Some(first.format(format).to_string())
+    }
+
+    pub fn show_year_progress(&self) -> bool {
+        self.config.show_year_progress
+    }
+
+    pub fn show_legend(&self) -> bool {
+        self.config.show_legend
+    }
+
+    pub fn show_adjacent_days(&self) -> bool {
+        self.config.show_adjacent_days
+    }
+
+    /// Whether any ICS event was loaded, so the grid can show event markers
+    pub fn has_events(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    pub fn has_birthdays(&self) -> bool {
+        !self.config.lunar_birthdays.is_empty()
+    }
+
+    pub fn has_anniversaries(&self) -> bool {
+        !self.config.anniversaries.is_empty()
+    }
+
+    /// Weekdays styled and counted as the weekend
+    pub fn weekend(&self) -> &WeekendDays {
+        &self.config.weekend
+    }
+
+    /// Weekday of the leftmost calendar column
+    pub fn week_start(&self) -> WeekStart {
+        self.config.week_start
+    }
+
+    /// Whether the details panel shows the Julian and Unix day numbers
+    pub fn show_astronomical(&self) -> bool {
+        self.config.show_astronomical
+    }
+
+    /// Whether the details panel counts the viewed month's workdays and days off
+    pub fn show_month_stats(&self) -> bool {
+        self.config.show_month_stats
+    }
+
+    /// Whether the details panel shows the 宜/忌 almanac
+    pub fn show_almanac(&self) -> bool {
+        self.config.show_almanac
+    }
+
+    pub fn show_details(&self) -> bool {
+        !self.details_hidden
+    }
+
+    /// Collapse the details column so the calendar takes the full width, or bring it back
+    pub fn toggle_details(&mut self) {
+        self.details_hidden = !self.details_hidden;
+    }
+
+    /// Terminal width and height below which the compact layout is used
+    pub fn compact_thresholds(&self) -> (u16, u16) {
+        (self.config.compact_width, self.config.compact_height)
+    }
+
+    pub fn details_width_percent(&self) -> u16 {
+        self.config.details_width_percent
+    }
+
+    /// Widen or narrow the details column by `delta` percent, within the configured limits
+    pub fn resize_details(&mut self, delta: i16) {
+        let percent = (self.config.details_width_percent as i16 + delta).clamp(
+            *DETAILS_WIDTH_RANGE.start() as i16,
+            *DETAILS_WIDTH_RANGE.end() as i16,
+        ) as u16;
+        self.config.details_width_percent = percent;
+        self.notify(
+            Msg::DetailsWidth.fill(self.locale(), &[&percent]),
+            MessageLevel::Info,
+        );
+    }
+
+    pub fn show_help_bar(&self) -> bool {
+        self.config.show_help_bar
+    }
+
+    pub fn show_cell_labels(&self) -> bool {
+        self.config.show_cell_labels
+    }
+
+    /// Hide or show the labels under the day numbers for the rest of the session
+    pub fn toggle_cell_labels(&mut self) {
+        self.config.show_cell_labels = !self.config.show_cell_labels;
+    }
+
+    /// Hide or show the shortcut bar for the rest of the session
+    pub fn toggle_help_bar(&mut self) {
+        self.config.show_help_bar = !self.config.show_help_bar;
+    }
+
+    pub fn show_unbound_actions(&self) -> bool {
+        self.config.show_unbound_actions
+    }
+
+    /// How long the event loop may wait for input before the screen needs attention: the status
+    /// message expiring, or the next whole minute, where the double-hour and the date turn
+    /// Waking each minute rather than at the turn itself also catches up after the machine
+    /// slept, since the wait does not count sleeping time
+    /// A configured `tick_rate_ms` caps the wait on top of that
+    pub fn next_wakeup(&self, now: Instant, clock: NaiveTime) -> StdDuration {
+        let into_minute = StdDuration::new(
+            u64::from(clock.second()),
+            // A leap second shows as nanoseconds past one billion
+            clock.nanosecond().min(999_999_999),
+        );
+        let mut wait = StdDuration::from_secs(60).saturating_sub(into_minute);
+        if let Some(message) = &self.message {
+            wait = wait.min(message.expires_at.saturating_duration_since(now));
+        }
+        if let Some(tick_rate) = self.config.tick_rate() {
+            wait = wait.min(tick_rate);
+        }
+        wait
+    }
+
+    pub fn sequence_timeout(&self) -> Option<StdDuration> {
+        self.config.sequence_timeout()
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn theme_name(&self) -> &'static str {
+        self.config.theme.preset.name()
+    }
+
+    /// Problems from loading the config, shown until a key is pressed
+    pub fn config_warnings(&self) -> &[String] {
+        &self.config_warnings
+    }
+
+    pub fn set_config_warnings(&mut self, warnings: Vec<String>) {
+        self.config_warnings = warnings;
+    }
+
+    pub fn dismiss_config_warnings(&mut self) {
+        self.config_warnings.clear();
+    }
+
+    /// Replace the settings and ICS events after the config files were reloaded
+    pub fn apply_config(&mut self, config: AppConfig, events: EventStore) {
+        self.theme = resolve_theme(&config);
+        self.upcoming_birthdays = birthdays::upcoming_birthdays(
+            &config.lunar_birthdays,
+            self.today,
+            UPCOMING_BIRTHDAY_COUNT,
+        );
+        self.upcoming_anniversaries = anniversaries::upcoming_anniversaries(
+            &config.anniversaries,
+            self.today,
+            UPCOMING_ANNIVERSARY_COUNT,
+            config.anniversary_leap_day,
+        );
+        self.countdown = next_countdown(&config, self.today);
+        self.config = config;
+        self.events = events;
+        // The weekend may have changed
+        self.month_cache = MonthCache::default();
+    }
+
+    /// Switch to the next built-in preset, keeping the configured color overrides
+    pub fn cycle_theme(&mut self) {
+        self.config.theme.preset = self.config.theme.preset.next();
+        self.theme = resolve_theme(&self.config);
+        self.notify(
+            Msg::ThemeChanged.fill(self.locale(), &[&self.theme_name()]),
+            MessageLevel::Info,
+        );
+    }
+
+    /// Whether the calendar shows a leading ISO week number column
+    pub fn show_week_numbers(&self) -> bool {
+        self.config.show_week_numbers
+    }
+
+    /// Switch between the month grid and the whole-year overview
+    pub fn toggle_year_view(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::Year => ViewMode::Month,
+            ViewMode::Month | ViewMode::Week => ViewMode::Year,
+        };
+    }
+
+    /// Switch between the month grid and the single-week view
+    pub fn toggle_week_view(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::Week => ViewMode::Month,
+            ViewMode::Month | ViewMode::Year => ViewMode::Week,
+        };
+    }
+
+    /// Return to the month grid focused on the selected date
+    pub fn show_month_view(&mut self) {
+        self.view_mode = ViewMode::Month;
+    }
+
     /// Build the month view where each row is a week covering the month
     pub fn month_rows(&self) -> Vec<Vec<DayCell>> {
-        let first_day = NaiveDate::from_ymd_opt(self.view_year, self.view_month, 1).unwrap();
-        let offset = first_day.weekday().num_days_from_monday() as i64;
+        self.month_rows_for(self.view_year, self.view_month)
+    }
+
+    /// Count typed so far, if any
+    pub fn pending_count(&self) -> Option<u32> {
+        self.pending_count
+    }
+
+    /// Append a digit to the pending count, saturating at MAX_COUNT
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self.pending_count.unwrap_or(0) as u64 * 10 + digit as u64;
+        self.pending_count = Some(count.min(MAX_COUNT as u64) as u32);
+    }
+
+    /// Consume the pending count, defaulting to 1
+    pub fn take_count(&mut self) -> u32 {
+        self.pending_count.take().unwrap_or(1).max(1)
+    }
+
+    pub fn clear_count(&mut self) {
+        self.pending_count = None;
+    }
+
+    pub fn tri_month_toggled(&self) -> bool {
+        self.tri_month_toggled
+    }
+
+    /// Flip between the single-month and the three-month layout
+    pub fn toggle_tri_month(&mut self) {
+        self.tri_month_toggled = !self.tri_month_toggled;
+    }
+
+    /// Build the week containing the selected date, starting on the configured weekday
+    pub fn week_cells(&self) -> Vec<DayCell> {
+        let selected = self.selected_date();
+        let offset = self.config.week_start.column_of(selected.weekday()) as i64;
+        let start = selected.checked_sub_signed(Duration::days(offset)).unwrap();
+        start
+            .iter_days()
+            .zip(lunar::solar_to_lunar_range(start, 7))
+            .map(|(date, lunar)| self.day_cell(date, lunar, date.year(), date.month()))
+            .collect()
+    }
+
+    /// Build compact month grids for all twelve months of the viewed year
+    pub fn year_cells(&self) -> Vec<Vec<Vec<DayCell>>> {
+        (1..=12)
+            .map(|month| self.month_rows_for(self.view_year, month))
+            .collect()
+    }
+
+    /// Build the week rows covering an arbitrary month
+    /// Rows after the month ends are dropped unless six fixed rows are configured
+    pub fn month_rows_for(&self, year: i32, month: u32) -> Vec<Vec<DayCell>> {
+        let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        let offset = self.config.week_start.column_of(first_day.weekday()) as i64;
         let start = first_day
             .checked_sub_signed(Duration::days(offset))
             .unwrap();
-        let mut cursor = start;
+        let mut days = start
+            .iter_days()
+            .zip(lunar::solar_to_lunar_range(start, 42));
         let mut rows = Vec::with_capacity(6);
         for _ in 0..6 {
             let mut week = Vec::with_capacity(7);
-            for _ in 0..7 {
-                let is_current_month =
-                    cursor.month() == self.view_month && cursor.year() == self.view_year;
-                let is_today = cursor == self.today;
-                let is_selected = cursor == self.selected_date();
-                let lunar = lunar::solar_to_lunar(cursor);
-                let solar_term = solar_term_name(cursor);
-                let holiday = holiday_for(cursor, lunar.as_ref(), solar_term);
-                week.push(DayCell {
-                    date: cursor,
-                    is_current_month,
-                    is_today,
-                    is_selected,
-                    lunar,
-                    holiday,
-                    solar_term,
-                });
-                cursor = cursor.succ_opt().unwrap();
+            for (date, lunar) in days.by_ref().take(7) {
+                week.push(self.day_cell(date, lunar, year, month));
+            }
+            if !self.config.fixed_six_rows && !week.iter().any(|cell| cell.is_current_month) {
+                break;
             }
             rows.push(week);
         }
         rows
     }
 
+    /// Collect everything shown for one date, relative to the month being displayed
+    fn day_cell(
+        &self,
+        date: NaiveDate,
+        lunar: Option<lunar::LunarInfo>,
+        year: i32,
+        month: u32,
+    ) -> DayCell {
+        let solar_term = solar_term_name(date);
+        let holidays = holidays_for(date, lunar.as_ref(), solar_term);
+        DayCell {
+            date,
+            is_current_month: date.month() == month && date.year() == year,
+            is_today: date == self.today,
+            is_selected: date == self.selected_date(),
+            is_weekend: self.config.weekend.contains(date.weekday()),
+            lunar,
+            holidays,
+            solar_term,
+            festival: lunar.and_then(|info| info.festival),
+            arrangement: self.schedule.arrangement_for(date),
+            is_marked: self.marks.values().any(|marked| *marked == date),
+            in_range: self
+                .visual_range()
+                .is_some_and(|(start, end)| (start..=end).contains(&date)),
+            has_note: self.notes.contains(date),
+            is_countdown: self.is_countdown_target(date),
+            events: self.events.events_on(date),
+            birthdays: birthdays::birthdays_on(&self.config.lunar_birthdays, date),
+            anniversaries: anniversaries::anniversaries_on(
+                &self.config.anniversaries,
+                date,
+                self.config.anniversary_leap_day,
+            ),
+        }
+    }
+
     /// Get the lunar date for the selected Gregorian date
     pub fn selected_lunar(&self) -> Option<lunar::LunarInfo> {
         lunar::solar_to_lunar(self.selected_date())
@@ -241,12 +1026,9 @@ impl App {
         solar_term_name(self.selected_date())
     }
 
-    /// Get the holiday info for the selected date
-    pub fn selected_holiday(&self) -> Option<HolidayInfo> {
-        let date = self.selected_date();
-        let lunar = lunar::solar_to_lunar(date);
-        let solar_term = self.selected_solar_term();
-        holiday_for(date, lunar.as_ref(), solar_term)
+    /// Get every holiday on the selected date, ordered by priority
+    pub fn selected_holidays(&self) -> Vec<HolidayInfo> {
+        holidays_on(self.selected_date())
     }
 
     /// Get lunar info for the first day of the viewed month
@@ -255,89 +1037,240 @@ impl App {
         lunar::solar_to_lunar(anchor)
     }
 
-    /// Move view to the previous month
-    pub fn prev_month(&mut self) {
-        if self.view_month == 1 {
-            self.view_month = 12;
-            self.view_year -= 1;
-        } else {
-            self.view_month -= 1;
-        }
-        // Clamp the year within supported bounds
-        self.constrain_year();
-        // Clamp the day within the target month
-        self.sync_day();
+    /// Lunar date whose year the header names: the selected date's, since the 干支 year and
+    /// 生肖 change at 春节 partway through January or February, or the month's first day's
+    /// when the selection has no lunar date
+    pub fn header_lunar(&self) -> Option<lunar::LunarInfo> {
+        self.selected_lunar().or_else(|| self.month_anchor_lunar())
     }
 
-    /// Move view to the next month
-    pub fn next_month(&mut self) {
-        if self.view_month == 12 {
-            self.view_month = 1;
-            self.view_year += 1;
-        } else {
-            self.view_month += 1;
+    /// Lunar dates of the first and last day of a solar month, for the calendar title
+    /// None when either end falls outside the lunar table
+    pub fn month_lunar_span(
+        &self,
+        year: i32,
+        month: u32,
+    ) -> Option<(lunar::LunarInfo, lunar::LunarInfo)> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+        Some((lunar::solar_to_lunar(first)?, lunar::solar_to_lunar(last)?))
+    }
+
+    /// Month title with the lunar dates the month covers, e.g. "2025年6月 · 五月初六—六月初六"
+    pub fn month_title(&self, year: i32, month: u32) -> String {
+        let locale = self.locale();
+        let title = self.format_month(year, month).unwrap_or_else(|| {
+            let month_name = match locale {
+                Locale::Zh | Locale::ZhHant => month.to_string(),
+                Locale::En => i18n::month_name(month, locale),
+            };
+            Msg::MonthTitle.fill(locale, &[&year, &month_name])
+        });
+        match self.month_lunar_span(year, month) {
+            Some((first, last)) => Msg::LunarSpan.fill(
+                locale,
+                &[
+                    &title,
+                    &i18n::lunar_month_day(&first, locale),
+                    &i18n::lunar_month_day(&last, locale),
+                ],
+            ),
+            None => title,
         }
-        self.constrain_year();
-        self.sync_day();
     }
 
-    /// Move view to the previous year
-    pub fn prev_year(&mut self) {
-        self.view_year -= 1;
-        self.constrain_year();
-        self.sync_day();
+    /// Move view by a number of months, clamped within the supported range
+    pub fn shift_months(&mut self, delta: i64) {
+        let previous = self.selected_date();
+        let min_index = lunar::MIN_YEAR as i64 * 12;
+        let max_index = lunar::max_supported_year() as i64 * 12 + 11;
+        let index = (self.view_year as i64 * 12 + self.view_month as i64 - 1 + delta)
+            .clamp(min_index, max_index);
+        self.view_year = index.div_euclid(12) as i32;
+        self.view_month = index.rem_euclid(12) as u32 + 1;
+        // Pick the day within the target month according to the paging policy
+        self.sync_day(previous);
+    }
+
+    /// Move view by a number of years, clamped within the supported range
+    pub fn shift_years(&mut self, delta: i64) {
+        let previous = self.selected_date();
+        let min_year = lunar::MIN_YEAR as i64;
+        let max_year = lunar::max_supported_year() as i64;
+        self.view_year = (self.view_year as i64 + delta).clamp(min_year, max_year) as i32;
+        self.sync_day(previous);
+    }
+
+    /// Select the first day of the viewed month
+    pub fn start_of_month(&mut self) {
+        self.select_day(1);
+    }
+
+    /// Select the last day of the viewed month
+    pub fn end_of_month(&mut self) {
+        self.select_day(days_in_month(self.view_year, self.view_month));
+    }
+
+    /// Select January 1 of the viewed year
+    pub fn start_of_year(&mut self) {
+        self.view_month = 1;
+        self.select_day(1);
     }
 
-    /// Move view to the next year
-    pub fn next_year(&mut self) {
-        self.view_year += 1;
-        self.constrain_year();
-        self.sync_day();
+    /// Select December 31 of the viewed year
+    pub fn end_of_year(&mut self) {
+        self.view_month = 12;
+        self.select_day(31);
     }
 
     /// Jump back to today's date
     pub fn back_to_today(&mut self) {
-        self.view_year = self.today.year();
-        self.view_month = self.today.month();
-        self.selected_day = self.today.day();
+        self.select_date(self.today);
+    }
+
+    /// Select the date a jump shortcut names; "next:" targets count from the day after the
+    /// selection, so repeated presses walk through the coming years
+    pub fn jump_to_target(&mut self, target: &JumpTarget) {
+        let date = match target {
+            JumpTarget::Date(date) => Some(*date),
+            JumpTarget::Today(days) => self
+                .today
+                .checked_add_signed(Duration::try_days(*days).unwrap_or(Duration::MAX)),
+            JumpTarget::Next(name) => {
+                let found = self
+                    .selected_date()
+                    .succ_opt()
+                    .and_then(|from| next_named_date(name, from));
+                if found.is_none() {
+                    self.notify(
+                        Msg::NoUpcomingName
+                            .fill(self.locale(), &[&i18n::name(name, self.locale())]),
+                        MessageLevel::Warn,
+                    );
+                    return;
+                }
+                found
+            }
+        };
+        match date.filter(|date| (min_supported_date()..=max_supported_date()).contains(date)) {
+            Some(date) => self.select_date(date),
+            None => self.notify(Msg::OutOfRange.text(self.locale()), MessageLevel::Warn),
+        }
     }
 
     /// Move the selection by a number of days relative to the current selection
     pub fn move_selection(&mut self, delta_days: i64) {
         let current = self.selected_date();
         if let Some(mut new_date) = current.checked_add_signed(Duration::days(delta_days)) {
-            let min_date = NaiveDate::from_ymd_opt(lunar::MIN_YEAR, 1, 1).unwrap();
-            let max_date = NaiveDate::from_ymd_opt(lunar::max_supported_year(), 12, 31).unwrap();
+            let min_date = min_supported_date();
+            let max_date = max_supported_date();
             if new_date < min_date {
                 new_date = min_date;
             } else if new_date > max_date {
                 new_date = max_date;
             }
-            self.view_year = new_date.year();
-            self.view_month = new_date.month();
-            self.selected_day = new_date.day();
+            self.select_date(new_date);
         }
     }
 
-    /// If the previous day exceeds the new month's max day, clamp it
-    fn sync_day(&mut self) {
-        let max_day = days_in_month(self.view_year, self.view_month);
-        if self.selected_day > max_day {
-            self.selected_day = max_day;
+    /// Replace the click targets with those of the frame just drawn
+    pub fn set_hit_zones(&mut self, zones: Vec<HitZone>) {
+        self.hit_zones = zones;
+    }
+
+    /// Target under a screen cell; zones drawn later sit on top
+    pub fn target_at(&self, column: u16, row: u16) -> Option<ClickTarget> {
+        self.hit_zones
+            .iter()
+            .rev()
+            .find(|zone| {
+                let area = zone.area;
+                (area.left()..area.right()).contains(&column)
+                    && (area.top()..area.bottom()).contains(&row)
+            })
+            .map(|zone| zone.target)
+    }
+
+    /// Act on a left click at a screen cell
+    /// Days are selected here; an action target is returned for the caller to run
+    pub fn click(&mut self, column: u16, row: u16) -> Option<Action> {
+        match self.target_at(column, row)? {
+            ClickTarget::Day(date) => {
+                self.select_date(date.clamp(min_supported_date(), max_supported_date()));
+                None
+            }
+            ClickTarget::Action(action) => Some(action),
+        }
+    }
+
+    /// Move the selection to the next date that has a holiday
+    pub fn next_holiday(&mut self) {
+        if let Some(date) = find_holiday_date(self.selected_date(), 1) {
+            self.select_date(date);
+        }
+    }
+
+    /// Move the selection to the previous date that has a holiday
+    pub fn prev_holiday(&mut self) {
+        if let Some(date) = find_holiday_date(self.selected_date(), -1) {
+            self.select_date(date);
         }
     }
 
-    /// Clamp the view year within the supported min and max
-    fn constrain_year(&mut self) {
-        let min_year = lunar::MIN_YEAR;
-        let max_year = lunar::max_supported_year();
-        if self.view_year < min_year {
-            self.view_year = min_year;
-        } else if self.view_year > max_year {
-            self.view_year = max_year;
+    /// Move the selection to the next solar term date
+    pub fn next_solar_term(&mut self) {
+        self.jump_to_solar_term(true);
+    }
+
+    /// Move the selection to the previous solar term date
+    pub fn prev_solar_term(&mut self) {
+        self.jump_to_solar_term(false);
+    }
+
+    fn jump_to_solar_term(&mut self, forward: bool) {
+        match find_solar_term_date(self.selected_date(), forward) {
+            Some(date) => self.select_date(date),
+            None => self.notify(
+                Msg::NoMoreSolarTerms
+                    .fill(self.locale(), &[&SOLAR_TERM_MIN_YEAR, &SOLAR_TERM_MAX_YEAR]),
+                MessageLevel::Warn,
+            ),
         }
     }
 
+    /// Point the view and selection at the given date
+    fn select_date(&mut self, date: NaiveDate) {
+        self.view_year = date.year();
+        self.view_month = date.month();
+        self.select_day(date.day());
+    }
+
+    /// Select a day of the viewed month and remember it for later paging
+    fn select_day(&mut self, day: u32) {
+        self.selected_day = day;
+        self.preferred_day = day;
+    }
+
+    /// Fit the selection into the newly viewed month after paging away from `previous`
+    fn sync_day(&mut self, previous: NaiveDate) {
+        let max_day = days_in_month(self.view_year, self.view_month);
+        self.selected_day = match self.config.paging {
+            PagingPolicy::Clamp => self.selected_day.min(max_day),
+            PagingPolicy::Restore => self.preferred_day.min(max_day),
+            PagingPolicy::Weekday => {
+                // Same weekday in the same week of the month, or its last occurrence
+                let first = NaiveDate::from_ymd_opt(self.view_year, self.view_month, 1).unwrap();
+                let first_match = 1 + previous.weekday().days_since(first.weekday());
+                let nth = (previous.day() - 1) / 7;
+                let mut day = first_match + nth * 7;
+                while day > max_day {
+                    day -= 7;
+                }
+                day
+            }
+        };
+    }
+
     /// Whether the jump prompt should be shown
     pub fn jump_prompt_active(&self) -> bool {
         self.jump_prompt.is_some()
@@ -347,6 +1280,10 @@ impl App {
         self.jump_prompt.as_ref().map(|prompt| JumpPromptView {
             input: &prompt.buffer,
             error: prompt.error.as_deref(),
+            history_position: prompt
+                .history_index
+                .map(|index| (index + 1, self.jump_history.len())),
+            preview: prompt.preview,
         })
     }
 
@@ -360,14 +1297,12 @@ impl App {
 
     /// Accept input while the jump prompt is open
     pub fn push_jump_input(&mut self, ch: char) {
-        if let Some(prompt) = self.jump_prompt.as_mut() {
-            if prompt.buffer.len() >= 16 {
-                return;
-            }
-            if ch.is_ascii_digit() || matches!(ch, '-' | '/' | '.' | ' ') {
-                prompt.buffer.push(ch);
-                prompt.error = None;
-            }
+        if let Some(prompt) = self.jump_prompt.as_mut()
+            && push_date_char(&mut prompt.buffer, ch)
+        {
+            prompt.error = None;
+            prompt.history_index = None;
+            self.refresh_jump_preview();
         }
     }
 
@@ -376,48 +1311,1497 @@ impl App {
         if let Some(prompt) = self.jump_prompt.as_mut() {
             prompt.buffer.pop();
             prompt.error = None;
+            prompt.history_index = None;
+            self.refresh_jump_preview();
         }
     }
 
-    pub fn confirm_jump_prompt(&mut self) {
+    /// Re-resolve the jump input for the live preview
+    fn refresh_jump_preview(&mut self) {
+        let (selected, today, locale) = (self.selected_date(), self.today, self.locale());
+        if let Some(prompt) = self.jump_prompt.as_mut() {
+            prompt.preview = resolve_jump_input(&prompt.buffer, selected, today, locale).ok();
+        }
+    }
+
+    /// Step through earlier jump inputs; negative steps go back in time
+    /// Stepping forward past the newest entry restores the text typed before browsing
+    pub fn recall_jump_history(&mut self, step: i64) {
         let Some(prompt) = self.jump_prompt.as_mut() else {
             return;
         };
-        if let Some(date) = parse_jump_input(&prompt.buffer) {
-            if date.year() < lunar::MIN_YEAR || date.year() > lunar::max_supported_year() {
-                prompt.error = Some("超出支持范围".to_string());
-                return;
-            }
-            self.view_year = date.year();
-            self.view_month = date.month();
-            self.selected_day = date.day();
-            self.jump_prompt = None;
-        } else {
-            prompt.error = Some("无法识别日期格式".to_string());
+        let len = self.jump_history.len();
+        if len == 0 {
+            return;
         }
+        let next = match prompt.history_index {
+            None if step < 0 => {
+                prompt.draft = prompt.buffer.clone();
+                Some(len - 1)
+            }
+            None => return,
+            Some(index) if step < 0 => Some(index.saturating_sub(1)),
+            Some(index) if index + 1 < len => Some(index + 1),
+            Some(_) => None,
+        };
+        prompt.buffer = match next {
+            Some(index) => self.jump_history[index].clone(),
+            None => prompt.draft.clone(),
+        };
+        prompt.history_index = next;
+        prompt.error = None;
+        self.refresh_jump_preview();
     }
-}
 
-#[derive(Default)]
+    pub fn confirm_jump_prompt(&mut self) {
+        let (selected, today, locale) = (self.selected_date(), self.today, self.locale());
+        let Some(prompt) = self.jump_prompt.as_mut() else {
+            return;
+        };
+        let date = match resolve_jump_input(&prompt.buffer, selected, today, locale) {
+            Ok(date) => date,
+            Err(message) => {
+                log::debug!("jump input {:?} rejected: {message}", prompt.buffer);
+                prompt.error = Some(message);
+                return;
+            }
+        };
+        if date.year() < lunar::MIN_YEAR || date.year() > lunar::max_supported_year() {
+            log::debug!("jump input {:?} gives {date}, out of range", prompt.buffer);
+            prompt.error = Some(Msg::OutOfRange.text(locale).to_string());
+            return;
+        }
+        let input = prompt.buffer.trim().to_string();
+        self.jump_history.retain(|entry| *entry != input);
+        self.jump_history.push(input);
+        if self.jump_history.len() > JUMP_HISTORY_LEN {
+            self.jump_history.remove(0);
+        }
+        self.select_date(date);
+        self.jump_prompt = None;
+    }
+
+    /// Whether the date difference calculator should be shown
+    pub fn date_diff_active(&self) -> bool {
+        self.date_diff.is_some()
+    }
+
+    pub fn date_diff_view(&self) -> Option<DateDiffView<'_>> {
+        self.date_diff.as_ref().map(|prompt| DateDiffView {
+            inputs: [&prompt.inputs[0], &prompt.inputs[1]],
+            active: prompt.active,
+            error: prompt.error.as_deref(),
+            result: prompt.result.as_ref(),
+        })
+    }
+
+    pub fn bazi_active(&self) -> bool {
+        self.bazi.is_some()
+    }
+
+    pub fn bazi_view(&self) -> Option<BaziView> {
+        self.bazi.as_ref().map(|popup| {
+            let date = self.selected_date();
+            BaziView {
+                date,
+                hour: popup.hour,
+                pillars: bazi::four_pillars(date, popup.hour),
+            }
+        })
+    }
+
+    /// Open the four pillars of the selected date at the current double-hour
+    pub fn open_bazi(&mut self) {
+        self.bazi = Some(BaziPopup {
+            hour: (Local::now().hour().div_ceil(2) % 12) as usize,
+            digit: None,
+        });
+    }
+
+    pub fn close_bazi(&mut self) {
+        self.bazi = None;
+    }
+
+    /// Step through the double-hours, wrapping from 亥 to 子
+    pub fn shift_bazi_hour(&mut self, delta: i64) {
+        if let Some(popup) = self.bazi.as_mut() {
+            popup.hour = (popup.hour as i64 + delta).rem_euclid(12) as usize;
+            popup.digit = None;
+        }
+    }
+
+    /// Pick the double-hour from a typed clock hour (0-23) or branch character such as 午
+    /// Each digit takes effect at once; a second digit completes a two-digit hour
+    pub fn push_bazi_input(&mut self, ch: char) {
+        let Some(popup) = self.bazi.as_mut() else {
+            return;
+        };
+        if let Some(branch) = lunar::branch_index(ch) {
+            popup.hour = branch;
+            popup.digit = None;
+            return;
+        }
+        let Some(digit) = ch.to_digit(10) else {
+            return;
+        };
+        let hour = match popup.digit.take() {
+            Some(first) if first * 10 + digit <= 23 => first * 10 + digit,
+            _ => {
+                popup.digit = Some(digit);
+                digit
+            }
+        };
+        popup.hour = (hour.div_ceil(2) % 12) as usize;
+    }
+
+    /// Open the calculator with the selected date as the start
+    pub fn start_date_diff(&mut self) {
+        let start = self.selected_date().format("%Y-%m-%d").to_string();
+        self.date_diff = Some(DateDiffPrompt {
+            inputs: [start, String::new()],
+            active: 1,
+            error: None,
+            result: None,
+        });
+    }
+
+    pub fn cancel_date_diff(&mut self) {
+        self.date_diff = None;
+    }
+
+    /// Move the cursor to the other date field
+    pub fn switch_date_diff_field(&mut self) {
+        if let Some(prompt) = self.date_diff.as_mut() {
+            prompt.active = 1 - prompt.active;
+        }
+    }
+
+    /// Accept input into the active date field
+    pub fn push_date_diff_input(&mut self, ch: char) {
+        if let Some(prompt) = self.date_diff.as_mut()
+            && push_date_char(&mut prompt.inputs[prompt.active], ch)
+        {
+            prompt.error = None;
+            prompt.result = None;
+        }
+    }
+
+    /// Remove the last character from the active date field
+    pub fn pop_date_diff_input(&mut self) {
+        if let Some(prompt) = self.date_diff.as_mut() {
+            prompt.inputs[prompt.active].pop();
+            prompt.error = None;
+            prompt.result = None;
+        }
+    }
+
+    /// Enter on the first field moves on; on the second it computes the difference
+    pub fn confirm_date_diff(&mut self) {
+        let (selected, today, locale) = (self.selected_date(), self.today, self.locale());
+        let Some(prompt) = self.date_diff.as_mut() else {
+            return;
+        };
+        if prompt.active == 0 {
+            prompt.active = 1;
+            return;
+        }
+        let mut dates = [None; 2];
+        for (index, label) in [Msg::DiffStart, Msg::DiffEnd].into_iter().enumerate() {
+            let label = label.text(locale);
+            // Each field takes whatever the jump prompt does, festival names included
+            match resolve_jump_input(&prompt.inputs[index], selected, today, locale) {
+                Ok(date)
+                    if (lunar::MIN_YEAR..=lunar::max_supported_year()).contains(&date.year()) =>
+                {
+                    dates[index] = Some(date);
+                }
+                Ok(_) => {
+                    prompt.error = Some(Msg::FieldOutOfRange.fill(locale, &[&label]));
+                    prompt.active = index;
+                    return;
+                }
+                Err(message) => {
+                    prompt.error = Some(Msg::FieldUnrecognized.fill(locale, &[&label, &message]));
+                    prompt.active = index;
+                    return;
+                }
+            }
+        }
+        if let [Some(start), Some(end)] = dates {
+            prompt.result = Some(DateDiff::between(start, end, &self.config.weekend));
+        }
+    }
+
+    /// Imported calendar events on the selected date
+    pub fn selected_events(&self) -> Vec<String> {
+        self.events.events_on(self.selected_date())
+    }
+
+    /// Lunar birthdays on the selected date
+    pub fn selected_birthdays(&self) -> Vec<String> {
+        birthdays::birthdays_on(&self.config.lunar_birthdays, self.selected_date())
+    }
+
+    /// Gregorian anniversaries on the selected date
+    pub fn selected_anniversaries(&self) -> Vec<String> {
+        anniversaries::anniversaries_on(
+            &self.config.anniversaries,
+            self.selected_date(),
+            self.config.anniversary_leap_day,
+        )
+    }
+
+    /// Note attached to the selected date
+    pub fn selected_note(&self) -> Option<&str> {
+        self.notes.get(self.selected_date())
+    }
+
+    /// Whether the note editor should be shown
+    pub fn note_prompt_active(&self) -> bool {
+        self.note_prompt.is_some()
+    }
+
+    pub fn note_prompt_input(&self) -> Option<&str> {
+        self.note_prompt.as_deref()
+    }
+
+    /// Edit the selected date's note, starting from its current text
+    pub fn start_note_prompt(&mut self) {
+        self.note_prompt = Some(self.selected_note().unwrap_or_default().to_string());
+    }
+
+    pub fn cancel_note_prompt(&mut self) {
+        self.note_prompt = None;
+    }
+
+    pub fn push_note_input(&mut self, ch: char) {
+        if let Some(buffer) = self.note_prompt.as_mut()
+            && buffer.chars().count() < MAX_NOTE_CHARS
+            && !ch.is_control()
+        {
+            buffer.push(ch);
+        }
+    }
+
+    pub fn pop_note_input(&mut self) {
+        if let Some(buffer) = self.note_prompt.as_mut() {
+            buffer.pop();
+        }
+    }
+
+    /// Configured countdowns followed by the ones pinned while running
+    pub fn countdowns(&self) -> impl Iterator<Item = &Countdown> {
+        self.config
+            .countdowns
+            .iter()
+            .chain(self.countdowns.pinned())
+    }
+
+    fn is_countdown_target(&self, date: NaiveDate) -> bool {
+        self.countdowns().any(|countdown| countdown.date == date)
+    }
+
+    /// Pin a countdown to the selected date, or unpin the one already there
+    /// It is named after the date's note or first holiday, else the date itself
+    pub fn toggle_countdown(&mut self) {
+        let date = self.selected_date();
+        let locale = self.locale();
+        if self
+            .config
+            .countdowns
+            .iter()
+            .any(|countdown| countdown.date == date)
+        {
+            self.notify(
+                Msg::CountdownInConfig.text(locale).to_string(),
+                MessageLevel::Warn,
+            );
+            return;
+        }
+        let name = self
+            .notes
+            .get(date)
+            .map(str::to_string)
+            .or_else(|| {
+                self.selected_holidays()
+                    .first()
+                    .map(|info| i18n::name(info.name, locale).to_string())
+            })
+            .unwrap_or_else(|| self.format_date(date));
+        let message = match self.countdowns.toggle(date, name) {
+            Ok(Some(countdown)) => Msg::CountdownPinned.fill(locale, &[&countdown.name]),
+            Ok(None) => Msg::CountdownUnpinned.fill(locale, &[&self.format_date(date)]),
+            Err(err) => {
+                self.notify(
+                    Msg::CountdownSaveFailed.fill(locale, &[&err]),
+                    MessageLevel::Error,
+                );
+                return;
+            }
+        };
+        self.notify(message, MessageLevel::Info);
+    }
+
+    /// Write the viewed month as a Markdown table into `export_dir`, named by the month
+    pub fn export_month(&mut self) {
+        let (year, month) = (self.view_year, self.view_month);
+        let dir = self.config.export_path().unwrap_or_default();
+        let path = dir.join(format!("moli-{year}-{month:02}.md"));
+        let content = export::month_markdown(self, year, month);
+        match fs::create_dir_all(&dir).and_then(|()| fs::write(&path, content)) {
+            Ok(()) => self.notify(
+                Msg::MonthExported.fill(self.locale(), &[&path.display()]),
+                MessageLevel::Info,
+            ),
+            Err(err) => self.notify(
+                Msg::MonthExportFailed.fill(self.locale(), &[&err]),
+                MessageLevel::Error,
+            ),
+        }
+    }
+
+    /// Save the note, or clear it when the input is blank
+    pub fn confirm_note_prompt(&mut self) {
+        let Some(text) = self.note_prompt.take() else {
+            return;
+        };
+        if let Err(err) = self.notes.set(self.selected_date(), &text) {
+            self.notify(
+                Msg::NoteSaveFailed.fill(self.locale(), &[&err]),
+                MessageLevel::Error,
+            );
+        }
+    }
+
+    /// Whether the note search overlay should be shown
+    pub fn note_search_active(&self) -> bool {
+        self.note_search.is_some()
+    }
+
+    pub fn note_search_view(&self) -> Option<NoteSearchView<'_>> {
+        self.note_search.as_ref().map(|search| NoteSearchView {
+            query: &search.query,
+            matches: &search.matches,
+            selected: search.selected,
+        })
+    }
+
+    /// Open the note search listing every note
+    pub fn start_note_search(&mut self) {
+        self.note_search = Some(NoteSearch {
+            query: String::new(),
+            matches: self.notes.search(""),
+            selected: 0,
+        });
+    }
+
+    pub fn cancel_note_search(&mut self) {
+        self.note_search = None;
+    }
+
+    pub fn push_note_search_input(&mut self, ch: char) {
+        if let Some(search) = self.note_search.as_mut()
+            && search.query.chars().count() < MAX_QUERY_CHARS
+            && !ch.is_control()
+        {
+            search.query.push(ch);
+            search.refresh(&self.notes);
+        }
+    }
+
+    pub fn pop_note_search_input(&mut self) {
+        if let Some(search) = self.note_search.as_mut() {
+            search.query.pop();
+            search.refresh(&self.notes);
+        }
+    }
+
+    /// Move the highlighted match
+    pub fn move_note_search(&mut self, delta: i64) {
+        if let Some(search) = self.note_search.as_mut() {
+            let last = search.matches.len().saturating_sub(1) as i64;
+            search.selected = (search.selected as i64 + delta).clamp(0, last) as usize;
+        }
+    }
+
+    /// Jump to the highlighted match and close the search
+    pub fn confirm_note_search(&mut self) {
+        let Some(search) = self.note_search.take() else {
+            return;
+        };
+        if let Some((date, _)) = search.matches.get(search.selected) {
+            let date = (*date).clamp(min_supported_date(), max_supported_date());
+            self.select_date(date);
+        }
+    }
+
+    /// Whether the festival search overlay should be shown
+    pub fn festival_search_active(&self) -> bool {
+        self.festival_search.is_some()
+    }
+
+    pub fn festival_search_view(&self) -> Option<FestivalSearchView<'_>> {
+        self.festival_search
+            .as_ref()
+            .map(|search| FestivalSearchView {
+                year: search.year,
+                query: &search.query,
+                matches: &search.matches,
+                selected: search.selected,
+            })
+    }
+
+    /// Open the festival search listing every named day of the viewed year
+    pub fn start_festival_search(&mut self) {
+        let days = named_days_for_year(self.view_year);
+        self.festival_search = Some(FestivalSearch {
+            year: self.view_year,
+            query: String::new(),
+            matches: days.clone(),
+            days,
+            selected: 0,
+        });
+    }
+
+    pub fn cancel_festival_search(&mut self) {
+        self.festival_search = None;
+    }
+
+    pub fn push_festival_search_input(&mut self, ch: char) {
+        if let Some(search) = self.festival_search.as_mut()
+            && search.query.chars().count() < MAX_QUERY_CHARS
+            && !ch.is_control()
+        {
+            search.query.push(ch);
+            search.refresh();
+        }
+    }
+
+    pub fn pop_festival_search_input(&mut self) {
+        if let Some(search) = self.festival_search.as_mut() {
+            search.query.pop();
+            search.refresh();
+        }
+    }
+
+    /// Move the highlighted named day
+    pub fn move_festival_search(&mut self, delta: i64) {
+        if let Some(search) = self.festival_search.as_mut() {
+            let last = search.matches.len().saturating_sub(1) as i64;
+            search.selected = (search.selected as i64 + delta).clamp(0, last) as usize;
+        }
+    }
+
+    /// Jump to the highlighted named day and close the search
+    pub fn confirm_festival_search(&mut self) {
+        let Some(search) = self.festival_search.take() else {
+            return;
+        };
+        if let Some(day) = search.matches.get(search.selected) {
+            self.select_date(day.date);
+        }
+    }
+
+    /// Whether the year holiday list should be shown
+    pub fn holiday_list_active(&self) -> bool {
+        self.holiday_list.is_some()
+    }
+
+    pub fn holiday_list_view(&self) -> Option<HolidayListView<'_>> {
+        self.holiday_list.as_ref().map(|list| HolidayListView {
+            year: list.year,
+            entries: &list.entries,
+            selected: list.selected,
+        })
+    }
+
+    /// Open the list of every holiday in the viewed year, starting at the selected date
+    pub fn open_holiday_list(&mut self) {
+        let entries = holidays_for_year(self.view_year);
+        let selected = self.selected_date();
+        let index = entries
+            .iter()
+            .position(|(date, _)| *date >= selected)
+            .unwrap_or(entries.len().saturating_sub(1));
+        self.holiday_list = Some(HolidayList {
+            year: self.view_year,
+            entries,
+            selected: index,
+        });
+    }
+
+    pub fn close_holiday_list(&mut self) {
+        self.holiday_list = None;
+    }
+
+    /// Move the highlighted entry of the holiday list
+    pub fn move_holiday_list(&mut self, delta: i64) {
+        if let Some(list) = self.holiday_list.as_mut() {
+            let last = list.entries.len().saturating_sub(1) as i64;
+            list.selected = (list.selected as i64 + delta).clamp(0, last) as usize;
+        }
+    }
+
+    /// Jump to the highlighted holiday and close the list
+    pub fn confirm_holiday_list(&mut self) {
+        let Some(list) = self.holiday_list.take() else {
+            return;
+        };
+        if let Some((date, _)) = list.entries.get(list.selected) {
+            self.select_date(*date);
+        }
+    }
+
+    /// Scroll offset of the keymap overlay while it is open
+    pub fn keymap_help(&self) -> Option<usize> {
+        self.keymap_help
+    }
+
+    pub fn open_keymap_help(&mut self) {
+        self.keymap_help = Some(0);
+    }
+
+    pub fn close_keymap_help(&mut self) {
+        self.keymap_help = None;
+    }
+
+    pub fn scroll_keymap_help(&mut self, delta: i64) {
+        if let Some(offset) = self.keymap_help.as_mut() {
+            *offset = (*offset as i64 + delta).max(0) as usize;
+        }
+    }
+
+    /// Keep the keymap overlay from scrolling past its last full page
+    pub fn clamp_keymap_help(&mut self, max: usize) {
+        if let Some(offset) = self.keymap_help.as_mut() {
+            *offset = (*offset).min(max);
+        }
+    }
+
+    /// Whether the month agenda panel is open and receives movement keys
+    pub fn agenda_active(&self) -> bool {
+        self.agenda.is_some()
+    }
+
+    /// Open or close the month agenda panel
+    pub fn toggle_agenda(&mut self) {
+        self.agenda = match self.agenda {
+            Some(_) => None,
+            None => Some(0),
+        };
+    }
+
+    pub fn close_agenda(&mut self) {
+        self.agenda = None;
+    }
+
+    /// Highlighted agenda row, kept within the current month's entries
+    pub fn agenda_selected(&self, len: usize) -> usize {
+        self.agenda.unwrap_or(0).min(len.saturating_sub(1))
+    }
+
+    /// Move the highlighted agenda row
+    pub fn move_agenda(&mut self, delta: i64) {
+        let last = self.agenda_entries().len().saturating_sub(1) as i64;
+        if let Some(selected) = self.agenda.as_mut() {
+            *selected = (*selected as i64 + delta).clamp(0, last) as usize;
+        }
+    }
+
+    /// Select the date of the highlighted agenda row, keeping the panel open
+    pub fn confirm_agenda(&mut self) {
+        let entries = self.agenda_entries();
+        if let Some(entry) = entries.get(self.agenda_selected(entries.len())) {
+            self.select_date(entry.date);
+        }
+    }
+
+    /// Every notable item of the viewed month in date order
+    /// Within a day: holidays, the solar term, birthdays, anniversaries, imported events, then the note
+    pub fn agenda_entries(&self) -> Vec<AgendaEntry> {
+        let mut entries = Vec::new();
+        for cell in self.month_rows().into_iter().flatten() {
+            if !cell.is_current_month {
+                continue;
+            }
+            let date = cell.date;
+            for holiday in &cell.holidays {
+                entries.push(AgendaEntry {
+                    date,
+                    kind: AgendaKind::Holiday(holiday.category),
+                    text: i18n::name(holiday.name, self.locale()).to_string(),
+                });
+            }
+            // 清明 and 冬至 are already listed as holidays
+            if let Some(term) = cell.solar_term
+                && !cell
+                    .holidays
+                    .iter()
+                    .any(|holiday| holiday.name.starts_with(term))
+            {
+                entries.push(AgendaEntry {
+                    date,
+                    kind: AgendaKind::SolarTerm,
+                    text: i18n::name(term, self.locale()).to_string(),
+                });
+            }
+            for name in cell.birthdays {
+                entries.push(AgendaEntry {
+                    date,
+                    kind: AgendaKind::Birthday,
+                    text: name,
+                });
+            }
+            for name in cell.anniversaries {
+                entries.push(AgendaEntry {
+                    date,
+                    kind: AgendaKind::Anniversary,
+                    text: name,
+                });
+            }
+            for event in cell.events {
+                entries.push(AgendaEntry {
+                    date,
+                    kind: AgendaKind::Event,
+                    text: event,
+                });
+            }
+            if let Some(note) = self.notes.get(date) {
+                entries.push(AgendaEntry {
+                    date,
+                    kind: AgendaKind::Note,
+                    text: note.to_string(),
+                });
+            }
+        }
+        entries
+    }
+
+    /// The mark operation waiting for its letter, if any
+    pub fn mark_pending(&self) -> Option<MarkOperation> {
+        self.mark_pending
+    }
+
+    /// Wait for the next key to name a mark
+    pub fn start_mark(&mut self, operation: MarkOperation) {
+        self.mark_pending = Some(operation);
+    }
+
+    /// Complete the pending mark operation with the given letter
+    /// Non-letters cancel the operation; unknown marks are ignored
+    pub fn finish_mark(&mut self, letter: char) {
+        let Some(operation) = self.mark_pending.take() else {
+            return;
+        };
+        if !letter.is_ascii_alphabetic() {
+            return;
+        }
+        match operation {
+            MarkOperation::Set => {
+                let date = self.selected_date();
+                self.marks.insert(letter, date);
+                self.notify(
+                    Msg::MarkSet.fill(self.locale(), &[&letter, &self.format_date(date)]),
+                    MessageLevel::Info,
+                );
+            }
+            MarkOperation::Jump => match self.marks.get(&letter).copied() {
+                Some(date) => {
+                    let date = date.clamp(min_supported_date(), max_supported_date());
+                    self.select_date(date);
+                }
+                None => self.notify(
+                    Msg::MarkUnset.fill(self.locale(), &[&letter]),
+                    MessageLevel::Warn,
+                ),
+            },
+        }
+    }
+
+    pub fn cancel_mark(&mut self) {
+        self.mark_pending = None;
+    }
+
+    /// Details panel scroll offset, reset whenever the selected date changes
+    pub fn details_scroll(&self) -> u16 {
+        let (date, offset) = self.details_scroll;
+        if date == self.selected_date() {
+            offset
+        } else {
+            0
+        }
+    }
+
+    /// Scroll the details panel by a number of lines
+    pub fn scroll_details(&mut self, delta: i32) {
+        let offset = (self.details_scroll() as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+        self.details_scroll = (self.selected_date(), offset);
+    }
+
+    /// Keep the scroll offset within the rendered content
+    pub fn clamp_details_scroll(&mut self, max: u16) {
+        self.details_scroll = (self.selected_date(), self.details_scroll().min(max));
+    }
+
+    /// Show a short message in the status line for a few seconds, replacing any earlier one
+    pub fn notify(&mut self, text: impl Into<String>, level: MessageLevel) {
+        self.message = Some(StatusMessage {
+            text: text.into(),
+            level,
+            expires_at: Instant::now() + MESSAGE_DURATION,
+        });
+    }
+
+    /// The current transient message; `tick` drops it once it expires
+    pub fn message(&self) -> Option<&StatusMessage> {
+        self.message.as_ref()
+    }
+
+    /// Whether visual range selection is active
+    pub fn visual_active(&self) -> bool {
+        self.visual_anchor.is_some()
+    }
+
+    /// Anchor the visual range at the selected date, or leave visual mode
+    pub fn toggle_visual(&mut self) {
+        self.visual_anchor = match self.visual_anchor {
+            Some(_) => None,
+            None => Some(self.selected_date()),
+        };
+    }
+
+    pub fn exit_visual(&mut self) {
+        self.visual_anchor = None;
+    }
+
+    /// Inclusive range between the anchor and the selection, earliest first
+    pub fn visual_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let anchor = self.visual_anchor?;
+        let selected = self.selected_date();
+        Some((anchor.min(selected), anchor.max(selected)))
+    }
+
+    /// Day counts of a month under the configured weekend and the holiday schedule
+    fn count_month_stats(&self, year: i32, month: u32) -> MonthStats {
+        let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        let has_arrangements = self.schedule.has_year(year);
+        let mut stats = MonthStats::default();
+        let lunar_dates = lunar::solar_to_lunar_range(first, days_in_month(year, month));
+        for (date, lunar) in first.iter_days().zip(lunar_dates) {
+            stats.days += 1;
+            let is_weekend = self.config.weekend.contains(date.weekday());
+            let is_day_off = match self.schedule.arrangement_for(date) {
+                Some(DayArrangement::Work) => {
+                    stats.workdays += 1;
+                    continue;
+                }
+                Some(DayArrangement::Rest) => true,
+                // Without the year's arrangement, the holiday itself is the day off
+                None => {
+                    !has_arrangements
+                        && holidays_for(date, lunar.as_ref(), solar_term_name(date))
+                            .iter()
+                            .any(|info| matches!(info.category, HolidayCategory::Statutory))
+                }
+            };
+            if is_weekend {
+                stats.weekend_days += 1;
+            } else if is_day_off {
+                stats.holidays += 1;
+            } else {
+                stats.workdays += 1;
+            }
+        }
+        stats
+    }
+
+    /// Statistics for the visual range shown in the details panel
+    pub fn range_summary(&self) -> Option<RangeSummary> {
+        let (start, end) = self.visual_range()?;
+        let mut summary = RangeSummary {
+            days: 0,
+            weekend_days: 0,
+            holidays: Vec::new(),
+        };
+        let length = (end - start).num_days() as u32 + 1;
+        for (date, lunar) in start
+            .iter_days()
+            .zip(lunar::solar_to_lunar_range(start, length))
+        {
+            summary.days += 1;
+            if self.config.weekend.contains(date.weekday()) {
+                summary.weekend_days += 1;
+            }
+            let solar_term = solar_term_name(date);
+            for info in holidays_for(date, lunar.as_ref(), solar_term) {
+                if !summary.holidays.contains(&info.name) {
+                    summary.holidays.push(info.name);
+                }
+            }
+        }
+        Some(summary)
+    }
+}
+
+/// Data of the viewed month, worked out once per month
+#[derive(Default)]
+struct MonthCache {
+    /// Year and month the data belongs to; None until first filled
+    month: Option<(i32, u32)>,
+    terms: Vec<(&'static str, NaiveDate)>,
+    stats: MonthStats,
+}
+
+/// Day counts of a month; every day is in exactly one of the last three
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MonthStats {
+    pub days: u32,
+    /// Ordinary weekdays and makeup workdays (班)
+    pub workdays: u32,
+    /// Configured weekend days that are not makeup workdays
+    pub weekend_days: u32,
+    /// Official days off (休) on weekdays; for years without arrangement data, statutory
+    /// holidays that fall on weekdays
+    pub holidays: u32,
+}
+
+/// Span statistics of the visual range
+pub struct RangeSummary {
+    pub days: u32,
+    pub weekend_days: u32,
+    /// Distinct holiday names in date order
+    pub holidays: Vec<&'static str>,
+}
+
+/// Two-stage mark commands: `m{letter}` and `'{letter}`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkOperation {
+    Set,
+    Jump,
+}
+
+#[derive(Default)]
 struct JumpPrompt {
     buffer: String,
     error: Option<String>,
+    /// History entry shown in the buffer while browsing with Up/Down
+    history_index: Option<usize>,
+    /// Text typed before browsing started
+    draft: String,
+    /// Date the current input resolves to, refreshed on every edit
+    preview: Option<NaiveDate>,
+}
+
+pub struct JumpPromptView<'a> {
+    pub input: &'a str,
+    pub error: Option<&'a str>,
+    /// One-based history entry and history length while browsing
+    pub history_position: Option<(usize, usize)>,
+    /// Date the input would jump to, possibly outside the supported range
+    pub preview: Option<NaiveDate>,
+}
+
+struct BaziPopup {
+    /// Double-hour shown, 0 for 子 … 11 for 亥
+    hour: usize,
+    /// First digit of a clock hour being typed
+    digit: Option<u32>,
+}
+
+pub struct BaziView {
+    pub date: NaiveDate,
+    pub hour: usize,
+    /// None outside the solar term tables
+    pub pillars: Option<bazi::FourPillars>,
+}
+
+struct DateDiffPrompt {
+    /// Start and end date fields
+    inputs: [String; 2],
+    /// Index of the field receiving input
+    active: usize,
+    error: Option<String>,
+    result: Option<DateDiff>,
+}
+
+pub struct DateDiffView<'a> {
+    pub inputs: [&'a str; 2],
+    pub active: usize,
+    pub error: Option<&'a str>,
+    pub result: Option<&'a DateDiff>,
+}
+
+/// Statistics between two dates, in either order
+pub struct DateDiff {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Days from start to end, not counting the start date
+    pub days: i64,
+    /// Configured weekend days between the dates, both ends included
+    pub weekend_days: i64,
+    pub start_lunar: Option<lunar::LunarInfo>,
+    pub end_lunar: Option<lunar::LunarInfo>,
+}
+
+impl DateDiff {
+    fn between(start: NaiveDate, end: NaiveDate, weekend: &WeekendDays) -> Self {
+        let (first, last) = (start.min(end), start.max(end));
+        let weekend_days = first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .filter(|date| weekend.contains(date.weekday()))
+            .count() as i64;
+        DateDiff {
+            start,
+            end,
+            days: (last - first).num_days(),
+            weekend_days,
+            start_lunar: lunar::solar_to_lunar(start),
+            end_lunar: lunar::solar_to_lunar(end),
+        }
+    }
+}
+
+struct NoteSearch {
+    query: String,
+    matches: Vec<(NaiveDate, String)>,
+    selected: usize,
+}
+
+impl NoteSearch {
+    /// Re-run the query and keep the highlight within the new matches
+    fn refresh(&mut self, notes: &NoteStore) {
+        self.matches = notes.search(&self.query);
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+    }
+}
+
+pub struct NoteSearchView<'a> {
+    pub query: &'a str,
+    pub matches: &'a [(NaiveDate, String)],
+    pub selected: usize,
+}
+
+/// A holiday, lunar festival or solar term with its date in a given year
+#[derive(Clone, Copy, Debug)]
+pub struct NamedDay {
+    pub date: NaiveDate,
+    pub name: &'static str,
+    /// Colored like the agenda row of the same kind
+    pub kind: AgendaKind,
+}
+
+struct FestivalSearch {
+    year: i32,
+    query: String,
+    /// Every named day of the year, kept to filter again as the query changes
+    days: Vec<NamedDay>,
+    matches: Vec<NamedDay>,
+    selected: usize,
+}
+
+impl FestivalSearch {
+    /// Re-run the query and keep the highlight within the new matches
+    fn refresh(&mut self) {
+        self.matches = filter_named_days(&self.days, &self.query);
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+    }
+}
+
+pub struct FestivalSearchView<'a> {
+    pub year: i32,
+    pub query: &'a str,
+    pub matches: &'a [NamedDay],
+    pub selected: usize,
+}
+
+struct HolidayList {
+    year: i32,
+    entries: Vec<(NaiveDate, HolidayInfo)>,
+    selected: usize,
+}
+
+pub struct HolidayListView<'a> {
+    pub year: i32,
+    pub entries: &'a [(NaiveDate, HolidayInfo)],
+    pub selected: usize,
 }
 
-pub struct JumpPromptView<'a> {
-    pub input: &'a str,
-    pub error: Option<&'a str>,
+/// What an agenda row refers to, used for its colour
+#[derive(Clone, Copy, Debug)]
+pub enum AgendaKind {
+    Holiday(HolidayCategory),
+    SolarTerm,
+    Birthday,
+    Anniversary,
+    Event,
+    Note,
+}
+
+/// One row of the month agenda
+#[derive(Clone, Debug)]
+pub struct AgendaEntry {
+    pub date: NaiveDate,
+    pub kind: AgendaKind,
+    pub text: String,
+}
+
+/// Append a character accepted in date inputs, returning whether it was taken
+fn push_date_char(buffer: &mut String, ch: char) -> bool {
+    // Letters cover "today" as well as CJK festival and solar term names
+    let accepted = ch.is_alphanumeric() || matches!(ch, '-' | '/' | '.' | ' ' | '+');
+    if buffer.chars().count() >= 16 || !accepted {
+        return false;
+    }
+    buffer.push(ch);
+    true
+}
+
+/// Parse a date typed into a prompt
+/// Accepts `2025-3-5`, `20250305`, year-month `2025-03` or `202503` (day 1),
+/// month-day `03-05` in the selected date's year, signed day offsets such as `+30`
+/// from the selected date, and `today`/`今天`
+fn parse_jump_input(input: &str, selected: NaiveDate, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("today") || input == "今天" {
+        return Some(today);
+    }
+    if let Some(sign) = input.chars().next().filter(|ch| matches!(ch, '+' | '-')) {
+        let days: i64 = input[1..].trim().parse().ok()?;
+        let days = if sign == '-' { -days } else { days };
+        return selected.checked_add_signed(Duration::try_days(days)?);
+    }
+    let parts: Vec<&str> = input
+        .split(['-', '/', '.', ' '])
+        .filter(|part| !part.is_empty())
+        .collect();
+    if !parts
+        .iter()
+        .all(|part| part.chars().all(|ch| ch.is_ascii_digit()))
+    {
+        return None;
+    }
+    let number = |part: &str, max_len: usize| -> Option<u32> {
+        (part.len() <= max_len).then(|| part.parse().ok()).flatten()
+    };
+    match parts.as_slice() {
+        [digits] if digits.len() == 8 => NaiveDate::from_ymd_opt(
+            digits[0..4].parse().ok()?,
+            digits[4..6].parse().ok()?,
+            digits[6..8].parse().ok()?,
+        ),
+        [digits] if digits.len() == 6 => {
+            NaiveDate::from_ymd_opt(digits[0..4].parse().ok()?, digits[4..6].parse().ok()?, 1)
+        }
+        [year, month] if year.len() == 4 => {
+            NaiveDate::from_ymd_opt(year.parse().ok()?, number(month, 2)?, 1)
+        }
+        [month, day] => {
+            NaiveDate::from_ymd_opt(selected.year(), number(month, 2)?, number(day, 2)?)
+        }
+        [year, month, day] if year.len() == 4 => {
+            NaiveDate::from_ymd_opt(year.parse().ok()?, number(month, 2)?, number(day, 2)?)
+        }
+        _ => None,
+    }
+}
+
+/// Next date of the configured countdown festival, none when the setting is empty
+fn next_countdown(config: &AppConfig, today: NaiveDate) -> Option<NaiveDate> {
+    (!config.countdown.is_empty())
+        .then(|| next_named_date(&config.countdown, today))
+        .flatten()
+}
+
+/// The configured colors, drawn with ASCII glyphs in `ascii_mode`
+fn resolve_theme(config: &AppConfig) -> Theme {
+    let mut theme = config.theme.theme();
+    if config.ascii_mode {
+        theme.glyphs = ASCII_GLYPHS;
+    }
+    let ambiguous_width = config.ambiguous_width.unwrap_or(match config.locale {
+        Locale::Zh | Locale::ZhHant => AmbiguousWidth::Wide,
+        Locale::En => AmbiguousWidth::Narrow,
+    });
+    theme.glyphs.wide_ambiguous = ambiguous_width == AmbiguousWidth::Wide;
+    theme
+}
+
+/// Resolve anything the jump prompt accepts, with the message to show on failure
+/// Anything beyond ASCII is taken as a festival or solar term name,
+/// as are letters in English, so "mid-autumn 2026" works too
+fn resolve_jump_input(
+    input: &str,
+    selected: NaiveDate,
+    today: NaiveDate,
+    locale: Locale,
+) -> Result<NaiveDate, String> {
+    let named = !input.is_ascii()
+        || (locale == Locale::En && input.chars().any(|ch| ch.is_ascii_alphabetic()));
+    match parse_jump_input(input, selected, today) {
+        Some(date) => Ok(date),
+        None if named => resolve_named_date(input, selected, today, locale),
+        None => Err(Msg::UnknownDateFormat.text(locale).to_string()),
+    }
+}
+
+/// Resolve a festival or solar term name, optionally followed by a year, e.g. "中秋 2026"
+/// Without a year the next occurrence on or after today is used; when the selection is
+/// already there the following one is taken, so repeating the jump moves forward
+/// Unknown names report the known names sharing their first character
+fn resolve_named_date(
+    input: &str,
+    selected: NaiveDate,
+    today: NaiveDate,
+    locale: Locale,
+) -> Result<NaiveDate, String> {
+    let input = input.trim();
+    let name = input
+        .trim_end_matches(|ch: char| ch.is_ascii_digit())
+        .trim();
+    let year = match input[name.len()..].trim() {
+        "" => None,
+        digits => Some(
+            digits
+                .parse::<i32>()
+                .map_err(|_| Msg::UnknownYear.text(locale).to_string())?,
+        ),
+    };
+    let lowercase = name.to_lowercase();
+    let matches = |entry: &str| {
+        entry.starts_with(name)
+            || (locale != Locale::Zh
+                && i18n::name(entry, locale)
+                    .to_lowercase()
+                    .starts_with(&lowercase))
+    };
+    let found = match year {
+        Some(year) => named_dates_for_year(year)
+            .into_iter()
+            .find(|(_, entry)| matches(entry)),
+        None => (today.year()..=today.year() + 1)
+            .flat_map(named_dates_for_year)
+            .filter(|(date, entry)| *date >= today && matches(entry))
+            .find(|(date, _)| *date != selected),
+    };
+    if let Some((date, _)) = found {
+        return Ok(date);
+    }
+    if year.is_some_and(|year| !(lunar::MIN_YEAR..=lunar::max_supported_year()).contains(&year)) {
+        return Err(Msg::OutOfRange.text(locale).to_string());
+    }
+    let first = lowercase.chars().next().unwrap_or_default();
+    let mut suggestions: Vec<&str> = named_dates_for_year(today.year())
+        .into_iter()
+        .map(|(_, entry)| i18n::name(entry, locale))
+        .filter(|entry| entry.to_lowercase().starts_with(first))
+        .collect();
+    suggestions.sort_unstable();
+    suggestions.dedup();
+    if suggestions.is_empty() {
+        Err(Msg::UnknownName.fill(locale, &[&name]))
+    } else {
+        Err(Msg::UnknownNameSuggest.fill(
+            locale,
+            &[&name, &suggestions.join(i18n::list_separator(locale))],
+        ))
+    }
+}
+
+/// Names of the holidays, lunar festival and solar term on the date; a name may repeat
+fn names_on(date: NaiveDate) -> impl Iterator<Item = &'static str> {
+    let lunar = lunar::solar_to_lunar(date);
+    let solar_term = solar_term_name(date);
+    holidays_for(date, lunar.as_ref(), solar_term)
+        .into_iter()
+        .map(|info| info.name)
+        .chain(lunar.and_then(|info| info.festival))
+        .chain(solar_term)
+}
+
+/// First date on or after `from` carrying the holiday, festival or solar term `name`
+/// A lunar year has at most 385 days, so the scan never needs to look further
+fn next_named_date(name: &str, from: NaiveDate) -> Option<NaiveDate> {
+    from.iter_days()
+        .take(NAMED_DATE_SCAN_DAYS)
+        .take_while(|date| *date <= max_supported_date())
+        .find(|date| names_on(*date).any(|entry| entry == name))
+}
+
+/// Every festival, holiday and solar term of the Gregorian year with its name, by date
+fn named_dates_for_year(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let Some(mut cursor) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    let mut result = Vec::new();
+    while cursor.year() == year {
+        for name in names_on(cursor) {
+            if !result.contains(&(cursor, name)) {
+                result.push((cursor, name));
+            }
+        }
+        let Some(next) = cursor.succ_opt() else {
+            break;
+        };
+        cursor = next;
+    }
+    result
+}
+
+/// Every holiday, lunar festival and solar term of the Gregorian year, by date
+/// Unlike the agenda, 清明 and 冬至 are listed both as holidays and as solar terms, so
+/// all 24 terms are there; a lunar festival already named by a holiday is not repeated
+fn named_days_for_year(year: i32) -> Vec<NamedDay> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    let mut result = Vec::new();
+    let dates = first.iter_days().take_while(|date| date.year() == year);
+    for (date, lunar) in dates.zip(lunar::solar_to_lunar_range(first, days_in_year(year))) {
+        let solar_term = solar_term_name(date);
+        let holidays = holidays_for(date, lunar.as_ref(), solar_term);
+        for info in &holidays {
+            result.push(NamedDay {
+                date,
+                name: info.name,
+                kind: AgendaKind::Holiday(info.category),
+            });
+        }
+        if let Some(festival) = lunar.and_then(|info| info.festival)
+            && !holidays.iter().any(|info| info.name == festival)
+        {
+            result.push(NamedDay {
+                date,
+                name: festival,
+                kind: AgendaKind::Holiday(HolidayCategory::OtherTraditional),
+            });
+        }
+        if let Some(term) = solar_term {
+            result.push(NamedDay {
+                date,
+                name: term,
+                kind: AgendaKind::SolarTerm,
+            });
+        }
+    }
+    result
+}
+
+/// Named days whose Chinese or English name contains the query, ignoring case
+/// A blank query keeps them all
+fn filter_named_days(days: &[NamedDay], query: &str) -> Vec<NamedDay> {
+    let query = query.trim().to_lowercase();
+    days.iter()
+        .filter(|day| {
+            query.is_empty()
+                || day.name.contains(&query)
+                || i18n::name(day.name, Locale::En)
+                    .to_lowercase()
+                    .contains(&query)
+        })
+        .copied()
+        .collect()
+}
+
+/// List every holiday of the Gregorian year, sorted by date
+pub fn holidays_for_year(year: i32) -> Vec<(NaiveDate, HolidayInfo)> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    let mut result = Vec::new();
+    let dates = first.iter_days().take_while(|date| date.year() == year);
+    for (date, lunar) in dates.zip(lunar::solar_to_lunar_range(first, days_in_year(year))) {
+        let solar_term = solar_term_name(date);
+        for info in holidays_for(date, lunar.as_ref(), solar_term) {
+            result.push((date, info));
+        }
+    }
+    result
+}
+
+/// Every holiday on the date, ordered by priority
+pub fn holidays_on(date: NaiveDate) -> Vec<HolidayInfo> {
+    let lunar = lunar::solar_to_lunar(date);
+    holidays_for(date, lunar.as_ref(), solar_term_name(date))
+}
+
+/// List the next `count` holidays starting from `from` (inclusive), nearest first
+/// Each holiday appears once, while different holidays on adjacent days (除夕/春节) are all kept
+pub fn upcoming_holidays(from: NaiveDate, count: usize) -> Vec<(NaiveDate, HolidayInfo)> {
+    let mut result: Vec<(NaiveDate, HolidayInfo)> = Vec::with_capacity(count);
+    for (date, lunar) in days_with_lunar(from, true) {
+        if result.len() == count {
+            break;
+        }
+        for info in holidays_for(date, lunar.as_ref(), solar_term_name(date)) {
+            if result.len() == count {
+                break;
+            }
+            if !result.iter().any(|(_, seen)| seen.name == info.name) {
+                result.push((date, info));
+            }
+        }
+    }
+    result
+}
+
+/// Days converted per `solar_to_lunar_range` call while walking an open-ended span
+const LUNAR_WALK_CHUNK: i64 = 366;
+
+/// Consecutive days from `from` (inclusive) in the given direction, each with its lunar date,
+/// up to the end of the supported range in that direction
+/// Conversion runs a year at a time, so a long walk scans the lunar tables once per chunk
+fn days_with_lunar(
+    from: NaiveDate,
+    forward: bool,
+) -> impl Iterator<Item = (NaiveDate, Option<lunar::LunarInfo>)> {
+    let (min_date, max_date) = (min_supported_date(), max_supported_date());
+    let mut edge = Some(from).filter(|date| {
+        if forward {
+            *date <= max_date
+        } else {
+            *date >= min_date
+        }
+    });
+    std::iter::from_fn(move || {
+        let from = edge?;
+        let (start, end) = if forward {
+            (
+                from,
+                max_date.min(from + Duration::days(LUNAR_WALK_CHUNK - 1)),
+            )
+        } else {
+            (
+                min_date.max(from - Duration::days(LUNAR_WALK_CHUNK - 1)),
+                from,
+            )
+        };
+        let days = (end - start).num_days() as u32 + 1;
+        let mut chunk: Vec<_> = start
+            .iter_days()
+            .zip(lunar::solar_to_lunar_range(start, days))
+            .collect();
+        if forward {
+            edge = end.succ_opt().filter(|date| *date <= max_date);
+        } else {
+            edge = start.pred_opt().filter(|date| *date >= min_date);
+            chunk.reverse();
+        }
+        Some(chunk)
+    })
+    .flatten()
+}
+
+/// Days between the Julian Day Number and chrono's day count from 0001-01-01
+const JDN_CE_OFFSET: i64 = 1_721_425;
+/// JDN of 1858-11-17, day zero of the Modified Julian Date
+const MJD_EPOCH_JDN: i64 = 2_400_001;
+/// JDN of 1970-01-01
+const UNIX_EPOCH_JDN: i64 = 2_440_588;
+
+/// Julian Day Number of the date, the JD at its noon, e.g. 2451545 for 2000-01-01
+pub fn julian_day(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) + JDN_CE_OFFSET
+}
+
+/// Modified Julian Date at the date's midnight, e.g. 51544 for 2000-01-01
+pub fn modified_julian_day(date: NaiveDate) -> i64 {
+    julian_day(date) - MJD_EPOCH_JDN
+}
+
+/// Days since 1970-01-01, negative before it
+pub fn unix_day(date: NaiveDate) -> i64 {
+    julian_day(date) - UNIX_EPOCH_JDN
+}
+
+/// Share of its year that has passed by the end of `date`, 1.0 on December 31st
+/// Counted against the year's own length, so leap years have 366 steps
+pub fn year_progress(date: NaiveDate) -> f64 {
+    let days_in_year =
+        NaiveDate::from_ymd_opt(date.year(), 12, 31).map_or(365, |last| last.ordinal());
+    f64::from(date.ordinal()) / f64::from(days_in_year)
 }
 
-fn parse_jump_input(input: &str) -> Option<NaiveDate> {
-    let digits: String = input.chars().filter(|c| c.is_ascii_digit()).collect();
-    if digits.len() != 8 {
+/// Describe how far `to` is from `from`, e.g. "还有 45 天 · 约 1 个月" or "已过去 12 天"
+/// Gaps of a month or more get a rough year/month breakdown based on calendar months
+pub fn format_day_offset(from: NaiveDate, to: NaiveDate, locale: Locale) -> String {
+    let days = (to - from).num_days();
+    let mut text = match days {
+        0 => return Msg::Today.text(locale).to_string(),
+        1.. => Msg::InDays.fill(locale, &[&days]),
+        _ => Msg::DaysAgo.fill(locale, &[&-days]),
+    };
+    let (earlier, later) = (from.min(to), from.max(to));
+    let mut months =
+        (later.year() - earlier.year()) * 12 + later.month() as i32 - earlier.month() as i32;
+    if later.day() < earlier.day() {
+        months -= 1;
+    }
+    let rough = match (months / 12, months % 12) {
+        (0, 0) => None,
+        (0, months) => Some(Msg::Months.fill(locale, &[&months])),
+        (years, 0) => Some(Msg::Years.fill(locale, &[&years])),
+        (years, months) => Some(Msg::YearsMonths.fill(locale, &[&years, &months])),
+    };
+    if let Some(rough) = rough {
+        text.push_str(&Msg::About.fill(locale, &[&rough]));
+    }
+    text
+}
+
+/// Shift a (year, month) pair by a number of months
+pub fn shift_month(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 + delta;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+fn min_supported_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(lunar::MIN_YEAR, 1, 1).unwrap()
+}
+
+fn max_supported_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(lunar::max_supported_year(), 12, 31).unwrap()
+}
+
+/// Walk day by day from `from` (exclusive) in the given direction until a holiday is found
+/// Stops at the supported range boundary and returns None if nothing was found
+fn find_holiday_date(from: NaiveDate, step: i64) -> Option<NaiveDate> {
+    let supported = min_supported_date()..=max_supported_date();
+    let start = from.checked_add_signed(Duration::days(step))?;
+    if !supported.contains(&start) {
         return None;
     }
-    let year = digits[0..4].parse().ok()?;
-    let month = digits[4..6].parse().ok()?;
-    let day = digits[6..8].parse().ok()?;
-    NaiveDate::from_ymd_opt(year, month, day)
+    days_with_lunar(start, step > 0)
+        .find(|(date, lunar)| {
+            !holidays_for(*date, lunar.as_ref(), solar_term_name(*date)).is_empty()
+        })
+        .map(|(date, _)| date)
+}
+
+/// Nearest solar term date strictly after (or before) `from`
+/// Every year has a term near each end, so the year after (or before) always holds the answer;
+/// None past the edges of the term tables and the supported range
+fn find_solar_term_date(from: NaiveDate, forward: bool) -> Option<NaiveDate> {
+    let supported = min_supported_date()..=max_supported_date();
+    let found = if forward {
+        [from.year(), from.year() + 1]
+            .into_iter()
+            .flat_map(solar_terms_for_year)
+            .map(|(date, _)| date)
+            .find(|date| *date > from)
+    } else {
+        [from.year(), from.year() - 1]
+            .into_iter()
+            .flat_map(|year| solar_terms_for_year(year).into_iter().rev())
+            .map(|(date, _)| date)
+            .find(|date| *date < from)
+    };
+    found.filter(|date| supported.contains(date))
+}
+
+/// Number of days in the Gregorian year
+fn days_in_year(year: i32) -> u32 {
+    (1..=12).map(|month| days_in_month(year, month)).sum()
 }
 
 /// Calculate days in a month by subtracting the first day of this month from the first day of next month
@@ -430,16 +2814,21 @@ fn days_in_month(year: i32, month: u32) -> u32 {
     (next - first).num_days() as u32
 }
 
-fn holiday_for(
+/// Collect every holiday on the date, given its already computed lunar date and solar term
+/// Ordered by priority: solar > Qingming > lunar statutory > major traditional > other traditional
+pub fn holidays_for(
     date: NaiveDate,
     lunar: Option<&lunar::LunarInfo>,
     solar_term: Option<&'static str>,
-) -> Option<HolidayInfo> {
+) -> Vec<HolidayInfo> {
     solar_holiday(date)
-        .or_else(|| qingming_holiday(solar_term))
-        .or_else(|| lunar_statutory_holiday(lunar))
-        .or_else(|| major_traditional_holiday(lunar))
-        .or_else(|| other_traditional_holiday(lunar, solar_term))
+        .into_iter()
+        .chain(qingming_holiday(solar_term))
+        .chain(lunar_statutory_holiday(lunar))
+        .chain(major_traditional_holiday(lunar))
+        .chain(other_lunar_holiday(lunar))
+        .chain(dongzhi_holiday(solar_term))
+        .collect()
 }
 
 fn solar_holiday(date: NaiveDate) -> Option<HolidayInfo> {
@@ -466,6 +2855,10 @@ fn lunar_statutory_holiday(lunar: Option<&lunar::LunarInfo>) -> Option<HolidayIn
     if info.festival == Some("除夕") {
         return Some(HOLIDAY_SPRING_EVE);
     }
+    // A leap month repeats the number of the month before it, whose festivals come only once
+    if info.date.is_leap {
+        return None;
+    }
     if month == 1 && day == 1 {
         return Some(HOLIDAY_SPRING_FESTIVAL);
     }
@@ -477,7 +2870,7 @@ fn lunar_statutory_holiday(lunar: Option<&lunar::LunarInfo>) -> Option<HolidayIn
 }
 
 fn major_traditional_holiday(lunar: Option<&lunar::LunarInfo>) -> Option<HolidayInfo> {
-    let info = lunar?;
+    let info = lunar.filter(|info| !info.date.is_leap)?;
     match (info.date.month, info.date.day) {
         (1, 15) => Some(HOLIDAY_LANTERN),
         (7, 7) => Some(HOLIDAY_QIXI),
@@ -486,25 +2879,59 @@ fn major_traditional_holiday(lunar: Option<&lunar::LunarInfo>) -> Option<Holiday
     }
 }
 
-fn other_traditional_holiday(
-    lunar: Option<&lunar::LunarInfo>,
-    solar_term: Option<&'static str>,
-) -> Option<HolidayInfo> {
-    if let Some(info) = lunar {
-        match (info.date.month, info.date.day) {
-            (2, 2) => return Some(HOLIDAY_LONGTAITOU),
-            (7, 15) => return Some(HOLIDAY_ZHONGYUAN),
-            (12, 8) => return Some(HOLIDAY_LABA),
-            _ => {}
-        }
+fn other_lunar_holiday(lunar: Option<&lunar::LunarInfo>) -> Option<HolidayInfo> {
+    let info = lunar.filter(|info| !info.date.is_leap)?;
+    match (info.date.month, info.date.day) {
+        (2, 2) => Some(HOLIDAY_LONGTAITOU),
+        (7, 15) => Some(HOLIDAY_ZHONGYUAN),
+        (12, 8) => Some(HOLIDAY_LABA),
+        _ => None,
+    }
+}
+
+fn dongzhi_holiday(solar_term: Option<&'static str>) -> Option<HolidayInfo> {
+    solar_term
+        .filter(|term| *term == "冬至")
+        .map(|_| HOLIDAY_DONGZHI)
+}
+
+/// The 24 solar terms of a Gregorian year in date order, from 小寒 to 冬至
+/// Empty outside `SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR`
+pub fn solar_terms_for_year(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let Some(base) = solar_term_base_datetime() else {
+        return Vec::new();
+    };
+    if !(SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR).contains(&year) {
+        return Vec::new();
     }
-    if solar_term == Some("冬至") {
-        return Some(HOLIDAY_DONGZHI);
+    SOLAR_TERM_NAMES
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &name)| Some((solar_term_date_from_base(base, year, idx)?, name)))
+        .collect()
+}
+
+/// The two solar terms of a Gregorian month in date order, e.g. 芒种 and 夏至 for June
+/// Empty outside `SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR`
+pub fn terms_in_month(year: i32, month: u32) -> Vec<(&'static str, NaiveDate)> {
+    let Some(base) = solar_term_base_datetime() else {
+        return Vec::new();
+    };
+    if !(SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR).contains(&year) {
+        return Vec::new();
     }
-    None
+    // The table starts with 小寒 in January, two terms per month
+    let first = (month as usize).saturating_sub(1) * 2;
+    (first..first + 2)
+        .filter_map(|idx| {
+            let name = *SOLAR_TERM_NAMES.get(idx)?;
+            Some((name, solar_term_date_from_base(base, year, idx)?))
+        })
+        .collect()
 }
 
-fn solar_term_name(date: NaiveDate) -> Option<&'static str> {
+/// Name of the solar term falling on the date, if any
+pub fn solar_term_name(date: NaiveDate) -> Option<&'static str> {
     if !(SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR).contains(&date.year()) {
         return None;
     }
@@ -528,9 +2955,902 @@ fn solar_term_date_from_base(base: NaiveDateTime, year: i32, index: usize) -> Op
         .map(|dt| dt.date())
 }
 
+/// Approximate moment a solar term begins, `index` counting from 小寒; the formula can be
+/// a couple of hours off, which only matters on the day of the term itself
+/// None outside `SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR`
+pub fn solar_term_moment(year: i32, index: usize) -> Option<NaiveDateTime> {
+    if !(SOLAR_TERM_MIN_YEAR..=SOLAR_TERM_MAX_YEAR).contains(&year) {
+        return None;
+    }
+    let offset = solar_term_offset_ms(year, index)?;
+    solar_term_base_datetime()?.checked_add_signed(Duration::milliseconds(offset))
+}
+
 fn solar_term_offset_ms(year: i32, index: usize) -> Option<i64> {
     let minutes = *SOLAR_TERM_OFFSETS.get(index)?;
     let year_offset = (year - SOLAR_TERM_BASE_YEAR) as f64 * SOLAR_TERM_YEAR_MS;
     let term_offset = minutes as f64 * 60_000.0;
     Some((year_offset + term_offset).round() as i64)
 }
+
+/// An app pinned to `today` at noon with the bundled holiday data and nothing read from disk
+#[cfg(test)]
+pub fn test_app(today: NaiveDate, config: AppConfig) -> App {
+    let mut app = App::with_today(
+        HolidaySchedule::bundled(),
+        NoteStore::default(),
+        CountdownStore::default(),
+        EventStore::default(),
+        config,
+        today,
+    );
+    // The double-hour shown for today would otherwise follow the wall clock
+    app.shi_chen = "午时".to_string();
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{anniversaries::Anniversary, birthdays::LunarBirthday};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn names(date: NaiveDate) -> Vec<&'static str> {
+        holidays_on(date).iter().map(|info| info.name).collect()
+    }
+
+    #[test]
+    fn coinciding_holidays_are_all_reported() {
+        assert_eq!(names(date(2020, 10, 1)), ["国庆节", "中秋节"]);
+        let app = test_app(date(2020, 10, 1), AppConfig::default());
+        let selected: Vec<_> = app
+            .selected_holidays()
+            .iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(selected, ["国庆节", "中秋节"]);
+    }
+
+    #[test]
+    fn year_edges_work_at_the_supported_limits() {
+        let max = lunar::max_supported_year();
+        for year in [lunar::MIN_YEAR, 2024, max] {
+            let mut app = test_app(date(year, 6, 15), AppConfig::default());
+            app.start_of_year();
+            assert_eq!(app.selected_date(), date(year, 1, 1));
+            app.end_of_year();
+            assert_eq!(app.selected_date(), date(year, 12, 31));
+        }
+    }
+
+    #[test]
+    fn today_follows_the_clock_past_midnight() {
+        let mut app = test_app(date(2024, 2, 9), AppConfig::default());
+        let clock = app.clock_date;
+        assert!(!app.advance_clock(clock));
+        assert!(app.advance_clock(clock + Duration::days(1)));
+        assert_eq!(app.today, date(2024, 2, 10));
+        assert!(!app.advance_clock(clock + Duration::days(1)));
+        app.shift_months(-1);
+        app.back_to_today();
+        assert_eq!(app.selected_date(), date(2024, 2, 10));
+    }
+
+    #[test]
+    fn pinned_today_seeds_the_view() {
+        let app = test_app(date(2030, 2, 1), AppConfig::default());
+        assert_eq!(app.today, date(2030, 2, 1));
+        assert_eq!(app.selected_date(), date(2030, 2, 1));
+        assert_eq!((app.view_year(), app.view_month()), (2030, 2));
+    }
+
+    fn first_cell(week_start: WeekStart) -> NaiveDate {
+        let config = AppConfig {
+            week_start,
+            ..AppConfig::default()
+        };
+        let app = test_app(date(2023, 10, 1), config);
+        app.month_rows_for(2023, 10)[0][0].date
+    }
+
+    #[test]
+    fn first_column_follows_the_week_start() {
+        // October 2023 begins on a Sunday
+        assert_eq!(first_cell(WeekStart::Monday), date(2023, 9, 25));
+        assert_eq!(first_cell(WeekStart::Sunday), date(2023, 10, 1));
+        assert_eq!(first_cell(WeekStart::Saturday), date(2023, 9, 30));
+    }
+
+    #[test]
+    fn month_grid_drops_empty_trailing_weeks() {
+        let app = test_app(date(2021, 2, 1), AppConfig::default());
+        // February 2021 starts on a Monday and fills exactly four weeks
+        assert_eq!(app.month_rows_for(2021, 2).len(), 4);
+        // October 2023 starts on a Sunday, the last column under Monday-first
+        assert_eq!(app.month_rows_for(2023, 10).len(), 6);
+        assert_eq!(app.month_rows_for(2024, 2).len(), 5);
+    }
+
+    #[test]
+    fn fixed_six_rows_keeps_every_week() {
+        let config = AppConfig {
+            fixed_six_rows: true,
+            ..AppConfig::default()
+        };
+        let app = test_app(date(2021, 2, 1), config);
+        assert_eq!(app.month_rows_for(2021, 2).len(), 6);
+    }
+
+    fn page(paging: PagingPolicy, start: NaiveDate, deltas: &[i64]) -> Vec<NaiveDate> {
+        let config = AppConfig {
+            paging,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(start, config);
+        deltas
+            .iter()
+            .map(|delta| {
+                app.shift_months(*delta);
+                app.selected_date()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn paging_policies_pick_the_day() {
+        let jan31 = date(2024, 1, 31);
+        assert_eq!(
+            page(PagingPolicy::Restore, jan31, &[1, 1]),
+            [date(2024, 2, 29), date(2024, 3, 31)]
+        );
+        assert_eq!(
+            page(PagingPolicy::Clamp, jan31, &[1, 1]),
+            [date(2024, 2, 29), date(2024, 3, 29)]
+        );
+        // The second Tuesday of January 2024, then of February
+        assert_eq!(
+            page(PagingPolicy::Weekday, date(2024, 1, 9), &[1]),
+            [date(2024, 2, 13)]
+        );
+        // The fifth Wednesday falls back to the last one in a month without it
+        assert_eq!(
+            page(PagingPolicy::Weekday, date(2024, 1, 31), &[1]),
+            [date(2024, 2, 28)]
+        );
+    }
+
+    #[test]
+    fn day_offsets_read_naturally() {
+        let cases = [
+            (date(2025, 5, 20), date(2025, 5, 20), "就是今天"),
+            (
+                date(2025, 1, 1),
+                date(2025, 2, 15),
+                "还有 45 天 · 约 1 个月",
+            ),
+            (date(2025, 5, 20), date(2025, 5, 8), "已过去 12 天"),
+            (
+                date(2023, 1, 1),
+                date(2024, 4, 15),
+                "还有 470 天 · 约 1 年 3 个月",
+            ),
+            (
+                date(2024, 2, 29),
+                date(2025, 2, 28),
+                "还有 365 天 · 约 11 个月",
+            ),
+            (
+                date(2024, 3, 1),
+                date(2020, 2, 29),
+                "已过去 1462 天 · 约 4 年",
+            ),
+        ];
+        for (from, to, expected) in cases {
+            assert_eq!(
+                format_day_offset(from, to, Locale::Zh),
+                expected,
+                "{from} -> {to}"
+            );
+        }
+    }
+
+    #[test]
+    fn wakeup_comes_at_the_next_minute_or_message_expiry() {
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        let late = NaiveTime::from_hms_milli_opt(12, 0, 59, 999).unwrap();
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let now = Instant::now();
+        assert_eq!(app.next_wakeup(now, noon), StdDuration::from_secs(60));
+        assert_eq!(app.next_wakeup(now, late), StdDuration::from_millis(1));
+        app.notify("copied", MessageLevel::Info);
+        let now = Instant::now();
+        let wait = app.next_wakeup(now, noon);
+        assert!(
+            wait > StdDuration::ZERO && wait <= MESSAGE_DURATION,
+            "{wait:?}"
+        );
+        // The minute still wins when it turns before the message expires
+        assert_eq!(app.next_wakeup(now, late), StdDuration::from_millis(1));
+        // An expired message asks for an immediate wake-up
+        assert_eq!(
+            app.next_wakeup(now + MESSAGE_DURATION, noon),
+            StdDuration::ZERO
+        );
+    }
+
+    #[test]
+    fn tick_rate_caps_the_wakeup() {
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        let late = NaiveTime::from_hms_milli_opt(12, 0, 59, 999).unwrap();
+        let config = AppConfig {
+            tick_rate_ms: Some(250),
+            ..AppConfig::default()
+        };
+        let app = test_app(date(2025, 6, 18), config);
+        let now = Instant::now();
+        assert_eq!(app.next_wakeup(now, noon), StdDuration::from_millis(250));
+        assert_eq!(app.next_wakeup(now, late), StdDuration::from_millis(1));
+    }
+
+    #[test]
+    fn national_day_month_stats() {
+        let stats = |days, workdays, weekend_days, holidays| MonthStats {
+            days,
+            workdays,
+            weekend_days,
+            holidays,
+        };
+        // 2025 data: 10月1日至8日休, with Saturday 10月11日 worked
+        let mut app = test_app(date(2025, 10, 15), AppConfig::default());
+        assert_eq!(app.month_stats(), stats(31, 18, 7, 6));
+        // Without the year's arrangement only 国庆节 and 中秋节 themselves are days off
+        let mut app = App::with_today(
+            HolidaySchedule::default(),
+            NoteStore::default(),
+            CountdownStore::default(),
+            EventStore::default(),
+            AppConfig::default(),
+            date(2025, 10, 15),
+        );
+        assert_eq!(app.month_stats(), stats(31, 21, 8, 2));
+        // The cached counts follow the view to another month
+        app.shift_months(1);
+        assert_eq!(app.month_stats(), stats(30, 20, 10, 0));
+    }
+
+    #[test]
+    fn date_difference_takes_named_dates() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let diff_of = |app: &mut App, start: &str, end: &str| {
+            app.start_date_diff();
+            let prompt = app.date_diff.as_mut().unwrap();
+            prompt.inputs = [start.to_string(), end.to_string()];
+            app.confirm_date_diff();
+            let view = app.date_diff_view().unwrap();
+            (
+                view.result.map(|diff| diff.days),
+                view.error.map(str::to_string),
+            )
+        };
+        assert_eq!(diff_of(&mut app, "2025-06-18", "+10"), (Some(10), None));
+        assert_eq!(diff_of(&mut app, "今天", "中秋 2025"), (Some(110), None));
+        assert_eq!(
+            diff_of(&mut app, "春节 2025", "春节 2026"),
+            (Some(384), None)
+        );
+        let (result, error) = diff_of(&mut app, "2025-06-18", "2025-13-40");
+        assert_eq!(result, None);
+        assert_eq!(error.as_deref(), Some("结束日期：无法识别日期格式"));
+        let (_, error) = diff_of(&mut app, "不存在的节日", "2025-06-18");
+        assert_eq!(
+            error.as_deref(),
+            Some("起始日期：未知节日或节气：不存在的节日")
+        );
+        let (_, error) = diff_of(&mut app, "1800-01-01", "2025-06-18");
+        assert_eq!(error.as_deref(), Some("起始日期超出支持范围"));
+    }
+
+    #[test]
+    fn jump_input_formats_and_offsets() {
+        let selected = date(2025, 6, 18);
+        let today = date(2025, 1, 2);
+        let parse = |input: &str| parse_jump_input(input, selected, today);
+        for input in ["2025-3-5", "2025/03/05", "2025.3.5", "2025 3 5", "20250305"] {
+            assert_eq!(parse(input), Some(date(2025, 3, 5)), "{input}");
+        }
+        assert_eq!(parse("2026-02"), Some(date(2026, 2, 1)));
+        assert_eq!(parse("202602"), Some(date(2026, 2, 1)));
+        assert_eq!(parse("12-31"), Some(date(2025, 12, 31)));
+        assert_eq!(parse(" today "), Some(today));
+        assert_eq!(parse("TODAY"), Some(today));
+        assert_eq!(parse("今天"), Some(today));
+        assert_eq!(parse("+30"), Some(date(2025, 7, 18)));
+        assert_eq!(parse("-18"), Some(date(2025, 5, 31)));
+        assert_eq!(parse("+ 1"), Some(date(2025, 6, 19)));
+        for input in [
+            "",
+            "+",
+            "+x",
+            "2025-02-30",
+            "2025-13",
+            "123-4",
+            "03-005",
+            "1234567",
+        ] {
+            assert_eq!(parse(input), None, "{input}");
+        }
+        assert_eq!(parse(&format!("+{}", i64::MAX)), None);
+    }
+
+    #[test]
+    fn named_dates_resolve_by_name_and_year() {
+        let today = date(2025, 6, 18);
+        let resolve = |input: &str, selected: NaiveDate, locale: Locale| {
+            resolve_named_date(input, selected, today, locale)
+        };
+        let year = named_dates_for_year(2025);
+        assert!(year.contains(&(date(2025, 10, 6), "中秋节")));
+        assert!(year.contains(&(date(2025, 12, 21), "冬至")));
+        assert!(year.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+        assert_eq!(resolve("中秋", today, Locale::Zh), Ok(date(2025, 10, 6)));
+        // Repeating the jump from the found date moves on to the next year
+        assert_eq!(
+            resolve("中秋", date(2025, 10, 6), Locale::Zh),
+            Ok(date(2026, 9, 25))
+        );
+        assert_eq!(
+            resolve("中秋 2024", today, Locale::Zh),
+            Ok(date(2024, 9, 17))
+        );
+        assert_eq!(
+            resolve("春节2026", today, Locale::Zh),
+            Ok(date(2026, 2, 17))
+        );
+        assert_eq!(
+            resolve("mid-autumn 2026", today, Locale::En),
+            Ok(date(2026, 9, 25))
+        );
+        assert_eq!(resolve("Winter", today, Locale::En), Ok(date(2025, 12, 21)));
+
+        assert_eq!(
+            resolve("春天", today, Locale::Zh),
+            Err("未知节日：春天，是否指 春分、春节？".to_string())
+        );
+        assert_eq!(
+            resolve("不存在", today, Locale::Zh),
+            Err("未知节日或节气：不存在".to_string())
+        );
+        assert_eq!(
+            resolve("中秋 1800", today, Locale::Zh),
+            Err("超出支持范围".to_string())
+        );
+        assert_eq!(
+            resolve("中秋 99999999999", today, Locale::Zh),
+            Err("无法识别年份".to_string())
+        );
+    }
+
+    fn jump(app: &mut App, input: &str) {
+        app.start_jump_prompt();
+        input.chars().for_each(|ch| app.push_jump_input(ch));
+        app.confirm_jump_prompt();
+    }
+
+    fn jump_input(app: &App) -> (String, Option<(usize, usize)>) {
+        let view = app.jump_prompt_view().unwrap();
+        (view.input.to_string(), view.history_position)
+    }
+
+    #[test]
+    fn jump_history_browses_and_restores_the_draft() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_jump_prompt();
+        // Nothing to browse yet
+        app.recall_jump_history(-1);
+        assert_eq!(jump_input(&app), (String::new(), None));
+        app.cancel_jump_prompt();
+
+        for input in ["2025-01-01", "+3", "2025-01-01 ", "03-05"] {
+            jump(&mut app, input);
+        }
+        // Re-used inputs move to the end instead of repeating
+        assert_eq!(app.jump_history, ["+3", "2025-01-01", "03-05"]);
+        // Rejected inputs are not remembered
+        jump(&mut app, "2025-13-01");
+        app.cancel_jump_prompt();
+        assert_eq!(app.jump_history.len(), 3);
+
+        app.start_jump_prompt();
+        app.push_jump_input('1');
+        app.recall_jump_history(1);
+        assert_eq!(jump_input(&app), ("1".to_string(), None));
+        app.recall_jump_history(-1);
+        assert_eq!(jump_input(&app), ("03-05".to_string(), Some((3, 3))));
+        app.recall_jump_history(-1);
+        app.recall_jump_history(-1);
+        assert_eq!(jump_input(&app), ("+3".to_string(), Some((1, 3))));
+        // The oldest entry is as far back as it goes
+        app.recall_jump_history(-1);
+        assert_eq!(jump_input(&app), ("+3".to_string(), Some((1, 3))));
+        app.recall_jump_history(1);
+        app.recall_jump_history(1);
+        assert_eq!(jump_input(&app), ("03-05".to_string(), Some((3, 3))));
+        // Past the newest entry the draft comes back
+        app.recall_jump_history(1);
+        assert_eq!(jump_input(&app), ("1".to_string(), None));
+        // Editing a recalled entry leaves browsing
+        app.recall_jump_history(-1);
+        app.pop_jump_input();
+        assert_eq!(jump_input(&app), ("03-0".to_string(), None));
+    }
+
+    #[test]
+    fn jump_history_keeps_the_latest_entries() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        for offset in 0..JUMP_HISTORY_LEN + 5 {
+            jump(&mut app, &format!("+{offset}"));
+        }
+        assert_eq!(app.jump_history.len(), JUMP_HISTORY_LEN);
+        assert_eq!(app.jump_history[0], "+5");
+        assert_eq!(
+            app.jump_history.last().unwrap(),
+            &format!("+{}", JUMP_HISTORY_LEN + 4)
+        );
+    }
+
+    #[test]
+    fn cycling_themes_visits_every_preset_in_order() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let mut names = vec![app.theme_name()];
+        for _ in 0..5 {
+            app.cycle_theme();
+            names.push(app.theme_name());
+        }
+        assert_eq!(
+            names,
+            [
+                "default",
+                "light",
+                "ansi16",
+                "high-contrast",
+                "colorblind",
+                "default"
+            ]
+        );
+        assert_eq!(
+            app.theme.selected_bg,
+            crate::theme::ThemePreset::Default.theme().selected_bg
+        );
+    }
+
+    #[test]
+    fn weekend_counts_follow_the_configured_days() {
+        let weekend = |days: &str| ron::from_str::<WeekendDays>(days).unwrap();
+        let (first, last) = (date(2025, 6, 1), date(2025, 6, 30));
+        let count = |days: &str| DateDiff::between(last, first, &weekend(days)).weekend_days;
+        assert_eq!(count(r#"["sat", "sun"]"#), 9);
+        assert_eq!(count(r#"["fri", "sat"]"#), 8);
+        assert_eq!(count(r#"["sun"]"#), 5);
+        assert_eq!(count("[]"), 0);
+
+        let config = AppConfig {
+            weekend: weekend(r#"["fri", "sat"]"#),
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 18), config);
+        let stats = app.month_stats();
+        assert_eq!(stats.weekend_days, 8);
+        assert_eq!(
+            stats.days,
+            stats.workdays + stats.weekend_days + stats.holidays
+        );
+    }
+
+    #[test]
+    fn month_titles_show_the_lunar_span() {
+        let app = test_app(date(2025, 6, 18), AppConfig::default());
+        let titles: Vec<String> = [(2025, 6), (2025, 7), (2025, 8), (1900, 1), (2100, 12)]
+            .iter()
+            .map(|&(year, month)| app.month_title(year, month))
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "2025年6月 · 五月初六—六月初六",
+                // A leap month starting mid-month keeps its 闰 prefix at either end
+                "2025年7月 · 六月初七—闰六月初七",
+                "2025年8月 · 闰六月初八—七月初九",
+                // Days before the lunar table leave the plain title
+                "1900年1月",
+                "2100年12月 · 冬月初二—腊月初二",
+            ]
+        );
+        let config = AppConfig {
+            locale: Locale::En,
+            ..AppConfig::default()
+        };
+        let app = test_app(date(2025, 6, 18), config);
+        assert_eq!(app.month_title(2025, 7), "Jul 2025 · lunar 6/7–L6/7");
+    }
+
+    #[test]
+    fn messages_replace_each_other_and_expire() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        assert!(app.message().is_none());
+        app.notify("copied", MessageLevel::Info);
+        app.tick();
+        assert_eq!(app.message().unwrap().text, "copied");
+        // A newer message takes the line and starts its own timer
+        app.message.as_mut().unwrap().expires_at = Instant::now();
+        app.notify("reload failed", MessageLevel::Error);
+        let message = app.message().unwrap();
+        assert_eq!(message.text, "reload failed");
+        assert_eq!(message.level, MessageLevel::Error);
+        assert!(message.expires_at > Instant::now() + MESSAGE_DURATION / 2);
+        app.tick();
+        assert!(app.message().is_some());
+        app.take_dirty();
+        app.message.as_mut().unwrap().expires_at = Instant::now();
+        app.tick();
+        assert!(app.message().is_none());
+        assert!(app.take_dirty());
+    }
+
+    #[test]
+    fn festivals_stay_apart_from_holidays_on_cells() {
+        let app = test_app(date(2025, 1, 15), AppConfig::default());
+        let cells: Vec<DayCell> = app.month_rows().into_iter().flatten().collect();
+        let cell = |day: NaiveDate| cells.iter().find(|cell| cell.date == day).unwrap();
+        let default = LabelPriority::default();
+        // 小年 is only a festival, while 除夕 is also a holiday that outranks it by default
+        let xiaonian = cell(date(2025, 1, 22));
+        assert_eq!(xiaonian.festival, Some("小年"));
+        assert!(xiaonian.holidays.is_empty());
+        let (text, source) = xiaonian.label(&default, Locale::Zh).unwrap();
+        assert!(text == "小年" && matches!(source, LabelSource::Festival));
+        let eve = cell(date(2025, 1, 28));
+        assert_eq!(eve.festival, Some("除夕"));
+        assert_eq!(eve.holidays[0].name, "除夕");
+        let (text, source) = eve.label(&default, Locale::Zh).unwrap();
+        assert!(
+            text == "除夕" && matches!(source, LabelSource::Holiday(HolidayCategory::Statutory))
+        );
+        // The lunar day name no longer carries the festival
+        assert_eq!(eve.lunar.unwrap().day_name(), "廿九");
+        let config: AppConfig = ron::from_str(r#"(label_priority: ["festival"])"#).unwrap();
+        let (text, source) = eve.label(&config.label_priority, Locale::Zh).unwrap();
+        assert!(text == "除夕" && matches!(source, LabelSource::Festival));
+        let plain = cell(date(2025, 1, 27));
+        assert_eq!(plain.festival, None);
+        let (text, source) = plain.label(&default, Locale::Zh).unwrap();
+        assert!(text == "廿八" && matches!(source, LabelSource::Lunar));
+    }
+
+    #[test]
+    fn countdown_rolls_over_once_the_festival_passes() {
+        let next = |today: NaiveDate, festival: &str| {
+            let config = AppConfig {
+                countdown: festival.to_string(),
+                ..AppConfig::default()
+            };
+            test_app(today, config).countdown().map(|(_, date)| date)
+        };
+        let cases = [
+            (date(2025, 1, 28), date(2025, 1, 29)),
+            // The day itself still counts, and the day after looks a year ahead
+            (date(2025, 1, 29), date(2025, 1, 29)),
+            (date(2025, 1, 30), date(2026, 2, 17)),
+            (date(2025, 12, 31), date(2026, 2, 17)),
+            (date(2026, 2, 17), date(2026, 2, 17)),
+        ];
+        for (today, expected) in cases {
+            assert_eq!(next(today, "春节"), Some(expected), "{today}");
+        }
+        assert_eq!(next(date(2025, 10, 7), "中秋节"), Some(date(2026, 9, 25)));
+        assert_eq!(next(date(2025, 10, 7), ""), None);
+        // Midnight moves the countdown along with today
+        let mut app = test_app(date(2025, 1, 29), AppConfig::default());
+        let clock = app.clock_date;
+        assert!(app.advance_clock(clock + Duration::days(1)));
+        assert_eq!(app.countdown(), Some(("春节", date(2026, 2, 17))));
+    }
+
+    #[test]
+    fn solar_term_hops_cross_years_and_stop_at_the_table_end() {
+        let cases = [
+            // Sitting on a term moves to the neighbouring one, not onto itself
+            (date(2025, 6, 5), true, Some(date(2025, 6, 21))),
+            (date(2025, 6, 5), false, Some(date(2025, 5, 21))),
+            (date(2025, 6, 6), false, Some(date(2025, 6, 5))),
+            // 冬至 and 小寒 sit on either side of New Year
+            (date(2024, 12, 25), true, Some(date(2025, 1, 5))),
+            (date(2025, 1, 3), false, Some(date(2024, 12, 21))),
+            (date(2100, 12, 25), true, None),
+            (date(1900, 1, 5), false, None),
+            (date(1900, 1, 5), true, Some(date(1900, 1, 6))),
+        ];
+        for (from, forward, expected) in cases {
+            assert_eq!(
+                find_solar_term_date(from, forward),
+                expected,
+                "{from} {forward}"
+            );
+        }
+        let mut app = test_app(date(2025, 1, 3), AppConfig::default());
+        app.prev_solar_term();
+        assert_eq!(app.selected_date(), date(2024, 12, 21));
+        assert_eq!((app.view_year(), app.view_month()), (2024, 12));
+        app.next_solar_term();
+        assert_eq!(app.selected_date(), date(2025, 1, 5));
+        // At the end the selection stays put and a message says why
+        let mut app = test_app(date(2100, 12, 25), AppConfig::default());
+        app.next_solar_term();
+        assert_eq!(app.selected_date(), date(2100, 12, 25));
+        assert_eq!(
+            app.message().unwrap().text,
+            "没有更多节气（节气数据覆盖 1900–2100 年）"
+        );
+    }
+
+    #[test]
+    fn each_month_holds_its_two_solar_terms() {
+        let cases = [
+            (
+                (2025, 1),
+                [("小寒", date(2025, 1, 5)), ("大寒", date(2025, 1, 20))],
+            ),
+            (
+                (2024, 2),
+                [("立春", date(2024, 2, 4)), ("雨水", date(2024, 2, 19))],
+            ),
+            (
+                (2025, 6),
+                [("芒种", date(2025, 6, 5)), ("夏至", date(2025, 6, 21))],
+            ),
+            (
+                (2025, 12),
+                [("大雪", date(2025, 12, 7)), ("冬至", date(2025, 12, 21))],
+            ),
+        ];
+        for ((year, month), expected) in cases {
+            assert_eq!(terms_in_month(year, month), expected, "{year}-{month}");
+        }
+        assert!(terms_in_month(1899, 12).is_empty());
+        assert!(terms_in_month(2101, 1).is_empty());
+        assert_eq!(terms_in_month(2100, 12).len(), 2);
+        // The cached list follows the view, not the selection's day
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        assert_eq!(app.month_solar_terms()[0].0, "芒种");
+        app.shift_months(7);
+        assert_eq!(app.month_solar_terms(), terms_in_month(2026, 1));
+    }
+
+    #[test]
+    fn year_progress_counts_leap_years() {
+        let percent = |day: NaiveDate| (year_progress(day) * 1000.0).round() / 10.0;
+        assert_eq!(percent(date(2025, 1, 1)), 0.3);
+        assert_eq!(percent(date(2024, 1, 1)), 0.3);
+        assert_eq!(percent(date(2025, 7, 2)), 50.1);
+        // The leap day pushes the rest of 2024 one day further in
+        assert_eq!(year_progress(date(2024, 2, 29)), 60.0 / 366.0);
+        assert_eq!(year_progress(date(2024, 3, 1)), 61.0 / 366.0);
+        assert_eq!(year_progress(date(2025, 3, 1)), 60.0 / 365.0);
+        assert_eq!(year_progress(date(2024, 12, 31)), 1.0);
+        assert_eq!(year_progress(date(2025, 12, 31)), 1.0);
+    }
+
+    #[test]
+    fn day_numbers_match_reference_epochs() {
+        assert_eq!(julian_day(date(2000, 1, 1)), 2_451_545);
+        assert_eq!(modified_julian_day(date(2000, 1, 1)), 51_544);
+        assert_eq!(unix_day(date(2000, 1, 1)), 10_957);
+        assert_eq!(modified_julian_day(date(1858, 11, 17)), 0);
+        assert_eq!(unix_day(date(1970, 1, 1)), 0);
+        assert_eq!(unix_day(date(1969, 12, 31)), -1);
+        // Proleptic Gregorian out to both ends of the range
+        assert_eq!(julian_day(date(1582, 10, 15)), 2_299_161);
+        assert_eq!(julian_day(date(1, 1, 1)), 1_721_426);
+        assert_eq!(julian_day(date(-4713, 11, 24)), 0);
+        assert_eq!(julian_day(date(9999, 12, 31)), 5_373_484);
+        assert_eq!(
+            julian_day(date(2024, 3, 1)) - julian_day(date(2024, 2, 28)),
+            2
+        );
+    }
+
+    #[test]
+    fn holiday_hops_skip_shared_days_cross_years_and_stop_at_the_table_ends() {
+        // 国庆 and 中秋 fell on the same day in 2020, which is a single stop
+        let mut app = test_app(date(2020, 9, 30), AppConfig::default());
+        app.next_holiday();
+        assert_eq!(app.selected_date(), date(2020, 10, 1));
+        assert!(holidays_on(date(2020, 10, 1)).len() >= 2);
+        app.next_holiday();
+        let after = app.selected_date();
+        assert!(after > date(2020, 10, 1));
+        app.prev_holiday();
+        assert_eq!(app.selected_date(), date(2020, 10, 1));
+        assert_eq!(find_holiday_date(after, -1), Some(date(2020, 10, 1)));
+
+        // 冬至 then 元旦 across the new year, in both directions
+        let mut app = test_app(date(2024, 12, 22), AppConfig::default());
+        app.next_holiday();
+        assert_eq!(app.selected_date(), date(2025, 1, 1));
+        assert_eq!(app.view_year, 2025);
+        app.prev_holiday();
+        assert_eq!(app.selected_date(), date(2024, 12, 21));
+        assert_eq!((app.view_year, app.view_month), (2024, 12));
+
+        // Past the last (or before the first) holiday of the tables the selection stays
+        let last = max_supported_date();
+        let final_holiday = find_holiday_date(last, -1).unwrap();
+        assert_eq!(find_holiday_date(final_holiday, 1), None);
+        assert_eq!(find_holiday_date(last, 1), None);
+        let mut app = test_app(final_holiday, AppConfig::default());
+        app.next_holiday();
+        assert_eq!(app.selected_date(), final_holiday);
+        // The first supported day is itself 元旦
+        let first = min_supported_date();
+        assert!(!holidays_on(first).is_empty());
+        assert_eq!(find_holiday_date(date(1900, 1, 31), -1), Some(first));
+        let mut app = test_app(first, AppConfig::default());
+        app.prev_holiday();
+        assert_eq!(app.selected_date(), first);
+    }
+
+    #[test]
+    fn month_edges_follow_each_months_length() {
+        let mut app = test_app(date(2024, 2, 10), AppConfig::default());
+        app.end_of_month();
+        assert_eq!(app.selected_date(), date(2024, 2, 29));
+        app.start_of_month();
+        assert_eq!(app.selected_date(), date(2024, 2, 1));
+        app.shift_years(1);
+        app.end_of_month();
+        assert_eq!(app.selected_date(), date(2025, 2, 28));
+        for (month, last) in [(4, 30), (12, 31)] {
+            app.shift_months(i64::from(month) - i64::from(app.view_month));
+            app.end_of_month();
+            assert_eq!(app.selected_date(), date(2025, month, last));
+        }
+        // The edge becomes the day later paging comes back to
+        let config = AppConfig {
+            paging: PagingPolicy::Restore,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 1, 10), config);
+        app.end_of_month();
+        app.shift_months(1);
+        assert_eq!(app.selected_date(), date(2025, 2, 28));
+        app.shift_months(1);
+        assert_eq!(app.selected_date(), date(2025, 3, 31));
+        app.start_of_month();
+        app.shift_months(-1);
+        assert_eq!(app.selected_date(), date(2025, 2, 1));
+    }
+
+    #[test]
+    fn agenda_lists_the_viewed_month_by_day_and_kind() {
+        let config = AppConfig {
+            lunar_birthdays: vec![LunarBirthday {
+                name: "奶奶生日".to_string(),
+                month: 3,
+                day: 7,
+                include_leap: false,
+            }],
+            anniversaries: vec![Anniversary {
+                name: "结婚纪念日".to_string(),
+                month: Some(4),
+                day: Some(4),
+                date: None,
+            }],
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 4, 10), config);
+        app.notes.set(date(2025, 4, 4), "扫墓").unwrap();
+        // Days of the neighbouring months shown in the grid stay out
+        app.notes.set(date(2025, 3, 31), "三月").unwrap();
+        let rows: Vec<_> = app
+            .agenda_entries()
+            .into_iter()
+            .map(|entry| (entry.date.day(), format!("{:?}", entry.kind), entry.text))
+            .collect();
+        // 清明 is listed once, as the holiday
+        let expected = [
+            (4, "Holiday(Statutory)", "清明节"),
+            (4, "Birthday", "奶奶生日"),
+            (4, "Anniversary", "结婚纪念日"),
+            (4, "Note", "扫墓"),
+            (20, "SolarTerm", "谷雨"),
+        ];
+        assert_eq!(rows.len(), expected.len());
+        for (row, (day, kind, text)) in rows.iter().zip(expected) {
+            assert_eq!((row.0, row.1.as_str(), row.2.as_str()), (day, kind, text));
+        }
+        // The highlight stays on the list, and confirming selects its day
+        app.toggle_agenda();
+        app.move_agenda(10);
+        assert_eq!(app.agenda_selected(rows.len()), 4);
+        app.confirm_agenda();
+        assert_eq!(app.selected_date(), date(2025, 4, 20));
+        assert!(app.agenda_active());
+        app.move_agenda(-10);
+        app.confirm_agenda();
+        assert_eq!(app.selected_date(), date(2025, 4, 4));
+    }
+
+    #[test]
+    fn header_lunar_year_follows_the_selection_across_spring_festival() {
+        // 春节 2026 is on February 17th, so the February view spans 乙巳 and 丙午
+        let mut app = test_app(date(2026, 2, 10), AppConfig::default());
+        let year = |app: &App| app.header_lunar().map(|info| info.date.year);
+        assert_eq!(year(&app), Some(2025));
+        assert_eq!(app.month_anchor_lunar().unwrap().date.year, 2025);
+        app.move_selection(7);
+        assert_eq!(app.selected_date(), date(2026, 2, 17));
+        assert_eq!(year(&app), Some(2026));
+        assert_eq!(app.month_anchor_lunar().unwrap().date.year, 2025);
+        app.move_selection(-1);
+        assert_eq!(year(&app), Some(2025));
+    }
+
+    #[test]
+    fn festival_search_resolves_and_filters_named_days() {
+        let days = named_days_for_year(2025);
+        let dates_of = |name: &str| {
+            days.iter()
+                .filter(|day| day.name == name)
+                .map(|day| day.date)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(dates_of("小年"), [date(2025, 1, 22)]);
+        assert_eq!(dates_of("中秋节"), [date(2025, 10, 6)]);
+        assert_eq!(dates_of("芒种"), [date(2025, 6, 5)]);
+        // 除夕 is both a festival and a holiday but is listed once
+        assert_eq!(dates_of("除夕"), [date(2025, 1, 28)]);
+        assert!(days.is_sorted_by_key(|day| day.date));
+        let names = |query: &str| {
+            filter_named_days(&days, query)
+                .iter()
+                .map(|day| day.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(" 中秋 "), ["中秋节"]);
+        // English names match too, whatever the case
+        assert_eq!(names("QINGMING"), ["清明节"]);
+        assert!(names("春").contains(&"春分") && names("春").contains(&"春节"));
+        assert!(names("xyz").is_empty());
+        assert_eq!(names("").len(), days.len());
+    }
+
+    #[test]
+    fn festival_search_keeps_the_highlight_and_jumps() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_festival_search();
+        app.move_festival_search(1000);
+        let view = app.festival_search_view().unwrap();
+        assert_eq!(view.selected, view.matches.len() - 1);
+        // Narrowing the matches pulls the highlight back in
+        "中秋"
+            .chars()
+            .for_each(|ch| app.push_festival_search_input(ch));
+        assert_eq!(app.festival_search_view().unwrap().selected, 0);
+        app.push_festival_search_input('\n');
+        assert_eq!(app.festival_search_view().unwrap().query, "中秋");
+        app.confirm_festival_search();
+        assert!(!app.festival_search_active());
+        assert_eq!(app.selected_date(), date(2025, 10, 6));
+        // Esc and a query without matches leave the selection alone
+        app.start_festival_search();
+        app.push_festival_search_input('z');
+        app.confirm_festival_search();
+        assert_eq!(app.selected_date(), date(2025, 10, 6));
+        app.start_festival_search();
+        app.cancel_festival_search();
+        assert!(!app.festival_search_active());
+    }
+}
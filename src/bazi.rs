@@ -0,0 +1,202 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+use crate::{app, i18n::Locale, lunar};
+
+/// Index of 立春 in the solar term table, where the 八字 year begins
+const LICHUN: usize = 2;
+
+/// The five phases (五行) of stems and branches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Element {
+    Wood,
+    Fire,
+    Earth,
+    Metal,
+    Water,
+}
+
+impl Element {
+    pub fn label(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (Element::Wood, Locale::Zh | Locale::ZhHant) => "木",
+            (Element::Fire, Locale::Zh | Locale::ZhHant) => "火",
+            (Element::Earth, Locale::Zh | Locale::ZhHant) => "土",
+            (Element::Metal, Locale::Zh | Locale::ZhHant) => "金",
+            (Element::Water, Locale::Zh | Locale::ZhHant) => "水",
+            (Element::Wood, Locale::En) => "Wood",
+            (Element::Fire, Locale::En) => "Fire",
+            (Element::Earth, Locale::En) => "Earth",
+            (Element::Metal, Locale::En) => "Metal",
+            (Element::Water, Locale::En) => "Water",
+        }
+    }
+}
+
+/// Phase of each stem, two stems per phase from 甲乙 (wood)
+const STEM_ELEMENTS: [Element; 5] = [
+    Element::Wood,
+    Element::Fire,
+    Element::Earth,
+    Element::Metal,
+    Element::Water,
+];
+
+/// Phase of each branch from 子
+const BRANCH_ELEMENTS: [Element; 12] = [
+    Element::Water,
+    Element::Earth,
+    Element::Wood,
+    Element::Wood,
+    Element::Earth,
+    Element::Fire,
+    Element::Fire,
+    Element::Earth,
+    Element::Metal,
+    Element::Metal,
+    Element::Earth,
+    Element::Water,
+];
+
+/// One pillar: a heavenly stem and an earthly branch, counted from 甲 and 子
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pillar {
+    pub stem: usize,
+    pub branch: usize,
+}
+
+impl Pillar {
+    /// Pillar at a position of the sexagenary cycle, 0 being 甲子
+    fn from_cycle(index: usize) -> Self {
+        Pillar {
+            stem: index % 10,
+            branch: index % 12,
+        }
+    }
+
+    pub fn stem_element(self) -> Element {
+        STEM_ELEMENTS[self.stem / 2]
+    }
+
+    pub fn branch_element(self) -> Element {
+        BRANCH_ELEMENTS[self.branch]
+    }
+
+    pub fn zodiac(self) -> char {
+        lunar::branch_zodiac(self.branch)
+    }
+}
+
+/// 年柱, 月柱, 日柱 and 时柱 of a moment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FourPillars {
+    pub year: Pillar,
+    pub month: Pillar,
+    pub day: Pillar,
+    pub hour: Pillar,
+}
+
+/// The four pillars of a date in the double-hour `hour_branch` (0 for 子 … 11 for 亥)
+/// The year turns at 立春 and the month at each 节 (立春, 惊蛰, …), not on the lunar calendar;
+/// 子时 is the early one, 00:00–01:00 of the date, so the day pillar stays the date's own
+/// None outside the solar term tables
+pub fn four_pillars(date: NaiveDate, hour_branch: usize) -> Option<FourPillars> {
+    let hour_branch = hour_branch % 12;
+    let moment = date.and_hms_opt(hour_branch as u32 * 2, 0, 0)?;
+    let lichun = app::solar_term_moment(date.year(), LICHUN)?;
+    let year = if moment < lichun {
+        date.year() - 1
+    } else {
+        date.year()
+    };
+    let year = Pillar::from_cycle((year - 4).rem_euclid(60) as usize);
+    let month_branch = month_branch(moment)?;
+    // 五虎遁: the 寅 month's stem follows from the year stem, later months count on from it
+    let months_from_yin = (month_branch + 10) % 12;
+    let month = Pillar {
+        stem: (year.stem * 2 + 2 + months_from_yin) % 10,
+        branch: month_branch,
+    };
+    let day = Pillar::from_cycle(lunar::gan_zhi_day_index(date));
+    // 五鼠遁: the 子 hour's stem follows from the day stem
+    let hour = Pillar {
+        stem: (day.stem * 2 + hour_branch) % 10,
+        branch: hour_branch,
+    };
+    Some(FourPillars {
+        year,
+        month,
+        day,
+        hour,
+    })
+}
+
+/// Branch of the solar month holding `moment`: the month of the latest 节 before it
+/// 节 are the even entries of the term table, 小寒 opening the 丑 month and 大雪 the 子 month
+fn month_branch(moment: NaiveDateTime) -> Option<usize> {
+    let year = moment.year();
+    let mut latest = None;
+    for index in (0..24).step_by(2) {
+        if app::solar_term_moment(year, index)? <= moment {
+            latest = Some(index);
+        }
+    }
+    // Before 小寒 the month is still the previous year's 大雪 month
+    Some(latest.map_or(0, |index| (index / 2 + 1) % 12))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// The pillars as "年 月 日 时", e.g. "己卯 丙子 戊午 戊午"
+    fn pillars(date: NaiveDate, hour_branch: usize) -> String {
+        let pillars = four_pillars(date, hour_branch).unwrap();
+        [pillars.year, pillars.month, pillars.day, pillars.hour]
+            .iter()
+            .map(|pillar| {
+                format!(
+                    "{}{}",
+                    lunar::stem(pillar.stem),
+                    lunar::branch(pillar.branch)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn published_charts() {
+        // Noon of 2000-01-01, still in 己卯 before 立春 and the 子 month before 小寒
+        assert_eq!(pillars(date(2000, 1, 1), 6), "己卯 丙子 戊午 戊午");
+        // 1949-10-01 in the afternoon
+        assert_eq!(pillars(date(1949, 10, 1), 8), "己丑 癸酉 甲子 壬申");
+    }
+
+    #[test]
+    fn year_and_month_turn_at_lichun() {
+        // 立春 2024 fell in the afternoon; the term formula is only good to a couple of hours,
+        // so the checks keep clear of it on either side
+        assert_eq!(pillars(date(2024, 2, 4), 6), "癸卯 乙丑 戊戌 戊午");
+        assert_eq!(pillars(date(2024, 2, 4), 10), "甲辰 丙寅 戊戌 壬戌");
+        assert_eq!(pillars(date(2024, 2, 3), 11), "癸卯 乙丑 丁酉 辛亥");
+        // 小寒 moves the month but not the year
+        assert_eq!(pillars(date(2000, 1, 5), 6), "己卯 丙子 壬戌 丙午");
+        assert_eq!(pillars(date(2000, 1, 7), 6), "己卯 丁丑 甲子 庚午");
+    }
+
+    #[test]
+    fn early_zi_hour_keeps_the_dates_own_day() {
+        assert_eq!(pillars(date(2000, 1, 1), 0), "己卯 丙子 戊午 壬子");
+        assert_eq!(pillars(date(2000, 1, 2), 0), "己卯 丙子 己未 甲子");
+        // Branches wrap, so 12 is 子 again
+        assert_eq!(
+            four_pillars(date(2000, 1, 1), 12),
+            four_pillars(date(2000, 1, 1), 0)
+        );
+        assert_eq!(four_pillars(date(1899, 6, 1), 6), None);
+    }
+}
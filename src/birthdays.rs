@@ -0,0 +1,146 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+
+use crate::lunar;
+
+/// A yearly anniversary on a lunar date, declared in the config file
+#[derive(Clone, Debug, Deserialize)]
+pub struct LunarBirthday {
+    pub name: String,
+    pub month: u8,
+    pub day: u8,
+    /// Also celebrate in a leap month of the same number
+    #[serde(default)]
+    pub include_leap: bool,
+}
+
+impl LunarBirthday {
+    /// Whether the month and day lie within the lunar calendar
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && (1..=12).contains(&self.month)
+            && (1..=30).contains(&self.day)
+    }
+
+    /// Gregorian dates of the anniversary in one lunar year, in date order
+    /// Years whose month has no day 30 yield nothing for that month
+    fn occurrences(&self, lunar_year: i32) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        dates.extend(lunar::lunar_to_solar(
+            lunar_year, self.month, self.day, false,
+        ));
+        if self.include_leap {
+            dates.extend(lunar::lunar_to_solar(
+                lunar_year, self.month, self.day, true,
+            ));
+        }
+        dates
+    }
+}
+
+/// Names of the anniversaries falling on the date
+pub fn birthdays_on(birthdays: &[LunarBirthday], date: NaiveDate) -> Vec<String> {
+    let Some(info) = lunar::solar_to_lunar(date) else {
+        return Vec::new();
+    };
+    birthdays
+        .iter()
+        .filter(|birthday| {
+            birthday.month == info.date.month
+                && birthday.day == info.date.day
+                && (!info.date.is_leap || birthday.include_leap)
+        })
+        .map(|birthday| birthday.name.clone())
+        .collect()
+}
+
+/// The next occurrence of each anniversary on or after the date, nearest first
+/// A day 30 waits for the next year whose month is long, which can be several years away
+pub fn upcoming_birthdays(
+    birthdays: &[LunarBirthday],
+    from: NaiveDate,
+    count: usize,
+) -> Vec<(NaiveDate, String)> {
+    // The lunar year starting in the previous solar year still covers early dates
+    let mut upcoming: Vec<(NaiveDate, String)> = birthdays
+        .iter()
+        .filter_map(|birthday| {
+            (from.year() - 1..=lunar::max_supported_year())
+                .flat_map(|year| birthday.occurrences(year))
+                .find(|date| *date >= from)
+                .map(|date| (date, birthday.name.clone()))
+        })
+        .collect();
+    upcoming.sort_by_key(|(date, _)| *date);
+    upcoming.truncate(count);
+    upcoming
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn birthday(name: &str, month: u8, day: u8, include_leap: bool) -> LunarBirthday {
+        LunarBirthday {
+            name: name.to_string(),
+            month,
+            day,
+            include_leap,
+        }
+    }
+
+    #[test]
+    fn validity_covers_name_month_and_day() {
+        assert!(birthday("奶奶", 12, 30, false).is_valid());
+        assert!(birthday("奶奶", 1, 1, true).is_valid());
+        assert!(!birthday("  ", 1, 1, false).is_valid());
+        assert!(!birthday("奶奶", 0, 1, false).is_valid());
+        assert!(!birthday("奶奶", 13, 1, false).is_valid());
+        assert!(!birthday("奶奶", 1, 0, false).is_valid());
+        assert!(!birthday("奶奶", 1, 31, false).is_valid());
+    }
+
+    #[test]
+    fn upcoming_dates_follow_leap_months_and_long_months() {
+        // 2025 repeats its sixth month: 六月初十 is July 4th, 闰六月初十 August 3rd
+        let regular = birthday("爷爷", 6, 10, false);
+        let leap = birthday("外婆", 6, 10, true);
+        let list = [regular.clone(), leap.clone()];
+        assert_eq!(
+            upcoming_birthdays(&list, date(2025, 7, 4), 5),
+            [
+                (date(2025, 7, 4), "爷爷".to_string()),
+                (date(2025, 7, 4), "外婆".to_string())
+            ]
+        );
+        let after = upcoming_birthdays(&list, date(2025, 7, 5), 5);
+        assert_eq!(after[0], (date(2025, 8, 3), "外婆".to_string()));
+        assert_eq!(
+            after[1].0,
+            lunar::lunar_to_solar(2026, 6, 10, false).unwrap()
+        );
+        // Only the leap birthday shows in the leap month
+        assert_eq!(birthdays_on(&list, date(2025, 8, 3)), ["外婆"]);
+        assert_eq!(birthdays_on(&list, date(2025, 7, 4)), ["爷爷", "外婆"]);
+
+        // 腊月 is short from 2024 to 2028, so 腊月三十 next comes in lunar 2029
+        let eve = [birthday("除夕生", 12, 30, false)];
+        let next = lunar::lunar_to_solar(2029, 12, 30, false).unwrap();
+        assert_eq!(next.year(), 2030);
+        assert_eq!(
+            upcoming_birthdays(&eve, date(2025, 6, 1), 3),
+            [(next, "除夕生".to_string())]
+        );
+        // Early in the year the lunar year that began the solar year before still counts
+        let laba = [birthday("腊八", 12, 8, false)];
+        assert_eq!(
+            upcoming_birthdays(&laba, date(2025, 1, 1), 1)[0].0,
+            date(2025, 1, 7)
+        );
+        assert!(upcoming_birthdays(&laba, date(2025, 1, 1), 0).is_empty());
+    }
+}
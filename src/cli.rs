@@ -0,0 +1,1669 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, BufWriter, IsTerminal, Write},
+    iter,
+    path::PathBuf,
+};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Serialize;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    app::{self, App, HolidayCategory, HolidayInfo, LabelSource},
+    config::{self, ConfigKind},
+    events, export,
+    i18n::{self, Locale},
+    lunar::{self, LunarInfo},
+    schedule::{DayArrangement, HolidaySchedule},
+    ui,
+};
+
+/// Narrowest and widest day column of `--print`; longer labels are cut
+const PRINT_MIN_COLUMN: usize = 4;
+const PRINT_MAX_COLUMN: usize = 12;
+
+const USAGE: &str = "用法：moli [--date YYYY-MM-DD] [--config <文件>] [--no-alt-screen] [--inline[=行数]] [--pick [--format <格式>]] | moli --print [YYYY[-MM]] [--color <auto|always|never>] [--json | --markdown] | moli convert [--lunar] <日期 | --stdin [--strict]> [--json] | moli term [年份 | --next] [--json] | moli holidays <年份> [--json] | moli export-ics <年份> [--out <文件>] | moli --init-config [--force] | moli --check-config [文件] | moli completions <bash|zsh|fish>\n运行 moli --help 查看说明";
+
+const HELP: &str = "moli — 终端农历日历
+
+用法：
+  moli [选项]                       启动交互式日历
+  moli --print [YYYY[-MM]] [--color <auto|always|never>]
+                                    输出本月、指定月份或全年的月历后退出；
+                                    --color 默认 auto，--no-color 同 never
+  moli --print [YYYY[-MM]] --markdown
+                                    以 Markdown 表格输出月历
+  moli convert <YYYY-MM-DD>         查询公历日期对应的农历、干支、节气与节日
+  moli convert --lunar <YYYY-M-D>   由农历日期查询公历，闰月写作 闰6 或 L6
+  moli convert --stdin [--strict]   从标准输入逐行读取日期批量转换，输出制表符分隔的行
+  moli term [年份]                  列出一年的二十四节气，默认为今年
+  moli term --next                  显示下一个节气及倒计时
+  moli holidays <年份>              列出当年全部节假日
+  moli export-ics <年份> [--out <文件>]
+                                    导出当年节假日为 ICS 日历
+  moli --init-config [--force]      生成带注释的默认配置文件
+  moli --check-config [文件]        检查配置文件
+  moli completions <bash|zsh|fish>  输出 shell 补全脚本
+
+选项：
+  --date <YYYY-MM-DD>   以指定日期作为“今天”启动
+  --config <文件>       使用指定的键位配置文件，同环境变量 MOLI_KEY_CONFIG
+  --no-alt-screen       不切换到备用屏幕，便于调试
+  --inline[=行数]       在提示符下方占用指定行数绘制（默认 24 行），
+                        退出后月历留在终端的滚动记录中
+  --pick                作为日期选择器运行：界面画在标准错误上，按 Enter 后把选中的日期
+                        输出到标准输出；按 Esc 或 q 取消时不输出并返回退出码 1
+  --format <格式>       --pick 输出日期的 strftime 格式，默认为配置中的 date_format
+  --json                --print、convert、term、holidays 改为输出一个 JSON 文档
+  -h, --help            显示本说明
+  -V, --version         显示版本号";
+
+/// What the process was asked to do
+pub enum Command {
+    /// Start the interactive calendar
+    Tui(TuiOptions),
+    /// Print the usage text and exit
+    Help,
+    /// Print the version and exit
+    Version,
+    /// Print month grids like cal(1) and exit: this month, the given month, or a whole year
+    Print {
+        year: Option<i32>,
+        month: Option<u32>,
+        color: ColorChoice,
+        json: bool,
+        markdown: bool,
+    },
+    /// Print what a Gregorian date is, or which Gregorian date a lunar one falls on, and exit
+    /// With no input, dates are read from stdin one per line
+    Convert {
+        input: Option<String>,
+        lunar: bool,
+        json: bool,
+        strict: bool,
+    },
+    /// Print the solar terms of a year, or only the next one from today, and exit
+    Term {
+        year: Option<i32>,
+        next: bool,
+        json: bool,
+    },
+    /// Print every holiday of a year and exit
+    Holidays { year: i32, json: bool },
+    /// Write a year's holidays as an ICS calendar, to a file or stdout
+    ExportIcs { year: i32, out: Option<PathBuf> },
+    /// Write commented default config files, replacing existing ones only with `force`
+    InitConfig { force: bool },
+    /// Validate the config files moli would load, or the given file, without starting the TUI
+    CheckConfig { path: Option<PathBuf> },
+    /// Print the completion script for a shell
+    Completions { shell: Shell },
+}
+
+/// When `--print` styles its output with ANSI escapes
+#[derive(Clone, Copy, Default)]
+pub enum ColorChoice {
+    /// Only on a terminal, and not when `NO_COLOR` is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    fn enabled(self) -> bool {
+        self.enabled_for(
+            env::var_os("NO_COLOR").is_some(),
+            io::stdout().is_terminal(),
+        )
+    }
+
+    fn enabled_for(self, no_color: bool, terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => !no_color && terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Shells that moli ships completion scripts for
+#[derive(Clone, Copy)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+
+    /// The script, as installed from the `completions` directory of the source tree
+    fn script(self) -> &'static str {
+        match self {
+            Shell::Bash => include_str!("../completions/moli.bash"),
+            Shell::Zsh => include_str!("../completions/_moli"),
+            Shell::Fish => include_str!("../completions/moli.fish"),
+        }
+    }
+}
+
+/// Options of the interactive calendar
+pub struct TuiOptions {
+    /// Pretend today is this date
+    pub date: Option<NaiveDate>,
+    /// Key binding file to use instead of the one found in the config directory
+    pub key_config: Option<PathBuf>,
+    /// Draw on the normal screen instead of the alternate one
+    pub alt_screen: bool,
+    /// Run as a date picker, printing the confirmed date
+    pub pick: bool,
+    /// strftime format of the picked date; the config's `date_format` when unset
+    pub pick_format: Option<String>,
+    /// Draw in this many rows under the prompt instead of taking the whole screen
+    pub inline: Option<u16>,
+}
+
+/// Parse command-line arguments (without the program name)
+/// `--help` and `--version` win wherever they appear, so a broken command line can still ask for help
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let args: Vec<String> = args.into_iter().collect();
+    for arg in &args {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(Command::Help),
+            "--version" | "-V" => return Ok(Command::Version),
+            _ => {}
+        }
+    }
+    let mut args = args.into_iter();
+    // Subcommands come first; anything else is an option of the calendar
+    let subcommand = match args.as_slice().first().map(String::as_str) {
+        Some(
+            "--print" | "convert" | "term" | "holidays" | "export-ics" | "--init-config"
+            | "--check-config" | "completions",
+        ) => args.next().unwrap_or_default(),
+        _ => return parse_tui_options(args),
+    };
+    match subcommand.as_str() {
+        "--print" => {
+            let (mut year, mut month, mut json, mut markdown) = (None, None, false, false);
+            let mut color = ColorChoice::default();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--no-color" => color = ColorChoice::Never,
+                    "--color" => {
+                        let raw = args
+                            .next()
+                            .ok_or_else(|| format!("--color 缺少取值\n{USAGE}"))?;
+                        color = parse_color(&raw)?;
+                    }
+                    _ if arg.starts_with("--color=") => {
+                        color = parse_color(&arg["--color=".len()..])?
+                    }
+                    "--json" => json = true,
+                    "--markdown" | "--md" => markdown = true,
+                    _ if year.is_none() && !arg.starts_with('-') => {
+                        let (y, m) = parse_period(&arg)?;
+                        year = Some(y);
+                        month = m;
+                    }
+                    _ => return Err(format!("多余的参数 '{arg}'\n{USAGE}")),
+                }
+            }
+            if json && markdown {
+                return Err(format!("--json 不能与 --markdown 同时使用\n{USAGE}"));
+            }
+            Ok(Command::Print {
+                year,
+                month,
+                color,
+                json,
+                markdown,
+            })
+        }
+        "convert" => {
+            let (mut lunar, mut json, mut stdin, mut strict) = (false, false, false, false);
+            let mut input = None;
+            for arg in args {
+                match arg.as_str() {
+                    "--lunar" | "-l" => lunar = true,
+                    "--json" => json = true,
+                    "--stdin" => stdin = true,
+                    "--strict" => strict = true,
+                    _ if input.is_none() && !stdin => input = Some(arg),
+                    _ => return Err(format!("多余的参数 '{arg}'\n{USAGE}")),
+                }
+            }
+            if stdin && input.is_some() {
+                return Err(format!("--stdin 不能与日期参数同时使用\n{USAGE}"));
+            }
+            // A piped stdin stands in for the date, as `--stdin` asks explicitly
+            if input.is_none() && !stdin && io::stdin().is_terminal() {
+                return Err(format!("缺少日期参数\n{USAGE}"));
+            }
+            Ok(Command::Convert {
+                input,
+                lunar,
+                json,
+                strict,
+            })
+        }
+        "term" => {
+            let (mut year, mut next, mut json) = (None, false, false);
+            for arg in args {
+                match arg.as_str() {
+                    "--json" => json = true,
+                    "--next" | "-n" if year.is_none() => next = true,
+                    _ if year.is_none() && !next && !arg.starts_with('-') => {
+                        year = Some(
+                            arg.parse()
+                                .map_err(|_| format!("无法识别的年份 '{arg}'\n{USAGE}"))?,
+                        );
+                    }
+                    _ => return Err(format!("多余的参数 '{arg}'\n{USAGE}")),
+                }
+            }
+            Ok(Command::Term { year, next, json })
+        }
+        "holidays" => {
+            let (mut year, mut json) = (None, false);
+            for arg in args {
+                match arg.as_str() {
+                    "--json" => json = true,
+                    _ if year.is_none() => {
+                        year = Some(
+                            arg.parse()
+                                .map_err(|_| format!("无法识别的年份 '{arg}'\n{USAGE}"))?,
+                        );
+                    }
+                    _ => return Err(format!("多余的参数 '{arg}'\n{USAGE}")),
+                }
+            }
+            let year = year.ok_or_else(|| format!("缺少年份参数\n{USAGE}"))?;
+            Ok(Command::Holidays { year, json })
+        }
+        "export-ics" => {
+            let raw = args
+                .next()
+                .ok_or_else(|| format!("缺少年份参数\n{USAGE}"))?;
+            let year = raw
+                .parse()
+                .map_err(|_| format!("无法识别的年份 '{raw}'\n{USAGE}"))?;
+            let mut out = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--out" | "-o" => {
+                        let path = args
+                            .next()
+                            .ok_or_else(|| format!("缺少输出文件参数\n{USAGE}"))?;
+                        out = Some(PathBuf::from(path));
+                    }
+                    _ => return Err(format!("多余的参数 '{arg}'\n{USAGE}")),
+                }
+            }
+            Ok(Command::ExportIcs { year, out })
+        }
+        "--init-config" => {
+            let mut force = false;
+            for arg in args {
+                match arg.as_str() {
+                    "--force" | "-f" => force = true,
+                    _ => return Err(format!("多余的参数 '{arg}'\n{USAGE}")),
+                }
+            }
+            Ok(Command::InitConfig { force })
+        }
+        "--check-config" => {
+            let path = args.next().map(PathBuf::from);
+            if let Some(extra) = args.next() {
+                return Err(format!("多余的参数 '{extra}'\n{USAGE}"));
+            }
+            Ok(Command::CheckConfig { path })
+        }
+        "completions" => {
+            let raw = args
+                .next()
+                .ok_or_else(|| format!("缺少 shell 参数\n{USAGE}"))?;
+            let shell = Shell::parse(&raw)
+                .ok_or_else(|| format!("不支持的 shell '{raw}'，可选 bash、zsh、fish\n{USAGE}"))?;
+            if let Some(extra) = args.next() {
+                return Err(format!("多余的参数 '{extra}'\n{USAGE}"));
+            }
+            Ok(Command::Completions { shell })
+        }
+        other => Err(format!("未知参数 '{other}'\n{USAGE}")),
+    }
+}
+
+/// Parse the calendar's options, which may come in any order
+fn parse_tui_options(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut options = TuiOptions {
+        date: None,
+        key_config: None,
+        alt_screen: true,
+        pick: false,
+        pick_format: None,
+        inline: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--date" => {
+                let raw = args
+                    .next()
+                    .ok_or_else(|| format!("缺少日期参数\n{USAGE}"))?;
+                options.date = Some(parse_date(&raw)?);
+            }
+            "--config" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("缺少配置文件参数\n{USAGE}"))?;
+                options.key_config = Some(PathBuf::from(path));
+            }
+            "--no-alt-screen" => options.alt_screen = false,
+            "--pick" => options.pick = true,
+            "--inline" => options.inline = Some(DEFAULT_INLINE_HEIGHT),
+            _ if arg.starts_with("--inline=") => {
+                options.inline = Some(parse_inline_height(&arg["--inline=".len()..])?);
+            }
+            "--format" => {
+                let raw = args
+                    .next()
+                    .ok_or_else(|| format!("缺少格式参数\n{USAGE}"))?;
+                options.pick_format = Some(parse_date_format(raw)?);
+            }
+            _ if arg.starts_with('-') => return Err(format!("未知选项 '{arg}'\n{USAGE}")),
+            _ => return Err(format!("未知参数 '{arg}'\n{USAGE}")),
+        }
+    }
+    // The inline viewport finds its place by asking the terminal for the cursor position on
+    // stdout, which the picker keeps for the date
+    if options.pick && options.inline.is_some() {
+        return Err(format!("--inline 不能与 --pick 同时使用\n{USAGE}"));
+    }
+    if !options.pick && options.pick_format.is_some() {
+        return Err(format!("--format 只能与 --pick 一起使用\n{USAGE}"));
+    }
+    Ok(Command::Tui(options))
+}
+
+/// Rows of `--inline` without a count: the compact header and month grid
+const DEFAULT_INLINE_HEIGHT: u16 = 24;
+
+/// Parse the row count of `--inline=N`, which must leave room for the calendar
+fn parse_inline_height(raw: &str) -> Result<u16, String> {
+    match raw.parse() {
+        Ok(height) if height >= ui::MIN_TERMINAL_HEIGHT => Ok(height),
+        _ => Err(format!(
+            "无法识别的行数 '{raw}'，至少为 {}\n{USAGE}",
+            ui::MIN_TERMINAL_HEIGHT
+        )),
+    }
+}
+
+/// Check a strftime format up front, since formatting a date with a broken one panics
+fn parse_date_format(raw: String) -> Result<String, String> {
+    if !config::is_date_format(&raw) {
+        return Err(format!("无法识别的日期格式 '{raw}'\n{USAGE}"));
+    }
+    Ok(raw)
+}
+
+/// Print the full usage text, returning the process exit code
+pub fn print_help() -> i32 {
+    write_stdout(&format!("{HELP}\n"))
+}
+
+/// Print the program version, returning the process exit code
+pub fn print_version() -> i32 {
+    write_stdout(&format!("moli {}\n", env!("CARGO_PKG_VERSION")))
+}
+
+/// Print a shell completion script, returning the process exit code
+pub fn print_completions(shell: Shell) -> i32 {
+    write_stdout(shell.script())
+}
+
+/// Parse `YYYY` or `YYYY-MM`
+fn parse_period(raw: &str) -> Result<(i32, Option<u32>), String> {
+    let invalid = || format!("无法识别的年月 '{raw}'\n{USAGE}");
+    let (year, month) = match raw.split_once('-') {
+        Some((year, month)) => (year, Some(month)),
+        None => (raw, None),
+    };
+    let year = year.parse().map_err(|_| invalid())?;
+    let month = match month {
+        Some(month) => Some(
+            month
+                .parse()
+                .ok()
+                .filter(|month| (1..=12).contains(month))
+                .ok_or_else(invalid)?,
+        ),
+        None => None,
+    };
+    Ok((year, month))
+}
+
+fn parse_color(raw: &str) -> Result<ColorChoice, String> {
+    ColorChoice::parse(raw).ok_or_else(|| {
+        format!("无法识别的 --color 取值 '{raw}'（可选 auto、always、never）\n{USAGE}")
+    })
+}
+
+/// Parse a `YYYY-MM-DD` date within the supported range
+fn parse_date(raw: &str) -> Result<NaiveDate, String> {
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map_err(|_| format!("无法识别的日期 '{raw}'\n{USAGE}"))?;
+    if !(lunar::MIN_YEAR..=lunar::max_supported_year()).contains(&date.year()) {
+        return Err(format!(
+            "日期 {raw} 超出支持范围（{}–{}）",
+            lunar::MIN_YEAR,
+            lunar::max_supported_year()
+        ));
+    }
+    Ok(date)
+}
+
+/// Print month grids to stdout, returning the process exit code
+/// With `--color auto`, ANSI styling is left out for `NO_COLOR` and output that is not a terminal
+pub fn print_months(
+    app: &App,
+    year: i32,
+    month: Option<u32>,
+    color: ColorChoice,
+    json: bool,
+    markdown: bool,
+) -> i32 {
+    if !check_year(year) {
+        return 1;
+    }
+    let months: Vec<u32> = match month {
+        Some(month) => vec![month],
+        None => (1..=12).collect(),
+    };
+    if markdown {
+        let tables: Vec<String> = months
+            .into_iter()
+            .map(|month| export::month_markdown(app, year, month))
+            .collect();
+        return write_stdout(&tables.join("\n"));
+    }
+    if json {
+        let records: Vec<MonthRecord> = months
+            .into_iter()
+            .map(|month| MonthRecord::new(app, year, month))
+            .collect();
+        return print_json(&records);
+    }
+    let color = color.enabled();
+    let grids: Vec<String> = months
+        .into_iter()
+        .map(|month| format_month(app, year, month, color))
+        .collect();
+    write_stdout(&grids.join("\n"))
+}
+
+/// Render one month as text: title, weekday header, then per week a line of day numbers
+/// and a line of the labels the TUI shows under them; today is bracketed
+/// Days of the neighbouring months are left blank, as cal(1) does
+/// With `color`, days and labels take the theme's colors: today reversed, statutory holidays
+/// and weekends in their colors, labels as in the grid
+fn format_month(app: &App, year: i32, month: u32, color: bool) -> String {
+    let locale = app.locale();
+    let theme = app.theme();
+    let label_priority = app.label_priority();
+    let rows = app.month_rows_for(year, month);
+    let labels: Vec<Vec<Option<(String, LabelSource)>>> = rows
+        .iter()
+        .map(|week| {
+            week.iter()
+                .map(|cell| {
+                    cell.label(label_priority, locale)
+                        .filter(|_| cell.is_current_month)
+                })
+                .collect()
+        })
+        .collect();
+    // Columns widen to the longest label, within reason
+    let column = labels
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|(label, _)| label.width())
+        .max()
+        .unwrap_or(0)
+        .clamp(PRINT_MIN_COLUMN, PRINT_MAX_COLUMN);
+    let total = column * 7 + 6;
+    let mut out = String::new();
+    let title = center(&app.month_title(year, month), total, None, color);
+    push_line(&mut out, &[title]);
+    let header: Vec<String> =
+        iter::successors(Some(app.week_start().weekday()), |day| Some(day.succ()))
+            .take(7)
+            .map(|weekday| {
+                let style = app
+                    .weekend()
+                    .contains(weekday)
+                    .then(|| Style::default().fg(theme.weekend_color(weekday)));
+                center(i18n::weekday_short(weekday, locale), column, style, color)
+            })
+            .collect();
+    push_line(&mut out, &header);
+    for (week, labels) in rows.iter().zip(&labels) {
+        let days: Vec<String> = week
+            .iter()
+            .map(|cell| {
+                if !cell.is_current_month {
+                    return center("", column, None, color);
+                }
+                let style = if cell.is_today {
+                    Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+                } else if let Some(info) = cell
+                    .holidays
+                    .iter()
+                    .find(|info| matches!(info.category, HolidayCategory::Statutory))
+                {
+                    Style::default()
+                        .fg(theme.holiday(info.category))
+                        .add_modifier(Modifier::BOLD)
+                } else if cell.is_weekend {
+                    Style::default().fg(theme.weekend_color(cell.date.weekday()))
+                } else {
+                    Style::default()
+                };
+                let day = if cell.is_today {
+                    format!("[{:02}]", cell.date.day())
+                } else {
+                    format!("{:02}", cell.date.day())
+                };
+                center(&day, column, Some(style), color)
+            })
+            .collect();
+        push_line(&mut out, &days);
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|label| match label {
+                Some((text, source)) => center(
+                    &cut_to_width(text, column),
+                    column,
+                    Some(source.style(theme)),
+                    color,
+                ),
+                None => center("", column, None, color),
+            })
+            .collect();
+        push_line(&mut out, &labels);
+    }
+    out
+}
+
+/// Join padded columns with a space, dropping the trailing blanks
+fn push_line(out: &mut String, columns: &[String]) {
+    out.push_str(columns.join(" ").trim_end());
+    out.push('\n');
+}
+
+/// Center text in the given display width, styling only the text itself so the padding
+/// keeps the columns aligned
+fn center(text: &str, width: usize, style: Option<Style>, color: bool) -> String {
+    let padding = width.saturating_sub(text.width());
+    let left = " ".repeat(padding / 2);
+    let right = " ".repeat(padding - padding / 2);
+    match style.map(sgr) {
+        Some(codes) if color && !codes.is_empty() && !text.is_empty() => {
+            format!("{left}\x1b[{codes}m{text}\x1b[0m{right}")
+        }
+        _ => format!("{left}{text}{right}"),
+    }
+}
+
+/// SGR parameters for a theme style, e.g. "1;31"; empty when it changes nothing
+fn sgr(style: Style) -> String {
+    let mut codes: Vec<String> = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+    .map(|(_, code)| code.to_string())
+    .collect();
+    codes.extend(style.fg.and_then(|color| color_code(color, false)));
+    codes.extend(style.bg.and_then(|color| color_code(color, true)));
+    codes.join(";")
+}
+
+/// SGR parameter of a foreground or background color; None for the terminal default
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let (base, bright) = if background { (40, 100) } else { (30, 90) };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => bright,
+        Color::LightRed => bright + 1,
+        Color::LightGreen => bright + 2,
+        Color::LightYellow => bright + 3,
+        Color::LightBlue => bright + 4,
+        Color::LightMagenta => bright + 5,
+        Color::LightCyan => bright + 6,
+        Color::White => bright + 7,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{r};{g};{b}", base + 8)),
+        Color::Indexed(index) => return Some(format!("{};5;{index}", base + 8)),
+    };
+    Some(code.to_string())
+}
+
+/// Cut text to the given display width without splitting a wide character
+fn cut_to_width(text: &str, width: usize) -> String {
+    let mut used = 0;
+    text.chars()
+        .take_while(|ch| {
+            used += ch.width().unwrap_or(0);
+            used <= width
+        })
+        .collect()
+}
+
+/// Print the breakdown of a Gregorian date, or of the date a lunar `Y-M-D` falls on,
+/// returning the process exit code
+pub fn convert(input: &str, lunar: bool, json: bool, schedule: &HolidaySchedule) -> i32 {
+    let date = if lunar {
+        parse_lunar_date(input)
+    } else {
+        parse_date(input)
+    };
+    match date {
+        Ok(date) if json => print_json(&DayRecord::new(date, schedule.arrangement_for(date))),
+        Ok(date) => write_stdout(&format_conversion(date)),
+        Err(message) => {
+            eprintln!("moli: {message}");
+            1
+        }
+    }
+}
+
+/// Convert every line of stdin, writing one output line per input line in the same order
+/// Blank lines stay blank; a line that does not convert becomes an error line, or with
+/// `strict` stops the run. Returns the process exit code, 1 when any line failed
+pub fn convert_stream(lunar: bool, json: bool, strict: bool, schedule: &HolidaySchedule) -> i32 {
+    let mut out = BufWriter::new(io::stdout().lock());
+    convert_lines(io::stdin().lock(), &mut out, lunar, json, strict, schedule)
+}
+
+/// `convert_stream` over any input and output
+fn convert_lines(
+    mut reader: impl BufRead,
+    out: &mut impl Write,
+    lunar: bool,
+    json: bool,
+    strict: bool,
+    schedule: &HolidaySchedule,
+) -> i32 {
+    let mut line = String::new();
+    let mut number = 0;
+    let mut failed = false;
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("moli: 无法读取标准输入：{err}");
+                return 1;
+            }
+        }
+        number += 1;
+        let input = line.trim();
+        let written = if input.is_empty() {
+            writeln!(out)
+        } else {
+            let date = if lunar {
+                parse_lunar_date(input)
+            } else {
+                parse_date(input)
+            };
+            match date {
+                Ok(date) if json => {
+                    let record = DayRecord::new(date, schedule.arrangement_for(date));
+                    serde_json::to_writer(&mut *out, &record)
+                        .map_err(io::Error::from)
+                        .and_then(|()| writeln!(out))
+                }
+                Ok(date) => write_conversion_fields(out, date),
+                Err(message) => {
+                    // Only the first line; the usage text is noise in a batch
+                    let message = message.lines().next().unwrap_or_default();
+                    if strict {
+                        let _ = out.flush();
+                        eprintln!("moli: 第 {number} 行：{message}");
+                        return 1;
+                    }
+                    failed = true;
+                    if json {
+                        let error = serde_json::json!({ "line": number, "error": message });
+                        writeln!(out, "{error}")
+                    } else {
+                        writeln!(out, "ERROR\t{message}")
+                    }
+                }
+            }
+        };
+        // A closed pipe such as `| head` simply ends the run
+        if written.is_err() {
+            return i32::from(failed);
+        }
+    }
+    if out.flush().is_err() {
+        return 1;
+    }
+    i32::from(failed)
+}
+
+/// One tab-separated line of `convert --stdin`: date, weekday, lunar date, 干支, 生肖,
+/// solar term and holidays, with "-" for anything missing
+fn write_conversion_fields(out: &mut impl Write, date: NaiveDate) -> io::Result<()> {
+    let locale = Locale::Zh;
+    write!(
+        out,
+        "{}\t{}\t",
+        date.format("%Y-%m-%d"),
+        i18n::weekday_name(date.weekday(), locale)
+    )?;
+    let lunar = lunar::solar_to_lunar(date);
+    let solar_term = app::solar_term_name(date);
+    match lunar {
+        Some(info) => write!(
+            out,
+            "{}\t{}\t{}\t",
+            i18n::lunar_date(&info, locale),
+            lunar::gan_zhi_year(info.date.year),
+            lunar::zodiac_animal(info.date.year)
+        )?,
+        None => write!(out, "-\t-\t-\t")?,
+    }
+    write!(out, "{}\t", solar_term.unwrap_or("-"))?;
+    let holidays = app::holidays_for(date, lunar.as_ref(), solar_term);
+    if holidays.is_empty() {
+        write!(out, "-")?;
+    }
+    for (index, info) in holidays.iter().enumerate() {
+        if index > 0 {
+            write!(out, "、")?;
+        }
+        write!(out, "{}", info.name)?;
+    }
+    writeln!(out)
+}
+
+/// Parse a lunar `YYYY-M-D`, with the leap month written `闰6` or `L6`
+fn parse_lunar_date(raw: &str) -> Result<NaiveDate, String> {
+    let invalid = || format!("无法识别的农历日期 '{raw}'（格式如 2025-8-15 或 2025-闰6-1）");
+    let mut parts = raw.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let (month, is_leap) = match month
+        .strip_prefix('闰')
+        .or_else(|| month.strip_prefix(['L', 'l']))
+    {
+        Some(month) => (month, true),
+        None => (month, false),
+    };
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let month: u8 = month.parse().map_err(|_| invalid())?;
+    let day: u8 = day.parse().map_err(|_| invalid())?;
+    if !(lunar::MIN_YEAR..=lunar::max_supported_year()).contains(&year) {
+        return Err(format!(
+            "农历年份 {year} 超出支持范围（{}–{}）",
+            lunar::MIN_YEAR,
+            lunar::max_supported_year()
+        ));
+    }
+    lunar::lunar_to_solar(year, month, day, is_leap).ok_or_else(|| format!("农历 {raw} 不存在"))
+}
+
+/// Render a date the way the details panel describes it, one `label：value` line each
+fn format_conversion(date: NaiveDate) -> String {
+    let locale = Locale::Zh;
+    let mut out = format!(
+        "公历：{} {}\n",
+        date.format("%Y-%m-%d"),
+        i18n::weekday_name(date.weekday(), locale)
+    );
+    match lunar::solar_to_lunar(date) {
+        Some(info) => {
+            out.push_str(&format!(
+                "农历：{}年 {}\n生肖：{}\n",
+                lunar::gan_zhi_year(info.date.year),
+                i18n::lunar_date(&info, locale),
+                lunar::zodiac_animal(info.date.year)
+            ));
+        }
+        None => out.push_str("农历：-\n生肖：-\n"),
+    }
+    out.push_str(&format!("日柱：{}\n", lunar::gan_zhi_day(date)));
+    out.push_str(&format!(
+        "节气：{}\n",
+        app::solar_term_name(date).unwrap_or("-")
+    ));
+    let holidays: Vec<&str> = app::holidays_on(date)
+        .into_iter()
+        .map(|info| info.name)
+        .collect();
+    if holidays.is_empty() {
+        out.push_str("节日：-\n");
+    } else {
+        out.push_str(&format!("节日：{}\n", holidays.join("、")));
+    }
+    out
+}
+
+/// Print a year's solar terms, one per line, returning the process exit code
+pub fn print_solar_terms(year: i32, json: bool) -> i32 {
+    let terms = app::solar_terms_for_year(year);
+    if terms.is_empty() {
+        eprintln!(
+            "moli: 年份 {year} 超出节气支持范围（{}–{}）",
+            app::SOLAR_TERM_MIN_YEAR,
+            app::SOLAR_TERM_MAX_YEAR
+        );
+        return 1;
+    }
+    if json {
+        let records: Vec<TermRecord> = terms
+            .into_iter()
+            .map(|(date, name)| TermRecord::new(date, name, None))
+            .collect();
+        return print_json(&records);
+    }
+    let lines: String = terms
+        .into_iter()
+        .map(|(date, name)| format!("{}\n", format_term(date, name)))
+        .collect();
+    write_stdout(&lines)
+}
+
+/// Print the first solar term on or after `today` with a countdown, returning the process exit code
+pub fn print_next_solar_term(today: NaiveDate, json: bool) -> i32 {
+    match next_solar_term(today) {
+        Some((date, name)) if json => {
+            let days = (date - today).num_days();
+            print_json(&TermRecord::new(date, name, Some(days)))
+        }
+        Some((date, name)) => write_stdout(&format!(
+            "{}  {}\n",
+            format_term(date, name),
+            app::format_day_offset(today, date, Locale::Zh)
+        )),
+        None => {
+            eprintln!(
+                "moli: {} 之后超出节气支持范围（{}–{}）",
+                today.format("%Y-%m-%d"),
+                app::SOLAR_TERM_MIN_YEAR,
+                app::SOLAR_TERM_MAX_YEAR
+            );
+            1
+        }
+    }
+}
+
+/// First solar term on or after the date, looking into the next year after 冬至
+fn next_solar_term(today: NaiveDate) -> Option<(NaiveDate, &'static str)> {
+    [today.year(), today.year() + 1]
+        .into_iter()
+        .flat_map(app::solar_terms_for_year)
+        .find(|(date, _)| *date >= today)
+}
+
+/// One solar term line, e.g. "2025-02-03  周一  立春"
+fn format_term(date: NaiveDate, name: &str) -> String {
+    format!(
+        "{}  {}  {}",
+        date.format("%Y-%m-%d"),
+        i18n::weekday_abbr(date.weekday(), Locale::Zh),
+        name
+    )
+}
+
+/// One date as the `--json` outputs describe it
+#[derive(Serialize)]
+struct DayRecord {
+    date: NaiveDate,
+    weekday: Weekday,
+    /// None outside the lunar table
+    lunar: Option<LunarInfo>,
+    /// Year 干支 and 生肖 of the lunar year
+    gan_zhi: Option<String>,
+    zodiac: Option<char>,
+    gan_zhi_day: String,
+    solar_term: Option<&'static str>,
+    holidays: Vec<HolidayInfo>,
+    arrangement: Option<DayArrangement>,
+}
+
+impl DayRecord {
+    /// Look the date up with the same functions the calendar uses
+    fn new(date: NaiveDate, arrangement: Option<DayArrangement>) -> Self {
+        let lunar = lunar::solar_to_lunar(date);
+        let solar_term = app::solar_term_name(date);
+        DayRecord {
+            date,
+            weekday: date.weekday(),
+            lunar,
+            gan_zhi: lunar.map(|info| lunar::gan_zhi_year(info.date.year)),
+            zodiac: lunar.map(|info| lunar::zodiac_animal(info.date.year)),
+            gan_zhi_day: lunar::gan_zhi_day(date),
+            solar_term,
+            holidays: app::holidays_for(date, lunar.as_ref(), solar_term),
+            arrangement,
+        }
+    }
+}
+
+/// One month of `--print --json`, with a record per day of the month
+#[derive(Serialize)]
+struct MonthRecord {
+    year: i32,
+    month: u32,
+    title: String,
+    days: Vec<DayRecord>,
+}
+
+impl MonthRecord {
+    fn new(app: &App, year: i32, month: u32) -> Self {
+        let days = app
+            .month_rows_for(year, month)
+            .into_iter()
+            .flatten()
+            .filter(|cell| cell.is_current_month)
+            .map(|cell| DayRecord::new(cell.date, cell.arrangement))
+            .collect();
+        MonthRecord {
+            year,
+            month,
+            title: app.month_title(year, month),
+            days,
+        }
+    }
+}
+
+/// One solar term of `term --json`; `days` counts down to it for `--next`
+#[derive(Serialize)]
+struct TermRecord {
+    date: NaiveDate,
+    weekday: Weekday,
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    days: Option<i64>,
+}
+
+impl TermRecord {
+    fn new(date: NaiveDate, name: &'static str, days: Option<i64>) -> Self {
+        TermRecord {
+            date,
+            weekday: date.weekday(),
+            name,
+            days,
+        }
+    }
+}
+
+/// One holiday of `holidays --json`, its note including the year's official arrangement
+#[derive(Serialize)]
+struct HolidayRecord {
+    date: NaiveDate,
+    name: &'static str,
+    category: HolidayCategory,
+    note: String,
+}
+
+/// Write a single JSON document to stdout, returning the process exit code
+fn print_json<T: Serialize>(value: &T) -> i32 {
+    match serde_json::to_string(value) {
+        Ok(json) => write_stdout(&format!("{json}\n")),
+        Err(err) => {
+            eprintln!("moli: 无法生成 JSON：{err}");
+            1
+        }
+    }
+}
+
+/// Write scripting output to stdout in one piece, returning the process exit code
+fn write_stdout(text: &str) -> i32 {
+    write_output(&mut io::stdout().lock(), text)
+}
+
+/// `write_stdout` over any output
+/// A closed pipe such as `| head` simply ends the output, like `convert_lines`
+fn write_output(out: &mut impl Write, text: &str) -> i32 {
+    match out.write_all(text.as_bytes()).and_then(|()| out.flush()) {
+        Ok(()) => 0,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(err) => {
+            eprintln!("moli: 无法写入标准输出：{err}");
+            1
+        }
+    }
+}
+
+/// Print the holidays of a year, one per line, returning the process exit code
+pub fn print_holidays(year: i32, schedule: &HolidaySchedule, json: bool) -> i32 {
+    if !check_year(year) {
+        return 1;
+    }
+    if json {
+        let records: Vec<HolidayRecord> = app::holidays_for_year(year)
+            .into_iter()
+            .map(|(date, info)| HolidayRecord {
+                date,
+                name: info.name,
+                category: info.category,
+                note: schedule.note(&info, year, Locale::Zh).into_owned(),
+            })
+            .collect();
+        return print_json(&records);
+    }
+    write_stdout(&format_holidays(year, schedule))
+}
+
+/// Write the holidays of a year as ICS, returning the process exit code
+pub fn export_ics(year: i32, out: Option<PathBuf>, schedule: &HolidaySchedule) -> i32 {
+    if !check_year(year) {
+        return 1;
+    }
+    let content = events::holidays_ics(year, schedule);
+    let Some(path) = out else {
+        return write_stdout(&content);
+    };
+    match fs::write(&path, content) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("moli: 无法写入 {path:?}：{err}");
+            1
+        }
+    }
+}
+
+/// Write the default key binding and general config files, returning the process exit code
+/// A file is skipped when a RON or TOML file of the same name exists, unless `force` is set
+pub fn init_config(force: bool) -> i32 {
+    let Some(dir) = config::config_dir() else {
+        eprintln!("moli: 无法确定配置目录");
+        return 1;
+    };
+    if let Err(err) = fs::create_dir_all(&dir) {
+        eprintln!("moli: 无法创建配置目录 {dir:?}：{err}");
+        return 1;
+    }
+    let mut status = 0;
+    for (stem, content) in config::DEFAULT_CONFIG_FILES {
+        if !force && let Some(existing) = config::existing_config_files(stem).first() {
+            eprintln!("moli: {existing:?} 已存在，未覆盖（加 --force 强制写入）");
+            status = 1;
+            continue;
+        }
+        let path = dir.join(format!("{stem}.ron"));
+        match fs::write(&path, content) {
+            Ok(()) => status = status.max(write_stdout(&format!("已写入 {}\n", path.display()))),
+            Err(err) => {
+                eprintln!("moli: 无法写入 {path:?}：{err}");
+                status = 1;
+            }
+        }
+    }
+    status
+}
+
+/// Print each checked config file with its problems or effective bindings, returning the process exit code
+pub fn check_config(path: Option<PathBuf>) -> i32 {
+    let mut status = 0;
+    let mut report = String::new();
+    for check in config::check_config(path) {
+        let label = match check.kind {
+            ConfigKind::KeyBindings => "键位配置",
+            ConfigKind::General => "通用设置",
+        };
+        let location = match &check.path {
+            Some(path) => path.display().to_string(),
+            None => "未找到文件，使用默认值".to_string(),
+        };
+        if check.problems.is_empty() {
+            report.push_str(&format!("{label}（{location}）：OK\n"));
+            for (action, labels) in &check.bindings {
+                if labels.is_empty() {
+                    report.push_str(&format!("  {action:?}: （已禁用）\n"));
+                } else {
+                    report.push_str(&format!("  {action:?}: {}\n", labels.join(", ")));
+                }
+            }
+        } else {
+            status = 1;
+            report.push_str(&format!(
+                "{label}（{location}）：{} 个问题\n",
+                check.problems.len()
+            ));
+            for problem in &check.problems {
+                report.push_str(&format!("  - {problem}\n"));
+            }
+        }
+    }
+    status.max(write_stdout(&report))
+}
+
+/// Report years outside the lunar table, returning whether the year is usable
+fn check_year(year: i32) -> bool {
+    let supported = (lunar::MIN_YEAR..=lunar::max_supported_year()).contains(&year);
+    if !supported {
+        eprintln!(
+            "moli: 年份 {year} 超出支持范围（{}–{}）",
+            lunar::MIN_YEAR,
+            lunar::max_supported_year()
+        );
+    }
+    supported
+}
+
+/// Render the holiday listing for a year
+fn format_holidays(year: i32, schedule: &HolidaySchedule) -> String {
+    let holidays = app::holidays_for_year(year);
+    let name_width = holidays
+        .iter()
+        .map(|(_, info)| info.name.width())
+        .max()
+        .unwrap_or(0);
+    let category_width = holidays
+        .iter()
+        .map(|(_, info)| info.category.label(Locale::Zh).width())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for (date, info) in holidays {
+        out.push_str(&format!(
+            "{}  {}  {}  {}\n",
+            date.format("%Y-%m-%d"),
+            pad_to_width(info.name, name_width),
+            pad_to_width(info.category.label(Locale::Zh), category_width),
+            schedule.note(&info, year, Locale::Zh)
+        ));
+    }
+    out
+}
+
+/// Right-pad text with spaces up to the given display width
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn tui_date(raw: &[&str]) -> Result<Option<NaiveDate>, String> {
+        match parse_args(args(raw))? {
+            Command::Tui(options) => Ok(options.date),
+            _ => panic!("{raw:?} is not a calendar command line"),
+        }
+    }
+
+    #[test]
+    fn date_flag_pins_today() {
+        let expected = NaiveDate::from_ymd_opt(2030, 2, 1);
+        assert_eq!(tui_date(&["--date", "2030-02-01"]), Ok(expected));
+        assert_eq!(
+            tui_date(&["--no-alt-screen", "--date", "2030-02-01"]),
+            Ok(expected)
+        );
+        assert_eq!(tui_date(&[]), Ok(None));
+    }
+
+    #[test]
+    fn date_flag_rejects_bad_dates() {
+        let past_max = format!("{}-01-01", lunar::max_supported_year() + 1);
+        for raw in ["2030-02-30", "tomorrow", "1899-12-31", &past_max] {
+            assert!(tui_date(&["--date", raw]).is_err(), "{raw}");
+        }
+        assert!(tui_date(&["--date"]).is_err());
+    }
+
+    #[test]
+    fn holidays_listing_matches_golden_file() {
+        let expected = include_str!("../tests/fixtures/holidays-2024.txt");
+        assert_eq!(format_holidays(2024, &HolidaySchedule::bundled()), expected);
+    }
+
+    #[test]
+    fn leap_months_do_not_repeat_festivals() {
+        let schedule = HolidaySchedule::bundled();
+        // 闰五月 2009, 闰七月 2006 and 闰八月 1995
+        for (year, names) in [
+            (2009, ["端午节"].as_slice()),
+            (2006, &["七夕节", "中元节"]),
+            (1995, &["中秋节"]),
+        ] {
+            let listing = format_holidays(year, &schedule);
+            for name in names {
+                assert_eq!(listing.matches(name).count(), 1, "{year} {name}\n{listing}");
+            }
+        }
+        let ics = events::holidays_ics(2009, &schedule);
+        assert_eq!(ics.matches("SUMMARY:端午节").count(), 1);
+    }
+
+    #[test]
+    fn holidays_outside_the_lunar_table_fail() {
+        let schedule = HolidaySchedule::bundled();
+        assert_eq!(print_holidays(lunar::MIN_YEAR - 1, &schedule, false), 1);
+        assert_eq!(
+            print_holidays(lunar::max_supported_year() + 1, &schedule, false),
+            1
+        );
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn lunar_dates_convert_including_leap_months() {
+        assert_eq!(parse_lunar_date("2025-8-15"), Ok(date(2025, 10, 6)));
+        assert_eq!(parse_lunar_date("2025-6-1"), Ok(date(2025, 6, 25)));
+        assert_eq!(parse_lunar_date("2025-闰6-1"), Ok(date(2025, 7, 25)));
+        assert_eq!(parse_lunar_date("2025-L6-1"), Ok(date(2025, 7, 25)));
+        assert_eq!(parse_lunar_date("2025-l6-1"), Ok(date(2025, 7, 25)));
+        assert_eq!(
+            parse_lunar_date("2024-闰6-1"),
+            Err("农历 2024-闰6-1 不存在".to_string())
+        );
+        assert_eq!(
+            parse_lunar_date("2025-12-30"),
+            Err("农历 2025-12-30 不存在".to_string())
+        );
+        assert_eq!(
+            parse_lunar_date("1899-1-1"),
+            Err(format!(
+                "农历年份 1899 超出支持范围（1900–{}）",
+                lunar::max_supported_year()
+            ))
+        );
+        for raw in ["2025-8", "2025-8-15-1", "2025-八-15", "2025-闰-1"] {
+            let err = parse_lunar_date(raw).unwrap_err();
+            assert!(err.starts_with("无法识别的农历日期"), "{raw}: {err}");
+        }
+        let schedule = HolidaySchedule::bundled();
+        assert_eq!(convert("2025-13-01", false, false, &schedule), 1);
+        assert_eq!(convert("2024-闰6-1", true, false, &schedule), 1);
+    }
+
+    #[test]
+    fn conversion_describes_the_date() {
+        assert_eq!(
+            format_conversion(date(2025, 10, 6)),
+            "公历：2025-10-06 星期一\n农历：乙巳年 八月十五\n生肖：蛇\n日柱：戊申日\n节气：-\n节日：中秋节\n"
+        );
+        let leap = format_conversion(date(2025, 7, 25));
+        assert!(leap.contains("农历：乙巳年 闰六月初一\n"), "{leap}");
+    }
+
+    fn convert_text(input: &str, lunar: bool, json: bool, strict: bool) -> (i32, String) {
+        let mut out = Vec::new();
+        let schedule = HolidaySchedule::bundled();
+        let code = convert_lines(input.as_bytes(), &mut out, lunar, json, strict, &schedule);
+        (code, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn stream_keeps_one_line_per_input_line() {
+        let (code, out) = convert_text("2025-10-06\n\n  \nnope\n2025-10-01", false, false, false);
+        assert_eq!(code, 1);
+        assert_eq!(
+            out,
+            "2025-10-06\t星期一\t八月十五\t乙巳\t蛇\t-\t中秋节\n\n\nERROR\t无法识别的日期 'nope'\n2025-10-01\t星期三\t八月初十\t乙巳\t蛇\t-\t国庆节\n"
+        );
+        let (code, out) = convert_text("2025-8-15\n2025-闰6-1\n", true, false, false);
+        assert_eq!(code, 0);
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.lines().nth(1).unwrap().starts_with("2025-07-25\t"));
+    }
+
+    #[test]
+    fn strict_stream_stops_at_the_first_bad_line() {
+        let (code, out) = convert_text("2025-10-06\nnope\n2025-10-01\n", false, false, true);
+        assert_eq!(code, 1);
+        assert_eq!(out.lines().count(), 1);
+        assert!(out.starts_with("2025-10-06\t"));
+    }
+
+    #[test]
+    fn json_stream_reports_errors_as_records() {
+        let (code, out) = convert_text("2025-10-06\n\n2025-02-30\n", false, true, false);
+        assert_eq!(code, 1);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["date"], "2025-10-06");
+        assert_eq!(lines[1], "");
+        let error: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(
+            error,
+            serde_json::json!({ "line": 3, "error": "无法识别的日期 '2025-02-30'" })
+        );
+    }
+
+    /// A writer whose reader has gone away, or that fails another way
+    struct FailingWriter(io::ErrorKind);
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(self.0.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn closed_pipe_ends_output_quietly() {
+        let text = format_holidays(2025, &HolidaySchedule::bundled());
+        let mut out = Vec::new();
+        assert_eq!(write_output(&mut out, &text), 0);
+        assert_eq!(String::from_utf8(out).unwrap(), text);
+        assert_eq!(
+            write_output(&mut FailingWriter(io::ErrorKind::BrokenPipe), &text),
+            0
+        );
+        assert_eq!(
+            write_output(&mut FailingWriter(io::ErrorKind::Other), &text),
+            1
+        );
+    }
+
+    /// The text with its SGR escapes removed
+    fn strip_ansi(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                chars.by_ref().find(|ch| *ch == 'm');
+            } else {
+                out.push(ch);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn colored_grid_keeps_the_plain_layout() {
+        let app = app::test_app(date(2025, 10, 1), config::AppConfig::default());
+        for month in [1, 2, 10] {
+            let plain = format_month(&app, 2025, month, false);
+            let colored = format_month(&app, 2025, month, true);
+            assert!(!plain.contains('\x1b'));
+            assert_eq!(strip_ansi(&colored), plain, "month {month}");
+        }
+        // Today is bold and reversed, with the padding left outside the escapes
+        let colored = format_month(&app, 2025, 10, true);
+        assert!(colored.contains(" \x1b[1;7m[01]\x1b[0m "), "{colored}");
+    }
+
+    #[test]
+    fn auto_color_needs_a_terminal_without_no_color() {
+        assert!(ColorChoice::Auto.enabled_for(false, true));
+        assert!(!ColorChoice::Auto.enabled_for(true, true));
+        assert!(!ColorChoice::Auto.enabled_for(false, false));
+        assert!(ColorChoice::Always.enabled_for(true, false));
+        assert!(!ColorChoice::Never.enabled_for(false, true));
+    }
+
+    #[test]
+    fn month_grid_matches_golden_file() {
+        let app = app::test_app(date(2025, 10, 1), config::AppConfig::default());
+        let expected = include_str!("../tests/fixtures/print-2025-10.txt");
+        assert_eq!(format_month(&app, 2025, 10, false), expected);
+    }
+
+    #[test]
+    fn json_records_keep_their_schema() {
+        let schedule = HolidaySchedule::bundled();
+        let day = date(2025, 10, 1);
+        let record =
+            serde_json::to_value(DayRecord::new(day, schedule.arrangement_for(day))).unwrap();
+        let expected = serde_json::json!({
+            "date": "2025-10-01",
+            "weekday": "Wed",
+            "lunar": {
+                "date": { "year": 2025, "month": 8, "day": 10, "is_leap": false },
+                "festival": null,
+            },
+            "gan_zhi": "乙巳",
+            "zodiac": "蛇",
+            "gan_zhi_day": "癸卯日",
+            "solar_term": null,
+            "holidays": [
+                { "name": "国庆节", "category": "statutory", "note": "公历10月1日 · 国庆" },
+            ],
+            "arrangement": "rest",
+        });
+        assert_eq!(record, expected);
+
+        let app = app::test_app(day, config::AppConfig::default());
+        let month = serde_json::to_value(MonthRecord::new(&app, 2025, 10)).unwrap();
+        assert_eq!(month["year"], 2025);
+        assert_eq!(month["month"], 10);
+        assert_eq!(month["title"], "2025年10月 · 八月初十—九月十一");
+        let days = month["days"].as_array().unwrap();
+        assert_eq!(days.len(), 31);
+        assert_eq!(days[0], expected);
+        assert_eq!(days[30]["date"], "2025-10-31");
+
+        let term = TermRecord::new(date(2025, 10, 8), "寒露", None);
+        assert_eq!(
+            serde_json::to_value(term).unwrap(),
+            serde_json::json!({ "date": "2025-10-08", "weekday": "Wed", "name": "寒露" })
+        );
+        let term = TermRecord::new(date(2025, 10, 8), "寒露", Some(7));
+        assert_eq!(serde_json::to_value(term).unwrap()["days"], 7);
+    }
+
+    #[test]
+    fn next_solar_term_crosses_the_year_and_stops_at_the_table_end() {
+        assert_eq!(
+            next_solar_term(date(2025, 12, 21)),
+            Some((date(2025, 12, 21), "冬至"))
+        );
+        assert_eq!(
+            next_solar_term(date(2025, 12, 22)),
+            Some((date(2026, 1, 5), "小寒"))
+        );
+        let last_year = app::solar_terms_for_year(app::SOLAR_TERM_MAX_YEAR);
+        let (last, name) = *last_year.last().unwrap();
+        assert_eq!(name, "冬至");
+        assert_eq!(next_solar_term(last), Some((last, name)));
+        assert_eq!(next_solar_term(last.succ_opt().unwrap()), None);
+        assert_eq!(print_next_solar_term(last.succ_opt().unwrap(), false), 1);
+
+        assert_eq!(
+            format_term(date(2025, 2, 3), "立春"),
+            "2025-02-03  周一  立春"
+        );
+        assert_eq!(app::solar_terms_for_year(2025).len(), 24);
+        assert_eq!(print_solar_terms(app::SOLAR_TERM_MIN_YEAR - 1, false), 1);
+        assert_eq!(print_solar_terms(app::SOLAR_TERM_MAX_YEAR + 1, true), 1);
+    }
+
+    #[test]
+    fn completions_name_every_flag_and_subcommand() {
+        let mut flags: Vec<&str> = HELP
+            .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-'))
+            .filter(|word| word.starts_with("--") && word.len() > 2)
+            .collect();
+        flags.sort_unstable();
+        flags.dedup();
+        assert!(flags.contains(&"--strict") && flags.contains(&"--no-color"));
+        let subcommands = ["convert", "term", "holidays", "export-ics", "completions"];
+        let shells = [
+            (Shell::Bash, "bash"),
+            (Shell::Zsh, "zsh"),
+            (Shell::Fish, "fish"),
+        ];
+        for (shell, name) in shells {
+            let script = shell.script();
+            for flag in &flags {
+                let fish_flag = format!("-l {}", &flag[2..]);
+                let named = match shell {
+                    Shell::Fish => script.contains(&fish_flag),
+                    Shell::Bash | Shell::Zsh => script.contains(flag),
+                };
+                assert!(named, "{flag} missing from the {name} script");
+            }
+            for subcommand in subcommands {
+                assert!(script.contains(subcommand), "{subcommand} in {name}");
+            }
+        }
+    }
+
+    /// The first line of a parse error, without the usage text
+    fn parse_error(raw: &[&str]) -> String {
+        match parse_args(args(raw)) {
+            Ok(_) => panic!("{raw:?} should not parse"),
+            Err(err) => err.lines().next().unwrap_or_default().to_string(),
+        }
+    }
+
+    #[test]
+    fn new_flags_parse() {
+        let tui = |raw: &[&str]| match parse_args(args(raw)) {
+            Ok(Command::Tui(options)) => options,
+            _ => panic!("{raw:?} is not a calendar command line"),
+        };
+        assert_eq!(tui(&["--inline"]).inline, Some(DEFAULT_INLINE_HEIGHT));
+        assert_eq!(tui(&["--inline=30"]).inline, Some(30));
+        let options = tui(&["--pick", "--format", "%d/%m/%Y"]);
+        assert!(options.pick);
+        assert_eq!(options.pick_format.as_deref(), Some("%d/%m/%Y"));
+        assert!(!tui(&["--no-alt-screen"]).alt_screen);
+        assert!(matches!(
+            parse_args(args(&["--print", "2025-10", "--color=always", "--json"])),
+            Ok(Command::Print {
+                year: Some(2025),
+                month: Some(10),
+                color: ColorChoice::Always,
+                json: true,
+                markdown: false,
+            })
+        ));
+        assert!(matches!(
+            parse_args(args(&["--print", "--md", "--no-color"])),
+            Ok(Command::Print {
+                year: None,
+                color: ColorChoice::Never,
+                markdown: true,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_args(args(&["convert", "--stdin", "--strict", "-l"])),
+            Ok(Command::Convert {
+                input: None,
+                lunar: true,
+                strict: true,
+                json: false,
+            })
+        ));
+        assert!(matches!(
+            parse_args(args(&["term", "--next", "--json"])),
+            Ok(Command::Term {
+                year: None,
+                next: true,
+                json: true,
+            })
+        ));
+        assert!(matches!(
+            parse_args(args(&["completions", "zsh"])),
+            Ok(Command::Completions { shell: Shell::Zsh })
+        ));
+        // Help wins even on a broken command line
+        assert!(matches!(
+            parse_args(args(&["--inline=1", "--help"])),
+            Ok(Command::Help)
+        ));
+    }
+
+    #[test]
+    fn bad_command_lines_are_rejected() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["--inline=5"], "无法识别的行数 '5'，至少为 12"),
+            (&["--inline=x"], "无法识别的行数 'x'，至少为 12"),
+            (&["--pick", "--inline"], "--inline 不能与 --pick 同时使用"),
+            (&["--format", "%Y"], "--format 只能与 --pick 一起使用"),
+            (&["--pick", "--format"], "缺少格式参数"),
+            (&["--bogus"], "未知选项 '--bogus'"),
+            (
+                &["--print", "--color", "sometimes"],
+                "无法识别的 --color 取值 'sometimes'（可选 auto、always、never）",
+            ),
+            (&["--print", "--color"], "--color 缺少取值"),
+            (
+                &["--print", "--json", "--markdown"],
+                "--json 不能与 --markdown 同时使用",
+            ),
+            (
+                &["convert", "2025-01-01", "--stdin"],
+                "--stdin 不能与日期参数同时使用",
+            ),
+            (
+                &["convert", "--stdin", "2025-01-01"],
+                "多余的参数 '2025-01-01'",
+            ),
+            (&["term", "--next", "2025"], "多余的参数 '2025'"),
+            (&["holidays"], "缺少年份参数"),
+            (&["export-ics", "2025", "--out"], "缺少输出文件参数"),
+            (
+                &["completions", "powershell"],
+                "不支持的 shell 'powershell'，可选 bash、zsh、fish",
+            ),
+            (&["--check-config", "a.ron", "b.ron"], "多余的参数 'b.ron'"),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parse_error(raw), *expected, "{raw:?}");
+        }
+        assert!(parse_error(&["--pick", "--format", "%H:%M"]).contains("%H:%M"));
+    }
+}
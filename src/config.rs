@@ -1,19 +1,54 @@
 use std::{
     collections::HashMap,
-    env, fs,
+    env,
+    fmt::Write as _,
+    fs,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use serde::Deserialize;
+use chrono::{
+    NaiveDate, Weekday,
+    format::{Item, StrftimeItems},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any,
+};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    anniversaries::{Anniversary, LeapDayRule},
+    birthdays::LunarBirthday,
+    countdowns::Countdown,
+    i18n::{self, Locale},
+    theme::ThemeConfig,
+};
 
 const KEY_CONFIG_ENV: &str = "MOLI_KEY_CONFIG";
-const CONFIG_FILE_NAME: &str = "key_bindings.ron";
+const KEY_CONFIG_FILE_STEM: &str = "key_bindings";
+const APP_CONFIG_ENV: &str = "MOLI_CONFIG";
+const APP_CONFIG_FILE_STEM: &str = "config";
 const CONFIG_DIR_NAME: &str = "moli";
+const CONFIG_DIR_ENV: &str = "MOLI_CONFIG_DIR";
+
+/// Commented default files written by `moli --init-config`, keyed by file stem
+pub const DEFAULT_CONFIG_FILES: [(&str, &str); 2] = [
+    (
+        KEY_CONFIG_FILE_STEM,
+        include_str!("../key_bindings.example.ron"),
+    ),
+    (APP_CONFIG_FILE_STEM, include_str!("../config.example.ron")),
+];
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Action {
     Quit,
+    ConfirmPick,
     MoveLeft,
     MoveRight,
     MoveUp,
@@ -24,24 +59,808 @@ pub enum Action {
     NextYear,
     BackToToday,
     OpenJumpPrompt,
+    NextHoliday,
+    PrevHoliday,
+    NextSolarTerm,
+    PrevSolarTerm,
+    SearchFestivals,
+    ShowYearHolidays,
+    ToggleYearView,
+    ToggleWeekView,
+    ToggleTriMonth,
+    ToggleCellLabels,
+    StartOfMonth,
+    EndOfMonth,
+    StartOfYear,
+    EndOfYear,
+    SetMark,
+    JumpToMark,
+    ToggleVisual,
+    OpenDateDiff,
+    OpenBazi,
+    PinCountdown,
+    ScrollDetailsUp,
+    ScrollDetailsDown,
+    YankDate,
+    ExportMonth,
+    EditNote,
+    SearchNotes,
+    ToggleAgenda,
+    CycleTheme,
+    ReloadConfig,
+    Suspend,
+    ShowHelp,
+    ToggleHelpBar,
+    ToggleDetails,
+    ShrinkDetails,
+    GrowDetails,
+}
+
+/// What a completed key sequence runs: an action, or a jump shortcut by its index
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Action(Action),
+    Jump(usize),
+}
+
+impl Bound {
+    pub fn action(self) -> Option<Action> {
+        match self {
+            Bound::Action(action) => Some(action),
+            Bound::Jump(_) => None,
+        }
+    }
+}
+
+/// Where a jump shortcut lands
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JumpTarget {
+    /// A fixed date, e.g. "2025-01-01"
+    Date(NaiveDate),
+    /// Days from today, e.g. "today+7"
+    Today(i64),
+    /// The next date after the selection carrying a built-in holiday, festival or solar
+    /// term, e.g. "next:中秋节"
+    Next(&'static str),
+}
+
+impl JumpTarget {
+    fn parse(raw: &str) -> Result<JumpTarget, String> {
+        let target = raw.trim();
+        if let Some(name) = target.strip_prefix("next:") {
+            return i18n::builtin_name(name)
+                .map(JumpTarget::Next)
+                .ok_or_else(|| {
+                    format!("unknown holiday, festival or solar term '{}'", name.trim())
+                });
+        }
+        if let Some(offset) = target.strip_prefix("today") {
+            let offset = offset.trim();
+            if offset.is_empty() {
+                return Ok(JumpTarget::Today(0));
+            }
+            let days = offset
+                .strip_prefix('+')
+                .unwrap_or(offset)
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| format!("'{target}' is not today, today+N or today-N"))?;
+            return Ok(JumpTarget::Today(days));
+        }
+        NaiveDate::parse_from_str(target, "%Y-%m-%d")
+            .map(JumpTarget::Date)
+            .map_err(|_| {
+                format!("'{target}' is not a YYYY-MM-DD date, today, today±N or next:<name>")
+            })
+    }
+}
+
+/// A key sequence that selects a date named in the key config
+#[derive(Clone, Debug)]
+pub struct JumpShortcut {
+    /// Shown in the help bar and the keymap overlay: the configured name, else the target
+    pub name: String,
+    pub target: JumpTarget,
+}
+
+/// Sections of the help bar, in display order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionGroup {
+    Move,
+    Jump,
+    View,
+    Tools,
+    App,
+}
+
+impl ActionGroup {
+    pub const ALL: [ActionGroup; 5] = [
+        ActionGroup::Move,
+        ActionGroup::Jump,
+        ActionGroup::View,
+        ActionGroup::Tools,
+        ActionGroup::App,
+    ];
+
+    pub fn label(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (ActionGroup::Move, Locale::Zh) => "移动",
+            (ActionGroup::Jump, Locale::Zh) => "跳转",
+            (ActionGroup::View, Locale::Zh) => "视图",
+            (ActionGroup::Tools, Locale::Zh) => "工具",
+            (ActionGroup::App, Locale::Zh) => "应用",
+            (ActionGroup::Move, Locale::ZhHant) => "移動",
+            (ActionGroup::Jump, Locale::ZhHant) => "跳轉",
+            (ActionGroup::View, Locale::ZhHant) => "視圖",
+            (ActionGroup::Tools, Locale::ZhHant) => "工具",
+            (ActionGroup::App, Locale::ZhHant) => "應用",
+            (ActionGroup::Move, Locale::En) => "Move",
+            (ActionGroup::Jump, Locale::En) => "Jump",
+            (ActionGroup::View, Locale::En) => "View",
+            (ActionGroup::Tools, Locale::En) => "Tools",
+            (ActionGroup::App, Locale::En) => "App",
+        }
+    }
+}
+
+/// Every action with its Chinese label and help bar group, in declaration order
+pub const ACTIONS: &[(Action, &str, ActionGroup)] = &[
+    (Action::Quit, "退出", ActionGroup::App),
+    (Action::ConfirmPick, "选定日期", ActionGroup::App),
+    (Action::MoveLeft, "左移", ActionGroup::Move),
+    (Action::MoveRight, "右移", ActionGroup::Move),
+    (Action::MoveUp, "上移", ActionGroup::Move),
+    (Action::MoveDown, "下移", ActionGroup::Move),
+    (Action::PrevMonth, "上个月", ActionGroup::Move),
+    (Action::NextMonth, "下个月", ActionGroup::Move),
+    (Action::PrevYear, "上一年", ActionGroup::Move),
+    (Action::NextYear, "下一年", ActionGroup::Move),
+    (Action::BackToToday, "回到今天", ActionGroup::Move),
+    (Action::OpenJumpPrompt, "跳转日期", ActionGroup::Jump),
+    (Action::NextHoliday, "下个节日", ActionGroup::Jump),
+    (Action::PrevHoliday, "上个节日", ActionGroup::Jump),
+    (Action::NextSolarTerm, "下个节气", ActionGroup::Jump),
+    (Action::PrevSolarTerm, "上个节气", ActionGroup::Jump),
+    (Action::SearchFestivals, "查找节日", ActionGroup::Jump),
+    (Action::ShowYearHolidays, "全年节日", ActionGroup::View),
+    (Action::ToggleYearView, "全年视图", ActionGroup::View),
+    (Action::ToggleWeekView, "周视图", ActionGroup::View),
+    (Action::ToggleTriMonth, "三月并排", ActionGroup::View),
+    (Action::ToggleCellLabels, "日期标签", ActionGroup::View),
+    (Action::StartOfMonth, "月初", ActionGroup::Move),
+    (Action::EndOfMonth, "月末", ActionGroup::Move),
+    (Action::StartOfYear, "年初", ActionGroup::Move),
+    (Action::EndOfYear, "年末", ActionGroup::Move),
+    (Action::SetMark, "设置标记", ActionGroup::Jump),
+    (Action::JumpToMark, "回到标记", ActionGroup::Jump),
+    (Action::ToggleVisual, "区间选择", ActionGroup::Tools),
+    (Action::OpenDateDiff, "日期间隔", ActionGroup::Tools),
+    (Action::OpenBazi, "八字排盘", ActionGroup::Tools),
+    (Action::PinCountdown, "倒数日", ActionGroup::Tools),
+    (Action::ScrollDetailsUp, "向上滚动详情", ActionGroup::View),
+    (Action::ScrollDetailsDown, "向下滚动详情", ActionGroup::View),
+    (Action::YankDate, "复制日期", ActionGroup::Tools),
+    (Action::ExportMonth, "导出月历", ActionGroup::Tools),
+    (Action::EditNote, "笔记", ActionGroup::Tools),
+    (Action::SearchNotes, "搜索笔记", ActionGroup::Tools),
+    (Action::ToggleAgenda, "本月议程", ActionGroup::View),
+    (Action::CycleTheme, "切换配色", ActionGroup::View),
+    (Action::ReloadConfig, "重新加载配置", ActionGroup::App),
+    (Action::Suspend, "挂起到后台", ActionGroup::App),
+    (Action::ShowHelp, "全部快捷键", ActionGroup::App),
+    (Action::ToggleHelpBar, "切换快捷键栏", ActionGroup::View),
+    (Action::ToggleDetails, "切换详情栏", ActionGroup::View),
+    (Action::ShrinkDetails, "收窄详情栏", ActionGroup::View),
+    (Action::GrowDetails, "加宽详情栏", ActionGroup::View),
+];
+
+/// Traditional Chinese labels of `ACTIONS`, entry for entry
+const ACTION_LABELS_HANT: [&str; ACTIONS.len()] = [
+    "退出",
+    "選定日期",
+    "左移",
+    "右移",
+    "上移",
+    "下移",
+    "上個月",
+    "下個月",
+    "上一年",
+    "下一年",
+    "回到今天",
+    "跳轉日期",
+    "下個節日",
+    "上個節日",
+    "下個節氣",
+    "上個節氣",
+    "查找節日",
+    "全年節日",
+    "全年視圖",
+    "週視圖",
+    "三月並排",
+    "日期標籤",
+    "月初",
+    "月末",
+    "年初",
+    "年末",
+    "設置標記",
+    "回到標記",
+    "區間選擇",
+    "日期間隔",
+    "八字排盤",
+    "倒數日",
+    "向上滾動詳情",
+    "向下滾動詳情",
+    "複製日期",
+    "導出月曆",
+    "筆記",
+    "搜索筆記",
+    "本月議程",
+    "切換配色",
+    "重新加載配置",
+    "掛起到後臺",
+    "全部快捷鍵",
+    "切換快捷鍵欄",
+    "切換詳情欄",
+    "收窄詳情欄",
+    "加寬詳情欄",
+];
+
+/// English labels of `ACTIONS`, entry for entry
+const ACTION_LABELS_EN: [&str; ACTIONS.len()] = [
+    "quit",
+    "pick date",
+    "left",
+    "right",
+    "up",
+    "down",
+    "prev month",
+    "next month",
+    "prev year",
+    "next year",
+    "today",
+    "go to date",
+    "next holiday",
+    "prev holiday",
+    "next solar term",
+    "prev solar term",
+    "find festival",
+    "year holidays",
+    "year view",
+    "week view",
+    "three months",
+    "cell labels",
+    "month start",
+    "month end",
+    "year start",
+    "year end",
+    "set mark",
+    "go to mark",
+    "visual range",
+    "date difference",
+    "four pillars",
+    "pin countdown",
+    "scroll details up",
+    "scroll details down",
+    "copy date",
+    "export month",
+    "note",
+    "search notes",
+    "month agenda",
+    "cycle theme",
+    "reload config",
+    "suspend",
+    "all shortcuts",
+    "toggle shortcut bar",
+    "toggle details",
+    "narrow details",
+    "widen details",
+];
+
+// Keep the registry in step with `Action`: entry i must be the variant declared i-th,
+// and the last variant must close the table
+const _: () = {
+    let mut index = 0;
+    while index < ACTIONS.len() {
+        assert!(ACTIONS[index].0 as usize == index);
+        index += 1;
+    }
+    assert!(Action::LAST as usize + 1 == ACTIONS.len());
+};
+
+impl Action {
+    /// The variant declared last, which the registry check above relies on
+    const LAST: Action = Action::GrowDetails;
+
+    /// Short description, as used in the help bar
+    pub fn label(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Zh => ACTIONS[self as usize].1,
+            Locale::ZhHant => ACTION_LABELS_HANT[self as usize],
+            Locale::En => ACTION_LABELS_EN[self as usize],
+        }
+    }
+}
+
+/// Key bindings and general settings loaded at startup
+pub struct Config {
+    pub key_bindings: KeyBindings,
+    pub app: AppConfig,
+    /// Problems found while loading, shown in the TUI once it starts
+    pub warnings: Vec<String>,
+}
+
+/// General settings from `config.ron`
+/// Every field falls back to its default, so new settings only need a field here
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub week_start: WeekStart,
+    /// Weekdays styled as the weekend and counted as weekend days in range and date statistics
+    pub weekend: WeekendDays,
+    /// Always render six week rows instead of only the rows the month needs
+    pub fixed_six_rows: bool,
+    pub paging: PagingPolicy,
+    /// Prefix each calendar row with its ISO week number
+    pub show_week_numbers: bool,
+    /// Show the 宜/忌 almanac in the details panel
+    pub show_almanac: bool,
+    /// Show the Julian Day Number, Modified Julian Date and Unix day in the details panel
+    pub show_astronomical: bool,
+    /// Count the viewed month's workdays, weekend days and statutory days off in the details panel
+    pub show_month_stats: bool,
+    /// ICS calendars whose events are shown in the grid
+    pub ics_files: Vec<String>,
+    /// Plain-text file of dated event lines shown like ICS events
+    pub events_file: Option<String>,
+    /// Yearly anniversaries on lunar dates
+    pub lunar_birthdays: Vec<LunarBirthday>,
+    /// Gregorian anniversaries, yearly or one-off
+    pub anniversaries: Vec<Anniversary>,
+    /// Where yearly February 29th anniversaries go in common years
+    pub anniversary_leap_day: LeapDayRule,
+    /// Target dates counted down to below the calendar
+    pub countdowns: Vec<Countdown>,
+    /// Holiday, festival or solar term counted down to in the details panel; empty hides it
+    pub countdown: String,
+    /// strftime format of full Gregorian dates in the interface, the yanked text and `--pick`
+    pub date_format: String,
+    /// strftime format of month titles and the header's month; unset keeps the locale's own
+    pub month_format: Option<String>,
+    /// Interface colors: a preset name or a table of overrides
+    pub theme: ThemeConfig,
+    /// Milliseconds a partly typed key sequence such as `g d` waits for its next key; 0 waits forever
+    pub sequence_timeout_ms: u64,
+    /// Longest the event loop sleeps while idle, in milliseconds within `TICK_RATE_RANGE_MS`;
+    /// unset sleeps until something on screen is due to change
+    pub tick_rate_ms: Option<u64>,
+    /// List actions without keys in the help bar, dimmed, instead of leaving them out
+    pub show_unbound_actions: bool,
+    /// Let a letter binding match both cases, so "g" also fires on "G"
+    pub ignore_key_case: bool,
+    /// Reopen on the date, view and toggles of the last exit instead of today
+    pub restore_session: bool,
+    /// Show the shortcut bar at the bottom; it can still be toggled at runtime
+    pub show_help_bar: bool,
+    /// Share of the body width given to the details column, within `DETAILS_WIDTH_RANGE`
+    pub details_width_percent: u16,
+    /// Use the compact layout on terminals narrower than this; 0 never does
+    pub compact_width: u16,
+    /// Use the compact layout on terminals shorter than this; 0 never does
+    pub compact_height: u16,
+    pub cell_detail: CellDetail,
+    /// Show labels under the day numbers; without them every week row is one line
+    pub show_cell_labels: bool,
+    /// Which source labels a month cell when a day has several
+    pub label_priority: LabelPriority,
+    /// Extra marking for today's date besides the theme's `today_fg`
+    pub today_marker: TodayMarker,
+    /// Explain the grid's colors and badges in the calendar's bottom border
+    pub show_legend: bool,
+    /// Show how much of the current year has passed in the header, room permitting
+    pub show_year_progress: bool,
+    /// Fill the month grid's leading and trailing cells with the neighbouring months' days
+    pub show_adjacent_days: bool,
+    /// Where the month export is written; unset uses the working directory
+    pub export_dir: Option<String>,
+    /// Draw `+-|` borders and ASCII symbols and key names for terminals without box drawing
+    pub ascii_mode: bool,
+    /// Columns the terminal gives "·", "…" and similar symbols; unset follows the locale
+    pub ambiguous_width: Option<AmbiguousWidth>,
+    /// Ask terminals that speak the kitty keyboard protocol for unambiguous key reports
+    pub kitty_keyboard: bool,
+    /// Language of the interface
+    pub locale: Locale,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            week_start: WeekStart::default(),
+            weekend: WeekendDays::default(),
+            fixed_six_rows: false,
+            paging: PagingPolicy::default(),
+            show_week_numbers: false,
+            show_almanac: true,
+            show_astronomical: false,
+            show_month_stats: true,
+            ics_files: Vec::new(),
+            events_file: None,
+            lunar_birthdays: Vec::new(),
+            anniversaries: Vec::new(),
+            anniversary_leap_day: LeapDayRule::default(),
+            countdowns: Vec::new(),
+            countdown: DEFAULT_COUNTDOWN.to_string(),
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            month_format: None,
+            theme: ThemeConfig::default(),
+            sequence_timeout_ms: 800,
+            tick_rate_ms: None,
+            show_unbound_actions: false,
+            ignore_key_case: false,
+            restore_session: false,
+            show_help_bar: true,
+            details_width_percent: 30,
+            compact_width: 80,
+            compact_height: 30,
+            cell_detail: CellDetail::default(),
+            show_cell_labels: true,
+            label_priority: LabelPriority::default(),
+            today_marker: TodayMarker::default(),
+            show_legend: true,
+            show_year_progress: true,
+            show_adjacent_days: true,
+            export_dir: None,
+            ascii_mode: false,
+            ambiguous_width: None,
+            kitty_keyboard: true,
+            locale: Locale::default(),
+        }
+    }
+}
+
+/// Festival counted down to unless `countdown` names another
+const DEFAULT_COUNTDOWN: &str = "春节";
+
+/// Date format used unless `date_format` names another
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Accepted range of `details_width_percent`
+pub const DETAILS_WIDTH_RANGE: RangeInclusive<u16> = 10..=60;
+
+/// Accepted range of `tick_rate_ms`
+pub const TICK_RATE_RANGE_MS: RangeInclusive<u64> = 16..=5000;
+
+impl AppConfig {
+    /// Upper bound on an idle wait, if one is configured
+    pub fn tick_rate(&self) -> Option<Duration> {
+        self.tick_rate_ms.map(Duration::from_millis)
+    }
+
+    pub fn sequence_timeout(&self) -> Option<Duration> {
+        (self.sequence_timeout_ms > 0).then(|| Duration::from_millis(self.sequence_timeout_ms))
+    }
+
+    /// ICS calendar paths with a leading `~/` resolved
+    pub fn ics_paths(&self) -> Vec<PathBuf> {
+        self.ics_files.iter().map(|raw| expand_home(raw)).collect()
+    }
+
+    /// The plain-text events file with a leading `~/` resolved
+    pub fn events_file_path(&self) -> Option<PathBuf> {
+        self.events_file.as_deref().map(expand_home)
+    }
+
+    /// The month export directory with a leading `~/` resolved
+    pub fn export_path(&self) -> Option<PathBuf> {
+        self.export_dir.as_deref().map(expand_home)
+    }
+}
+
+/// How the selected day is chosen when paging by months or years
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PagingPolicy {
+    /// Keep the day of month, clamped to the target month's length
+    #[default]
+    Clamp,
+    /// Return to the originally selected day of month once the target month has it
+    Restore,
+    /// Keep the weekday and its week position within the month
+    Weekday,
+}
+
+/// How many labels a month grid cell shows under its date
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellDetail {
+    /// Holiday or solar term plus the lunar day, except in the compact layout
+    #[default]
+    Auto,
+    /// Only the most important label
+    Single,
+    /// Holiday or solar term plus the lunar day, even in the compact layout
+    Full,
+}
+
+/// How today's cell stands out in the month grid
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TodayMarker {
+    /// Shown next to the day number, e.g. "今" or "●"; empty shows nothing
+    pub symbol: String,
+    pub position: MarkerPosition,
+    /// Underline the day number as well
+    pub underline: bool,
+}
+
+/// Side of the day number the today symbol goes on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerPosition {
+    Before,
+    #[default]
+    After,
+}
+
+/// How the terminal draws East Asian ambiguous-width characters
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmbiguousWidth {
+    /// Two columns, the usual setting of CJK terminals
+    Wide,
+    /// One column
+    Narrow,
+}
+
+/// Widest today symbol in terminal columns, so the day line still fits a cell
+pub const TODAY_SYMBOL_MAX_WIDTH: usize = 2;
+
+/// Weekday shown in the leftmost calendar column
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Saturday => Weekday::Sat,
+        }
+    }
+
+    /// Column index of the weekday when weeks start on this day
+    pub fn column_of(self, weekday: Weekday) -> u32 {
+        weekday.days_since(self.weekday())
+    }
+}
+
+/// Weekdays treated as the weekend, one bit per day counted from Monday
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeekendDays {
+    bits: u8,
+    /// Problems found while reading the setting, reported by the config loader
+    warnings: Vec<String>,
+}
+
+impl WeekendDays {
+    fn from_days(days: impl IntoIterator<Item = Weekday>) -> Self {
+        WeekendDays {
+            bits: days
+                .into_iter()
+                .fold(0, |bits, day| bits | 1 << day.num_days_from_monday()),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, weekday: Weekday) -> bool {
+        self.bits & 1 << weekday.num_days_from_monday() != 0
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+impl Default for WeekendDays {
+    fn default() -> Self {
+        WeekendDays::from_days([Weekday::Sat, Weekday::Sun])
+    }
+}
+
+/// Days are written like `"sat"` or `"saturday"`; an unknown one rejects the whole list, since
+/// a partial weekend would silently change the day counts
+impl<'de> Deserialize<'de> for WeekendDays {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tokens = Vec::<String>::deserialize(deserializer)?;
+        let mut days = Vec::new();
+        for token in &tokens {
+            match token.trim().parse::<Weekday>() {
+                Ok(day) => days.push(day),
+                Err(_) => {
+                    let mut weekend = WeekendDays::default();
+                    weekend.warnings.push(format!(
+                        "unknown weekend day {token:?} (use mon, tue, wed, thu, fri, sat or sun); using [\"sat\", \"sun\"]"
+                    ));
+                    return Ok(weekend);
+                }
+            }
+        }
+        Ok(WeekendDays::from_days(days))
+    }
+}
+
+/// A source of the label under a month cell's date
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabelKind {
+    Holiday,
+    Birthday,
+    SolarTerm,
+    /// Lunar festival that is not already shown as a holiday, such as 小年
+    Festival,
+    Lunar,
+}
+
+impl LabelKind {
+    const ALL: [LabelKind; 5] = [
+        LabelKind::Holiday,
+        LabelKind::Birthday,
+        LabelKind::SolarTerm,
+        LabelKind::Festival,
+        LabelKind::Lunar,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "holiday" => Some(LabelKind::Holiday),
+            "birthday" => Some(LabelKind::Birthday),
+            "solar_term" => Some(LabelKind::SolarTerm),
+            "festival" => Some(LabelKind::Festival),
+            "lunar" => Some(LabelKind::Lunar),
+            _ => None,
+        }
+    }
+}
+
+/// Order in which a month cell picks its label; the first source the day has wins
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelPriority {
+    order: Vec<LabelKind>,
+    /// Problems found while reading the setting, reported by the config loader
+    warnings: Vec<String>,
+}
+
+impl LabelPriority {
+    /// Sources left out of the list follow it in the default order, so a day still gets a label
+    fn from_kinds(mut order: Vec<LabelKind>) -> Self {
+        for kind in LabelKind::ALL {
+            if !order.contains(&kind) {
+                order.push(kind);
+            }
+        }
+        LabelPriority {
+            order,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn order(&self) -> &[LabelKind] {
+        &self.order
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+impl Default for LabelPriority {
+    fn default() -> Self {
+        LabelPriority::from_kinds(LabelKind::ALL.to_vec())
+    }
+}
+
+/// An unknown or repeated source rejects the whole list in favor of the default order
+impl<'de> Deserialize<'de> for LabelPriority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tokens = Vec::<String>::deserialize(deserializer)?;
+        let mut order = Vec::new();
+        for token in &tokens {
+            let problem = match LabelKind::from_name(token.trim()) {
+                Some(kind) if order.contains(&kind) => {
+                    format!("label_priority lists {token:?} twice")
+                }
+                Some(kind) => {
+                    order.push(kind);
+                    continue;
+                }
+                None => format!(
+                    "unknown label_priority entry {token:?} (use holiday, birthday, solar_term, festival or lunar)"
+                ),
+            };
+            let mut priority = LabelPriority::default();
+            priority
+                .warnings
+                .push(format!("{problem}; using the default order"));
+            return Ok(priority);
+        }
+        Ok(LabelPriority::from_kinds(order))
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct KeyBindings {
-    bindings: Vec<(Binding, Action)>,
+    bindings: Vec<(Binding, Bound)>,
     labels: HashMap<Action, Vec<Binding>>,
+    /// From `jump_shortcuts`, each with its bindings, indexed by `Bound::Jump`
+    jump_shortcuts: Vec<(JumpShortcut, Vec<Binding>)>,
+    /// Let letter bindings match either case, from `ignore_key_case`
+    ignore_case: bool,
+    /// Name keys and wheel turns without arrows, from `ascii_mode`
+    ascii_labels: bool,
 }
 
 impl KeyBindings {
+    /// Name keys and wheel turns in plain ASCII, for `ascii_mode`
+    pub fn with_ascii_labels(mut self, ascii: bool) -> Self {
+        self.ascii_labels = ascii;
+        self
+    }
+
     // return the label of the binding for the action
     pub fn labels_for(&self, action: Action) -> Vec<String> {
         self.labels
             .get(&action)
-            .map(|bindings| bindings.iter().map(|b| b.label()).collect())
+            .map(|bindings| {
+                bindings
+                    .iter()
+                    .map(|b| b.label(self.ascii_labels))
+                    .collect()
+            })
             .unwrap_or_default()
     }
 
-    fn from_config(config: KeyBindingConfig) -> Self {
+    /// Every jump shortcut with the labels of its keys, in config order
+    pub fn jump_shortcuts(&self) -> impl Iterator<Item = (&JumpShortcut, Vec<String>)> {
+        self.jump_shortcuts.iter().map(|(shortcut, bindings)| {
+            let labels = bindings
+                .iter()
+                .map(|binding| binding.label(self.ascii_labels))
+                .collect();
+            (shortcut, labels)
+        })
+    }
+
+    pub fn jump_shortcut(&self, index: usize) -> &JumpShortcut {
+        &self.jump_shortcuts[index].0
+    }
+
+    /// What a resolved sequence does, as the help popups name it
+    pub fn bound_label(&self, bound: Bound, locale: Locale) -> &str {
+        match bound {
+            Bound::Action(action) => action.label(locale),
+            Bound::Jump(index) => &self.jump_shortcut(index).name,
+        }
+    }
+
+    /// Whether any binding starts with this key event
+    pub fn has_prefix(&self, event: KeyEvent) -> bool {
+        self.bindings
+            .iter()
+            .any(|(binding, _)| binding.matches_at(0, event, self.ignore_case))
+    }
+
+    /// With `ignore_case` set, letter bindings that differ only in case share a key, and the
+    /// later one is reported as shadowed
+    fn from_config(
+        config: KeyBindingConfig,
+        ignore_case: bool,
+        problems: &mut Vec<String>,
+    ) -> Self {
         let mut bindings = Vec::new();
         let mut labels: HashMap<Action, Vec<Binding>> = HashMap::new();
         bind_action(
@@ -50,6 +869,15 @@ impl KeyBindings {
             Action::Quit,
             config.quit,
             &["Esc", "q", "Q"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ConfirmPick,
+            config.confirm_pick,
+            &["Enter"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -57,6 +885,7 @@ impl KeyBindings {
             Action::MoveLeft,
             config.move_left,
             &["h", "H"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -64,6 +893,7 @@ impl KeyBindings {
             Action::MoveRight,
             config.move_right,
             &["l", "L"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -71,6 +901,7 @@ impl KeyBindings {
             Action::MoveUp,
             config.move_up,
             &["k", "K"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -78,20 +909,23 @@ impl KeyBindings {
             Action::MoveDown,
             config.move_down,
             &["j", "J"],
+            problems,
         );
         bind_action(
             &mut bindings,
             &mut labels,
             Action::PrevMonth,
             config.prev_month,
-            &["Left"],
+            &["Left", "WheelUp"],
+            problems,
         );
         bind_action(
             &mut bindings,
             &mut labels,
             Action::NextMonth,
             config.next_month,
-            &["Right"],
+            &["Right", "WheelDown"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -99,6 +933,7 @@ impl KeyBindings {
             Action::PrevYear,
             config.prev_year,
             &["Up"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -106,6 +941,7 @@ impl KeyBindings {
             Action::NextYear,
             config.next_year,
             &["Down"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -113,6 +949,7 @@ impl KeyBindings {
             Action::BackToToday,
             config.back_to_today,
             &["t", "T"],
+            problems,
         );
         bind_action(
             &mut bindings,
@@ -120,155 +957,1311 @@ impl KeyBindings {
             Action::OpenJumpPrompt,
             config.open_jump_prompt,
             &["g+d"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::NextHoliday,
+            config.next_holiday,
+            &["n"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::PrevHoliday,
+            config.prev_holiday,
+            &["N"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::NextSolarTerm,
+            config.next_solar_term,
+            &["s"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::PrevSolarTerm,
+            config.prev_solar_term,
+            &["S"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::SearchFestivals,
+            config.search_festivals,
+            &["F"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ShowYearHolidays,
+            config.show_year_holidays,
+            &["g+h"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleYearView,
+            config.toggle_year_view,
+            &["y"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleWeekView,
+            config.toggle_week_view,
+            &["w"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleTriMonth,
+            config.toggle_tri_month,
+            &["M"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleCellLabels,
+            config.toggle_cell_labels,
+            &["g+l"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::StartOfMonth,
+            config.start_of_month,
+            &["Home"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::EndOfMonth,
+            config.end_of_month,
+            &["End"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::StartOfYear,
+            config.start_of_year,
+            &["Shift+Home"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::EndOfYear,
+            config.end_of_year,
+            &["Shift+End"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::SetMark,
+            config.set_mark,
+            &["m"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::JumpToMark,
+            config.jump_to_mark,
+            &["'"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleVisual,
+            config.toggle_visual,
+            &["v"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::OpenDateDiff,
+            config.open_date_diff,
+            &["d"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::OpenBazi,
+            config.open_bazi,
+            &["b"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::PinCountdown,
+            config.pin_countdown,
+            &["c"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ScrollDetailsUp,
+            config.scroll_details_up,
+            &["Ctrl+u"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ScrollDetailsDown,
+            config.scroll_details_down,
+            &["Ctrl+d"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::YankDate,
+            config.yank_date,
+            &["Y"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ExportMonth,
+            config.export_month,
+            &["E"],
+            problems,
         );
-        KeyBindings { bindings, labels }
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::EditNote,
+            config.edit_note,
+            &["e"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::SearchNotes,
+            config.search_notes,
+            &["/"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleAgenda,
+            config.toggle_agenda,
+            &["a"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::CycleTheme,
+            config.cycle_theme,
+            &["g+c"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ReloadConfig,
+            config.reload_config,
+            &["Ctrl+r"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::Suspend,
+            config.suspend,
+            &["Ctrl+z"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ShowHelp,
+            config.show_help,
+            &["?"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleHelpBar,
+            config.toggle_help_bar,
+            &["F1"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ToggleDetails,
+            config.toggle_details,
+            &["i"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::ShrinkDetails,
+            config.shrink_details,
+            &["<"],
+            problems,
+        );
+        bind_action(
+            &mut bindings,
+            &mut labels,
+            Action::GrowDetails,
+            config.grow_details,
+            &[">"],
+            problems,
+        );
+        let jump_shortcuts = bind_jump_shortcuts(
+            &mut bindings,
+            config.jump_shortcuts.unwrap_or_default(),
+            problems,
+        );
+        let describe = |bound: &Bound| match bound {
+            Bound::Action(action) => format!("{action:?}"),
+            Bound::Jump(index) => format!("jump shortcut '{}'", jump_shortcuts[*index].0.name),
+        };
+        for (index, (binding, bound)) in bindings.iter().enumerate() {
+            let Some((other, earlier)) = bindings[..index]
+                .iter()
+                .find(|(other, earlier)| other.same_keys(binding, ignore_case) && earlier != bound)
+            else {
+                continue;
+            };
+            if other.sequence == binding.sequence {
+                problems.push(format!(
+                    "'{}' is bound to both {} and {}",
+                    binding.label(false),
+                    describe(earlier),
+                    describe(bound)
+                ));
+            } else {
+                // The resolver takes the first match, so the later binding never fires
+                problems.push(format!(
+                    "'{}' for {} is shadowed by '{}' for {} with ignore_key_case set",
+                    binding.label(false),
+                    describe(bound),
+                    other.label(false),
+                    describe(earlier)
+                ));
+            }
+        }
+        KeyBindings {
+            bindings,
+            labels,
+            jump_shortcuts,
+            ignore_case,
+            ascii_labels: false,
+        }
+    }
+
+    /// Whether the config turned the action off with an empty list or "none"
+    pub fn is_disabled(&self, action: Action) -> bool {
+        self.labels.get(&action).is_some_and(Vec::is_empty)
+    }
+
+    /// Every bound action with its key labels in binding order, then the disabled actions without labels
+    pub fn summary(&self) -> Vec<(Action, Vec<String>)> {
+        let mut summary: Vec<(Action, Vec<String>)> = Vec::new();
+        for action in self.bindings.iter().filter_map(|(_, bound)| bound.action()) {
+            if !summary.iter().any(|(seen, _)| *seen == action) {
+                summary.push((action, self.labels_for(action)));
+            }
+        }
+        let mut disabled = self
+            .labels
+            .keys()
+            .filter(|action| self.is_disabled(**action))
+            .map(|action| (*action, Vec::new()))
+            .collect::<Vec<_>>();
+        disabled.sort_by_key(|(action, _)| format!("{action:?}"));
+        summary.extend(disabled);
+        summary
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings::from_config(KeyBindingConfig::default(), false, &mut Vec::new())
+    }
+}
+
+/// One way to finish a partly typed key sequence
+pub struct PendingHint {
+    /// Keys typed so far, e.g. "g"
+    pub typed: String,
+    /// Keys still needed, e.g. "d"
+    pub remaining: String,
+    pub bound: Bound,
+}
+
+pub struct BindingResolver {
+    pending: Vec<(usize, usize)>,
+    /// When the pending sequences last advanced
+    last_key: Instant,
+    /// How long a partly typed sequence waits for its next key; `None` waits forever
+    timeout: Option<Duration>,
+}
+
+impl BindingResolver {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        BindingResolver {
+            pending: Vec::new(),
+            last_key: Instant::now(),
+            timeout,
+        }
+    }
+
+    /// Whether a multi-key sequence is partially typed
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Completions of the partly typed sequences, ordered by their remaining keys
+    pub fn pending_hints(&self, bindings: &KeyBindings) -> Vec<PendingHint> {
+        let mut hints = self
+            .pending
+            .iter()
+            .map(|&(idx, progress)| {
+                let (binding, bound) = &bindings.bindings[idx];
+                let (typed, remaining) = binding.sequence.split_at(progress);
+                PendingHint {
+                    typed: sequence_label(typed, bindings.ascii_labels),
+                    remaining: sequence_label(remaining, bindings.ascii_labels),
+                    bound: *bound,
+                }
+            })
+            .collect::<Vec<_>>();
+        hints.sort_by(|a, b| a.remaining.cmp(&b.remaining));
+        hints
+    }
+
+    /// Drop a partly typed sequence that has waited longer than the timeout; returns whether
+    /// one was dropped
+    pub fn expire(&mut self) -> bool {
+        if let Some(timeout) = self.timeout
+            && self.last_key.elapsed() >= timeout
+            && !self.pending.is_empty()
+        {
+            self.pending.clear();
+            return true;
+        }
+        false
+    }
+
+    /// When the partly typed sequence, if any, goes stale
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.timeout {
+            Some(timeout) if !self.pending.is_empty() => Some(self.last_key + timeout),
+            _ => None,
+        }
+    }
+
+    pub fn process(&mut self, bindings: &KeyBindings, event: KeyEvent) -> Option<Bound> {
+        self.expire();
+        self.last_key = Instant::now();
+        let mut new_pending = Vec::new();
+        let current = std::mem::take(&mut self.pending);
+        let was_pending = !current.is_empty();
+        for (idx, progress) in current {
+            let (binding, bound) = &bindings.bindings[idx];
+            if binding.matches_at(progress, event, bindings.ignore_case) {
+                let next = progress + 1;
+                if next == binding.len() {
+                    log::debug!(
+                        "{} completes {} -> {bound:?}",
+                        key_label(event),
+                        binding.label(false)
+                    );
+                    return Some(*bound);
+                }
+                new_pending.push((idx, next));
+            }
+        }
+        // Esc abandons a pending sequence it does not continue, instead of acting on its own
+        if was_pending && new_pending.is_empty() && event.code == KeyCode::Esc {
+            log::debug!("Esc abandons the pending sequence");
+            return None;
+        }
+
+        for (idx, (binding, bound)) in bindings.bindings.iter().enumerate() {
+            if binding.matches_at(0, event, bindings.ignore_case) {
+                if binding.len() == 1 {
+                    log::debug!("{} -> {bound:?}", key_label(event));
+                    return Some(*bound);
+                }
+                new_pending.push((idx, 1));
+            }
+        }
+
+        if new_pending.is_empty() {
+            log::trace!("{} is not bound", key_label(event));
+        } else {
+            log::debug!(
+                "{} pending: {}",
+                key_label(event),
+                new_pending
+                    .iter()
+                    .map(|&(idx, _)| bindings.bindings[idx].0.label(false))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        self.pending = new_pending;
+        None
+    }
+
+    /// Resolve a click or wheel turn; mouse bindings are always a single step, so this also
+    /// abandons any partly typed key sequence
+    pub fn process_mouse(&mut self, bindings: &KeyBindings, event: MouseEvent) -> Option<Action> {
+        MouseInput::from_event(event.kind)?;
+        self.pending.clear();
+        bindings
+            .bindings
+            .iter()
+            .find(|(binding, _)| binding.matches_mouse(event))
+            .and_then(|(_, bound)| bound.action())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Binding {
+    sequence: Vec<InputPress>,
+}
+
+impl Binding {
+    fn len(&self) -> usize {
+        self.sequence.len()
+    }
+
+    fn matches_at(&self, index: usize, event: KeyEvent, ignore_case: bool) -> bool {
+        matches!(
+            self.sequence.get(index),
+            Some(InputPress::Key(press)) if press.matches(event, ignore_case)
+        )
+    }
+
+    fn matches_mouse(&self, event: MouseEvent) -> bool {
+        matches!(self.sequence.as_slice(), [InputPress::Mouse(press)] if press.matches(event))
+    }
+
+    /// Whether both bindings are typed with the same presses
+    fn same_keys(&self, other: &Binding, ignore_case: bool) -> bool {
+        self.len() == other.len()
+            && self
+                .sequence
+                .iter()
+                .zip(&other.sequence)
+                .all(|pair| match pair {
+                    (InputPress::Key(a), InputPress::Key(b)) => a.same_key(b, ignore_case),
+                    (a, b) => a == b,
+                })
+    }
+
+    fn has_mouse(&self) -> bool {
+        self.sequence
+            .iter()
+            .any(|press| matches!(press, InputPress::Mouse(_)))
+    }
+
+    fn label(&self, ascii: bool) -> String {
+        sequence_label(&self.sequence, ascii)
+    }
+}
+
+/// Key presses as shown to the user, e.g. "g d"
+fn sequence_label(sequence: &[InputPress], ascii: bool) -> String {
+    sequence
+        .iter()
+        .map(|press| press.label(ascii))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// One step of a binding: a key press, or a click or wheel turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InputPress {
+    Key(KeyPress),
+    Mouse(MousePress),
+}
+
+impl InputPress {
+    fn label(&self, ascii: bool) -> String {
+        match self {
+            InputPress::Key(press) => press.label(ascii),
+            InputPress::Mouse(press) => press.label(ascii),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct KeyPress {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    /// Letters match case-exactly unless `ignore_case` is set
+    fn matches(&self, event: KeyEvent, ignore_case: bool) -> bool {
+        let (code, mut event_modifiers) =
+            canonical_key(event.code, normalize_modifiers(event.modifiers));
+        let same_code = match (self.code, code) {
+            (KeyCode::Char(bound), KeyCode::Char(typed)) if ignore_case => {
+                bound.to_lowercase().eq(typed.to_lowercase())
+            }
+            (bound, typed) => bound == typed,
+        };
+        if !same_code {
+            return false;
+        }
+        // Symbols such as '?' arrive with Shift held; only a binding that names Shift needs it
+        if matches!(self.code, KeyCode::Char(_)) && !self.modifiers.contains(KeyModifiers::SHIFT) {
+            event_modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self.modifiers == event_modifiers
+    }
+
+    /// Whether the two presses are the same key, letters compared as `matches` does
+    fn same_key(&self, other: &KeyPress, ignore_case: bool) -> bool {
+        let same_code = match (self.code, other.code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) if ignore_case => {
+                a.to_lowercase().eq(b.to_lowercase())
+            }
+            (a, b) => a == b,
+        };
+        same_code && self.modifiers == other.modifiers
+    }
+
+    fn label(&self, ascii: bool) -> String {
+        let mut parts = modifier_labels(self.modifiers);
+        parts.push(get_key_code_label(self.code, ascii));
+        parts.join("+")
+    }
+}
+
+/// Fold Shift into the key where the key itself says it: "Shift+g" is 'G' and
+/// "Shift+Tab" is BackTab, matching what terminals send
+fn canonical_key(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(ch) if ch.is_alphabetic() && modifiers.contains(KeyModifiers::SHIFT) => (
+            KeyCode::Char(ch.to_uppercase().next().unwrap_or(ch)),
+            modifiers - KeyModifiers::SHIFT,
+        ),
+        KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+            (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT)
+        }
+        KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+/// Modifier names in label order, e.g. ["Ctrl", "Shift"]
+fn modifier_labels(modifiers: KeyModifiers) -> Vec<String> {
+    let mut parts: Vec<String> = Vec::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        parts.push("Ctrl".into());
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        parts.push("Alt".into());
+    }
+    if modifiers.contains(KeyModifiers::SUPER) {
+        parts.push("Meta".into());
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        parts.push("Shift".into());
+    }
+    parts
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MousePress {
+    input: MouseInput,
+    modifiers: KeyModifiers,
+}
+
+impl MousePress {
+    fn matches(&self, event: MouseEvent) -> bool {
+        MouseInput::from_event(event.kind) == Some(self.input)
+            && self.modifiers == normalize_modifiers(event.modifiers)
+    }
+
+    fn label(&self, ascii: bool) -> String {
+        let mut parts = modifier_labels(self.modifiers);
+        parts.push(self.input.label(ascii).to_string());
+        parts.join("+")
+    }
+}
+
+/// Mouse buttons and wheel directions that can be bound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MouseInput {
+    Left,
+    Right,
+    Middle,
+    WheelUp,
+    WheelDown,
+}
+
+impl MouseInput {
+    /// Button presses and wheel turns; releases, drags and moves are not bindable
+    fn from_event(kind: MouseEventKind) -> Option<Self> {
+        match kind {
+            MouseEventKind::Down(MouseButton::Left) => Some(MouseInput::Left),
+            MouseEventKind::Down(MouseButton::Right) => Some(MouseInput::Right),
+            MouseEventKind::Down(MouseButton::Middle) => Some(MouseInput::Middle),
+            MouseEventKind::ScrollUp => Some(MouseInput::WheelUp),
+            MouseEventKind::ScrollDown => Some(MouseInput::WheelDown),
+            _ => None,
+        }
+    }
+
+    fn parse(token: &str) -> Option<Self> {
+        match token.to_ascii_lowercase().as_str() {
+            "mouseleft" | "leftclick" => Some(MouseInput::Left),
+            "mouseright" | "rightclick" => Some(MouseInput::Right),
+            "mousemiddle" | "middleclick" => Some(MouseInput::Middle),
+            "wheelup" | "scrollup" => Some(MouseInput::WheelUp),
+            "wheeldown" | "scrolldown" => Some(MouseInput::WheelDown),
+            _ => None,
+        }
+    }
+
+    fn label(self, ascii: bool) -> &'static str {
+        match self {
+            MouseInput::Left => "LeftClick",
+            MouseInput::Right => "RightClick",
+            MouseInput::Middle => "MiddleClick",
+            MouseInput::WheelUp if ascii => "WheelUp",
+            MouseInput::WheelDown if ascii => "WheelDown",
+            MouseInput::WheelUp => "Wheel↑",
+            MouseInput::WheelDown => "Wheel↓",
+        }
+    }
+}
+
+/// Load both config files, also writing any problems to stderr
+/// `key_file` replaces the user's key binding file, as `--config` asks
+pub fn load_config(key_file: Option<&Path>) -> Config {
+    let mut problems = Vec::new();
+    let (mut config, _) = read_config(key_file, &mut problems);
+    for problem in &problems {
+        log::warn!("{problem}");
+        eprintln!("moli: {problem}");
+    }
+    config.warnings = problems;
+    config
+}
+
+/// Load both config files again for a running app
+/// Fails when a file cannot be read or parsed, or when no key would quit, so the caller keeps its config
+pub fn reload_config(key_file: Option<&Path>) -> Result<Config, String> {
+    let mut problems = Vec::new();
+    let loaded = read_config(key_file, &mut problems);
+    check_reload(loaded, problems)
+}
+
+/// `reload_config` from the given files only, without looking for system or user files
+#[cfg(test)]
+pub fn reload_config_files(
+    app_file: Option<&Path>,
+    key_file: Option<&Path>,
+) -> Result<Config, String> {
+    let mut problems = Vec::new();
+    let loaded = read_config_files(app_file, None, key_file, &mut problems);
+    check_reload(loaded, problems)
+}
+
+/// Reject a reloaded config that failed to load or cannot quit, keeping its problems as warnings
+fn check_reload(
+    (mut config, failure): (Config, Option<String>),
+    problems: Vec<String>,
+) -> Result<Config, String> {
+    if let Some(err) = failure {
+        log::warn!("reload failed: {err}");
+        return Err(err);
+    }
+    if config.key_bindings.labels_for(Action::Quit).is_empty() {
+        log::warn!("reload failed: no key is bound to quit");
+        return Err("no key is bound to quit".to_string());
+    }
+    for problem in &problems {
+        log::warn!("{problem}");
+    }
+    config.warnings = problems;
+    Ok(config)
+}
+
+/// Read both config files; a file that cannot be read or parsed keeps its defaults
+/// and the last such error is returned beside the config
+fn read_config(key_file: Option<&Path>, problems: &mut Vec<String>) -> (Config, Option<String>) {
+    // General settings are not layered: the shared system file only applies when the user has
+    // no general settings of their own, and is skipped as a whole otherwise. Only key bindings
+    // are merged action by action below
+    let app_path = match find_config_file(APP_CONFIG_ENV, APP_CONFIG_FILE_STEM, problems) {
+        Some(path) => {
+            if let Some(system) = system_config_file(APP_CONFIG_FILE_STEM, &mut Vec::new()) {
+                log::info!("general settings {path:?} replace the system file {system:?}");
+            }
+            Some(path)
+        }
+        None => system_config_file(APP_CONFIG_FILE_STEM, problems),
+    };
+    let system_key_path = system_config_file(KEY_CONFIG_FILE_STEM, problems);
+    let key_path = match key_file {
+        Some(path) => Some(path.to_path_buf()),
+        None => find_config_file(KEY_CONFIG_ENV, KEY_CONFIG_FILE_STEM, problems),
+    };
+    read_config_files(
+        app_path.as_deref(),
+        system_key_path.as_deref(),
+        key_path.as_deref(),
+        problems,
+    )
+}
+
+/// Read the general settings and the system and user key files at the given paths
+fn read_config_files(
+    app_path: Option<&Path>,
+    system_key_path: Option<&Path>,
+    key_path: Option<&Path>,
+    problems: &mut Vec<String>,
+) -> (Config, Option<String>) {
+    let mut failure = None;
+    log::info!("general settings: {app_path:?}");
+    let mut app = match app_path.map(read_app_config) {
+        Some(Ok(Some(app))) => app,
+        Some(Err(err)) => {
+            problems.push(format!("{err}; using defaults"));
+            failure = Some(err);
+            AppConfig::default()
+        }
+        _ => AppConfig::default(),
+    };
+    // System key bindings are the base layer and the user's file overrides them action by action
+    let mut key_config = match system_key_path.map(read_key_file) {
+        Some(Ok(config)) => config,
+        Some(Err(err)) => {
+            problems.push(format!("{err}; skipping it"));
+            KeyBindingConfig::default()
+        }
+        None => KeyBindingConfig::default(),
+    };
+    log::info!("key bindings: {key_path:?}");
+    if let Some(path) = key_path {
+        match read_key_file(path) {
+            Ok(user) => key_config = key_config.merge(user),
+            Err(err) => {
+                problems.push(err.clone());
+                failure = Some(err);
+            }
+        }
+    }
+    key_config.apply_legacy_settings(&mut app);
+    let key_bindings = KeyBindings::from_config(key_config, app.ignore_key_case, problems)
+        .with_ascii_labels(app.ascii_mode);
+    validate_app_config(&mut app, problems);
+    let config = Config {
+        key_bindings,
+        app,
+        warnings: Vec::new(),
+    };
+    (config, failure)
+}
+
+/// Drop settings that cannot be used, reporting each one
+fn validate_app_config(app: &mut AppConfig, problems: &mut Vec<String>) {
+    problems.append(&mut app.theme.take_warnings());
+    problems.append(&mut app.weekend.take_warnings());
+    problems.append(&mut app.label_priority.take_warnings());
+    if let Some(tick_rate) = app.tick_rate_ms
+        && !TICK_RATE_RANGE_MS.contains(&tick_rate)
+    {
+        let clamped = tick_rate.clamp(*TICK_RATE_RANGE_MS.start(), *TICK_RATE_RANGE_MS.end());
+        problems.push(format!(
+            "tick_rate_ms {tick_rate} is outside {}-{}; using {clamped}",
+            TICK_RATE_RANGE_MS.start(),
+            TICK_RATE_RANGE_MS.end()
+        ));
+        app.tick_rate_ms = Some(clamped);
+    }
+    if !DETAILS_WIDTH_RANGE.contains(&app.details_width_percent) {
+        let clamped = app
+            .details_width_percent
+            .clamp(*DETAILS_WIDTH_RANGE.start(), *DETAILS_WIDTH_RANGE.end());
+        problems.push(format!(
+            "details_width_percent {} is outside {}-{}; using {clamped}",
+            app.details_width_percent,
+            DETAILS_WIDTH_RANGE.start(),
+            DETAILS_WIDTH_RANGE.end()
+        ));
+        app.details_width_percent = clamped;
+    }
+    let symbol_width = app.today_marker.symbol.width();
+    if symbol_width > TODAY_SYMBOL_MAX_WIDTH {
+        problems.push(format!(
+            "today_marker.symbol {:?} is {symbol_width} columns wide, more than {TODAY_SYMBOL_MAX_WIDTH}; not showing it",
+            app.today_marker.symbol
+        ));
+        app.today_marker.symbol.clear();
+    }
+    if !app.countdown.is_empty() {
+        match i18n::builtin_name(&app.countdown) {
+            Some(name) => app.countdown = name.to_string(),
+            None => {
+                problems.push(format!(
+                    "countdown {:?} is not a built-in holiday, festival or solar term; counting down to {DEFAULT_COUNTDOWN}",
+                    app.countdown
+                ));
+                app.countdown = DEFAULT_COUNTDOWN.to_string();
+            }
+        }
+    }
+    if !is_date_format(&app.date_format) {
+        problems.push(format!(
+            "date_format {:?} cannot format a date; using {DEFAULT_DATE_FORMAT:?}",
+            app.date_format
+        ));
+        app.date_format = DEFAULT_DATE_FORMAT.to_string();
+    }
+    if let Some(format) = &app.month_format
+        && !is_date_format(format)
+    {
+        problems.push(format!(
+            "month_format {format:?} cannot format a date; using the default month title"
+        ));
+        app.month_format = None;
+    }
+    app.lunar_birthdays.retain(|birthday| {
+        let valid = birthday.is_valid();
+        if !valid {
+            problems.push(format!(
+                "ignoring lunar birthday {:?}: month must be 1-12 and day 1-30",
+                birthday.name
+            ));
+        }
+        valid
+    });
+    app.anniversaries.retain(|anniversary| {
+        let problem = anniversary.problem();
+        if let Some(problem) = problem {
+            problems.push(format!(
+                "ignoring anniversary {:?}: {problem}",
+                anniversary.name
+            ));
+        }
+        problem.is_none()
+    });
+    app.countdowns.retain(|countdown| {
+        let valid = !countdown.name.trim().is_empty();
+        if !valid {
+            problems.push(format!(
+                "ignoring the countdown to {}: the name is empty",
+                countdown.date
+            ));
+        }
+        valid
+    });
+}
+
+/// Whether `format` is a non-empty strftime format that can format a bare date
+/// Unknown specifiers and time fields such as `%H` would make chrono fail, or panic in `to_string`
+pub fn is_date_format(format: &str) -> bool {
+    let sample = NaiveDate::from_ymd_opt(2025, 6, 1).expect("sample date is valid");
+    let mut out = String::new();
+    !format.trim().is_empty()
+        && !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+        && write!(out, "{}", sample.format(format)).is_ok()
+}
+
+/// Read `config.ron` or `config.toml`; a missing file is not an error
+fn read_app_config(path: &Path) -> Result<Option<AppConfig>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("failed to read config {path:?}: {err}"))?;
+    if content.trim().is_empty() {
+        return Ok(Some(AppConfig::default()));
+    }
+    parse_config_file(path, &content)
+        .map(Some)
+        .map_err(|err| format!("failed to parse config {path:?}: {err}"))
+}
+
+/// Parse a key binding file without resolving it; an empty file sets nothing
+fn read_key_file(path: &Path) -> Result<KeyBindingConfig, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read key config {path:?}: {err}"))?;
+    if content.trim().is_empty() {
+        return Ok(KeyBindingConfig::default());
     }
+    parse_config_file::<ConfigFile>(path, &content)
+        .map(ConfigFile::into_inner)
+        .map_err(|err| format!("failed to parse key config {path:?}: {err}"))
 }
 
-impl Default for KeyBindings {
-    fn default() -> Self {
-        KeyBindings::from_config(KeyBindingConfig::default())
+/// The two kinds of configuration file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigKind {
+    KeyBindings,
+    General,
+}
+
+impl ConfigKind {
+    fn env_var(self) -> &'static str {
+        match self {
+            ConfigKind::KeyBindings => KEY_CONFIG_ENV,
+            ConfigKind::General => APP_CONFIG_ENV,
+        }
+    }
+
+    fn stem(self) -> &'static str {
+        match self {
+            ConfigKind::KeyBindings => KEY_CONFIG_FILE_STEM,
+            ConfigKind::General => APP_CONFIG_FILE_STEM,
+        }
+    }
+
+    /// Files named like `config.ron` hold general settings, anything else key bindings
+    fn of(path: &Path) -> Self {
+        match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(APP_CONFIG_FILE_STEM) => ConfigKind::General,
+            _ => ConfigKind::KeyBindings,
+        }
     }
 }
 
-#[derive(Default)]
-pub struct BindingResolver {
-    pending: Vec<(usize, usize)>,
+/// Outcome of checking one configuration file with `moli --check-config`
+pub struct ConfigCheck {
+    pub kind: ConfigKind,
+    /// `None` when no file exists and the defaults apply
+    pub path: Option<PathBuf>,
+    pub problems: Vec<String>,
+    /// Effective bindings per action, for a key binding file
+    pub bindings: Vec<(Action, Vec<String>)>,
 }
 
-impl BindingResolver {
-    pub fn process(&mut self, bindings: &KeyBindings, event: KeyEvent) -> Option<Action> {
-        let mut new_pending = Vec::new();
-        let current = std::mem::take(&mut self.pending);
-        for (idx, progress) in current {
-            let (binding, action) = &bindings.bindings[idx];
-            if binding.matches_at(progress, event) {
-                let next = progress + 1;
-                if next == binding.len() {
-                    return Some(*action);
-                }
-                new_pending.push((idx, next));
+/// Check the given file, or both files moli would load at startup
+/// Unlike normal loading this also reports fields that no setting uses
+pub fn check_config(path: Option<PathBuf>) -> Vec<ConfigCheck> {
+    let files = match path {
+        Some(path) => vec![(ConfigKind::of(&path), Some(path), Vec::new())],
+        None => [ConfigKind::KeyBindings, ConfigKind::General]
+            .into_iter()
+            .flat_map(|kind| {
+                let mut system_problems = Vec::new();
+                let system = system_config_file(kind.stem(), &mut system_problems)
+                    .map(|path| (kind, Some(path), system_problems));
+                let mut problems = Vec::new();
+                let path = find_config_file(kind.env_var(), kind.stem(), &mut problems);
+                // Without any file the defaults are reported once, unless a system file replaces them
+                let user = (path.is_some() || system.is_none()).then_some((kind, path, problems));
+                system.into_iter().chain(user)
+            })
+            .collect(),
+    };
+    files
+        .into_iter()
+        .map(|(kind, path, mut problems)| {
+            let bindings = match &path {
+                Some(path) => check_file(kind, path, &mut problems),
+                None => KeyBindings::default().summary(),
+            };
+            ConfigCheck {
+                kind,
+                path,
+                problems,
+                bindings: match kind {
+                    ConfigKind::KeyBindings => bindings,
+                    ConfigKind::General => Vec::new(),
+                },
             }
-        }
+        })
+        .collect()
+}
 
-        for (idx, (binding, action)) in bindings.bindings.iter().enumerate() {
-            if binding.matches_at(0, event) {
-                if binding.len() == 1 {
-                    return Some(*action);
+/// Load one file the way startup would, returning the effective bindings
+fn check_file(
+    kind: ConfigKind,
+    path: &Path,
+    problems: &mut Vec<String>,
+) -> Vec<(Action, Vec<String>)> {
+    let mut app = AppConfig::default();
+    let bindings = match kind {
+        ConfigKind::KeyBindings => {
+            // Show what the file resolves to on top of the system layer, whose own problems
+            // are reported when that file is checked
+            let base = system_config_file(KEY_CONFIG_FILE_STEM, &mut Vec::new())
+                .filter(|system| system != path)
+                .and_then(|system| read_key_file(&system).ok())
+                .unwrap_or_default();
+            let mut config = match read_key_file(path) {
+                Ok(config) => base.merge(config),
+                Err(err) => {
+                    problems.push(err);
+                    base
                 }
-                new_pending.push((idx, 1));
+            };
+            config.apply_legacy_settings(&mut app);
+            KeyBindings::from_config(config, app.ignore_key_case, problems)
+        }
+        ConfigKind::General => {
+            match read_app_config(path) {
+                Ok(Some(config)) => app = config,
+                Ok(None) => problems.push(format!("config {path:?} does not exist")),
+                Err(err) => problems.push(err),
+            }
+            KeyBindings::default()
+        }
+    };
+    validate_app_config(&mut app, problems);
+    if let Ok(content) = fs::read_to_string(path) {
+        let known = match kind {
+            ConfigKind::KeyBindings => struct_fields::<KeyBindingConfig>(),
+            ConfigKind::General => struct_fields::<AppConfig>(),
+        };
+        for field in top_level_keys(path, &content) {
+            if !known.contains(&field.as_str()) {
+                problems.push(format!("unknown field `{field}` in {path:?}"));
             }
         }
+    }
+    bindings.summary()
+}
 
-        self.pending = new_pending;
-        None
+/// Field names a struct accepts, read from its derived `Deserialize` impl
+fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldNames<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("field names read"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+            identifier ignored_any
+        }
     }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
 }
 
-#[derive(Clone, Debug)]
-struct Binding {
-    sequence: Vec<KeyPress>,
+/// Syntax of a configuration file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConfigFormat {
+    Ron,
+    Toml,
 }
 
-impl Binding {
-    fn len(&self) -> usize {
-        self.sequence.len()
+impl ConfigFormat {
+    /// Both formats in lookup order: a `.ron` file wins over a `.toml` file of the same name
+    const ALL: [ConfigFormat; 2] = [ConfigFormat::Ron, ConfigFormat::Toml];
+
+    fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Ron => "ron",
+            ConfigFormat::Toml => "toml",
+        }
     }
 
-    fn matches_at(&self, index: usize, event: KeyEvent) -> bool {
-        self.sequence
-            .get(index)
-            .map(|press| press.matches(event))
-            .unwrap_or(false)
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
     }
 
-    fn label(&self) -> String {
-        self.sequence
-            .iter()
-            .map(|press| press.label())
-            .collect::<Vec<_>>()
-            .join(" ")
+    fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T, String> {
+        match self {
+            ConfigFormat::Ron => parse_ron(content).map_err(|err| err.to_string()),
+            ConfigFormat::Toml => toml::from_str(content).map_err(|err| err.to_string()),
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct KeyPress {
-    code: KeyCode,
-    modifiers: KeyModifiers,
+/// Parse RON, also accepting enum values written as strings such as `"sunday"`
+fn parse_ron<T: DeserializeOwned>(content: &str) -> Result<T, ron::error::SpannedError> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lenient<T> {
+        Value(T),
+    }
+    ron::from_str(content).or_else(|err| match ron::from_str(content) {
+        Ok(Lenient::Value(value)) => Ok(value),
+        Err(_) => Err(err),
+    })
 }
 
-impl KeyPress {
-    fn matches(&self, event: KeyEvent) -> bool {
-        if self.code != event.code {
-            return false;
-        }
-        let mut event_modifiers = normalize_modifiers(event.modifiers);
-        if matches!(self.code, KeyCode::Char(_)) && !self.modifiers.contains(KeyModifiers::SHIFT) {
-            event_modifiers.remove(KeyModifiers::SHIFT);
-        }
-        self.modifiers == event_modifiers
+/// Parse by file extension; other extensions try RON first and then TOML
+fn parse_config_file<T: DeserializeOwned>(path: &Path, content: &str) -> Result<T, String> {
+    match ConfigFormat::from_path(path) {
+        Some(format) => format.parse(content),
+        None => ConfigFormat::Ron
+            .parse(content)
+            .or_else(|err| ConfigFormat::Toml.parse(content).map_err(|_| err)),
     }
+}
 
-    fn label(&self) -> String {
-        let mut parts: Vec<String> = Vec::new();
-        if self.modifiers.contains(KeyModifiers::CONTROL) {
-            parts.push("Ctrl".into());
-        }
-        if self.modifiers.contains(KeyModifiers::ALT) {
-            parts.push("Alt".into());
-        }
-        if self.modifiers.contains(KeyModifiers::SUPER) {
-            parts.push("Meta".into());
+/// The path from the environment variable if set, otherwise `<stem>.ron` or `<stem>.toml`
+/// in the config directory, preferring RON when both exist
+fn find_config_file(env_var: &str, stem: &str, problems: &mut Vec<String>) -> Option<PathBuf> {
+    if let Some(path) = env::var_os(env_var) {
+        return Some(PathBuf::from(path));
+    }
+    let mut found = existing_config_files(stem).into_iter();
+    let path = found.next()?;
+    report_ignored(&path, found, problems);
+    Some(path)
+}
+
+/// The system-wide `<stem>.ron` or `<stem>.toml`, preferring RON when both exist
+fn system_config_file(stem: &str, problems: &mut Vec<String>) -> Option<PathBuf> {
+    let mut found = existing_files_in(&system_config_dir()?, stem).into_iter();
+    let path = found.next()?;
+    report_ignored(&path, found, problems);
+    Some(path)
+}
+
+fn report_ignored(path: &Path, ignored: impl Iterator<Item = PathBuf>, problems: &mut Vec<String>) {
+    for ignored in ignored {
+        problems.push(format!(
+            "both {path:?} and {ignored:?} exist; using {path:?} (RON takes precedence over TOML)"
+        ));
+    }
+}
+
+/// Keys of the file's top-level table, by file extension like `parse_config_file`
+fn top_level_keys(path: &Path, content: &str) -> Vec<String> {
+    let keys = match ConfigFormat::from_path(path) {
+        Some(ConfigFormat::Ron) => ron_keys(content),
+        Some(ConfigFormat::Toml) => toml_keys(content),
+        None => ron_keys(content).or_else(|| toml_keys(content)),
+    };
+    keys.unwrap_or_default()
+}
+
+/// Top-level RON keys; a lone `bindings` wrapper is looked through
+fn ron_keys(content: &str) -> Option<Vec<String>> {
+    let mut value = ron::from_str::<ron::Value>(content).ok()?;
+    loop {
+        let ron::Value::Map(map) = value else {
+            return Some(Vec::new());
+        };
+        let keys = map
+            .keys()
+            .filter_map(|key| match key {
+                ron::Value::String(key) => Some(key.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if keys != ["bindings"] {
+            return Some(keys);
         }
-        if self.modifiers.contains(KeyModifiers::SHIFT) {
-            parts.push("Shift".into());
+        value = map.into_iter().next()?.1;
+    }
+}
+
+/// Top-level TOML keys; a lone `bindings` table is looked through
+fn toml_keys(content: &str) -> Option<Vec<String>> {
+    let mut table = toml::from_str::<toml::Table>(content).ok()?;
+    loop {
+        let keys = table.keys().cloned().collect::<Vec<_>>();
+        match table.remove("bindings") {
+            Some(toml::Value::Table(inner)) if keys.len() == 1 => table = inner,
+            _ => return Some(keys),
         }
-        parts.push(get_key_code_label(self.code));
-        parts.join("+")
     }
 }
 
-pub fn load_key_bindings() -> KeyBindings {
-    let path = env::var_os(KEY_CONFIG_ENV)
-        .map(PathBuf::from)
-        .or_else(default_config_path);
-    if let Some(path) = path
-        && let Some(bindings) = load_from_path(&path)
+/// Resolve a leading `~/` against the home directory
+fn expand_home(raw: &str) -> PathBuf {
+    if let Some(rest) = raw.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
     {
-        return bindings;
+        return home.join(rest);
     }
-    KeyBindings::default()
+    PathBuf::from(raw)
 }
 
-/// Load key bindings from the specified path
-fn load_from_path(path: &Path) -> Option<KeyBindings> {
-    let content = match fs::read_to_string(path) {
-        Ok(content) => content,
-        Err(err) => {
-            eprintln!("moli: failed to read key config {path:?}: {err}");
-            return None;
-        }
-    };
-    parse_config(&content).map(|config| KeyBindings::from_config(config.into_inner()))
+/// MoLi's directory under the platform config directory, or `MOLI_CONFIG_DIR` when set
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(CONFIG_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR_NAME))
 }
 
-/// Parse the RON configuration content
-fn parse_config(content: &str) -> Option<ConfigFile> {
-    match ron::from_str(content) {
-        Ok(parsed) => Some(parsed),
-        Err(err) => {
-            eprintln!("moli: failed to parse key config: {err}");
-            None
-        }
+/// Directory for settings shared by every user: `/etc/moli`, or `%ProgramData%\moli` on Windows
+fn system_config_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join(CONFIG_DIR_NAME))
+    } else {
+        Some(Path::new("/etc").join(CONFIG_DIR_NAME))
     }
 }
 
-fn default_config_path() -> Option<PathBuf> {
-    dirs::config_dir().map(|mut dir| {
-        dir.push(CONFIG_DIR_NAME);
-        dir.push(CONFIG_FILE_NAME);
-        dir
-    })
+/// Files in the config directory that would be loaded for this stem
+pub fn existing_config_files(stem: &str) -> Vec<PathBuf> {
+    config_dir()
+        .map(|dir| existing_files_in(&dir, stem))
+        .unwrap_or_default()
+}
+
+fn existing_files_in(dir: &Path, stem: &str) -> Vec<PathBuf> {
+    ConfigFormat::ALL
+        .into_iter()
+        .map(|format| dir.join(format!("{stem}.{}", format.extension())))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// A key event as a binding would name it, for the log
+fn key_label(event: KeyEvent) -> String {
+    let (code, modifiers) = canonical_key(event.code, normalize_modifiers(event.modifiers));
+    KeyPress { code, modifiers }.label(false)
 }
 
 /// Keep Shift/Ctrl/Alt/Super modifiers and drop the rest
@@ -277,11 +2270,17 @@ fn normalize_modifiers(modifiers: KeyModifiers) -> KeyModifiers {
         & (KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER)
 }
 
+/// Untagged variants are tried in order: the wrapper goes first, since unknown fields are
+/// ignored and a wrapped file would otherwise read as a direct one that sets nothing
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum ConfigFile {
+    Wrapped {
+        bindings: KeyBindingConfig,
+    },
     Direct(KeyBindingConfig),
-    Wrapped { bindings: KeyBindingConfig },
+    /// A bare `()`, which RON reads as a unit rather than a struct with no fields
+    Empty,
 }
 
 impl ConfigFile {
@@ -289,6 +2288,7 @@ impl ConfigFile {
         match self {
             ConfigFile::Direct(inner) => inner,
             ConfigFile::Wrapped { bindings } => bindings,
+            ConfigFile::Empty => KeyBindingConfig::default(),
         }
     }
 }
@@ -297,6 +2297,7 @@ impl ConfigFile {
 #[derive(Debug, Default, Deserialize)]
 struct KeyBindingConfig {
     quit: Option<Vec<String>>,
+    confirm_pick: Option<Vec<String>>,
     move_left: Option<Vec<String>>,
     move_right: Option<Vec<String>>,
     move_up: Option<Vec<String>>,
@@ -307,41 +2308,237 @@ struct KeyBindingConfig {
     next_year: Option<Vec<String>>,
     back_to_today: Option<Vec<String>>,
     open_jump_prompt: Option<Vec<String>>,
+    next_holiday: Option<Vec<String>>,
+    prev_holiday: Option<Vec<String>>,
+    next_solar_term: Option<Vec<String>>,
+    prev_solar_term: Option<Vec<String>>,
+    search_festivals: Option<Vec<String>>,
+    show_year_holidays: Option<Vec<String>>,
+    toggle_year_view: Option<Vec<String>>,
+    toggle_week_view: Option<Vec<String>>,
+    toggle_tri_month: Option<Vec<String>>,
+    toggle_cell_labels: Option<Vec<String>>,
+    start_of_month: Option<Vec<String>>,
+    end_of_month: Option<Vec<String>>,
+    start_of_year: Option<Vec<String>>,
+    end_of_year: Option<Vec<String>>,
+    set_mark: Option<Vec<String>>,
+    jump_to_mark: Option<Vec<String>>,
+    toggle_visual: Option<Vec<String>>,
+    open_date_diff: Option<Vec<String>>,
+    open_bazi: Option<Vec<String>>,
+    pin_countdown: Option<Vec<String>>,
+    scroll_details_up: Option<Vec<String>>,
+    scroll_details_down: Option<Vec<String>>,
+    yank_date: Option<Vec<String>>,
+    export_month: Option<Vec<String>>,
+    edit_note: Option<Vec<String>>,
+    search_notes: Option<Vec<String>>,
+    toggle_agenda: Option<Vec<String>>,
+    cycle_theme: Option<Vec<String>>,
+    reload_config: Option<Vec<String>>,
+    suspend: Option<Vec<String>>,
+    show_help: Option<Vec<String>>,
+    toggle_help_bar: Option<Vec<String>>,
+    toggle_details: Option<Vec<String>>,
+    shrink_details: Option<Vec<String>>,
+    grow_details: Option<Vec<String>>,
+    week_start: Option<WeekStart>,
+    fixed_six_rows: Option<bool>,
+    paging: Option<PagingPolicy>,
+    show_week_numbers: Option<bool>,
+    show_almanac: Option<bool>,
+    ics_files: Option<Vec<String>>,
+    lunar_birthdays: Option<Vec<LunarBirthday>>,
+    jump_shortcuts: Option<Vec<JumpShortcutConfig>>,
+}
+
+/// A `jump_shortcuts` entry: the keys, the target, and an optional display name
+#[derive(Debug, Deserialize)]
+struct JumpShortcutConfig {
+    keys: Vec<String>,
+    target: String,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl KeyBindingConfig {
+    /// Layer `over` on top of this config; every field it sets replaces the one here
+    fn merge(self, over: KeyBindingConfig) -> KeyBindingConfig {
+        KeyBindingConfig {
+            quit: over.quit.or(self.quit),
+            confirm_pick: over.confirm_pick.or(self.confirm_pick),
+            move_left: over.move_left.or(self.move_left),
+            move_right: over.move_right.or(self.move_right),
+            move_up: over.move_up.or(self.move_up),
+            move_down: over.move_down.or(self.move_down),
+            prev_month: over.prev_month.or(self.prev_month),
+            next_month: over.next_month.or(self.next_month),
+            prev_year: over.prev_year.or(self.prev_year),
+            next_year: over.next_year.or(self.next_year),
+            back_to_today: over.back_to_today.or(self.back_to_today),
+            open_jump_prompt: over.open_jump_prompt.or(self.open_jump_prompt),
+            next_holiday: over.next_holiday.or(self.next_holiday),
+            prev_holiday: over.prev_holiday.or(self.prev_holiday),
+            next_solar_term: over.next_solar_term.or(self.next_solar_term),
+            prev_solar_term: over.prev_solar_term.or(self.prev_solar_term),
+            search_festivals: over.search_festivals.or(self.search_festivals),
+            show_year_holidays: over.show_year_holidays.or(self.show_year_holidays),
+            toggle_year_view: over.toggle_year_view.or(self.toggle_year_view),
+            toggle_week_view: over.toggle_week_view.or(self.toggle_week_view),
+            toggle_tri_month: over.toggle_tri_month.or(self.toggle_tri_month),
+            toggle_cell_labels: over.toggle_cell_labels.or(self.toggle_cell_labels),
+            start_of_month: over.start_of_month.or(self.start_of_month),
+            end_of_month: over.end_of_month.or(self.end_of_month),
+            start_of_year: over.start_of_year.or(self.start_of_year),
+            end_of_year: over.end_of_year.or(self.end_of_year),
+            set_mark: over.set_mark.or(self.set_mark),
+            jump_to_mark: over.jump_to_mark.or(self.jump_to_mark),
+            toggle_visual: over.toggle_visual.or(self.toggle_visual),
+            open_date_diff: over.open_date_diff.or(self.open_date_diff),
+            open_bazi: over.open_bazi.or(self.open_bazi),
+            pin_countdown: over.pin_countdown.or(self.pin_countdown),
+            scroll_details_up: over.scroll_details_up.or(self.scroll_details_up),
+            scroll_details_down: over.scroll_details_down.or(self.scroll_details_down),
+            yank_date: over.yank_date.or(self.yank_date),
+            export_month: over.export_month.or(self.export_month),
+            edit_note: over.edit_note.or(self.edit_note),
+            search_notes: over.search_notes.or(self.search_notes),
+            toggle_agenda: over.toggle_agenda.or(self.toggle_agenda),
+            cycle_theme: over.cycle_theme.or(self.cycle_theme),
+            reload_config: over.reload_config.or(self.reload_config),
+            suspend: over.suspend.or(self.suspend),
+            show_help: over.show_help.or(self.show_help),
+            toggle_help_bar: over.toggle_help_bar.or(self.toggle_help_bar),
+            toggle_details: over.toggle_details.or(self.toggle_details),
+            shrink_details: over.shrink_details.or(self.shrink_details),
+            grow_details: over.grow_details.or(self.grow_details),
+            week_start: over.week_start.or(self.week_start),
+            fixed_six_rows: over.fixed_six_rows.or(self.fixed_six_rows),
+            paging: over.paging.or(self.paging),
+            show_week_numbers: over.show_week_numbers.or(self.show_week_numbers),
+            show_almanac: over.show_almanac.or(self.show_almanac),
+            ics_files: over.ics_files.or(self.ics_files),
+            lunar_birthdays: over.lunar_birthdays.or(self.lunar_birthdays),
+            jump_shortcuts: over.jump_shortcuts.or(self.jump_shortcuts),
+        }
+    }
+
+    /// Settings now belong in `config.ron`; when still present here they take precedence
+    fn apply_legacy_settings(&mut self, app: &mut AppConfig) {
+        if let Some(week_start) = self.week_start.take() {
+            app.week_start = week_start;
+        }
+        if let Some(fixed_six_rows) = self.fixed_six_rows.take() {
+            app.fixed_six_rows = fixed_six_rows;
+        }
+        if let Some(paging) = self.paging.take() {
+            app.paging = paging;
+        }
+        if let Some(show_week_numbers) = self.show_week_numbers.take() {
+            app.show_week_numbers = show_week_numbers;
+        }
+        if let Some(show_almanac) = self.show_almanac.take() {
+            app.show_almanac = show_almanac;
+        }
+        app.ics_files
+            .extend(self.ics_files.take().unwrap_or_default());
+        app.lunar_birthdays
+            .extend(self.lunar_birthdays.take().unwrap_or_default());
+    }
 }
 
 /// Bind an action to the provided key entries
 fn bind_action(
-    bindings: &mut Vec<(Binding, Action)>,
+    bindings: &mut Vec<(Binding, Bound)>,
     labels: &mut HashMap<Action, Vec<Binding>>,
     action: Action,
     entries: Option<Vec<String>>,
     fallback: &[&str],
+    problems: &mut Vec<String>,
 ) {
     let tokens = entries.unwrap_or_else(|| fallback.iter().map(|s| s.to_string()).collect());
+    // An empty list or "none" turns the action off on purpose, without a warning
+    if tokens
+        .iter()
+        .all(|token| token.trim().eq_ignore_ascii_case("none"))
+    {
+        labels.insert(action, Vec::new());
+        return;
+    }
     let mut is_add = false;
     for token in tokens {
-        match parse_binding(&token) {
-            Some(binding) => {
+        match parse_binding(&token, problems) {
+            Ok(binding) => {
                 labels.entry(action).or_default().push(binding.clone());
-                bindings.push((binding, action));
+                bindings.push((binding, Bound::Action(action)));
                 is_add = true;
             }
-            None => eprintln!("moli: unknown key binding token '{token}'"),
+            Err(err) => problems.push(err),
         }
     }
     if !is_add {
-        eprintln!(
-            "moli: no key bindings configured for {:?}; action disabled",
+        problems.push(format!(
+            "no key bindings configured for {:?}; action disabled",
             action
+        ));
+    }
+}
+
+/// Bind the configured jump shortcuts after the actions, so an action keeps a key both claim
+/// Entries with an unusable target or no usable keys are reported and dropped
+fn bind_jump_shortcuts(
+    bindings: &mut Vec<(Binding, Bound)>,
+    entries: Vec<JumpShortcutConfig>,
+    problems: &mut Vec<String>,
+) -> Vec<(JumpShortcut, Vec<Binding>)> {
+    let mut shortcuts = Vec::new();
+    for entry in entries {
+        let target = match JumpTarget::parse(&entry.target) {
+            Ok(target) => target,
+            Err(err) => {
+                problems.push(format!("jump shortcut skipped: {err}"));
+                continue;
+            }
+        };
+        let name = entry
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| entry.target.trim().to_string());
+        let mut keys = Vec::new();
+        for token in &entry.keys {
+            match parse_binding(token, problems) {
+                Ok(binding) if binding.has_mouse() => problems.push(format!(
+                    "jump shortcut '{name}' can't use the mouse input '{token}'"
+                )),
+                Ok(binding) => keys.push(binding),
+                Err(err) => problems.push(err),
+            }
+        }
+        if keys.is_empty() {
+            problems.push(format!(
+                "no key bindings configured for jump shortcut '{name}'; shortcut skipped"
+            ));
+            continue;
+        }
+        let index = shortcuts.len();
+        bindings.extend(
+            keys.iter()
+                .map(|binding| (binding.clone(), Bound::Jump(index))),
         );
+        shortcuts.push((JumpShortcut { name, target }, keys));
     }
+    shortcuts
 }
 
 /// Parse a key binding sequence from a string
-fn parse_binding(raw: &str) -> Option<Binding> {
+/// A mouse input must stand alone apart from its modifiers, e.g. "Ctrl+WheelUp"
+fn parse_binding(raw: &str, problems: &mut Vec<String>) -> Result<Binding, String> {
+    let unknown = || format!("unknown key binding token '{raw}'");
     let mut sequence = Vec::new();
     let mut modifiers = KeyModifiers::empty();
-    for part in raw.split('+') {
+    for part in split_binding(raw) {
         let token = part.trim();
         if token.is_empty() {
             continue;
@@ -350,21 +2547,52 @@ fn parse_binding(raw: &str) -> Option<Binding> {
             modifiers |= modifier;
             continue;
         }
-        let code = parse_key_code(token)?;
-        sequence.push(KeyPress {
-            code,
-            modifiers: normalize_modifiers(modifiers),
+        let step_modifiers = normalize_modifiers(modifiers);
+        sequence.push(match MouseInput::parse(token) {
+            Some(input) => InputPress::Mouse(MousePress {
+                input,
+                modifiers: step_modifiers,
+            }),
+            None => {
+                let code = parse_key_code(token).ok_or_else(unknown)?;
+                let (code, modifiers) = canonical_key(code, step_modifiers);
+                InputPress::Key(KeyPress { code, modifiers })
+            }
         });
         modifiers = KeyModifiers::empty();
     }
     if !modifiers.is_empty() {
-        eprintln!("moli: dangling modifiers in '{raw}'");
+        problems.push(format!("dangling modifiers in '{raw}'"));
+    }
+    if sequence.len() > 1
+        && sequence
+            .iter()
+            .any(|press| matches!(press, InputPress::Mouse(_)))
+    {
+        return Err(format!(
+            "'{raw}' mixes mouse input into a key sequence; mouse bindings must be a single step"
+        ));
     }
     if sequence.is_empty() {
-        None
+        Err(unknown())
     } else {
-        Some(Binding { sequence })
+        Ok(Binding { sequence })
+    }
+}
+
+/// Split a binding at its `+` separators; a `+` that starts a token is the key itself,
+/// so "+", "Ctrl++" and "g++" all bind the plus key
+fn split_binding(raw: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    for (index, ch) in raw.char_indices() {
+        if ch == '+' && index > start {
+            tokens.push(&raw[start..index]);
+            start = index + 1;
+        }
     }
+    tokens.push(&raw[start..]);
+    tokens
 }
 
 /// Parse modifier keys such as Ctrl/Shift
@@ -405,29 +2633,40 @@ fn parse_key_code(raw: &str) -> Option<KeyCode> {
         "end" => Some(KeyCode::End),
         "pageup" | "page_up" => Some(KeyCode::PageUp),
         "pagedown" | "page_down" => Some(KeyCode::PageDown),
+        "backtab" => Some(KeyCode::BackTab),
+        // Symbols that are awkward or impossible to write literally in a binding
+        "plus" => Some(KeyCode::Char('+')),
+        "minus" | "dash" => Some(KeyCode::Char('-')),
+        "comma" => Some(KeyCode::Char(',')),
+        "period" | "dot" => Some(KeyCode::Char('.')),
+        "slash" => Some(KeyCode::Char('/')),
+        "backslash" => Some(KeyCode::Char('\\')),
+        "semicolon" => Some(KeyCode::Char(';')),
+        "equal" | "equals" => Some(KeyCode::Char('=')),
         _ => parse_function_key(&lowered),
     }
 }
 
-/// Parse function keys F{1-12}
+/// Parse function keys F1-F24
 fn parse_function_key(token: &str) -> Option<KeyCode> {
     if let Some(rest) = token.strip_prefix('f')
         && let Ok(num) = rest.parse::<u8>()
+        && (1..=24).contains(&num)
     {
         return Some(KeyCode::F(num));
     }
     None
 }
 
-/// Map key codes to human-readable labels
-fn get_key_code_label(code: KeyCode) -> String {
+/// Map key codes to human-readable labels; `ascii` spells out the arrow keys
+fn get_key_code_label(code: KeyCode, ascii: bool) -> String {
     match code {
         KeyCode::Char(' ') => "Space".to_string(),
         KeyCode::Char(ch) => ch.to_string(),
         KeyCode::Enter => "Enter".to_string(),
         KeyCode::Backspace => "Backspace".to_string(),
         KeyCode::Tab => "Tab".to_string(),
-        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::BackTab => "Shift+Tab".to_string(),
         KeyCode::Delete => "Delete".to_string(),
         KeyCode::Insert => "Insert".to_string(),
         KeyCode::Esc => "Esc".to_string(),
@@ -435,6 +2674,10 @@ fn get_key_code_label(code: KeyCode) -> String {
         KeyCode::End => "End".to_string(),
         KeyCode::PageUp => "PageUp".to_string(),
         KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Left if ascii => "Left".to_string(),
+        KeyCode::Right if ascii => "Right".to_string(),
+        KeyCode::Up if ascii => "Up".to_string(),
+        KeyCode::Down if ascii => "Down".to_string(),
         KeyCode::Left => "←".to_string(),
         KeyCode::Right => "→".to_string(),
         KeyCode::Up => "↑".to_string(),
@@ -443,3 +2686,758 @@ fn get_key_code_label(code: KeyCode) -> String {
         other => format!("{other:?}"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn resolve(bindings: &KeyBindings, events: &[KeyEvent]) -> Option<Bound> {
+        let mut resolver = BindingResolver::new(None);
+        events
+            .iter()
+            .fold(None, |_, event| resolver.process(bindings, *event))
+    }
+
+    fn bindings_from(ron: &str) -> (KeyBindings, Vec<String>) {
+        let config: KeyBindingConfig = parse_ron(ron).unwrap();
+        let mut problems = Vec::new();
+        let bindings = KeyBindings::from_config(config, false, &mut problems);
+        (bindings, problems)
+    }
+
+    #[test]
+    fn default_bindings_do_not_conflict() {
+        let (_, problems) = bindings_from("()");
+        assert_eq!(problems, Vec::<String>::new());
+    }
+
+    #[test]
+    fn year_edges_are_bound_to_shifted_home_and_end() {
+        let bindings = KeyBindings::default();
+        let cases = [
+            (KeyCode::Home, KeyModifiers::SHIFT, Action::StartOfYear),
+            (KeyCode::End, KeyModifiers::SHIFT, Action::EndOfYear),
+            (KeyCode::Home, KeyModifiers::NONE, Action::StartOfMonth),
+            (KeyCode::End, KeyModifiers::NONE, Action::EndOfMonth),
+        ];
+        for (code, modifiers, action) in cases {
+            let bound = resolve(&bindings, &[key(code, modifiers)]);
+            assert_eq!(bound, Some(Bound::Action(action)), "{code:?} {modifiers:?}");
+        }
+    }
+
+    #[test]
+    fn rebinding_a_year_edge_key_is_reported() {
+        let (_, problems) = bindings_from(r#"(end_of_month: ["Shift+Home"])"#);
+        assert_eq!(
+            problems,
+            ["'Shift+Home' is bound to both EndOfMonth and StartOfYear"]
+        );
+    }
+
+    #[test]
+    fn tick_rate_is_clamped_to_its_range() {
+        let cases = [
+            (None, None, false),
+            (Some(16), Some(16), false),
+            (Some(5000), Some(5000), false),
+            (Some(15), Some(16), true),
+            (Some(0), Some(16), true),
+            (Some(5001), Some(5000), true),
+        ];
+        for (configured, expected, warns) in cases {
+            let mut app = AppConfig {
+                tick_rate_ms: configured,
+                ..AppConfig::default()
+            };
+            let mut problems = Vec::new();
+            validate_app_config(&mut app, &mut problems);
+            assert_eq!(app.tick_rate_ms, expected, "{configured:?}");
+            assert_eq!(!problems.is_empty(), warns, "{configured:?}: {problems:?}");
+        }
+        let app: AppConfig = parse_ron("(tick_rate_ms: 100)").unwrap();
+        assert_eq!(app.tick_rate(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn deadline_follows_a_pending_sequence() {
+        let bindings = KeyBindings::default();
+        let timeout = Duration::from_millis(800);
+        let mut resolver = BindingResolver::new(Some(timeout));
+        assert_eq!(resolver.deadline(), None);
+        let before = Instant::now();
+        assert_eq!(
+            resolver.process(&bindings, key(KeyCode::Char('g'), KeyModifiers::NONE)),
+            None
+        );
+        let deadline = resolver.deadline().expect("g starts a sequence");
+        assert!(deadline >= before + timeout && deadline <= Instant::now() + timeout);
+        resolver.process(&bindings, key(KeyCode::Char('d'), KeyModifiers::NONE));
+        assert_eq!(resolver.deadline(), None);
+        // Without a timeout a sequence waits forever
+        let mut resolver = BindingResolver::new(None);
+        resolver.process(&bindings, key(KeyCode::Char('g'), KeyModifiers::NONE));
+        assert!(resolver.is_pending());
+        assert_eq!(resolver.deadline(), None);
+    }
+
+    #[test]
+    fn stale_sequences_expire_and_esc_cancels_them() {
+        let bindings = KeyBindings::default();
+        let g = key(KeyCode::Char('g'), KeyModifiers::NONE);
+        let d = key(KeyCode::Char('d'), KeyModifiers::NONE);
+        let esc = key(KeyCode::Esc, KeyModifiers::NONE);
+        let mut resolver = BindingResolver::new(Some(Duration::from_secs(60)));
+        resolver.process(&bindings, g);
+        assert!(!resolver.expire());
+        assert_eq!(
+            resolver.process(&bindings, d),
+            Some(Bound::Action(Action::OpenJumpPrompt))
+        );
+        // Once the timeout passes `d` starts over as a key of its own
+        let mut resolver = BindingResolver::new(Some(Duration::ZERO));
+        resolver.process(&bindings, g);
+        assert!(resolver.expire());
+        assert!(!resolver.is_pending());
+        assert!(!resolver.expire());
+        resolver.process(&bindings, g);
+        assert_eq!(
+            resolver.process(&bindings, d),
+            Some(Bound::Action(Action::OpenDateDiff))
+        );
+        // Esc drops the pending `g` without quitting; a second Esc quits
+        let mut resolver = BindingResolver::new(None);
+        resolver.process(&bindings, g);
+        assert_eq!(resolver.process(&bindings, esc), None);
+        assert!(!resolver.is_pending());
+        assert_eq!(
+            resolver.process(&bindings, esc),
+            Some(Bound::Action(Action::Quit))
+        );
+    }
+
+    #[test]
+    fn mouse_bindings_stand_alone_and_match_modifiers() {
+        let mouse = |kind: MouseEventKind, modifiers: KeyModifiers| MouseEvent {
+            kind,
+            column: 0,
+            row: 0,
+            modifiers,
+        };
+        let mut resolver = BindingResolver::new(None);
+        let bindings = KeyBindings::default();
+        let cases = [
+            (MouseEventKind::ScrollUp, Some(Action::PrevMonth)),
+            (MouseEventKind::ScrollDown, Some(Action::NextMonth)),
+            (MouseEventKind::Up(MouseButton::Left), None),
+            (MouseEventKind::Moved, None),
+        ];
+        for (kind, expected) in cases {
+            let action = resolver.process_mouse(&bindings, mouse(kind, KeyModifiers::NONE));
+            assert_eq!(action, expected, "{kind:?}");
+        }
+        // A wheel turn abandons a partly typed sequence
+        resolver.process(&bindings, key(KeyCode::Char('g'), KeyModifiers::NONE));
+        resolver.process_mouse(
+            &bindings,
+            mouse(MouseEventKind::ScrollUp, KeyModifiers::NONE),
+        );
+        assert!(!resolver.is_pending());
+
+        let (bindings, problems) = bindings_from(
+            r#"(next_year: ["Ctrl+WheelDown"], prev_year: ["g+WheelUp", "WheelUp+g"])"#,
+        );
+        let ctrl_wheel = mouse(MouseEventKind::ScrollDown, KeyModifiers::CONTROL);
+        assert_eq!(
+            resolver.process_mouse(&bindings, ctrl_wheel),
+            Some(Action::NextYear)
+        );
+        assert_eq!(
+            resolver.process_mouse(
+                &bindings,
+                mouse(MouseEventKind::ScrollDown, KeyModifiers::NONE)
+            ),
+            Some(Action::NextMonth)
+        );
+        assert_eq!(
+            resolver.process_mouse(
+                &bindings,
+                mouse(MouseEventKind::ScrollDown, KeyModifiers::ALT)
+            ),
+            None
+        );
+        assert_eq!(
+            problems,
+            [
+                "'g+WheelUp' mixes mouse input into a key sequence; mouse bindings must be a single step",
+                "'WheelUp+g' mixes mouse input into a key sequence; mouse bindings must be a single step",
+                "no key bindings configured for PrevYear; action disabled",
+            ]
+        );
+    }
+
+    #[test]
+    fn shifted_letters_match_however_they_are_written() {
+        for ignore_case in [false, true] {
+            for configured in ["G", "Shift+g"] {
+                let config: KeyBindingConfig =
+                    parse_ron(&format!(r#"(end_of_year: ["{configured}"])"#)).unwrap();
+                let bindings = KeyBindings::from_config(config, ignore_case, &mut Vec::new());
+                for modifiers in [KeyModifiers::NONE, KeyModifiers::SHIFT] {
+                    let bound = resolve(&bindings, &[key(KeyCode::Char('G'), modifiers)]);
+                    assert_eq!(
+                        bound,
+                        Some(Bound::Action(Action::EndOfYear)),
+                        "{configured} {modifiers:?} ignore_case={ignore_case}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ignoring_case_reports_shadowed_defaults() {
+        let config: KeyBindingConfig = parse_ron("()").unwrap();
+        let mut problems = Vec::new();
+        KeyBindings::from_config(config, true, &mut problems);
+        let shadowed: Vec<_> = problems
+            .iter()
+            .map(|problem| problem.split('\'').nth(1).unwrap())
+            .collect();
+        assert_eq!(shadowed, ["N", "S", "m", "Y", "e"], "{problems:?}");
+        assert!(problems.iter().all(|problem| problem.contains("shadowed")));
+        // Letters bound to the same action in both cases are not a conflict
+        let config: KeyBindingConfig = parse_ron(r#"(move_left: ["h", "H"])"#).unwrap();
+        let mut problems = Vec::new();
+        KeyBindings::from_config(config, true, &mut problems);
+        assert!(!problems.iter().any(|problem| problem.contains("'H'")));
+    }
+
+    /// A fresh directory for one test's files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("moli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn user_key_file_overrides_the_system_one_action_by_action() {
+        let dir = scratch_dir("merge");
+        let system_path = dir.join("system.ron");
+        fs::write(&system_path, r#"(quit: ["x"], move_left: ["a"])"#).unwrap();
+        let cases = [
+            ("user-quit.ron", r#"(quit: ["Q"])"#, vec!["Q"]),
+            ("user-empty.ron", "", vec!["x"]),
+            ("user-unit.ron", "()", vec!["x"]),
+        ];
+        for (name, content, quit) in cases {
+            let user_path = dir.join(name);
+            fs::write(&user_path, content).unwrap();
+            let user = read_key_file(&user_path).unwrap();
+            let system = read_key_file(&system_path).unwrap();
+            let merged = system.merge(user);
+            let expected: Vec<String> = quit.into_iter().map(String::from).collect();
+            assert_eq!(merged.quit, Some(expected), "{name}");
+            // Actions the user file leaves out keep the system keys
+            assert_eq!(merged.move_left, Some(vec!["a".to_string()]), "{name}");
+            assert_eq!(merged.move_right, None, "{name}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn key_tokens_round_trip_through_their_labels() {
+        let mut cases = vec![
+            ("esc", "Esc"),
+            ("Escape", "Esc"),
+            ("enter", "Enter"),
+            ("return", "Enter"),
+            ("backspace", "Backspace"),
+            ("tab", "Tab"),
+            ("backtab", "Shift+Tab"),
+            ("Shift+Tab", "Shift+Tab"),
+            ("delete", "Delete"),
+            ("insert", "Insert"),
+            ("home", "Home"),
+            ("end", "End"),
+            ("pageup", "PageUp"),
+            ("page_down", "PageDown"),
+            ("left", "Left"),
+            ("right", "Right"),
+            ("up", "Up"),
+            ("down", "Down"),
+            ("space", "Space"),
+            ("plus", "+"),
+            ("+", "+"),
+            ("Ctrl++", "Ctrl++"),
+            ("Ctrl+plus", "Ctrl++"),
+            ("g++", "g +"),
+            ("minus", "-"),
+            ("dash", "-"),
+            ("comma", ","),
+            ("period", "."),
+            ("dot", "."),
+            ("slash", "/"),
+            ("backslash", "\\"),
+            ("semicolon", ";"),
+            ("equals", "="),
+            ("Shift+g", "G"),
+            ("Ctrl+Alt+x", "Ctrl+Alt+x"),
+            ("g+d", "g d"),
+        ];
+        let function_keys: Vec<(String, String)> = (1..=24)
+            .map(|n| (format!("f{n}"), format!("F{n}")))
+            .collect();
+        cases.extend(
+            function_keys
+                .iter()
+                .map(|(token, label)| (token.as_str(), label.as_str())),
+        );
+        for (token, label) in cases {
+            let binding = parse_binding(token, &mut Vec::new()).unwrap();
+            assert_eq!(binding.label(true), label, "{token}");
+            // Parsing the label gives back the same keys
+            let reparsed = parse_binding(&label.replace(' ', "+"), &mut Vec::new()).unwrap();
+            assert_eq!(reparsed.sequence, binding.sequence, "{token} -> {label}");
+        }
+        // Arrows get their symbols outside ASCII mode
+        let up = parse_binding("up", &mut Vec::new()).unwrap();
+        assert_eq!(up.label(false), "↑");
+        for token in ["f0", "f25", "f255", "f-1", "ctrl+f25"] {
+            assert!(parse_binding(token, &mut Vec::new()).is_err(), "{token}");
+        }
+    }
+
+    #[test]
+    fn toml_files_parse_and_ron_takes_precedence() {
+        let dir = scratch_dir("formats");
+        let write = |name: &str, content: &str| {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let quit = Some(vec!["x".to_string()]);
+        let flat = write("key_bindings.toml", "quit = [\"x\"]\n");
+        assert_eq!(read_key_file(&flat).unwrap().quit, quit);
+        let wrapped = write("wrapped.toml", "[bindings]\nquit = [\"x\"]\n");
+        assert_eq!(read_key_file(&wrapped).unwrap().quit, quit);
+        let wrapped = write("wrapped.ron", r#"(bindings: (quit: ["x"]))"#);
+        assert_eq!(read_key_file(&wrapped).unwrap().quit, quit);
+        let app = write(
+            "config.toml",
+            "week_start = \"sunday\"\nfixed_six_rows = true\n",
+        );
+        let app = read_app_config(&app).unwrap().unwrap();
+        assert_eq!(app.week_start, WeekStart::Sunday);
+        assert!(app.fixed_six_rows);
+        assert_eq!(app.date_format, DEFAULT_DATE_FORMAT);
+
+        // Without a known extension RON is tried first, then TOML
+        let ron = write("keys", r#"(quit: ["x"])"#);
+        assert_eq!(read_key_file(&ron).unwrap().quit, quit);
+        let toml = write("keys.conf", "quit = [\"x\"]\n");
+        assert_eq!(read_key_file(&toml).unwrap().quit, quit);
+        // Neither format parses: the RON error is the one reported
+        let broken = write("broken", "quit = [");
+        let err = read_key_file(&broken).unwrap_err();
+        assert!(err.starts_with("failed to parse key config"), "{err}");
+        assert!(!err.contains("TOML"), "{err}");
+        // A .toml extension is not read as RON
+        let mislabelled = write("mislabelled.toml", r#"(quit: ["x"])"#);
+        assert!(read_key_file(&mislabelled).is_err());
+
+        write("key_bindings.ron", r#"(quit: ["y"])"#);
+        let found = existing_files_in(&dir, KEY_CONFIG_FILE_STEM);
+        assert_eq!(
+            found,
+            [dir.join("key_bindings.ron"), dir.join("key_bindings.toml")]
+        );
+        let mut problems = Vec::new();
+        let mut found = found.into_iter();
+        let path = found.next().unwrap();
+        report_ignored(&path, found, &mut problems);
+        assert_eq!(
+            problems,
+            [format!(
+                "both {path:?} and {flat:?} exist; using {path:?} (RON takes precedence over TOML)"
+            )]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn default_config_files_parse_back_cleanly() {
+        let dir = scratch_dir("defaults");
+        for (stem, content) in DEFAULT_CONFIG_FILES {
+            let path = dir.join(format!("{stem}.ron"));
+            fs::write(&path, content).unwrap();
+            let mut problems = Vec::new();
+            check_file(ConfigKind::of(&path), &path, &mut problems);
+            assert_eq!(problems, Vec::<String>::new(), "{stem}");
+        }
+        let app = read_app_config(&dir.join("config.ron")).unwrap().unwrap();
+        assert_eq!(app.date_format, AppConfig::default().date_format);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn check_reports_unknown_fields() {
+        let dir = scratch_dir("check");
+        let cases = [
+            ("key_bindings.ron", r#"(quit: ["q"], qiut: ["x"])"#, "qiut"),
+            (
+                "wrapped.ron",
+                r#"(bindings: (quit: ["q"], qiut: ["x"]))"#,
+                "qiut",
+            ),
+            (
+                "key_bindings.toml",
+                "quit = [\"q\"]\nqiut = [\"x\"]\n",
+                "qiut",
+            ),
+            (
+                "wrapped.toml",
+                "[bindings]\nquit = [\"q\"]\nqiut = [\"x\"]\n",
+                "qiut",
+            ),
+            (
+                "config.ron",
+                "(week_start: monday, weekstart: sunday)",
+                "weekstart",
+            ),
+            (
+                "config.toml",
+                "week_start = \"monday\"\nweekstart = \"sunday\"\n",
+                "weekstart",
+            ),
+        ];
+        for (name, content, unknown) in cases {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            let mut problems = Vec::new();
+            check_file(ConfigKind::of(&path), &path, &mut problems);
+            assert_eq!(
+                problems,
+                [format!("unknown field `{unknown}` in {path:?}")],
+                "{name}"
+            );
+        }
+        // A wrapper beside other keys is not looked through
+        assert_eq!(
+            top_level_keys(Path::new("keys.toml"), "quit = [\"q\"]\n[bindings]\n"),
+            ["bindings", "quit"]
+        );
+        assert_eq!(
+            top_level_keys(Path::new("keys.ron"), "(("),
+            Vec::<String>::new()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn partial_general_settings_keep_the_defaults() {
+        let defaults = AppConfig::default();
+        assert_eq!(defaults.week_start, WeekStart::Monday);
+        assert_eq!(defaults.paging, PagingPolicy::Clamp);
+        assert!(defaults.show_almanac && !defaults.fixed_six_rows);
+        assert_eq!(defaults.countdown, DEFAULT_COUNTDOWN);
+        assert_eq!(defaults.date_format, DEFAULT_DATE_FORMAT);
+        assert_eq!(
+            defaults.sequence_timeout(),
+            Some(Duration::from_millis(800))
+        );
+        assert_eq!(defaults.tick_rate(), None);
+
+        let app: AppConfig = parse_ron("(paging: weekday, ics_files: [\"a.ics\"])").unwrap();
+        assert_eq!(app.paging, PagingPolicy::Weekday);
+        assert_eq!(app.ics_files, ["a.ics"]);
+        assert_eq!(app.week_start, defaults.week_start);
+        assert_eq!(app.details_width_percent, defaults.details_width_percent);
+        let app: AppConfig = parse_ron("(sequence_timeout_ms: 0)").unwrap();
+        assert_eq!(app.sequence_timeout(), None);
+    }
+
+    #[test]
+    fn legacy_settings_in_the_key_file_take_precedence() {
+        let mut app: AppConfig = parse_ron(
+            "(week_start: \"sunday\", paging: \"restore\", show_almanac: true, ics_files: [\"a.ics\"])",
+        )
+        .unwrap();
+        let mut keys: KeyBindingConfig = parse_ron(
+            "(week_start: \"monday\", show_almanac: false, fixed_six_rows: true, ics_files: [\"b.ics\"])",
+        )
+        .unwrap();
+        keys.apply_legacy_settings(&mut app);
+        assert_eq!(app.week_start, WeekStart::Monday);
+        assert!(!app.show_almanac);
+        assert!(app.fixed_six_rows);
+        // Settings the key file leaves out stay as config.ron has them
+        assert_eq!(app.paging, PagingPolicy::Restore);
+        // Lists are combined rather than replaced
+        assert_eq!(app.ics_files, ["a.ics", "b.ics"]);
+        assert_eq!(keys.week_start, None);
+        assert_eq!(keys.ics_files, None);
+    }
+
+    #[test]
+    fn none_or_an_empty_list_disables_an_action_quietly() {
+        let (bindings, problems) = bindings_from(
+            r#"(toggle_agenda: [], show_help: ["none"], search_notes: ["None", " none "])"#,
+        );
+        assert_eq!(problems, Vec::<String>::new());
+        for action in [Action::ToggleAgenda, Action::ShowHelp, Action::SearchNotes] {
+            assert!(bindings.labels_for(action).is_empty(), "{action:?}");
+        }
+        let unbound = [
+            key(KeyCode::Char('a'), KeyModifiers::NONE),
+            key(KeyCode::Char('?'), KeyModifiers::NONE),
+            key(KeyCode::Char('/'), KeyModifiers::NONE),
+        ];
+        for event in unbound {
+            assert_eq!(resolve(&bindings, &[event]), None, "{event:?}");
+        }
+        // Keys that all fail to parse still disable the action, but say so
+        let (bindings, problems) = bindings_from(r#"(toggle_agenda: ["Hyper+a"])"#);
+        assert!(bindings.labels_for(Action::ToggleAgenda).is_empty());
+        assert_eq!(
+            problems.last().unwrap(),
+            "no key bindings configured for ToggleAgenda; action disabled"
+        );
+    }
+
+    #[test]
+    fn weekend_days_parse_short_and_full_names() {
+        let weekend = |ron: &str| {
+            let mut app: AppConfig = parse_ron(&format!("(weekend: {ron})")).unwrap();
+            let warnings = app.weekend.take_warnings();
+            let days: Vec<Weekday> = [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ]
+            .into_iter()
+            .filter(|day| app.weekend.contains(*day))
+            .collect();
+            (days, warnings)
+        };
+        let none = Vec::<String>::new();
+        assert_eq!(
+            weekend(r#"["fri", "Saturday"]"#),
+            (vec![Weekday::Fri, Weekday::Sat], none.clone())
+        );
+        assert_eq!(weekend(r#"[" sun "]"#), (vec![Weekday::Sun], none.clone()));
+        assert_eq!(weekend("[]"), (vec![], none));
+        // One unknown day drops the whole list for the default weekend
+        let (days, warnings) = weekend(r#"["fri", "funday"]"#);
+        assert_eq!(days, [Weekday::Sat, Weekday::Sun]);
+        assert_eq!(
+            warnings,
+            [
+                "unknown weekend day \"funday\" (use mon, tue, wed, thu, fri, sat or sun); using [\"sat\", \"sun\"]"
+            ]
+        );
+        let mut problems = Vec::new();
+        let mut app: AppConfig = parse_ron(r#"(weekend: ["x"])"#).unwrap();
+        validate_app_config(&mut app, &mut problems);
+        assert_eq!(problems.len(), 1);
+    }
+
+    #[test]
+    fn label_priority_fills_in_and_rejects_bad_lists() {
+        let priority = |ron: &str| {
+            let mut app: AppConfig = parse_ron(&format!("(label_priority: {ron})")).unwrap();
+            let warnings = app.label_priority.take_warnings();
+            (app.label_priority.order().to_vec(), warnings)
+        };
+        let (order, warnings) = priority(r#"["lunar", "solar_term"]"#);
+        assert_eq!(
+            order,
+            [
+                LabelKind::Lunar,
+                LabelKind::SolarTerm,
+                LabelKind::Holiday,
+                LabelKind::Birthday,
+                LabelKind::Festival,
+            ]
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(priority("[]").0, LabelKind::ALL);
+        let (order, warnings) = priority(r#"["lunar", " lunar "]"#);
+        assert_eq!(order, LabelKind::ALL);
+        assert_eq!(
+            warnings,
+            ["label_priority lists \" lunar \" twice; using the default order"]
+        );
+        let (order, warnings) = priority(r#"["holiday", "moon"]"#);
+        assert_eq!(order, LabelKind::ALL);
+        assert_eq!(
+            warnings,
+            [
+                "unknown label_priority entry \"moon\" (use holiday, birthday, solar_term, festival or lunar); using the default order"
+            ]
+        );
+    }
+
+    #[test]
+    fn jump_targets_parse() {
+        let cases = [
+            (
+                "2025-10-01",
+                Ok(JumpTarget::Date(
+                    NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(),
+                )),
+            ),
+            (" today ", Ok(JumpTarget::Today(0))),
+            ("today+7", Ok(JumpTarget::Today(7))),
+            ("today + 7", Ok(JumpTarget::Today(7))),
+            ("today-30", Ok(JumpTarget::Today(-30))),
+            ("next:中秋", Ok(JumpTarget::Next("中秋节"))),
+            ("next:Mid-Autumn Festival", Ok(JumpTarget::Next("中秋节"))),
+            ("next:冬至", Ok(JumpTarget::Next("冬至"))),
+            (
+                "next:月圆",
+                Err("unknown holiday, festival or solar term '月圆'".to_string()),
+            ),
+            (
+                "todayish",
+                Err("'todayish' is not today, today+N or today-N".to_string()),
+            ),
+            (
+                "2025-13-01",
+                Err(
+                    "'2025-13-01' is not a YYYY-MM-DD date, today, today±N or next:<name>"
+                        .to_string(),
+                ),
+            ),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(JumpTarget::parse(raw), expected, "{raw}");
+        }
+    }
+
+    #[test]
+    fn actions_keep_keys_a_jump_shortcut_also_claims() {
+        let (bindings, problems) = bindings_from(
+            r#"(jump_shortcuts: [
+                (keys: ["n", "g+1"], target: "today+1", name: "tomorrow"),
+                (keys: ["WheelUp"], target: "today"),
+                (keys: ["g+2"], target: "someday"),
+            ])"#,
+        );
+        assert_eq!(
+            problems,
+            [
+                "jump shortcut 'today' can't use the mouse input 'WheelUp'",
+                "no key bindings configured for jump shortcut 'today'; shortcut skipped",
+                "jump shortcut skipped: 'someday' is not a YYYY-MM-DD date, today, today±N or next:<name>",
+                "'n' is bound to both NextHoliday and jump shortcut 'tomorrow'",
+            ]
+        );
+        let n = key(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(
+            resolve(&bindings, &[n]),
+            Some(Bound::Action(Action::NextHoliday))
+        );
+        let g = key(KeyCode::Char('g'), KeyModifiers::NONE);
+        let one = key(KeyCode::Char('1'), KeyModifiers::NONE);
+        assert_eq!(resolve(&bindings, &[g, one]), Some(Bound::Jump(0)));
+        assert_eq!(bindings.jump_shortcut(0).name, "tomorrow");
+        assert_eq!(bindings.jump_shortcut(0).target, JumpTarget::Today(1));
+    }
+
+    #[test]
+    fn date_formats_must_format_a_bare_date() {
+        for format in [
+            "%Y-%m-%d",
+            "%Y年%-m月%-d日",
+            "%d/%m/%Y %a",
+            "%F",
+            "%%Y",
+            "week %V",
+        ] {
+            assert!(is_date_format(format), "{format}");
+        }
+        for format in ["", "  ", "%H:%M", "%Y-%m-%d %T", "%Q", "%Y-%", "%s"] {
+            assert!(!is_date_format(format), "{format}");
+        }
+        let mut app: AppConfig =
+            parse_ron(r#"(date_format: "%Y %H", month_format: "%Y-%m")"#).unwrap();
+        let mut problems = Vec::new();
+        validate_app_config(&mut app, &mut problems);
+        assert_eq!(app.date_format, DEFAULT_DATE_FORMAT);
+        assert_eq!(app.month_format.as_deref(), Some("%Y-%m"));
+        assert_eq!(
+            problems,
+            ["date_format \"%Y %H\" cannot format a date; using \"%Y-%m-%d\""]
+        );
+    }
+
+    #[test]
+    fn ascii_mode_names_every_key_in_ascii() {
+        assert_eq!(
+            KeyBindings::default().labels_for(Action::PrevMonth),
+            ["←", "Wheel↑"]
+        );
+        let bindings = KeyBindings::default().with_ascii_labels(true);
+        assert_eq!(bindings.labels_for(Action::PrevMonth), ["Left", "WheelUp"]);
+        for (action, _, _) in ACTIONS {
+            for label in bindings.labels_for(*action) {
+                assert!(label.is_ascii(), "{action:?}: {label}");
+            }
+        }
+        for (_, labels) in bindings.jump_shortcuts() {
+            assert!(labels.iter().all(|label| label.is_ascii()), "{labels:?}");
+        }
+    }
+
+    #[test]
+    fn every_help_bar_group_has_bound_actions() {
+        let bindings = KeyBindings::default();
+        for group in ActionGroup::ALL {
+            assert!(
+                ACTIONS.iter().any(|(action, _, entry)| *entry == group
+                    && !bindings.labels_for(*action).is_empty()),
+                "{group:?}"
+            );
+        }
+        // The picker's confirmation sits with the app actions, next to quitting
+        assert_eq!(
+            ACTIONS[1],
+            (Action::ConfirmPick, "选定日期", ActionGroup::App)
+        );
+    }
+
+    #[test]
+    fn today_marker_parses_and_drops_wide_symbols() {
+        let marker = AppConfig::default().today_marker;
+        assert!(marker.symbol.is_empty() && !marker.underline);
+        assert_eq!(marker.position, MarkerPosition::After);
+        let app: AppConfig =
+            parse_ron(r#"(today_marker: (symbol: "今", position: before, underline: true))"#)
+                .unwrap();
+        assert_eq!(app.today_marker.symbol, "今");
+        assert_eq!(app.today_marker.position, MarkerPosition::Before);
+        assert!(app.today_marker.underline);
+        let app: AppConfig = toml::from_str("[today_marker]\nsymbol = \"●\"\n").unwrap();
+        assert_eq!(app.today_marker.symbol, "●");
+        assert_eq!(app.today_marker.position, MarkerPosition::After);
+
+        let mut app: AppConfig = parse_ron(r#"(today_marker: (symbol: "今天"))"#).unwrap();
+        let mut problems = Vec::new();
+        validate_app_config(&mut app, &mut problems);
+        assert_eq!(app.today_marker.symbol, "");
+        assert_eq!(
+            problems,
+            ["today_marker.symbol \"今天\" is 4 columns wide, more than 2; not showing it"]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::fsutil::write_atomically;
+
+const COUNTDOWNS_FILE_ENV: &str = "MOLI_COUNTDOWNS_FILE";
+const COUNTDOWNS_FILE_NAME: &str = "countdowns.json";
+const DATA_DIR_NAME: &str = "moli";
+
+/// A target date kept in view below the calendar, e.g. an exam or a deadline
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Countdown {
+    pub name: String,
+    pub date: NaiveDate,
+}
+
+impl Countdown {
+    /// Days from `today` to the target, negative once it has passed
+    pub fn days_from(&self, today: NaiveDate) -> i64 {
+        (self.date - today).num_days()
+    }
+}
+
+/// Countdowns pinned while running, saved as a JSON array in the data directory
+#[derive(Clone, Debug, Default)]
+pub struct CountdownStore {
+    /// Where changes are written; None keeps them in memory only
+    path: Option<PathBuf>,
+    pinned: Vec<Countdown>,
+}
+
+impl CountdownStore {
+    pub fn pinned(&self) -> &[Countdown] {
+        &self.pinned
+    }
+
+    /// Pin a countdown to the date, or unpin the one already there, and save the file
+    /// Returns the countdown now pinned, None when one was removed
+    pub fn toggle(&mut self, date: NaiveDate, name: String) -> io::Result<Option<&Countdown>> {
+        if let Some(index) = self
+            .pinned
+            .iter()
+            .position(|countdown| countdown.date == date)
+        {
+            self.pinned.remove(index);
+            self.save()?;
+            return Ok(None);
+        }
+        self.pinned.push(Countdown { name, date });
+        self.pinned.sort_by_key(|countdown| countdown.date);
+        self.save()?;
+        Ok(self.pinned.iter().find(|countdown| countdown.date == date))
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let content = serde_json::to_string_pretty(&self.pinned).map_err(io::Error::other)?;
+        write_atomically(path, &content)
+    }
+}
+
+/// Load the pinned countdowns from the user's data directory
+/// A missing file starts empty; unreadable files are reported in the returned warnings and
+/// left untouched
+pub fn load_countdowns() -> (CountdownStore, Vec<String>) {
+    let Some(path) = env::var_os(COUNTDOWNS_FILE_ENV)
+        .map(PathBuf::from)
+        .or_else(default_countdowns_path)
+    else {
+        return (CountdownStore::default(), Vec::new());
+    };
+    if !path.exists() {
+        let store = CountdownStore {
+            path: Some(path),
+            pinned: Vec::new(),
+        };
+        return (store, Vec::new());
+    }
+    match load_from_path(&path) {
+        Ok(pinned) => {
+            let store = CountdownStore {
+                path: Some(path),
+                pinned,
+            };
+            (store, Vec::new())
+        }
+        // Keep changes in memory so a broken file is not overwritten
+        Err(warning) => (CountdownStore::default(), vec![warning]),
+    }
+}
+
+fn load_from_path(path: &Path) -> Result<Vec<Countdown>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read countdowns file {}: {err}", path.display()))?;
+    serde_json::from_str(&content).map_err(|err| {
+        format!(
+            "failed to parse countdowns file {}: {err}; countdowns will not be saved",
+            path.display()
+        )
+    })
+}
+
+fn default_countdowns_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|mut dir| {
+        dir.push(DATA_DIR_NAME);
+        dir.push(COUNTDOWNS_FILE_NAME);
+        dir
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn days_count_across_leap_years() {
+        let target = |date| Countdown {
+            name: "高考".to_string(),
+            date,
+        };
+        assert_eq!(target(date(2024, 3, 1)).days_from(date(2024, 2, 28)), 2);
+        assert_eq!(target(date(2025, 3, 1)).days_from(date(2025, 2, 28)), 1);
+        assert_eq!(target(date(2024, 3, 1)).days_from(date(2023, 3, 1)), 366);
+        assert_eq!(target(date(2026, 6, 7)).days_from(date(2026, 6, 7)), 0);
+        assert_eq!(target(date(2024, 2, 29)).days_from(date(2025, 2, 28)), -365);
+    }
+
+    #[test]
+    fn pinned_countdowns_round_trip_through_the_file() {
+        let dir = env::temp_dir().join(format!("moli-countdowns-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join(COUNTDOWNS_FILE_NAME);
+        let mut store = CountdownStore {
+            path: Some(path.clone()),
+            pinned: Vec::new(),
+        };
+        store.toggle(date(2026, 6, 7), "高考".to_string()).unwrap();
+        let pinned = store.toggle(date(2025, 9, 30), "截止".to_string()).unwrap();
+        assert_eq!(pinned.unwrap().name, "截止");
+        // Kept in date order, on disk as in memory
+        assert_eq!(load_from_path(&path).unwrap(), store.pinned());
+        assert_eq!(store.pinned()[0].date, date(2025, 9, 30));
+        // Toggling a pinned date again unpins it
+        assert_eq!(
+            store.toggle(date(2025, 9, 30), String::new()).unwrap(),
+            None
+        );
+        assert_eq!(load_from_path(&path).unwrap(), store.pinned());
+        assert_eq!(store.pinned().len(), 1);
+
+        fs::write(&path, "[{\"name\": \"高考\"}]").unwrap();
+        let err = load_from_path(&path).unwrap_err();
+        assert!(err.contains("countdowns will not be saved"), "{err}");
+        // A store without a path keeps changes in memory only
+        let mut memory = CountdownStore::default();
+        memory.toggle(date(2026, 6, 7), "高考".to_string()).unwrap();
+        assert_eq!(memory.pinned().len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[{\"name\": \"高考\"}]");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,451 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{app, i18n::Locale, lunar, schedule::HolidaySchedule};
+
+/// Longest content line allowed by RFC 5545, in octets
+const ICS_LINE_LIMIT: usize = 75;
+
+/// Calendar events imported from ICS files and the plain-text events file
+#[derive(Clone, Debug, Default)]
+pub struct EventStore {
+    /// One-off events by date
+    single: BTreeMap<NaiveDate, Vec<String>>,
+    /// `FREQ=YEARLY` events, repeating on the start date's month and day
+    yearly: Vec<(NaiveDate, String)>,
+    /// `MM-DD` lines of the events file, repeating every year
+    annual: Vec<(u32, u32, String)>,
+    /// `L MM-DD` lines of the events file, repeating on the lunar month and day outside leap months
+    /// Day 30 is skipped in years where that month has only 29 days, like `lunar_birthdays`
+    lunar: Vec<(u8, u8, String)>,
+}
+
+impl EventStore {
+    pub fn is_empty(&self) -> bool {
+        self.single.is_empty()
+            && self.yearly.is_empty()
+            && self.annual.is_empty()
+            && self.lunar.is_empty()
+    }
+
+    /// Summaries of every event on the date, one-off events first
+    pub fn events_on(&self, date: NaiveDate) -> Vec<String> {
+        let single = self.single.get(&date).into_iter().flatten().cloned();
+        let yearly = self
+            .yearly
+            .iter()
+            .filter(|(start, _)| {
+                *start <= date && start.month() == date.month() && start.day() == date.day()
+            })
+            .map(|(_, summary)| summary.clone());
+        let annual = self
+            .annual
+            .iter()
+            .filter(|(month, day, _)| *month == date.month() && *day == date.day())
+            .map(|(_, _, summary)| summary.clone());
+        let lunar_date = (!self.lunar.is_empty())
+            .then(|| lunar::solar_to_lunar(date))
+            .flatten()
+            .map(|info| info.date)
+            .filter(|lunar_date| !lunar_date.is_leap);
+        let lunar = self
+            .lunar
+            .iter()
+            .filter(|(month, day, _)| {
+                lunar_date
+                    .is_some_and(|lunar_date| lunar_date.month == *month && lunar_date.day == *day)
+            })
+            .map(|(_, _, summary)| summary.clone());
+        single.chain(yearly).chain(annual).chain(lunar).collect()
+    }
+
+    /// Add the lines of a plain-text events file, returning warnings for lines that were skipped
+    /// Each line is `YYYY-MM-DD text`, `MM-DD text` for every year or `L MM-DD text` for a lunar
+    /// date; blank lines and lines starting with `#` are ignored
+    fn merge_text(&mut self, content: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match parse_event_line(line) {
+                Some(EventLine::Date(date, text)) => self.single.entry(date).or_default().push(text),
+                Some(EventLine::Yearly(month, day, text)) => self.annual.push((month, day, text)),
+                Some(EventLine::Lunar(month, day, text)) => self.lunar.push((month, day, text)),
+                None => warnings.push(format!(
+                    "line {}: cannot read {line:?} (use \"YYYY-MM-DD text\", \"MM-DD text\" or \"L MM-DD text\"); skipping it",
+                    index + 1
+                )),
+            }
+        }
+        warnings
+    }
+
+    /// Add the events of one ICS document, returning warnings for skipped content
+    fn merge_ics(&mut self, content: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for event in parse_vevents(content) {
+            let Some(start) = event.start else {
+                warnings.push(format!("event without a DTSTART date: {:?}", event.summary));
+                continue;
+            };
+            let summary = event.summary.unwrap_or_else(|| "(无标题)".to_string());
+            match event.rrule.as_deref() {
+                None => self.single.entry(start).or_default().push(summary),
+                Some(rule) if is_plain_yearly(rule) => self.yearly.push((start, summary)),
+                Some(rule) => {
+                    warnings.push(format!(
+                        "unsupported RRULE '{rule}' for '{summary}', showing the first occurrence only"
+                    ));
+                    self.single.entry(start).or_default().push(summary);
+                }
+            }
+        }
+        warnings
+    }
+}
+
+/// Load events from the configured ICS files and plain-text events file
+/// Unreadable files and unsupported entries are reported and skipped; problems with the events
+/// file are also returned so they can be shown beside the config warnings
+pub fn load_events(paths: &[PathBuf], events_file: Option<&Path>) -> (EventStore, Vec<String>) {
+    let mut store = EventStore::default();
+    let mut warnings = Vec::new();
+    for path in paths {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                warnings.push(format!("failed to read ICS file {}: {err}", path.display()));
+                continue;
+            }
+        };
+        log::info!("loading ICS file {path:?}");
+        warnings.extend(
+            store
+                .merge_ics(&content)
+                .into_iter()
+                .map(|warning| format!("{}: {warning}", path.display())),
+        );
+    }
+    if let Some(path) = events_file {
+        log::info!("loading events file {path:?}");
+        match fs::read_to_string(path) {
+            Ok(content) => warnings.extend(
+                store
+                    .merge_text(&content)
+                    .into_iter()
+                    .map(|warning| format!("{}: {warning}", path.display())),
+            ),
+            Err(err) => warnings.push(format!(
+                "failed to read events file {}: {err}",
+                path.display()
+            )),
+        }
+    }
+    for warning in &warnings {
+        log::warn!("{warning}");
+    }
+    (store, warnings)
+}
+
+/// Render every holiday of the year as an ICS calendar of all-day events
+/// UIDs derive from the date and the holiday's name, so re-imports replace instead of duplicate
+pub fn holidays_ics(year: i32, schedule: &HolidaySchedule) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//MoLi//Holidays//ZH".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(&format!("{year} 年节假日"))),
+    ];
+    for (date, info) in app::holidays_for_year(year) {
+        let end = date.succ_opt().unwrap_or(date);
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@moli", holiday_uid(date, info.name)),
+            format!("DTSTAMP:{year}0101T000000Z"),
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            format!("SUMMARY:{}", escape_text(info.name)),
+            format!(
+                "DESCRIPTION:{}",
+                escape_text(&schedule.note(&info, year, Locale::Zh))
+            ),
+            format!(
+                "CATEGORIES:{}",
+                escape_text(info.category.label(Locale::Zh))
+            ),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+/// Date and hex-encoded UTF-8 name, e.g. "20251001-e59bbde5ba86e88a82"
+/// Stays the same across releases and whatever other holidays share the date
+fn holiday_uid(date: NaiveDate, name: &str) -> String {
+    let hex: String = name.bytes().map(|byte| format!("{byte:02x}")).collect();
+    format!("{}-{hex}", date.format("%Y%m%d"))
+}
+
+/// Escape text values: backslash, semicolon, comma and newline
+fn escape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' | ';' | ',' => {
+                result.push('\\');
+                result.push(ch);
+            }
+            '\n' => result.push_str("\\n"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+/// Fold a content line at 75 octets without splitting UTF-8 characters, ending with CRLF
+fn fold_line(line: &str) -> String {
+    let mut result = String::with_capacity(line.len() + 2);
+    let mut used = 0;
+    for ch in line.chars() {
+        if used + ch.len_utf8() > ICS_LINE_LIMIT {
+            result.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            used = 1;
+        }
+        result.push(ch);
+        used += ch.len_utf8();
+    }
+    result.push_str("\r\n");
+    result
+}
+
+/// A parsed line of the plain-text events file
+#[derive(Debug)]
+enum EventLine {
+    Date(NaiveDate, String),
+    Yearly(u32, u32, String),
+    Lunar(u8, u8, String),
+}
+
+/// Read `YYYY-MM-DD text`, `MM-DD text` or `L MM-DD text`; the text must not be empty
+fn parse_event_line(line: &str) -> Option<EventLine> {
+    let (lunar, rest) = match line.strip_prefix(['L', 'l']) {
+        Some(rest) if rest.starts_with(char::is_whitespace) => (true, rest.trim_start()),
+        _ => (false, line),
+    };
+    let (date, text) = rest.split_once(char::is_whitespace)?;
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let text = text.to_string();
+    if lunar {
+        let (month, day) = date.split_once('-')?;
+        let (month, day) = (month.parse::<u8>().ok()?, day.parse::<u8>().ok()?);
+        return ((1..=12).contains(&month) && (1..=30).contains(&day))
+            .then_some(EventLine::Lunar(month, day, text));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        return Some(EventLine::Date(date, text));
+    }
+    let (month, day) = date.split_once('-')?;
+    let (month, day) = (month.parse::<u32>().ok()?, day.parse::<u32>().ok()?);
+    // A leap year accepts every month and day that can ever occur
+    NaiveDate::from_ymd_opt(2000, month, day).map(|_| EventLine::Yearly(month, day, text))
+}
+
+/// The fields MoLi uses from a VEVENT
+#[derive(Debug, Default)]
+struct VEvent {
+    start: Option<NaiveDate>,
+    summary: Option<String>,
+    rrule: Option<String>,
+}
+
+/// Collect VEVENT blocks after unfolding continuation lines
+fn parse_vevents(content: &str) -> Vec<VEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<VEvent> = None;
+    for line in unfold_lines(content) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as `;VALUE=DATE` or `;TZID=…` follow the property name
+        let property = name.split(';').next().unwrap_or_default();
+        match (property.to_ascii_uppercase().as_str(), current.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(VEvent::default());
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                events.extend(current.take());
+            }
+            ("DTSTART", Some(event)) => event.start = parse_ics_date(value),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape_text(value)),
+            ("RRULE", Some(event)) => event.rrule = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Join lines that continue with a leading space or tab
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        if let Some(rest) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+/// Read the date part of `20250101` or `20250101T090000Z`; times and zones are ignored
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Whether the rule is a yearly repetition without extra constraints
+fn is_plain_yearly(rule: &str) -> bool {
+    rule.split(';').all(|part| {
+        let (key, value) = part.split_once('=').unwrap_or((part, ""));
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => value.eq_ignore_ascii_case("YEARLY"),
+            "INTERVAL" => value == "1",
+            "WKST" => true,
+            _ => false,
+        }
+    }) && rule.to_ascii_uppercase().contains("FREQ=YEARLY")
+}
+
+/// Undo ICS text escaping; escaped newlines become spaces for single-line display
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push(' '),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn sample_ics_file() {
+        let mut store = EventStore::default();
+        let warnings = store.merge_ics(include_str!("../tests/fixtures/sample.ics"));
+        assert_eq!(
+            warnings,
+            [
+                "unsupported RRULE 'FREQ=WEEKLY;BYDAY=MO' for '周会', showing the first occurrence only",
+                "event without a DTSTART date: Some(\"待定\")",
+            ]
+        );
+        assert_eq!(store.events_on(date(2025, 3, 12)), ["牙医复诊"]);
+        assert!(store.events_on(date(2025, 3, 13)).is_empty());
+        // FREQ=YEARLY repeats from the start date on
+        assert_eq!(store.events_on(date(2030, 8, 5)), ["妈妈生日"]);
+        assert!(store.events_on(date(1989, 8, 5)).is_empty());
+        // An unsupported rule keeps only the first occurrence
+        assert_eq!(store.events_on(date(2025, 1, 6)), ["周会"]);
+        assert!(store.events_on(date(2025, 1, 13)).is_empty());
+        assert_eq!(
+            store.events_on(date(2025, 5, 1)),
+            ["Trip to Hangzhou, Suzhou and Shanghai with the whole family for the May Day holiday"]
+        );
+    }
+
+    #[test]
+    fn unreadable_ics_gives_no_events() {
+        let mut store = EventStore::default();
+        let warnings = store.merge_ics("not a calendar\r\nBEGIN:VEVENT\r\nDTSTART:garbage\r\n");
+        assert!(warnings.is_empty());
+        assert!(store.is_empty());
+    }
+
+    /// Bundled 2025 data with the 劳动节 note of `holidays-2025.json`, which has a comma to escape
+    #[test]
+    fn holidays_ics_matches_golden_file() {
+        let path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/holidays-2025.json");
+        let (schedule, _) = crate::schedule::schedule_with_file(Some(&path));
+        let ics = holidays_ics(2025, &schedule);
+        assert_eq!(ics, include_str!("../tests/fixtures/holidays-2025.ics"));
+        assert!(ics.split_inclusive('\n').all(|line| line.ends_with("\r\n")));
+        assert!(ics.split("\r\n").all(|line| line.len() <= ICS_LINE_LIMIT));
+        assert!(ics.contains("\r\n 共3天\\; 4月27日上班\\, 以通知为准\r\n"));
+    }
+
+    #[test]
+    fn holiday_uids_are_unique_and_stable() {
+        let ics = holidays_ics(2020, &HolidaySchedule::bundled());
+        let uids: Vec<_> = ics
+            .lines()
+            .filter(|line| line.starts_with("UID:"))
+            .collect();
+        let unique: std::collections::BTreeSet<_> = uids.iter().collect();
+        assert_eq!(unique.len(), uids.len());
+        // 国庆节 and 中秋节 share 2020-10-01 but keep their own UIDs
+        assert!(uids.contains(&"UID:20201001-e59bbde5ba86e88a82@moli"));
+        assert!(uids.contains(&"UID:20201001-e4b8ade7a78be88a82@moli"));
+    }
+
+    #[test]
+    fn file_problems_come_back_as_warnings() {
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let sample = fixtures.join("sample.ics");
+        let missing = fixtures.join("no-such-calendar.ics");
+        let (store, warnings) = load_events(&[sample.clone(), missing.clone()], None);
+        assert_eq!(store.events_on(date(2025, 3, 12)), ["牙医复诊"]);
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+        assert!(warnings[0].starts_with(&format!("{}: unsupported RRULE", sample.display())));
+        assert!(warnings[2].starts_with(&format!("failed to read ICS file {}", missing.display())));
+    }
+
+    #[test]
+    fn events_file_fixture() {
+        let mut store = EventStore::default();
+        let warnings = store.merge_text(include_str!("../tests/fixtures/events.txt"));
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].starts_with("line 7: cannot read \"13-01 月份不存在\""));
+        assert_eq!(store.events_on(date(2025, 9, 30)), ["项目截止"]);
+        assert!(store.events_on(date(2026, 9, 30)).is_empty());
+        assert_eq!(store.events_on(date(2025, 6, 18)), ["结婚纪念日"]);
+        assert_eq!(store.events_on(date(2030, 6, 18)), ["结婚纪念日"]);
+        // 八月廿三 of the lunar year
+        assert_eq!(store.events_on(date(2025, 10, 14)), ["妈妈生日"]);
+        // 腊月三十 fires where the month is long; 腊月 of lunar 2025 ends on 廿九 (2026-02-16)
+        assert_eq!(store.events_on(date(2024, 2, 9)), ["守岁"]);
+        let start = date(2026, 1, 1);
+        for day in start.iter_days().take(59) {
+            assert!(!store.events_on(day).contains(&"守岁".to_string()), "{day}");
+        }
+    }
+}
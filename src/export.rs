@@ -0,0 +1,94 @@
+use std::iter;
+
+use chrono::Datelike;
+
+use crate::{app::App, i18n};
+
+/// Render one month as a Markdown table built from the same cells the TUI draws: a weekday
+/// header, then a row per week with each day's number and label
+/// Days with a holiday are bold and today is bracketed; the neighbouring months' days are
+/// italic, or left blank when `show_adjacent_days` is off
+pub fn month_markdown(app: &App, year: i32, month: u32) -> String {
+    let locale = app.locale();
+    let label_priority = app.label_priority();
+    let mut out = format!("## {}\n\n", app.month_title(year, month));
+    let header: Vec<&str> =
+        iter::successors(Some(app.week_start().weekday()), |day| Some(day.succ()))
+            .take(7)
+            .map(|weekday| i18n::weekday_short(weekday, locale))
+            .collect();
+    push_row(&mut out, &header);
+    push_row(&mut out, &[":-:"; 7]);
+    for week in app.month_rows_for(year, month) {
+        let cells: Vec<String> = week
+            .iter()
+            .map(|cell| {
+                if !cell.is_current_month && !app.show_adjacent_days() {
+                    return String::new();
+                }
+                let day = if cell.is_today {
+                    format!("[{}]", cell.date.day())
+                } else {
+                    cell.date.day().to_string()
+                };
+                let text = match cell.label(label_priority, locale) {
+                    Some((label, _)) => format!("{day} {}", escape(&label)),
+                    None => day,
+                };
+                if !cell.is_current_month {
+                    format!("*{text}*")
+                } else if !cell.holidays.is_empty() {
+                    format!("**{text}**")
+                } else {
+                    text
+                }
+            })
+            .collect();
+        push_row(&mut out, &cells);
+    }
+    out
+}
+
+fn push_row(out: &mut String, cells: &[impl AsRef<str>]) {
+    for cell in cells {
+        out.push_str("| ");
+        out.push_str(cell.as_ref());
+        out.push(' ');
+    }
+    out.push_str("|\n");
+}
+
+/// Keep a label from breaking the table or turning into markup
+fn escape(text: &str) -> String {
+    text.chars()
+        .flat_map(|ch| {
+            let escaped = matches!(ch, '|' | '*' | '_' | '\\' | '[' | ']');
+            escaped.then_some('\\').into_iter().chain(iter::once(ch))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{app::test_app, config::AppConfig};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn month_table_matches_golden_file() {
+        let app = test_app(date(2025, 10, 1), AppConfig::default());
+        let expected = include_str!("../tests/fixtures/month-2025-10.md");
+        assert_eq!(month_markdown(&app, 2025, 10), expected);
+    }
+
+    #[test]
+    fn labels_cannot_break_the_table() {
+        assert_eq!(escape("a|b*c_d[e]\\"), "a\\|b\\*c\\_d\\[e\\]\\\\");
+        assert_eq!(escape("中秋节"), "中秋节");
+    }
+}
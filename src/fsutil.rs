@@ -0,0 +1,13 @@
+use std::{fs, io, path::Path};
+
+/// Write to a temporary file next to `path` first, then rename it over the old file, so a
+/// crash mid-write never leaves a truncated one; missing directories are created
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)
+}
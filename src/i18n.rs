@@ -0,0 +1,898 @@
+use std::fmt::Display;
+
+use chrono::Weekday;
+use serde::Deserialize;
+
+use crate::lunar::LunarInfo;
+
+/// Language of the interface; notes, events and other user text are shown as written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// Simplified Chinese
+    #[default]
+    #[serde(alias = "zh-hans", alias = "zh-Hans")]
+    Zh,
+    /// Traditional Chinese, from curated tables rather than character conversion
+    #[serde(rename = "zh-hant", alias = "zh-Hant")]
+    ZhHant,
+    En,
+}
+
+/// Interface strings, looked up per locale with `Msg::text`
+/// Entries containing `{}` are templates for `Msg::fill`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Msg {
+    Help,
+    HelpBar,
+    TooSmall,
+    HeaderMonth,
+    HeaderMonthFormatted,
+    HeaderToday,
+    HeaderLunar,
+    LunarUnavailable,
+    HeaderSelected,
+    SetMarkHint,
+    JumpMarkHint,
+    VisualHint,
+    CountHint,
+    WeekColumn,
+    LegendStatutory,
+    LegendTraditional,
+    LegendFolk,
+    LegendSolarTerm,
+    LegendBirthday,
+    LegendAnniversary,
+    LegendArrangement,
+    LegendNote,
+    LegendEvent,
+    MonthTitle,
+    LunarSpan,
+    LunarMonthLong,
+    LunarMonthShort,
+    WeekTitle,
+    WeekView,
+    YearTitle,
+    Details,
+    More,
+    Current,
+    None,
+    Range,
+    RangeSummary,
+    Note,
+    Birthday,
+    Anniversary,
+    Events,
+    YearPosition,
+    InYear,
+    Astronomical,
+    MonthStats,
+    AstronomicalDays,
+    FromToday,
+    SolarTerm,
+    Lunar,
+    Zodiac,
+    DayPillar,
+    Holiday,
+    OutOfRange,
+    NoUpcomingName,
+    Yi,
+    Ji,
+    Now,
+    Agenda,
+    JumpClose,
+    AgendaEmpty,
+    Today,
+    DaysLeft,
+    Countdown,
+    CountdownToday,
+    MonthTerms,
+    NoSolarTermData,
+    NoHolidayData,
+    Upcoming,
+    Unbound,
+    UnboundAction,
+    KeysTitle,
+    ConfigWarnings,
+    PreviewLunar,
+    PreviewOutOfRange,
+    KeepTyping,
+    JumpLabel,
+    JumpExamples,
+    JumpKeys,
+    JumpTitle,
+    NoteLabel,
+    NoteKeys,
+    NoteTitle,
+    DiffStart,
+    DiffEnd,
+    DiffKeys,
+    DiffDays,
+    DiffWeeks,
+    DiffFrom,
+    DiffTo,
+    DiffDate,
+    DiffTitle,
+    BaziTitle,
+    BaziKeys,
+    BaziYear,
+    BaziMonth,
+    BaziDay,
+    BaziHour,
+    BaziStem,
+    BaziBranch,
+    BaziElement,
+    SearchTitle,
+    SearchKeys,
+    SearchQuery,
+    SearchEmpty,
+    FestivalSearchTitle,
+    FestivalSearchEmpty,
+    HolidayListTitle,
+    KeymapGroup,
+    KeymapAction,
+    KeymapKeys,
+    KeymapTitle,
+    KeymapKeysHint,
+    DetailsWidth,
+    ThemeChanged,
+    MarkSet,
+    MarkUnset,
+    NoMoreSolarTerms,
+    NoteSaveFailed,
+    CountdownPinned,
+    CountdownUnpinned,
+    CountdownInConfig,
+    CountdownSaveFailed,
+    MonthExported,
+    MonthExportFailed,
+    TargetDaysLeft,
+    TargetToday,
+    TargetDaysAgo,
+    ConfigReloaded,
+    ConfigReloadFailed,
+    Copied,
+    CopyFailed,
+    FieldOutOfRange,
+    FieldUnrecognized,
+    UnknownDateFormat,
+    UnknownYear,
+    UnknownName,
+    UnknownNameSuggest,
+    InDays,
+    DaysAgo,
+    Months,
+    Years,
+    YearsMonths,
+    About,
+    LunarYear,
+}
+
+impl Msg {
+    pub fn text(self, locale: Locale) -> &'static str {
+        let (zh, hant, en) = match self {
+            Msg::Help => ("帮助", "幫助", "help"),
+            Msg::HelpBar => ("快捷键栏", "快捷鍵欄", "shortcut bar"),
+            Msg::TooSmall => (
+                "终端太小：需要至少 {}（当前 {}）",
+                "終端太小：需要至少 {}（當前 {}）",
+                "Terminal too small: needs at least {} (now {})",
+            ),
+            Msg::HeaderMonth => ("公历：{} 年 {} 月", "公曆：{} 年 {} 月", "Calendar: {} {}"),
+            Msg::HeaderMonthFormatted => ("公历：{}", "公曆：{}", "Calendar: {}"),
+            Msg::HeaderToday => ("今天：{}", "今天：{}", "Today: {}"),
+            Msg::HeaderLunar => (
+                "农历：{}年 · {}年",
+                "農曆：{}年 · {}年",
+                "Lunar: {} year · {}",
+            ),
+            Msg::LunarUnavailable => ("农历：暂不可用", "農曆：暫不可用", "Lunar: unavailable"),
+            Msg::HeaderSelected => (" | 选中：{}", " | 選中：{}", " | Selected: {}"),
+            Msg::SetMarkHint => (
+                " | 设置标记：按字母键",
+                " | 設置標記：按字母鍵",
+                " | Set mark: press a letter",
+            ),
+            Msg::JumpMarkHint => (
+                " | 跳转标记：按字母键",
+                " | 跳轉標記：按字母鍵",
+                " | Go to mark: press a letter",
+            ),
+            Msg::VisualHint => (" | 区间选择", " | 區間選擇", " | Visual range"),
+            Msg::CountHint => (" | 计数：{}", " | 計數：{}", " | Count: {}"),
+            Msg::WeekColumn => ("周", "週", "Wk"),
+            Msg::LegendStatutory => ("法定", "法定", "public"),
+            Msg::LegendTraditional => ("传统", "傳統", "traditional"),
+            Msg::LegendFolk => ("民俗", "民俗", "folk"),
+            Msg::LegendSolarTerm => ("节气", "節氣", "solar term"),
+            Msg::LegendBirthday => ("生日", "生日", "birthday"),
+            Msg::LegendAnniversary => ("纪念日", "紀念日", "anniversary"),
+            Msg::LegendArrangement => ("调休", "調休", "day off/work"),
+            Msg::LegendNote => ("笔记", "筆記", "note"),
+            Msg::LegendEvent => ("日程", "日程", "event"),
+            Msg::MonthTitle => ("{}年{}月", "{}年{}月", "{1} {0}"),
+            Msg::LunarSpan => ("{} · {}—{}", "{} · {}—{}", "{} · lunar {}–{}"),
+            Msg::LunarMonthLong => ("农历{}（大）", "農曆{}（大）", "Lunar {} (30 days)"),
+            Msg::LunarMonthShort => ("农历{}（小）", "農曆{}（小）", "Lunar {} (29 days)"),
+            Msg::WeekTitle => (
+                "周视图 · {} 年第 {} 周",
+                "週視圖 · {} 年第 {} 週",
+                "Week {1} of {0}",
+            ),
+            Msg::WeekView => ("周视图", "週視圖", "Week"),
+            Msg::YearTitle => ("{} 年", "{} 年", "{}"),
+            Msg::Details => ("详情", "詳情", "Details"),
+            Msg::More => ("更多", "更多", "more"),
+            Msg::Current => ("当前", "當前", "Date"),
+            Msg::None => ("无", "無", "none"),
+            Msg::Range => ("区间", "區間", "Range"),
+            Msg::RangeSummary => (
+                "共 {} 天 · 周末 {} 天 · 含节假日: {}",
+                "共 {} 天 · 週末 {} 天 · 含節假日: {}",
+                "{} days · {} weekend days · holidays: {}",
+            ),
+            Msg::Note => ("笔记", "筆記", "Note"),
+            Msg::Birthday => ("生日", "生日", "Birthday"),
+            Msg::Anniversary => ("纪念日", "紀念日", "Anniversary"),
+            Msg::Events => ("日程", "日程", "Events"),
+            Msg::YearPosition => (
+                "第 {} 天 · {} · 剩余 {} 天",
+                "第 {} 天 · {} · 剩餘 {} 天",
+                "day {} · {} · {} days left",
+            ),
+            Msg::InYear => ("年内", "年內", "Year"),
+            Msg::Astronomical => ("天文", "天文", "Astronomy"),
+            Msg::MonthStats => (
+                "{}天 · 工作日{} · 周末{} · 法定{}",
+                "{}天 · 工作日{} · 週末{} · 法定{}",
+                "{} days · {} workdays · {} weekend · {} holidays",
+            ),
+            Msg::AstronomicalDays => (
+                "JDN {} · MJD {} · Unix 日 {}",
+                "JDN {} · MJD {} · Unix 日 {}",
+                "JDN {} · MJD {} · Unix day {}",
+            ),
+            Msg::FromToday => ("距今天", "距今天", "From today"),
+            Msg::SolarTerm => ("节气", "節氣", "Solar term"),
+            Msg::Lunar => ("农历", "農曆", "Lunar"),
+            Msg::Zodiac => ("生肖", "生肖", "Zodiac"),
+            Msg::DayPillar => ("日柱", "日柱", "Day pillar"),
+            Msg::Holiday => ("节日", "節日", "Holiday"),
+            Msg::OutOfRange => ("超出支持范围", "超出支持範圍", "out of the supported range"),
+            Msg::NoUpcomingName => (
+                "支持范围内没有下一个{}",
+                "支持範圍內沒有下一個{}",
+                "no later {} in the supported range",
+            ),
+            Msg::Yi => ("宜", "宜", "Good for"),
+            Msg::Ji => ("忌", "忌", "Avoid"),
+            Msg::Now => ("现在", "現在", "Now"),
+            Msg::Agenda => ("{} 月议程", "{} 月議程", "Agenda for month {}"),
+            Msg::JumpClose => (
+                "Enter 跳转 · Esc 关闭",
+                "Enter 跳轉 · Esc 關閉",
+                "Enter go · Esc close",
+            ),
+            Msg::AgendaEmpty => ("本月暂无事项", "本月暫無事項", "Nothing this month"),
+            Msg::Today => ("就是今天", "就是今天", "today"),
+            Msg::DaysLeft => ("还有{}天", "還有{}天", "in {} days"),
+            Msg::Countdown => ("{} · 还有 {} 天", "{} · 還有 {} 天", "{} · in {} days"),
+            Msg::CountdownToday => ("就是今天！", "就是今天！", "today!"),
+            Msg::MonthTerms => ("本月节气", "本月節氣", "Terms this month"),
+            Msg::NoSolarTermData => ("节气数据不可用", "節氣數據不可用", "no solar term data"),
+            Msg::NoHolidayData => ("暂无节日数据", "暫無節日數據", "No holiday data"),
+            Msg::Upcoming => ("即将到来", "即將到來", "Upcoming"),
+            Msg::Unbound => ("未绑定", "未綁定", "unbound"),
+            Msg::UnboundAction => ("未绑定 {}", "未綁定 {}", "unbound {}"),
+            Msg::KeysTitle => ("快捷键 · 配色：{}", "快捷鍵 · 配色：{}", "Keys · theme: {}"),
+            Msg::ConfigWarnings => (
+                "配置警告（按任意键关闭）",
+                "配置警告（按任意鍵關閉）",
+                "Config warnings (press any key)",
+            ),
+            Msg::PreviewLunar => (" 农历{}", " 農曆{}", " lunar {}"),
+            Msg::PreviewOutOfRange => (
+                "{} {} 超出支持范围",
+                "{} {} 超出支持範圍",
+                "{} {} is out of range",
+            ),
+            Msg::KeepTyping => ("（继续输入…）", "（繼續輸入…）", "(keep typing…)"),
+            Msg::JumpLabel => ("目标日期：", "目標日期：", "Date: "),
+            Msg::JumpExamples => (
+                "如 2025-3-5 · 03-05 · +30 · today · 中秋 2026",
+                "如 2025-3-5 · 03-05 · +30 · today · 中秋 2026",
+                "e.g. 2025-3-5 · 03-05 · +30 · today · 中秋 2026",
+            ),
+            Msg::JumpKeys => (
+                "Enter 确认 · Esc 取消 · {} 历史",
+                "Enter 確認 · Esc 取消 · {} 歷史",
+                "Enter confirm · Esc cancel · {} history",
+            ),
+            Msg::JumpTitle => ("跳转到指定日期", "跳轉到指定日期", "Go to date"),
+            Msg::NoteLabel => ("笔记：", "筆記：", "Note: "),
+            Msg::NoteKeys => (
+                "Enter 保存（留空则删除）· Esc 取消",
+                "Enter 保存（留空則刪除）· Esc 取消",
+                "Enter save (empty deletes) · Esc cancel",
+            ),
+            Msg::NoteTitle => ("{} 笔记", "{} 筆記", "Note for {}"),
+            Msg::DiffStart => ("起始日期", "起始日期", "Start"),
+            Msg::DiffEnd => ("结束日期", "結束日期", "End"),
+            Msg::DiffKeys => (
+                "Tab 切换 · Enter 计算 · Esc 关闭",
+                "Tab 切換 · Enter 計算 · Esc 關閉",
+                "Tab switch · Enter compute · Esc close",
+            ),
+            Msg::DiffDays => (
+                "相差 {} 天 · 含首尾共 {} 天",
+                "相差 {} 天 · 含首尾共 {} 天",
+                "{} days apart · {} days inclusive",
+            ),
+            Msg::DiffWeeks => (
+                "合 {} 周 {} 天 · 周末 {} 天",
+                "合 {} 週 {} 天 · 週末 {} 天",
+                "{} weeks {} days · {} weekend days",
+            ),
+            Msg::DiffFrom => ("起", "起", "From"),
+            Msg::DiffTo => ("止", "止", "To"),
+            Msg::DiffDate => ("{}：{} · 农历{}", "{}：{} · 農曆{}", "{}: {} · lunar {}"),
+            Msg::DiffTitle => ("日期间隔", "日期間隔", "Date difference"),
+            Msg::BaziTitle => ("八字 · {} {}", "八字 · {} {}", "Four pillars · {} {}"),
+            Msg::BaziKeys => (
+                "{} 切换时辰 · 输入 0-23 点 · Esc 关闭",
+                "{} 切換時辰 · 輸入 0-23 點 · Esc 關閉",
+                "{} hour · type 0-23 · Esc close",
+            ),
+            Msg::BaziYear => ("年柱", "年柱", "Year"),
+            Msg::BaziMonth => ("月柱", "月柱", "Month"),
+            Msg::BaziDay => ("日柱", "日柱", "Day"),
+            Msg::BaziHour => ("时柱", "時柱", "Hour"),
+            Msg::BaziStem => ("天干", "天干", "Stem"),
+            Msg::BaziBranch => ("地支", "地支", "Branch"),
+            Msg::BaziElement => ("五行", "五行", "Element"),
+            Msg::SearchTitle => ("搜索笔记", "搜索筆記", "Search notes"),
+            Msg::SearchKeys => (
+                "{} 选择 · Enter 跳转 · Esc 关闭",
+                "{} 選擇 · Enter 跳轉 · Esc 關閉",
+                "{} select · Enter go · Esc close",
+            ),
+            Msg::SearchQuery => ("关键字：", "關鍵字：", "Search: "),
+            Msg::SearchEmpty => ("没有匹配的笔记", "沒有匹配的筆記", "No matching notes"),
+            Msg::FestivalSearchTitle => (
+                "{} 年节日与节气",
+                "{} 年節日與節氣",
+                "Festivals and solar terms in {}",
+            ),
+            Msg::FestivalSearchEmpty => (
+                "没有匹配的节日或节气",
+                "沒有匹配的節日或節氣",
+                "No matching festival or solar term",
+            ),
+            Msg::HolidayListTitle => ("{} 年节假日", "{} 年節假日", "Holidays in {}"),
+            Msg::KeymapGroup => ("分组", "分組", "Group"),
+            Msg::KeymapAction => ("动作", "動作", "Action"),
+            Msg::KeymapKeys => ("按键", "按鍵", "Keys"),
+            Msg::KeymapTitle => ("全部快捷键", "全部快捷鍵", "All shortcuts"),
+            Msg::KeymapKeysHint => (
+                "{} 滚动 · Esc 关闭",
+                "{} 滾動 · Esc 關閉",
+                "{} scroll · Esc close",
+            ),
+            Msg::DetailsWidth => ("详情栏宽度：{}%", "詳情欄寬度：{}%", "Details width: {}%"),
+            Msg::ThemeChanged => ("配色：{}", "配色：{}", "Theme: {}"),
+            Msg::MarkSet => ("标记 {}：{}", "標記 {}：{}", "Mark {}: {}"),
+            Msg::MarkUnset => ("标记 {} 未设置", "標記 {} 未設置", "Mark {} is not set"),
+            Msg::NoMoreSolarTerms => (
+                "没有更多节气（节气数据覆盖 {}–{} 年）",
+                "沒有更多節氣（節氣數據覆蓋 {}–{} 年）",
+                "No more solar terms (data covers {}–{})",
+            ),
+            Msg::NoteSaveFailed => (
+                "保存笔记失败：{}",
+                "保存筆記失敗：{}",
+                "Failed to save the note: {}",
+            ),
+            Msg::CountdownPinned => (
+                "已添加倒数日：{}",
+                "已添加倒數日：{}",
+                "Counting down to {}",
+            ),
+            Msg::CountdownUnpinned => (
+                "已移除 {} 的倒数日",
+                "已移除 {} 的倒數日",
+                "Stopped counting down to {}",
+            ),
+            Msg::CountdownInConfig => (
+                "该倒数日写在 config.ron 中，请在配置中移除",
+                "該倒數日寫在 config.ron 中，請在配置中移除",
+                "This countdown is set in config.ron; remove it there",
+            ),
+            Msg::CountdownSaveFailed => (
+                "保存倒数日失败：{}",
+                "保存倒數日失敗：{}",
+                "Failed to save the countdown: {}",
+            ),
+            Msg::MonthExported => ("月历已导出到 {}", "月曆已導出到 {}", "Month exported to {}"),
+            Msg::MonthExportFailed => (
+                "导出月历失败：{}",
+                "導出月曆失敗：{}",
+                "Failed to export the month: {}",
+            ),
+            Msg::TargetDaysLeft => ("距{}还有 {} 天", "距{}還有 {} 天", "{} in {} days"),
+            Msg::TargetToday => ("今天是{}", "今天是{}", "{} is today"),
+            Msg::TargetDaysAgo => ("{}已过 {} 天", "{}已過 {} 天", "{} was {} days ago"),
+            Msg::ConfigReloaded => ("已重新加载配置", "已重新加載配置", "Config reloaded"),
+            Msg::ConfigReloadFailed => (
+                "重新加载失败，保留原配置：{}",
+                "重新加載失敗，保留原配置：{}",
+                "Reload failed, keeping the current config: {}",
+            ),
+            Msg::Copied => ("已复制：{}", "已複製：{}", "Copied: {}"),
+            Msg::CopyFailed => ("复制失败：{}", "複製失敗：{}", "Copy failed: {}"),
+            Msg::FieldOutOfRange => (
+                "{}超出支持范围",
+                "{}超出支持範圍",
+                "{} is out of the supported range",
+            ),
+            Msg::FieldUnrecognized => ("{}：{}", "{}：{}", "{}: {}"),
+            Msg::UnknownDateFormat => ("无法识别日期格式", "無法識別日期格式", "Unrecognized date"),
+            Msg::UnknownYear => ("无法识别年份", "無法識別年份", "Unrecognized year"),
+            Msg::UnknownName => (
+                "未知节日或节气：{}",
+                "未知節日或節氣：{}",
+                "Unknown holiday or solar term: {}",
+            ),
+            Msg::UnknownNameSuggest => (
+                "未知节日：{}，是否指 {}？",
+                "未知節日：{}，是否指 {}？",
+                "Unknown holiday: {}, did you mean {}?",
+            ),
+            Msg::InDays => ("还有 {} 天", "還有 {} 天", "in {} days"),
+            Msg::DaysAgo => ("已过去 {} 天", "已過去 {} 天", "{} days ago"),
+            Msg::Months => ("{} 个月", "{} 個月", "{} months"),
+            Msg::Years => ("{} 年", "{} 年", "{} years"),
+            Msg::YearsMonths => ("{} 年 {} 个月", "{} 年 {} 個月", "{} years {} months"),
+            Msg::About => (" · 约 {}", " · 約 {}", " · about {}"),
+            Msg::LunarYear => ("{}年 {}", "{}年 {}", "{} year · {}"),
+        };
+        match locale {
+            Locale::Zh => zh,
+            Locale::ZhHant => hant,
+            Locale::En => en,
+        }
+    }
+
+    /// The template with each `{}` replaced by the next argument, or `{n}` by the n-th,
+    /// since word order differs between the languages
+    pub fn fill(self, locale: Locale, args: &[&dyn Display]) -> String {
+        let template = self.text(locale);
+        let mut result = String::new();
+        let mut next = 0;
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            result.push_str(&rest[..start]);
+            let index = match rest[start + 1..start + len].parse::<usize>() {
+                Ok(index) => index,
+                Err(_) => {
+                    next += 1;
+                    next - 1
+                }
+            };
+            if let Some(arg) = args.get(index) {
+                result.push_str(&arg.to_string());
+            }
+            rest = &rest[start + len + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+/// Separator between a detail label and its value
+pub fn colon(locale: Locale) -> &'static str {
+    match locale {
+        Locale::Zh | Locale::ZhHant => "：",
+        Locale::En => ": ",
+    }
+}
+
+/// Joins list items, e.g. holiday names
+pub fn list_separator(locale: Locale) -> &'static str {
+    match locale {
+        Locale::Zh | Locale::ZhHant => "、",
+        Locale::En => ", ",
+    }
+}
+
+/// Column header for a weekday, e.g. "六" or "Sa"
+pub fn weekday_short(weekday: Weekday, locale: Locale) -> &'static str {
+    let index = weekday.num_days_from_monday() as usize;
+    match locale {
+        Locale::Zh | Locale::ZhHant => ["一", "二", "三", "四", "五", "六", "日"][index],
+        Locale::En => ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"][index],
+    }
+}
+
+/// Abbreviated weekday, e.g. "周二" or "Tue"
+pub fn weekday_abbr(weekday: Weekday, locale: Locale) -> &'static str {
+    let index = weekday.num_days_from_monday() as usize;
+    match locale {
+        Locale::Zh => ["周一", "周二", "周三", "周四", "周五", "周六", "周日"][index],
+        Locale::ZhHant => ["週一", "週二", "週三", "週四", "週五", "週六", "週日"][index],
+        Locale::En => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"][index],
+    }
+}
+
+/// Full weekday name, e.g. "星期二" or "Tuesday"
+pub fn weekday_name(weekday: Weekday, locale: Locale) -> &'static str {
+    let index = weekday.num_days_from_monday() as usize;
+    match locale {
+        Locale::Zh | Locale::ZhHant => [
+            "星期一",
+            "星期二",
+            "星期三",
+            "星期四",
+            "星期五",
+            "星期六",
+            "星期日",
+        ][index],
+        Locale::En => [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ][index],
+    }
+}
+
+/// Month title of a mini calendar, e.g. "3月" or "Mar"
+pub fn month_name(month: u32, locale: Locale) -> String {
+    match locale {
+        Locale::Zh | Locale::ZhHant => format!("{}月", month),
+        Locale::En => [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ]
+        .get(month.wrapping_sub(1) as usize)
+        .map(|name| name.to_string())
+        .unwrap_or_default(),
+    }
+}
+
+/// Built-in holiday, lunar festival and solar term names with their traditional Chinese and
+/// English names
+const NAMES: [(&str, &str, &str); 39] = [
+    ("春节", "春節", "Spring Festival"),
+    ("除夕", "除夕", "New Year's Eve"),
+    ("元旦", "元旦", "New Year's Day"),
+    ("劳动节", "勞動節", "Labour Day"),
+    ("端午节", "端午節", "Dragon Boat Festival"),
+    ("中秋节", "中秋節", "Mid-Autumn Festival"),
+    ("国庆节", "國慶節", "National Day"),
+    ("清明节", "清明節", "Qingming Festival"),
+    ("元宵节", "元宵節", "Lantern Festival"),
+    ("七夕节", "七夕節", "Qixi Festival"),
+    ("重阳节", "重陽節", "Double Ninth Festival"),
+    ("龙抬头", "龍抬頭", "Dragon Head Raising"),
+    ("中元节", "中元節", "Ghost Festival"),
+    ("腊八节", "臘八節", "Laba Festival"),
+    ("小年", "小年", "Little New Year"),
+    ("冬至", "冬至", "Winter Solstice"),
+    ("小寒", "小寒", "Minor Cold"),
+    ("大寒", "大寒", "Major Cold"),
+    ("立春", "立春", "Start of Spring"),
+    ("雨水", "雨水", "Rain Water"),
+    ("惊蛰", "驚蟄", "Awakening of Insects"),
+    ("春分", "春分", "Spring Equinox"),
+    ("清明", "清明", "Pure Brightness"),
+    ("谷雨", "穀雨", "Grain Rain"),
+    ("立夏", "立夏", "Start of Summer"),
+    ("小满", "小滿", "Grain Buds"),
+    ("芒种", "芒種", "Grain in Ear"),
+    ("夏至", "夏至", "Summer Solstice"),
+    ("小暑", "小暑", "Minor Heat"),
+    ("大暑", "大暑", "Major Heat"),
+    ("立秋", "立秋", "Start of Autumn"),
+    ("处暑", "處暑", "End of Heat"),
+    ("白露", "白露", "White Dew"),
+    ("秋分", "秋分", "Autumn Equinox"),
+    ("寒露", "寒露", "Cold Dew"),
+    ("霜降", "霜降", "Frost's Descent"),
+    ("立冬", "立冬", "Start of Winter"),
+    ("小雪", "小雪", "Minor Snow"),
+    ("大雪", "大雪", "Major Snow"),
+];
+
+/// Built-in holiday, festival or solar term named by `input`: its simplified, traditional or
+/// English name, or the start of a Chinese one such as "中秋"
+pub fn builtin_name(input: &str) -> Option<&'static str> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    NAMES
+        .iter()
+        .find(|(zh, hant, en)| *zh == input || *hant == input || en.eq_ignore_ascii_case(input))
+        .or_else(|| {
+            NAMES
+                .iter()
+                .find(|(zh, hant, _)| zh.starts_with(input) || hant.starts_with(input))
+        })
+        .map(|(zh, _, _)| *zh)
+}
+
+/// Display name of a built-in holiday, festival or solar term; other names pass through
+pub fn name(zh: &str, locale: Locale) -> &str {
+    let entry = NAMES.iter().find(|(name, _, _)| *name == zh);
+    match locale {
+        Locale::Zh => zh,
+        Locale::ZhHant => entry.map_or(zh, |(_, hant, _)| hant),
+        Locale::En => entry.map_or(zh, |(_, _, en)| en),
+    }
+}
+
+/// Built-in holiday notes in traditional Chinese, keyed by the simplified note
+const HOLIDAY_NOTES_HANT: [(&str, &str); 15] = [
+    ("农历正月初一 · 辞旧迎新", "農曆正月初一 · 辭舊迎新"),
+    ("春节前夜 · 合家团圆", "春節前夜 · 合家團圓"),
+    ("公历1月1日 · 新年伊始", "公曆1月1日 · 新年伊始"),
+    ("公历5月1日 · 国际劳动节", "公曆5月1日 · 國際勞動節"),
+    ("农历五月初五 · 赛龙舟吃粽子", "農曆五月初五 · 賽龍舟吃粽子"),
+    ("农历八月十五 · 赏月团圆", "農曆八月十五 · 賞月團圓"),
+    ("公历10月1日 · 国庆", "公曆10月1日 · 國慶"),
+    ("清明时节 · 踏青祭祖", "清明時節 · 踏青祭祖"),
+    ("农历正月十五 · 元宵赏灯", "農曆正月十五 · 元宵賞燈"),
+    ("农历七月初七 · 牛郎织女传说", "農曆七月初七 · 牛郎織女傳說"),
+    ("农历九月初九 · 登高敬老", "農曆九月初九 · 登高敬老"),
+    ("农历二月初二 · 春耕开犁", "農曆二月初二 · 春耕開犁"),
+    ("农历七月十五 · 中元祭祖", "農曆七月十五 · 中元祭祖"),
+    ("农历腊月初八 · 喝腊八粥", "農曆臘月初八 · 喝臘八粥"),
+    ("冬至日 · 最重要节气之一", "冬至日 · 最重要節氣之一"),
+];
+
+/// Note of a built-in holiday in Chinese; English has no notes, so it shares the simplified one
+pub fn holiday_note(zh: &'static str, locale: Locale) -> &'static str {
+    match locale {
+        Locale::ZhHant => HOLIDAY_NOTES_HANT
+            .iter()
+            .find(|(note, _)| *note == zh)
+            .map_or(zh, |(_, hant)| hant),
+        Locale::Zh | Locale::En => zh,
+    }
+}
+
+const STEMS_PINYIN: [(char, &str); 10] = [
+    ('甲', "Jia"),
+    ('乙', "Yi"),
+    ('丙', "Bing"),
+    ('丁', "Ding"),
+    ('戊', "Wu"),
+    ('己', "Ji"),
+    ('庚', "Geng"),
+    ('辛', "Xin"),
+    ('壬', "Ren"),
+    ('癸', "Gui"),
+];
+
+const BRANCHES_PINYIN: [(char, &str); 12] = [
+    ('子', "Zi"),
+    ('丑', "Chou"),
+    ('寅', "Yin"),
+    ('卯', "Mao"),
+    ('辰', "Chen"),
+    ('巳', "Si"),
+    ('午', "Wu"),
+    ('未', "Wei"),
+    ('申', "Shen"),
+    ('酉', "You"),
+    ('戌', "Xu"),
+    ('亥', "Hai"),
+];
+
+/// Stem-branch text in the locale, e.g. "乙巳" or "Yi-Si"
+/// Characters other than stems and branches, such as the "日" of a day pillar, are dropped in English
+pub fn gan_zhi(text: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Zh | Locale::ZhHant => text.to_string(),
+        Locale::En => text
+            .chars()
+            .filter_map(|ch| {
+                STEMS_PINYIN
+                    .iter()
+                    .chain(&BRANCHES_PINYIN)
+                    .find(|(han, _)| *han == ch)
+                    .map(|(_, pinyin)| *pinyin)
+            })
+            .collect::<Vec<_>>()
+            .join("-"),
+    }
+}
+
+/// Zodiac animal in the locale, e.g. "蛇", "龍" or "Snake"
+pub fn zodiac(animal: char, locale: Locale) -> String {
+    const ANIMALS: [(char, char, &str); 12] = [
+        ('鼠', '鼠', "Rat"),
+        ('牛', '牛', "Ox"),
+        ('虎', '虎', "Tiger"),
+        ('兔', '兔', "Rabbit"),
+        ('龙', '龍', "Dragon"),
+        ('蛇', '蛇', "Snake"),
+        ('马', '馬', "Horse"),
+        ('羊', '羊', "Goat"),
+        ('猴', '猴', "Monkey"),
+        ('鸡', '雞', "Rooster"),
+        ('狗', '狗', "Dog"),
+        ('猪', '豬', "Pig"),
+    ];
+    let entry = ANIMALS.iter().find(|(han, _, _)| *han == animal);
+    match locale {
+        Locale::Zh => animal.to_string(),
+        Locale::ZhHant => entry.map_or(animal, |(_, hant, _)| *hant).to_string(),
+        Locale::En => entry.map_or_else(|| animal.to_string(), |(_, _, en)| en.to_string()),
+    }
+}
+
+/// Double-hour in the locale, e.g. "午时", "午時" or "Wu hour"
+pub fn shi_chen(text: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Zh => text.to_string(),
+        Locale::ZhHant => text.replace('时', "時"),
+        Locale::En => format!("{} hour", gan_zhi(text, locale)),
+    }
+}
+
+/// Lunar day: "十六" or "6/16"; day names are the same in traditional characters
+pub fn lunar_day(info: &LunarInfo, locale: Locale) -> String {
+    match locale {
+        Locale::Zh | Locale::ZhHant => info.day_name().to_string(),
+        Locale::En => {
+            let leap = if info.date.is_leap { "L" } else { "" };
+            format!("{}{}/{}", leap, info.date.month, info.date.day)
+        }
+    }
+}
+
+/// Lunar day for a month cell: "十六", "闰六月" on the first day, or "L6/16"
+pub fn lunar_cell_day(info: &LunarInfo, locale: Locale) -> String {
+    match locale {
+        Locale::Zh => info.cell_day_name(),
+        Locale::ZhHant if info.date.day == 1 => info.month_label_hant(),
+        Locale::ZhHant | Locale::En => lunar_day(info, locale),
+    }
+}
+
+/// Compact lunar month and day: "闰六月十六" or "L6/16"
+pub fn lunar_month_day(info: &LunarInfo, locale: Locale) -> String {
+    match locale {
+        Locale::Zh | Locale::ZhHant => format!("{}{}", lunar_month(info, locale), info.day_name()),
+        Locale::En => lunar_day(info, locale),
+    }
+}
+
+/// Lunar month alone: "闰六月", "閏六月" or "leap month 6"
+pub fn lunar_month(info: &LunarInfo, locale: Locale) -> String {
+    match locale {
+        Locale::Zh => info.month_label(),
+        Locale::ZhHant => info.month_label_hant(),
+        Locale::En => {
+            let leap = if info.date.is_leap { "leap " } else { "" };
+            format!("{}month {}", leap, info.date.month)
+        }
+    }
+}
+
+/// Lunar month and day: "六月十六" or "month 6 day 16"
+pub fn lunar_date(info: &LunarInfo, locale: Locale) -> String {
+    match locale {
+        Locale::Zh | Locale::ZhHant => format!("{}{}", lunar_month(info, locale), info.day_name()),
+        Locale::En => {
+            let leap = if info.date.is_leap { "leap " } else { "" };
+            format!("{}month {} day {}", leap, info.date.month, info.date.day)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{app::holidays_on, lunar::solar_to_lunar};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn weekday_names_cover_the_whole_week() {
+        let week = std::iter::successors(Some(Weekday::Mon), |day| Some(day.succ())).take(7);
+        let names: Vec<_> = week
+            .map(|weekday| weekday_name(weekday, Locale::Zh))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "星期一",
+                "星期二",
+                "星期三",
+                "星期四",
+                "星期五",
+                "星期六",
+                "星期日"
+            ]
+        );
+        assert_eq!(weekday_name(Weekday::Tue, Locale::En), "Tuesday");
+    }
+
+    #[test]
+    fn traditional_tables_cover_representative_strings() {
+        assert_eq!(Msg::Lunar.text(Locale::Zh), "农历");
+        assert_eq!(Msg::Lunar.text(Locale::ZhHant), "農曆");
+        assert_eq!(Msg::SolarTerm.text(Locale::ZhHant), "節氣");
+        assert_eq!(weekday_abbr(Weekday::Sun, Locale::Zh), "周日");
+        assert_eq!(weekday_abbr(Weekday::Sun, Locale::ZhHant), "週日");
+        assert_eq!(weekday_short(Weekday::Sat, Locale::ZhHant), "六");
+        assert_eq!(name("腊八节", Locale::Zh), "腊八节");
+        assert_eq!(name("腊八节", Locale::ZhHant), "臘八節");
+        assert_eq!(name("龙抬头", Locale::ZhHant), "龍抬頭");
+        assert_eq!(name("处暑", Locale::ZhHant), "處暑");
+        assert_eq!(name("我的生日", Locale::ZhHant), "我的生日");
+        assert_eq!(zodiac('龙', Locale::Zh), "龙");
+        assert_eq!(zodiac('龙', Locale::ZhHant), "龍");
+        assert_eq!(shi_chen("午时", Locale::ZhHant), "午時");
+        // The traditional name is also accepted when searching
+        assert_eq!(builtin_name("臘八節"), Some("腊八节"));
+    }
+
+    #[test]
+    fn traditional_lunar_months_and_holiday_notes() {
+        let day = |y, m, d| solar_to_lunar(date(y, m, d)).unwrap();
+        // 腊月初八 and the leap second month of 2023
+        let laba = day(2025, 1, 7);
+        assert_eq!(lunar_month(&laba, Locale::Zh), "腊月");
+        assert_eq!(lunar_month(&laba, Locale::ZhHant), "臘月");
+        assert_eq!(lunar_date(&laba, Locale::ZhHant), "臘月初八");
+        let leap = day(2023, 3, 22);
+        assert_eq!(lunar_cell_day(&leap, Locale::Zh), "闰二月");
+        assert_eq!(lunar_cell_day(&leap, Locale::ZhHant), "閏二月");
+        // Built-in notes have curated traditional versions
+        let days = [date(2025, 1, 7), date(2025, 1, 29), date(2025, 10, 1)];
+        for info in days.into_iter().flat_map(holidays_on) {
+            assert_ne!(
+                holiday_note(info.note, Locale::ZhHant),
+                info.note,
+                "{}",
+                info.name
+            );
+            assert_eq!(holiday_note(info.note, Locale::Zh), info.note);
+        }
+        assert_eq!(
+            holiday_note("农历腊月初八 · 喝腊八粥", Locale::ZhHant),
+            "農曆臘月初八 · 喝臘八粥"
+        );
+    }
+
+    #[test]
+    fn locales_parse_by_their_tags() {
+        #[derive(Deserialize)]
+        struct Config {
+            locale: Locale,
+        }
+        let parse = |tag: &str| {
+            toml::from_str::<Config>(&format!("locale = \"{tag}\""))
+                .unwrap()
+                .locale
+        };
+        assert_eq!(parse("zh-Hans"), Locale::Zh);
+        assert_eq!(parse("zh"), Locale::Zh);
+        assert_eq!(parse("zh-Hant"), Locale::ZhHant);
+        assert_eq!(parse("zh-hant"), Locale::ZhHant);
+        assert_eq!(parse("en"), Locale::En);
+        assert_eq!(Locale::default(), Locale::Zh);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::Local;
+use log::{LevelFilter, Log, Metadata, Record};
+
+const LOG_LEVEL_ENV: &str = "MOLI_LOG";
+const LOG_FILE_ENV: &str = "MOLI_LOG_FILE";
+const LOG_FILE_NAME: &str = "moli.log";
+const STATE_DIR_NAME: &str = "moli";
+
+/// Appends log records to a file, since stdout and stderr belong to the TUI while it runs
+struct FileLogger {
+    level: LevelFilter,
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // One write per record, so lines from different threads never interleave
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+impl FileLogger {
+    /// Append to the file at `path`, creating it and its directory when missing
+    fn open(path: &Path, level: LevelFilter) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("failed to create log directory {dir:?}: {err}"))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| format!("failed to open log file {path:?}: {err}"))?;
+        Ok(FileLogger {
+            level,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+/// Start logging when `MOLI_LOG` names a level (error, warn, info, debug or trace)
+/// Without it no logger is installed and every log call is a cheap level check
+pub fn init() {
+    let Some(raw) = env::var_os(LOG_LEVEL_ENV) else {
+        return;
+    };
+    let level = match parse_level(&raw.to_string_lossy()) {
+        Ok(level) => level,
+        Err(message) => {
+            eprintln!("moli: {message}");
+            return;
+        }
+    };
+    if level == LevelFilter::Off {
+        return;
+    }
+    let Some(path) = log_path() else {
+        eprintln!("moli: no state directory for the log; set {LOG_FILE_ENV}");
+        return;
+    };
+    let logger = match FileLogger::open(&path, level) {
+        Ok(logger) => Box::leak(Box::new(logger)),
+        Err(message) => {
+            eprintln!("moli: {message}");
+            return;
+        }
+    };
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Level named by `MOLI_LOG`, in any case; "off" turns logging off like leaving it unset
+fn parse_level(raw: &str) -> Result<LevelFilter, String> {
+    raw.parse().map_err(|_| {
+        format!(
+            "{LOG_LEVEL_ENV}={raw:?} is not a log level (error, warn, info, debug, trace); not logging"
+        )
+    })
+}
+
+/// `MOLI_LOG_FILE`, or moli.log in the platform state directory (`~/.local/state/moli` on
+/// Linux), falling back to the local data directory where there is none
+fn log_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(LOG_FILE_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(STATE_DIR_NAME).join(LOG_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+
+    use super::*;
+
+    #[test]
+    fn levels_parse_in_any_case() {
+        assert_eq!(parse_level("debug"), Ok(LevelFilter::Debug));
+        assert_eq!(parse_level("WARN"), Ok(LevelFilter::Warn));
+        assert_eq!(parse_level("Trace"), Ok(LevelFilter::Trace));
+        assert_eq!(parse_level("off"), Ok(LevelFilter::Off));
+        let err = parse_level("verbose").unwrap_err();
+        assert!(
+            err.starts_with("MOLI_LOG=\"verbose\" is not a log level"),
+            "{err}"
+        );
+        assert!(parse_level("").is_err());
+    }
+
+    #[test]
+    fn file_target_appends_records_at_or_above_its_level() {
+        let dir = env::temp_dir().join(format!("moli-logging-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        // The directory is created on the way
+        let path = dir.join("nested").join(LOG_FILE_NAME);
+        let log = |level, message: &str| {
+            let logger = FileLogger::open(&path, LevelFilter::Info).unwrap();
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .target("moli::app")
+                    .args(format_args!("{message}"))
+                    .build(),
+            );
+            logger.flush();
+        };
+        log(Level::Info, "first");
+        log(Level::Debug, "hidden");
+        log(Level::Warn, "second");
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2, "{content}");
+        assert!(lines[0].ends_with(" INFO  moli::app: first"), "{content}");
+        assert!(lines[1].ends_with(" WARN  moli::app: second"), "{content}");
+        // A directory in the file's place is reported rather than logged to
+        let err = FileLogger::open(&dir, LevelFilter::Info).err().unwrap();
+        assert!(err.starts_with("failed to open log file"), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate, NaiveTime, Timelike};
+use serde::Serialize;
 
 pub const MIN_YEAR: i32 = 1900;
 const BASE_MONTH: u32 = 1;
@@ -39,6 +40,9 @@ const ZODIAC: [char; 12] = [
 const LUNAR_MONTH_NAMES: [char; 12] = [
     '正', '二', '三', '四', '五', '六', '七', '八', '九', '十', '冬', '腊',
 ];
+const LUNAR_MONTH_NAMES_HANT: [char; 12] = [
+    '正', '二', '三', '四', '五', '六', '七', '八', '九', '十', '冬', '臘',
+];
 const LUNAR_DAY_NAMES: [&str; 30] = [
     "初一", "初二", "初三", "初四", "初五", "初六", "初七", "初八", "初九", "初十", "十一", "十二",
     "十三", "十四", "十五", "十六", "十七", "十八", "十九", "二十", "廿一", "廿二", "廿三", "廿四",
@@ -57,7 +61,7 @@ const LUNAR_FESTIVALS: [((u8, u8), &str); 10] = [
     ((12, 23), "小年"),
 ];
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub struct LunarDate {
     pub year: i32,
     pub month: u8,
@@ -65,23 +69,39 @@ pub struct LunarDate {
     pub is_leap: bool,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub struct LunarInfo {
     pub date: LunarDate,
     pub festival: Option<&'static str>,
 }
 
 impl LunarInfo {
-    pub fn display_label(&self) -> &'static str {
-        // Lunar festival name if present, otherwise the lunar day label
-        self.festival
-            .unwrap_or_else(|| lunar_day_name_for(self.date.day))
+    /// Lunar day name, e.g. "初六"; festivals are kept apart in `festival`
+    pub fn day_name(&self) -> &'static str {
+        lunar_day_name_for(self.date.day)
+    }
+
+    /// Day name for a calendar cell: the month, e.g. "闰二月", on the first day of a month
+    /// like paper calendars, so a leap month's 初一 is told apart from the regular month's
+    pub fn cell_day_name(&self) -> String {
+        if self.date.day == 1 {
+            self.month_label()
+        } else {
+            self.day_name().to_string()
+        }
     }
 
     pub fn month_label(&self) -> String {
         let prefix = if self.date.is_leap { "闰" } else { "" };
         format!("{}{}月", prefix, lunar_month_name_for(self.date.month))
     }
+
+    /// `month_label` in traditional characters, e.g. "閏二月" or "臘月"
+    pub fn month_label_hant(&self) -> String {
+        let prefix = if self.date.is_leap { "閏" } else { "" };
+        let index = usize::from(self.date.month - 1);
+        format!("{}{}月", prefix, LUNAR_MONTH_NAMES_HANT[index])
+    }
 }
 
 pub fn max_supported_year() -> i32 {
@@ -136,30 +156,148 @@ pub fn solar_to_lunar(date: NaiveDate) -> Option<LunarInfo> {
         }
     }
 
-    let day = (offset + 1) as u8;
+    Some(lunar_info(LunarDate {
+        year,
+        month: month as u8,
+        day: (offset + 1) as u8,
+        is_leap,
+    }))
+}
+
+/// Convert `days` consecutive dates from `start`, giving the same results as calling
+/// `solar_to_lunar` for each of them
+/// The year and month scan runs once for the first supported date; every later day steps the
+/// lunar date forward, so a year view or a year-long listing costs one scan instead of hundreds
+pub fn solar_to_lunar_range(start: NaiveDate, days: u32) -> Vec<Option<LunarInfo>> {
+    let mut result = Vec::with_capacity(days as usize);
+    let mut current: Option<LunarDate> = None;
+    let mut started = false;
+    for date in start.iter_days().take(days as usize) {
+        current = match current {
+            Some(previous) => next_lunar_date(previous),
+            // Once the walk has left the table nothing later converts either
+            None if started => None,
+            None => solar_to_lunar(date).map(|info| info.date),
+        };
+        started |= current.is_some();
+        result.push(current.map(lunar_info));
+    }
+    result
+}
+
+/// The lunar date following `date`, or None past the last supported year
+fn next_lunar_date(date: LunarDate) -> Option<LunarDate> {
+    let length = if date.is_leap {
+        leap_days(date.year)
+    } else {
+        month_days(date.year, i32::from(date.month))?
+    };
+    if date.day < length {
+        return Some(LunarDate {
+            day: date.day + 1,
+            ..date
+        });
+    }
+    if !date.is_leap && leap_month(date.year) == date.month {
+        return Some(LunarDate {
+            day: 1,
+            is_leap: true,
+            ..date
+        });
+    }
+    if date.month < 12 {
+        return Some(LunarDate {
+            month: date.month + 1,
+            day: 1,
+            is_leap: false,
+            ..date
+        });
+    }
+    let year = date.year + 1;
+    (year <= max_supported_year()).then_some(LunarDate {
+        year,
+        month: 1,
+        day: 1,
+        is_leap: false,
+    })
+}
+
+/// Attach the festival falling on a lunar date
+fn lunar_info(date: LunarDate) -> LunarInfo {
     // A leap month repeats a month; the festival only applies to the first occurrence
-    let mut festival = if is_leap {
+    let mut festival = if date.is_leap {
         None
     } else {
-        lunar_festival(month as u8, day)
+        lunar_festival(date.month, date.day)
     };
-    if !is_leap && month == 12 {
-        let last_day =
-            month_days(year, 12).unwrap_or_else(|| panic!("获取{}年农历12月天数失败", year));
-        if day == last_day {
+    if !date.is_leap && date.month == 12 {
+        let last_day = month_days(date.year, 12)
+            .unwrap_or_else(|| panic!("获取{}年农历12月天数失败", date.year));
+        if date.day == last_day {
             festival = Some("除夕");
         }
     }
+    LunarInfo { date, festival }
+}
 
-    Some(LunarInfo {
-        date: LunarDate {
-            year,
-            month: month as u8,
-            day,
-            is_leap,
-        },
-        festival,
-    })
+/// Convert a lunar date back to its Gregorian date
+/// Returns None outside the table, for a leap month the year lacks, or for day 30 of a short month
+pub fn lunar_to_solar(year: i32, month: u8, day: u8, is_leap: bool) -> Option<NaiveDate> {
+    year_info(year)?;
+    if !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+    let leap = leap_month(year);
+    if is_leap && leap != month {
+        return None;
+    }
+    let mut offset: i64 = (MIN_YEAR..year).map(|y| lunar_year_days(y) as i64).sum();
+    for m in 1..month {
+        offset += month_days(year, m as i32)? as i64;
+        if m == leap {
+            offset += leap_days(year) as i64;
+        }
+    }
+    // The leap month follows the regular month of the same number
+    let length = if is_leap {
+        offset += month_days(year, month as i32)? as i64;
+        leap_days(year)
+    } else {
+        month_days(year, month as i32)?
+    };
+    if day > length {
+        return None;
+    }
+    let base = NaiveDate::from_ymd_opt(MIN_YEAR, BASE_MONTH, BASE_DAY)?;
+    base.checked_add_signed(Duration::days(offset + day as i64 - 1))
+}
+
+/// Days in a lunar month, 29 or 30
+/// Returns None outside the table or for a leap month the year lacks
+pub fn lunar_month_days(year: i32, month: u8, is_leap: bool) -> Option<u8> {
+    year_info(year)?;
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+    if is_leap {
+        (leap_month(year) == month).then(|| leap_days(year))
+    } else {
+        month_days(year, month as i32)
+    }
+}
+
+/// First and last Gregorian dates of the lunar month `info` falls in, with its length
+pub fn lunar_month_span(info: &LunarInfo) -> Option<(NaiveDate, NaiveDate, u8)> {
+    let LunarDate {
+        year,
+        month,
+        is_leap,
+        ..
+    } = info.date;
+    let length = lunar_month_days(year, month, is_leap)?;
+    let first = lunar_to_solar(year, month, 1, is_leap)?;
+    let last = first.checked_add_signed(Duration::days(length as i64 - 1))?;
+    Some((first, last, length))
 }
 
 /// Get the Heavenly Stems/Earthly Branches year label
@@ -169,6 +307,46 @@ pub fn gan_zhi_year(year: i32) -> String {
     format!("{}{}", stem, branch)
 }
 
+/// Get the Heavenly Stems/Earthly Branches day label, e.g. "庚午日"
+/// Counted in the sexagenary cycle from 1900-01-01, a 甲戌 day
+pub fn gan_zhi_day(date: NaiveDate) -> String {
+    let index = gan_zhi_day_index(date);
+    format!("{}{}日", STEMS[index % 10], BRANCHES[index % 12])
+}
+
+/// Position of the day in the sexagenary cycle, 0 being 甲子
+pub fn gan_zhi_day_index(date: NaiveDate) -> usize {
+    let base = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap();
+    ((date - base).num_days() + 10).rem_euclid(60) as usize
+}
+
+/// Get the double-hour (时辰) of a time of day, e.g. "午时"
+/// 子时 spans 23:00–00:59 across midnight, each following branch covers two hours
+pub fn shi_chen(time: NaiveTime) -> String {
+    let branch = BRANCHES[(time.hour().div_ceil(2) % 12) as usize];
+    format!("{}时", branch)
+}
+
+/// Heavenly stem by its position from 甲
+pub fn stem(index: usize) -> char {
+    STEMS[index % STEMS.len()]
+}
+
+/// Earthly branch by its position from 子
+pub fn branch(index: usize) -> char {
+    BRANCHES[index % BRANCHES.len()]
+}
+
+/// Position of an earthly branch character from 子
+pub fn branch_index(ch: char) -> Option<usize> {
+    BRANCHES.iter().position(|branch| *branch == ch)
+}
+
+/// Zodiac animal of an earthly branch, e.g. "马" for 午
+pub fn branch_zodiac(index: usize) -> char {
+    ZODIAC[index % ZODIAC.len()]
+}
+
 /// Get the zodiac animal for the given year
 pub fn zodiac_animal(year: i32) -> char {
     ZODIAC[((year - 4).rem_euclid(12)) as usize]
@@ -255,3 +433,103 @@ fn year_info(year: i32) -> Option<u32> {
         .contains(&year)
         .then(|| LUNAR_INFO[(year - MIN_YEAR) as usize])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn shi_chen_boundaries() {
+        let cases = [
+            (time(23, 0), "子时"),
+            (time(23, 59), "子时"),
+            (time(0, 0), "子时"),
+            (time(0, 59), "子时"),
+            (time(1, 0), "丑时"),
+            (time(11, 0), "午时"),
+            (time(12, 59), "午时"),
+            (time(13, 0), "未时"),
+            (time(22, 59), "亥时"),
+        ];
+        for (at, expected) in cases {
+            assert_eq!(shi_chen(at), expected, "{at}");
+        }
+    }
+
+    #[test]
+    fn gan_zhi_day_of_known_dates() {
+        assert_eq!(gan_zhi_day(date(1900, 1, 1)), "甲戌日");
+        assert_eq!(gan_zhi_day(date(2000, 1, 1)), "戊午日");
+        // The cycle repeats every sixty days, also before the base date
+        assert_eq!(gan_zhi_day(date(1899, 11, 2)), "甲戌日");
+    }
+
+    #[test]
+    fn range_conversion_matches_single_dates() {
+        // 2020, 2023 and 2025 have leap months; the edges of the table end the walk
+        for year in [1900, 2020, 2023, 2025, max_supported_year()] {
+            // Start a month early and run past New Year to cross both year boundaries
+            let start = date(year - 1, 12, 1);
+            let days = 31 + 366 + 31;
+            let range = solar_to_lunar_range(start, days);
+            assert_eq!(range.len(), days as usize);
+            for (day, converted) in start.iter_days().zip(range) {
+                assert_eq!(converted, solar_to_lunar(day), "{day}");
+            }
+        }
+    }
+
+    #[test]
+    fn first_days_show_the_month_and_its_leap_prefix() {
+        // 2023 repeats its second month: 二月初一 on Feb 20, 闰二月初一 on Mar 22
+        let regular = solar_to_lunar(date(2023, 2, 20)).unwrap();
+        let leap = solar_to_lunar(date(2023, 3, 22)).unwrap();
+        assert_eq!((regular.day_name(), leap.day_name()), ("初一", "初一"));
+        assert_eq!(regular.cell_day_name(), "二月");
+        assert_eq!(leap.cell_day_name(), "闰二月");
+        assert_eq!(leap.month_label_hant(), "閏二月");
+        // Other days keep their day names, also inside the leap month
+        assert_eq!(
+            solar_to_lunar(date(2023, 3, 23)).unwrap().cell_day_name(),
+            "初二"
+        );
+        assert_eq!(
+            solar_to_lunar(date(2023, 3, 21)).unwrap().cell_day_name(),
+            "三十"
+        );
+    }
+
+    #[test]
+    fn month_spans_cover_long_short_and_leap_months() {
+        let span = |day| lunar_month_span(&solar_to_lunar(day).unwrap()).unwrap();
+        // 2023 二月 is long, its leap repeat short
+        assert_eq!(
+            span(date(2023, 3, 1)),
+            (date(2023, 2, 20), date(2023, 3, 21), 30)
+        );
+        assert_eq!(
+            span(date(2023, 4, 1)),
+            (date(2023, 3, 22), date(2023, 4, 19), 29)
+        );
+        assert_eq!(
+            span(date(2025, 6, 18)),
+            (date(2025, 5, 27), date(2025, 6, 24), 29)
+        );
+        // 甲辰 腊月 starts on New Year's Eve and runs up to 春节 2025
+        let last_month = (date(2024, 12, 31), date(2025, 1, 28), 29);
+        assert_eq!(span(date(2024, 12, 31)), last_month);
+        assert_eq!(span(date(2025, 1, 28)), last_month);
+        assert_eq!(
+            solar_to_lunar(date(2025, 1, 29)).unwrap().day_name(),
+            "初一"
+        );
+    }
+}
@@ -1,127 +1,1047 @@
+mod almanac;
+mod anniversaries;
 mod app;
+mod bazi;
+mod birthdays;
+mod cli;
 mod config;
+mod countdowns;
+mod events;
+mod export;
+mod fsutil;
+mod i18n;
+mod logging;
 mod lunar;
+mod notes;
+mod osc52;
+mod schedule;
+mod session;
+mod theme;
 mod ui;
 
-use std::{error::Error, io, time::Duration};
+use std::{
+    env,
+    error::Error,
+    io::{self, BufWriter, Write},
+    panic,
+    path::Path,
+    process,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
-use app::App;
-use config::{Action, BindingResolver, KeyBindings, load_key_bindings};
+use app::{App, MarkOperation, MessageLevel, ViewMode};
+use chrono::{Datelike, Local};
+use cli::{Command, TuiOptions};
+use config::{ACTIONS, Action, BindingResolver, Bound, KeyBindings, load_config};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    cursor::Show,
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+        MouseButton, MouseEventKind, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+        supports_keyboard_enhancement,
+    },
 };
+use i18n::Msg;
 use ratatui::{
-    Terminal,
+    Terminal, TerminalOptions, Viewport,
     backend::{Backend, CrosstermBackend},
 };
+#[cfg(unix)]
+use std::{fs::File, io::IsTerminal, os::unix::net::UnixStream};
 
 fn main() -> Result<(), Box<dyn Error>> {
-    // Switch the terminal to raw mode so key presses arrive immediately
-    enable_raw_mode()?;
-    // Create stdout and enter the alternate screen to avoid overwriting the main buffer
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    logging::init();
+    log::info!(
+        "moli {} started with {:?}",
+        env!("CARGO_PKG_VERSION"),
+        env::args().skip(1).collect::<Vec<_>>()
+    );
+    // Handle non-interactive commands before touching the terminal
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(Command::Tui(options)) => options,
+        Ok(Command::Help) => process::exit(cli::print_help()),
+        Ok(Command::Version) => process::exit(cli::print_version()),
+        Ok(Command::Print {
+            year,
+            month,
+            color,
+            json,
+            markdown,
+        }) => {
+            let config = load_config(None);
+            let (schedule, mut warnings) = schedule::load_holiday_schedule();
+            let (notes, note_warnings) = notes::load_notes();
+            let (events, event_warnings) = events::load_events(
+                &config.app.ics_paths(),
+                config.app.events_file_path().as_deref(),
+            );
+            let (countdowns, countdown_warnings) = countdowns::load_countdowns();
+            warnings.extend(note_warnings);
+            warnings.extend(event_warnings);
+            warnings.extend(countdown_warnings);
+            print_warnings(&warnings);
+            let app = App::new(schedule, notes, countdowns, events, config.app);
+            let (year, month) = match year {
+                Some(year) => (year, month),
+                None => (app.today().year(), Some(app.today().month())),
+            };
+            process::exit(cli::print_months(&app, year, month, color, json, markdown))
+        }
+        Ok(Command::Convert {
+            input,
+            lunar,
+            json,
+            strict,
+        }) => {
+            let schedule = load_schedule_for_command();
+            process::exit(match input {
+                Some(input) => cli::convert(&input, lunar, json, &schedule),
+                None => cli::convert_stream(lunar, json, strict, &schedule),
+            })
+        }
+        Ok(Command::Term { year, next, json }) => {
+            let today = Local::now().date_naive();
+            process::exit(if next {
+                cli::print_next_solar_term(today, json)
+            } else {
+                cli::print_solar_terms(year.unwrap_or(today.year()), json)
+            })
+        }
+        Ok(Command::Holidays { year, json }) => {
+            let schedule = load_schedule_for_command();
+            process::exit(cli::print_holidays(year, &schedule, json))
+        }
+        Ok(Command::ExportIcs { year, out }) => {
+            let schedule = load_schedule_for_command();
+            process::exit(cli::export_ics(year, out, &schedule))
+        }
+        Ok(Command::InitConfig { force }) => process::exit(cli::init_config(force)),
+        Ok(Command::CheckConfig { path }) => process::exit(cli::check_config(path)),
+        Ok(Command::Completions { shell }) => process::exit(cli::print_completions(shell)),
+        Err(message) => {
+            eprintln!("moli: {message}");
+            process::exit(2);
+        }
+    };
+    // Load key bindings and settings, then initialize app state with holiday data
+    // This happens before the alternate screen so warnings stay readable on stderr
+    let mut config = load_config(options.key_config.as_deref());
+    let (events, mut warnings) = events::load_events(
+        &config.app.ics_paths(),
+        config.app.events_file_path().as_deref(),
+    );
+    let (notes, note_warnings) = notes::load_notes();
+    let (countdowns, countdown_warnings) = countdowns::load_countdowns();
+    let (schedule, schedule_warnings) = schedule::load_holiday_schedule();
+    warnings.extend(note_warnings);
+    warnings.extend(countdown_warnings);
+    warnings.extend(schedule_warnings);
+    print_warnings(&warnings);
+    config.warnings.extend(warnings);
+    let key_bindings = config.key_bindings;
+    let mut app = match options.date {
+        Some(date) => App::with_today(schedule, notes, countdowns, events, config.app, date),
+        None => App::new(schedule, notes, countdowns, events, config.app),
+    };
+    app.set_config_warnings(config.warnings);
+    if options.pick {
+        app.start_pick();
+    } else if options.date.is_none()
+        && app.restores_session()
+        && let Some(session) = session::load_session()
+    {
+        app.restore_session(session);
+    }
+    let waiter = EventWaiter::new()?;
+    let screen = enter_terminal(Screen::new(&options, app.kitty_keyboard()))?;
+    // A panic must not leave the shell in raw mode, or its message drawn on the alternate screen
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        log::error!("panic: {info}");
+        let _ = restore_terminal(screen);
+        default_hook(info);
+    }));
     // Build the ratatui backend
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    // Initialize app state and load key bindings
-    let mut app = App::new();
-    let key_bindings = load_key_bindings();
+    let backend = CrosstermBackend::new(screen.writer());
+    let viewport = screen.viewport();
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+    if !screen.alt_screen && screen.inline.is_none() {
+        terminal.clear()?;
+    }
     // Enter the event loop
-    let res = run_app(&mut terminal, &mut app, &key_bindings);
-    // Restore normal terminal mode
-    disable_raw_mode()?;
-    // Leave the alternate screen
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    // Show the cursor again
-    terminal.show_cursor()?;
+    let key_config = options.key_config.as_deref();
+    let res = run_app(
+        &mut terminal,
+        &mut app,
+        key_bindings,
+        key_config,
+        screen,
+        &waiter,
+    );
+    if screen.inline.is_some() {
+        leave_inline_frame(&mut terminal)?;
+    }
+    restore_terminal(screen)?;
     // Return the run result to main
     res?;
+    if app.restores_session() && !options.pick {
+        session::save_session(&app.session());
+    }
+    // The picked date goes out only once the terminal is back to normal; cancelling prints nothing
+    if options.pick {
+        match pick_output(&app, options.pick_format.as_deref()) {
+            Some(line) => println!("{line}"),
+            None => process::exit(1),
+        }
+    }
+    Ok(())
+}
+
+/// Write loader warnings to stderr, which the calendar's notice repeats once the alternate
+/// screen hides them
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("moli: {warning}");
+    }
+}
+
+/// The holiday schedule for a command without the calendar, its warnings written to stderr
+fn load_schedule_for_command() -> schedule::HolidaySchedule {
+    let (schedule, warnings) = schedule::load_holiday_schedule();
+    print_warnings(&warnings);
+    schedule
+}
+
+/// The line `--pick` prints, or None when the picker was left without choosing a date
+fn pick_output(app: &App, format: Option<&str>) -> Option<String> {
+    let date = app.picked()?;
+    Some(match format {
+        Some(format) => date.format(format).to_string(),
+        None => app.format_date(date),
+    })
+}
+
+/// Where and how the calendar draws
+#[derive(Clone, Copy)]
+struct Screen {
+    /// Use the alternate screen rather than drawing over the normal one
+    alt_screen: bool,
+    /// Draw in this many rows under the prompt, on the normal screen
+    inline: Option<u16>,
+    /// Draw on stderr, keeping stdout free for the picked date
+    stderr: bool,
+    /// Switch on the kitty keyboard protocol while drawing; asked for rather than known to work
+    /// until `enter_terminal` has heard from the terminal
+    keyboard_enhancement: bool,
+}
+
+impl Screen {
+    /// The screen the command line asks for; an inline calendar always sits on the normal one
+    fn new(options: &TuiOptions, keyboard_enhancement: bool) -> Self {
+        Screen {
+            alt_screen: options.alt_screen && options.inline.is_none(),
+            inline: options.inline,
+            stderr: options.pick,
+            keyboard_enhancement,
+        }
+    }
+
+    fn viewport(self) -> Viewport {
+        match self.inline {
+            Some(height) => Viewport::Inline(height),
+            None => Viewport::Fullscreen,
+        }
+    }
+
+    fn writer(self) -> Box<dyn Write> {
+        if self.stderr {
+            // stderr is unbuffered, which would send every escape sequence of a frame on its own
+            Box::new(BufWriter::new(io::stderr()))
+        } else {
+            Box::new(io::stdout())
+        }
+    }
+}
+
+/// Keep the last inline frame in the scrollback, with the prompt coming back on the line below
+fn leave_inline_frame<B: Backend + Write>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    let area = terminal.get_frame().size();
+    terminal.set_cursor(0, area.bottom().saturating_sub(1))?;
+    // Still in raw mode, where a bare line feed would keep the column
+    write!(terminal.backend_mut(), "\r\n")?;
+    Write::flush(terminal.backend_mut())
+}
+
+/// Waits for terminal input, sleeping through idle time, and notices when moli is asked to stop
+/// by signal so the event loop exits through the normal teardown instead of dying with the tty
+/// still in raw mode, e.g. on `kill` or when the terminal window closes
+struct EventWaiter {
+    /// Set by SIGTERM, SIGHUP or SIGINT
+    shutdown: Arc<AtomicBool>,
+    /// Becomes readable when one of those signals arrives
+    #[cfg(unix)]
+    wake: UnixStream,
+    /// The terminal crossterm reads from when stdin is not one
+    #[cfg(unix)]
+    tty: Option<File>,
+}
+
+impl EventWaiter {
+    /// Ctrl+C arrives as a key in raw mode; SIGINT itself only comes from `kill -INT` and is
+    /// treated like SIGTERM
+    #[cfg(unix)]
+    fn new() -> io::Result<Self> {
+        // Same choice of terminal as crossterm makes
+        let tty = if io::stdin().is_terminal() {
+            None
+        } else {
+            Some(File::open("/dev/tty")?)
+        };
+        Self::with_tty(tty)
+    }
+
+    /// Listen for the signals, reading input from `tty`, or stdin when None
+    #[cfg(unix)]
+    fn with_tty(tty: Option<File>) -> io::Result<Self> {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (wake, notify) = UnixStream::pair()?;
+        for signal in [SIGTERM, SIGHUP, SIGINT] {
+            signal_hook::flag::register(signal, Arc::clone(&shutdown))?;
+            signal_hook::low_level::pipe::register(signal, notify.try_clone()?)?;
+        }
+        Ok(EventWaiter {
+            shutdown,
+            wake,
+            tty,
+        })
+    }
+
+    /// Other platforms close the console without a signal to catch
+    #[cfg(not(unix))]
+    fn new() -> io::Result<Self> {
+        Ok(EventWaiter {
+            shutdown: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    fn shutdown_requested(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    /// Whether an event is ready to read, waiting up to `timeout` for one
+    /// crossterm's own poll retries through signals, so it is only asked once input is there;
+    /// a signal or a resize cuts the wait short instead
+    #[cfg(unix)]
+    fn wait(&self, timeout: Duration) -> io::Result<bool> {
+        use std::os::fd::AsRawFd;
+        // Input already read and buffered by crossterm never shows on the descriptor
+        if event::poll(Duration::ZERO)? {
+            return Ok(true);
+        }
+        let tty = self
+            .tty
+            .as_ref()
+            .map_or(libc::STDIN_FILENO, AsRawFd::as_raw_fd);
+        let mut fds = [tty, self.wake.as_raw_fd()].map(|fd| libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        });
+        // Round up so a wait never ends just short of its deadline and spins
+        let millis = timeout.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32;
+        // SAFETY: `fds` is a valid array of `pollfd` that outlives the call
+        let result = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, millis) };
+        if result < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+        event::poll(Duration::ZERO)
+    }
+
+    #[cfg(not(unix))]
+    fn wait(&self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+}
+
+/// Prepare the terminal for the calendar: raw mode so key presses arrive immediately, the
+/// alternate screen to avoid overwriting the main buffer, and paste and mouse reporting
+/// `--no-alt-screen` draws over the normal screen instead, leaving the last frame behind
+/// Returns the screen with `keyboard_enhancement` kept only when the terminal speaks the kitty
+/// keyboard protocol
+fn enter_terminal(mut screen: Screen) -> io::Result<Screen> {
+    enable_raw_mode()?;
+    // The answer to the query comes back as input, which only raw mode lets us read. Terminals
+    // that answer neither it nor the device attributes one cost a two-second wait, hence the
+    // `kitty_keyboard` setting
+    screen.keyboard_enhancement &= supports_keyboard_enhancement().unwrap_or(false);
+    write_enter(&mut screen.writer(), screen)?;
+    Ok(screen)
+}
+
+/// The escape sequences of `enter_terminal`
+fn write_enter(out: &mut impl Write, screen: Screen) -> io::Result<()> {
+    if screen.alt_screen {
+        execute!(out, EnterAlternateScreen)?;
+    }
+    execute!(out, EnableBracketedPaste, EnableMouseCapture)?;
+    // Esc then arrives without the wait for a possible escape sequence, and Ctrl+i, Ctrl+m
+    // and Ctrl+Shift+letter reach us apart from Tab, Enter and Ctrl+letter. Releases are
+    // not requested; the event loop would ignore them anyway
+    if screen.keyboard_enhancement {
+        execute!(
+            out,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
+    Ok(())
+}
+
+/// Undo `enter_terminal` and show the cursor again
+/// Writes to the screen directly rather than through ratatui so the panic hook can use it too
+fn restore_terminal(screen: Screen) -> io::Result<()> {
+    disable_raw_mode()?;
+    write_restore(&mut screen.writer(), screen)
+}
+
+/// The escape sequences of `restore_terminal`, undoing `write_enter` in reverse order
+/// The keyboard flags live on a stack per screen, so they are popped before leaving the
+/// alternate one
+fn write_restore(out: &mut impl Write, screen: Screen) -> io::Result<()> {
+    if screen.keyboard_enhancement {
+        execute!(out, PopKeyboardEnhancementFlags)?;
+    }
+    execute!(out, DisableBracketedPaste, DisableMouseCapture)?;
+    if screen.alt_screen {
+        execute!(out, LeaveAlternateScreen)?;
+    }
+    execute!(out, Show)
+}
+
+/// Hand the terminal back to the shell and stop like Ctrl+Z does elsewhere, then take it back
+/// and redraw everything once the shell resumes moli with `fg`
+#[cfg(unix)]
+fn suspend<B: Backend>(terminal: &mut Terminal<B>, screen: Screen) -> io::Result<()> {
+    hand_over(
+        terminal,
+        || restore_terminal(screen),
+        stop_job,
+        || enter_terminal(screen).map(drop),
+    )
+}
+
+/// Stop moli's process group the way the terminal does for Ctrl+Z outside raw mode
+#[cfg(unix)]
+fn stop_job() {
+    // SAFETY: kill only sends a signal; pid 0 addresses moli's own process group. The default
+    // SIGTSTP action stops the process, so the call returns only after SIGCONT
+    unsafe {
+        libc::kill(0, libc::SIGTSTP);
+    }
+}
+
+/// Restore the terminal, stop, then set the terminal up again, in that order
+/// A terminal that could not be restored is not handed to the shell, so moli keeps running
+#[cfg(unix)]
+fn hand_over<B: Backend>(
+    terminal: &mut Terminal<B>,
+    restore: impl FnOnce() -> io::Result<()>,
+    stop: impl FnOnce(),
+    enter: impl FnOnce() -> io::Result<()>,
+) -> io::Result<()> {
+    restore()?;
+    stop();
+    enter()?;
+    // The shell has drawn over the screen meanwhile
+    terminal.clear()
+}
+
+/// There is no job control to hand over to outside Unix
+#[cfg(not(unix))]
+fn suspend<B: Backend>(_terminal: &mut Terminal<B>, _screen: Screen) -> io::Result<()> {
     Ok(())
 }
 
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
-    bindings: &KeyBindings,
+    mut bindings: KeyBindings,
+    key_config: Option<&Path>,
+    screen: Screen,
+    waiter: &EventWaiter,
 ) -> io::Result<()> {
-    let mut resolver = BindingResolver::default();
+    let mut resolver = BindingResolver::new(app.sequence_timeout());
     loop {
-        terminal.draw(|frame| ui::draw(frame, app, bindings))?;
-        if event::poll(Duration::from_millis(250))?
-            && let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
-        {
-            // Handle the jump prompt modal
-            if app.jump_prompt_active() {
-                handle_prompt_key(app, key);
+        // A termination signal ends the session, waking the wait below
+        if waiter.shutdown_requested() {
+            return Ok(());
+        }
+        // Forget a half-typed sequence such as `g` once it goes stale, hiding its hints
+        if resolver.expire() {
+            app.mark_dirty();
+        }
+        // Keep "today" current when moli stays open past midnight
+        app.tick();
+        // An idle screen is left alone, which spares slow terminals and SSH links
+        if app.take_dirty() {
+            let pending = resolver.pending_hints(&bindings);
+            terminal.draw(|frame| ui::draw(frame, app, &bindings, &pending))?;
+        }
+        // Sleep until input arrives or something on screen is due to change
+        let now = Instant::now();
+        let mut timeout = app.next_wakeup(now, Local::now().time());
+        if let Some(deadline) = resolver.deadline() {
+            timeout = timeout.min(deadline.saturating_duration_since(now));
+        }
+        if !waiter.wait(timeout)? {
+            continue;
+        }
+        let key = match event::read()? {
+            // Holding a key repeats it; the kitty protocol reports those repeats apart
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            Event::Paste(text) => {
+                if handle_paste(app, &text) {
+                    app.mark_dirty();
+                }
                 continue;
             }
-            // Handle actions
-            if let Some(action) = resolver.process(bindings, key)
-                && handle_action(app, action)
-            {
-                return Ok(());
+            // Clicks and wheel turns run their bound actions, but only outside dialogs
+            Event::Mouse(mouse) => {
+                if !app.config_warnings().is_empty()
+                    || app.jump_prompt_active()
+                    || app.note_prompt_active()
+                    || app.note_search_active()
+                    || app.festival_search_active()
+                    || app.date_diff_active()
+                    || app.bazi_active()
+                    || app.mark_pending().is_some()
+                    || app.holiday_list_active()
+                    || app.keymap_help().is_some()
+                {
+                    continue;
+                }
+                match resolver.process_mouse(&bindings, mouse) {
+                    Some(action) => {
+                        if run_action(
+                            terminal,
+                            app,
+                            &mut bindings,
+                            key_config,
+                            screen,
+                            &mut resolver,
+                            action,
+                        )? {
+                            return Ok(());
+                        }
+                    }
+                    // An unbound left click picks the day under the pointer
+                    None => {
+                        if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                            && let Some(action) = app.click(mouse.column, mouse.row)
+                            && run_action(
+                                terminal,
+                                app,
+                                &mut bindings,
+                                key_config,
+                                screen,
+                                &mut resolver,
+                                action,
+                            )?
+                        {
+                            return Ok(());
+                        }
+                    }
+                }
+                continue;
+            }
+            // The loop redraws at once; layout, compact mode, overlays and the details scroll
+            // are all worked out from the frame size while drawing
+            Event::Resize(..) => {
+                // Click targets of the old layout no longer match the screen
+                app.set_hit_zones(Vec::new());
+                app.mark_dirty();
+                continue;
             }
+            // Focus changes, key releases and the like leave the screen as it is
+            _ => continue,
+        };
+        if handle_key(
+            terminal,
+            app,
+            &mut bindings,
+            key_config,
+            screen,
+            &mut resolver,
+            key,
+        )? {
+            return Ok(());
         }
     }
 }
 
-/// Handle actions
-fn handle_action(app: &mut App, action: Action) -> bool {
+/// Handle a key press outside of mouse and paste input, marking the screen dirty only when the
+/// key changed something; returns true when moli should exit
+fn handle_key<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    bindings: &mut KeyBindings,
+    key_config: Option<&Path>,
+    screen: Screen,
+    resolver: &mut BindingResolver,
+    key: KeyEvent,
+) -> io::Result<bool> {
+    // Any key dismisses the config warning notice
+    if !app.config_warnings().is_empty() {
+        app.dismiss_config_warnings();
+        app.mark_dirty();
+        return Ok(false);
+    }
+    // Open dialogs and overlays take the key; only those that used it ask for a redraw
+    let modal = if app.jump_prompt_active() {
+        Some(handle_prompt_key(app, key))
+    } else if app.note_prompt_active() {
+        Some(handle_note_key(app, key))
+    } else if app.note_search_active() {
+        Some(handle_note_search_key(app, key))
+    } else if app.festival_search_active() {
+        Some(handle_festival_search_key(app, key))
+    } else if app.date_diff_active() {
+        Some(handle_date_diff_key(app, key))
+    } else if app.bazi_active() {
+        // The four pillars popup takes hours and movement keys until closed
+        let action = resolver.process(bindings, key).and_then(Bound::action);
+        Some(handle_bazi_key(app, key, action))
+    } else if app.mark_pending().is_some() {
+        // The key after `m` or `'` names the mark
+        match key.code {
+            KeyCode::Char(ch) => app.finish_mark(ch),
+            _ => app.cancel_mark(),
+        }
+        Some(true)
+    } else if app.keymap_help().is_some() {
+        // The keymap overlay takes scrolling keys until closed
+        let action = resolver.process(bindings, key).and_then(Bound::action);
+        Some(handle_keymap_help_key(app, key, action))
+    } else if app.holiday_list_active() {
+        let action = resolver.process(bindings, key).and_then(Bound::action);
+        Some(handle_holiday_list_key(app, key, action))
+    } else {
+        None
+    };
+    if let Some(changed) = modal {
+        if changed {
+            app.mark_dirty();
+        }
+        return Ok(false);
+    }
+    // Enter in the year overview opens the selected month
+    if app.view_mode() == ViewMode::Year && key.code == KeyCode::Enter {
+        app.show_month_view();
+        app.mark_dirty();
+        return Ok(false);
+    }
+    // Bare digits build a count prefix unless they belong to a binding
+    if let KeyCode::Char(ch) = key.code
+        && let Some(digit) = ch.to_digit(10)
+        && !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER)
+        && (digit != 0 || app.pending_count().is_some())
+        && !resolver.is_pending()
+        && !bindings.has_prefix(key)
+    {
+        app.push_count_digit(digit);
+        app.mark_dirty();
+        return Ok(false);
+    }
+    // Esc cancels a pending count instead of quitting
+    if key.code == KeyCode::Esc && app.pending_count().is_some() && !resolver.is_pending() {
+        app.clear_count();
+        app.mark_dirty();
+        return Ok(false);
+    }
+    // Esc leaves visual mode instead of quitting
+    if key.code == KeyCode::Esc && app.visual_active() && !resolver.is_pending() {
+        app.exit_visual();
+        app.mark_dirty();
+        return Ok(false);
+    }
+    // The open agenda takes Enter, Esc and the arrow keys from the grid
+    if app.agenda_active() && !resolver.is_pending() && handle_agenda_key(app, key) {
+        app.mark_dirty();
+        return Ok(false);
+    }
+    // Handle actions
+    let was_pending = resolver.is_pending();
+    match resolver.process(bindings, key) {
+        Some(Bound::Jump(index)) => {
+            app.clear_count();
+            app.jump_to_target(&bindings.jump_shortcut(index).target);
+            app.mark_dirty();
+        }
+        Some(Bound::Action(action)) => {
+            return run_action(
+                terminal, app, bindings, key_config, screen, resolver, action,
+            );
+        }
+        // The sequence hints appeared, changed or went away
+        None if was_pending || resolver.is_pending() => {
+            if !resolver.is_pending() {
+                app.clear_count();
+            }
+            app.mark_dirty();
+        }
+        // An unbound key drops the count, which is only on screen while typed
+        None => {
+            if app.pending_count().is_some() {
+                app.clear_count();
+                app.mark_dirty();
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Run a resolved action from the keyboard or mouse; returns true when moli should exit
+fn run_action<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    bindings: &mut KeyBindings,
+    key_config: Option<&Path>,
+    screen: Screen,
+    resolver: &mut BindingResolver,
+    action: Action,
+) -> io::Result<bool> {
+    if action == Action::Suspend {
+        app.clear_count();
+        suspend(terminal, screen)?;
+        app.mark_dirty();
+        return Ok(false);
+    }
+    if action == Action::ReloadConfig {
+        app.clear_count();
+        reload_config(app, bindings, key_config);
+        *resolver = BindingResolver::new(app.sequence_timeout());
+        app.mark_dirty();
+        return Ok(false);
+    }
+    if action == Action::YankDate {
+        app.clear_count();
+        yank_date(app, &mut screen.writer());
+        app.mark_dirty();
+        return Ok(false);
+    }
+    let count = app.take_count();
+    match handle_action(app, action, count) {
+        Handled::Quit => Ok(true),
+        Handled::Changed => {
+            app.mark_dirty();
+            Ok(false)
+        }
+        Handled::Ignored => Ok(false),
+    }
+}
+
+/// What handling an input did to the session
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Handled {
+    /// Nothing on screen changed
+    Ignored,
+    /// The screen needs a redraw
+    Changed,
+    /// moli should exit
+    Quit,
+}
+
+/// Swap in freshly loaded config files, keeping the current ones when loading fails
+fn reload_config(app: &mut App, bindings: &mut KeyBindings, key_config: Option<&Path>) {
+    apply_reloaded_config(app, bindings, config::reload_config(key_config));
+}
+
+/// Swap in a reloaded config, or report why it was rejected and keep the current one
+fn apply_reloaded_config(
+    app: &mut App,
+    bindings: &mut KeyBindings,
+    reloaded: Result<config::Config, String>,
+) {
+    match reloaded {
+        Ok(mut config) => {
+            *bindings = config.key_bindings;
+            let (events, warnings) = events::load_events(
+                &config.app.ics_paths(),
+                config.app.events_file_path().as_deref(),
+            );
+            config.warnings.extend(warnings);
+            app.apply_config(config.app, events);
+            app.notify(Msg::ConfigReloaded.text(app.locale()), MessageLevel::Info);
+            app.set_config_warnings(config.warnings);
+        }
+        Err(err) => app.notify(
+            Msg::ConfigReloadFailed.fill(app.locale(), &[&err]),
+            MessageLevel::Error,
+        ),
+    }
+}
+
+/// Handle actions, repeating movement and paging `count` times
+fn handle_action(app: &mut App, action: Action, count: u32) -> Handled {
+    let count = count as i64;
     match action {
-        Action::Quit => true,
+        Action::Quit => Handled::Quit,
         Action::MoveLeft => {
-            app.move_selection(-1);
-            false
+            app.move_selection(-count);
+            Handled::Changed
         }
         Action::MoveRight => {
-            app.move_selection(1);
-            false
+            app.move_selection(count);
+            Handled::Changed
+        }
+        Action::MoveUp if app.agenda_active() => {
+            app.move_agenda(-count);
+            Handled::Changed
+        }
+        Action::MoveDown if app.agenda_active() => {
+            app.move_agenda(count);
+            Handled::Changed
         }
         Action::MoveUp => {
-            app.move_selection(-7);
-            false
+            app.move_selection(-7 * count);
+            Handled::Changed
         }
         Action::MoveDown => {
-            app.move_selection(7);
-            false
+            app.move_selection(7 * count);
+            Handled::Changed
         }
         Action::PrevMonth => {
-            app.prev_month();
-            false
+            app.shift_months(-count);
+            Handled::Changed
         }
         Action::NextMonth => {
-            app.next_month();
-            false
+            app.shift_months(count);
+            Handled::Changed
         }
         Action::PrevYear => {
-            app.prev_year();
-            false
+            app.shift_years(-count);
+            Handled::Changed
         }
         Action::NextYear => {
-            app.next_year();
-            false
+            app.shift_years(count);
+            Handled::Changed
         }
         Action::BackToToday => {
             app.back_to_today();
-            false
+            Handled::Changed
         }
         Action::OpenJumpPrompt => {
             app.start_jump_prompt();
-            false
+            Handled::Changed
+        }
+        Action::NextHoliday => {
+            app.next_holiday();
+            Handled::Changed
+        }
+        Action::PrevHoliday => {
+            app.prev_holiday();
+            Handled::Changed
+        }
+        Action::NextSolarTerm => {
+            app.next_solar_term();
+            Handled::Changed
+        }
+        Action::PrevSolarTerm => {
+            app.prev_solar_term();
+            Handled::Changed
+        }
+        Action::SearchFestivals => {
+            app.start_festival_search();
+            Handled::Changed
+        }
+        Action::ShowYearHolidays => {
+            app.open_holiday_list();
+            Handled::Changed
+        }
+        Action::ToggleYearView => {
+            app.toggle_year_view();
+            Handled::Changed
+        }
+        Action::ToggleWeekView => {
+            app.toggle_week_view();
+            Handled::Changed
         }
+        Action::ToggleTriMonth => {
+            app.toggle_tri_month();
+            Handled::Changed
+        }
+        Action::ToggleCellLabels => {
+            app.toggle_cell_labels();
+            Handled::Changed
+        }
+        Action::StartOfMonth => {
+            app.start_of_month();
+            Handled::Changed
+        }
+        Action::EndOfMonth => {
+            app.end_of_month();
+            Handled::Changed
+        }
+        Action::StartOfYear => {
+            app.start_of_year();
+            Handled::Changed
+        }
+        Action::EndOfYear => {
+            app.end_of_year();
+            Handled::Changed
+        }
+        Action::SetMark => {
+            app.start_mark(MarkOperation::Set);
+            Handled::Changed
+        }
+        Action::JumpToMark => {
+            app.start_mark(MarkOperation::Jump);
+            Handled::Changed
+        }
+        Action::ToggleVisual => {
+            app.toggle_visual();
+            Handled::Changed
+        }
+        Action::OpenDateDiff => {
+            app.start_date_diff();
+            Handled::Changed
+        }
+        Action::OpenBazi => {
+            app.open_bazi();
+            Handled::Changed
+        }
+        Action::PinCountdown => {
+            app.toggle_countdown();
+            Handled::Changed
+        }
+        Action::ScrollDetailsUp => {
+            app.scroll_details(-(count as i32));
+            Handled::Changed
+        }
+        Action::ScrollDetailsDown => {
+            app.scroll_details(count as i32);
+            Handled::Changed
+        }
+        Action::EditNote => {
+            app.start_note_prompt();
+            Handled::Changed
+        }
+        Action::SearchNotes => {
+            app.start_note_search();
+            Handled::Changed
+        }
+        Action::ToggleAgenda => {
+            app.toggle_agenda();
+            Handled::Changed
+        }
+        Action::CycleTheme => {
+            app.cycle_theme();
+            Handled::Changed
+        }
+        // Needs the key bindings, so the event loop handles it
+        Action::ReloadConfig => Handled::Ignored,
+        // Needs the terminal, so the event loop handles it
+        Action::Suspend => Handled::Ignored,
+        // Outside pick mode there is nothing to confirm
+        Action::ConfirmPick if app.confirm_pick() => Handled::Quit,
+        Action::ConfirmPick => Handled::Ignored,
+        Action::ShowHelp => {
+            app.open_keymap_help();
+            Handled::Changed
+        }
+        Action::ToggleHelpBar => {
+            app.toggle_help_bar();
+            Handled::Changed
+        }
+        Action::ToggleDetails => {
+            app.toggle_details();
+            Handled::Changed
+        }
+        Action::ShrinkDetails => {
+            app.resize_details(-5);
+            Handled::Changed
+        }
+        Action::GrowDetails => {
+            app.resize_details(5);
+            Handled::Changed
+        }
+        Action::ExportMonth => {
+            app.export_month();
+            Handled::Changed
+        }
+        // Writes where the screen draws, so the event loop handles it
+        Action::YankDate => Handled::Ignored,
+    }
+}
+
+/// Copy the selected date's summary to the clipboard through the screen's own stream
+/// With `--pick` that is stderr, keeping stdout free for the picked date
+fn yank_date(app: &mut App, out: &mut impl Write) {
+    let summary = ui::date_summary(app);
+    match osc52::copy_to_clipboard(out, &summary) {
+        Ok(()) => app.notify(
+            Msg::Copied.fill(app.locale(), &[&summary]),
+            MessageLevel::Info,
+        ),
+        Err(err) => app.notify(
+            Msg::CopyFailed.fill(app.locale(), &[&err]),
+            MessageLevel::Error,
+        ),
     }
 }
 
-/// Handle input while the jump prompt is open
-fn handle_prompt_key(app: &mut App, key: KeyEvent) {
+/// Feed pasted text into the open text input, dropping newlines and control characters
+/// Single-line inputs take only the first line; the note editor joins every line
+/// Each input applies its own character filter and length cap; pastes elsewhere are ignored
+/// Returns whether an input took the text
+fn handle_paste(app: &mut App, text: &str) -> bool {
+    let text = if app.note_prompt_active() {
+        text
+    } else {
+        text.lines().next().unwrap_or_default()
+    };
+    let mut pasted = false;
+    for ch in text.chars().filter(|ch| !ch.is_control()) {
+        if app.jump_prompt_active() {
+            app.push_jump_input(ch);
+        } else if app.note_prompt_active() {
+            app.push_note_input(ch);
+        } else if app.note_search_active() {
+            app.push_note_search_input(ch);
+        } else if app.festival_search_active() {
+            app.push_festival_search_input(ch);
+        } else if app.date_diff_active() {
+            app.push_date_diff_input(ch);
+        } else {
+            return false;
+        }
+        pasted = true;
+    }
+    pasted
+}
+
+/// Handle input while the jump prompt is open, returning whether the key was used
+fn handle_prompt_key(app: &mut App, key: KeyEvent) -> bool {
     match key.code {
         KeyCode::Esc => app.cancel_jump_prompt(),
         KeyCode::Enter => app.confirm_jump_prompt(),
         KeyCode::Backspace => app.pop_jump_input(),
+        KeyCode::Up => app.recall_jump_history(-1),
+        KeyCode::Down => app.recall_jump_history(1),
         KeyCode::Char(ch)
             if !key
                 .modifiers
@@ -129,6 +1049,570 @@ fn handle_prompt_key(app: &mut App, key: KeyEvent) {
         {
             app.push_jump_input(ch)
         }
-        _ => {}
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the note editor is open, returning whether the key was used
+fn handle_note_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => app.cancel_note_prompt(),
+        KeyCode::Enter => app.confirm_note_prompt(),
+        KeyCode::Backspace => app.pop_note_input(),
+        KeyCode::Char(ch)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER) =>
+        {
+            app.push_note_input(ch)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the note search overlay is open, returning whether the key was used
+fn handle_note_search_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => app.cancel_note_search(),
+        KeyCode::Enter => app.confirm_note_search(),
+        KeyCode::Backspace => app.pop_note_search_input(),
+        KeyCode::Up => app.move_note_search(-1),
+        KeyCode::Down => app.move_note_search(1),
+        KeyCode::PageUp => app.move_note_search(-10),
+        KeyCode::PageDown => app.move_note_search(10),
+        KeyCode::Char(ch)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER) =>
+        {
+            app.push_note_search_input(ch)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the festival search overlay is open, returning whether the key was used
+fn handle_festival_search_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => app.cancel_festival_search(),
+        KeyCode::Enter => app.confirm_festival_search(),
+        KeyCode::Backspace => app.pop_festival_search_input(),
+        KeyCode::Up => app.move_festival_search(-1),
+        KeyCode::Down => app.move_festival_search(1),
+        KeyCode::PageUp => app.move_festival_search(-10),
+        KeyCode::PageDown => app.move_festival_search(10),
+        KeyCode::Char(ch)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER) =>
+        {
+            app.push_festival_search_input(ch)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the date difference modal is open, returning whether the key was used
+fn handle_date_diff_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => app.cancel_date_diff(),
+        KeyCode::Enter => app.confirm_date_diff(),
+        KeyCode::Tab | KeyCode::BackTab => app.switch_date_diff_field(),
+        KeyCode::Backspace => app.pop_date_diff_input(),
+        KeyCode::Char(ch)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER) =>
+        {
+            app.push_date_diff_input(ch)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the four pillars popup is open, returning whether the key was used
+/// Movement bindings step through the double-hours; digits and branch characters pick one
+fn handle_bazi_key(app: &mut App, key: KeyEvent, action: Option<Action>) -> bool {
+    match (key.code, action) {
+        (KeyCode::Esc | KeyCode::Enter, _) | (_, Some(Action::OpenBazi | Action::Quit)) => {
+            app.close_bazi()
+        }
+        (KeyCode::Up | KeyCode::Left, _) | (_, Some(Action::MoveUp | Action::MoveLeft)) => {
+            app.shift_bazi_hour(-1)
+        }
+        (KeyCode::Down | KeyCode::Right, _) | (_, Some(Action::MoveDown | Action::MoveRight)) => {
+            app.shift_bazi_hour(1)
+        }
+        (KeyCode::Char(ch), _) => app.push_bazi_input(ch),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the year holiday list is open, returning whether the key was used
+/// Movement bindings scroll the list in addition to the arrow keys
+fn handle_holiday_list_key(app: &mut App, key: KeyEvent, action: Option<Action>) -> bool {
+    match (key.code, action) {
+        (KeyCode::Esc, _) | (_, Some(Action::ShowYearHolidays)) => app.close_holiday_list(),
+        (KeyCode::Enter, _) => app.confirm_holiday_list(),
+        (KeyCode::Up, _) | (_, Some(Action::MoveUp)) => app.move_holiday_list(-1),
+        (KeyCode::Down, _) | (_, Some(Action::MoveDown)) => app.move_holiday_list(1),
+        (KeyCode::PageUp, _) => app.move_holiday_list(-10),
+        (KeyCode::PageDown, _) => app.move_holiday_list(10),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle input while the keymap overlay is open, returning whether the key was used
+fn handle_keymap_help_key(app: &mut App, key: KeyEvent, action: Option<Action>) -> bool {
+    match (key.code, action) {
+        (KeyCode::Esc, _) | (_, Some(Action::ShowHelp | Action::Quit)) => app.close_keymap_help(),
+        (KeyCode::Up, _) | (_, Some(Action::MoveUp)) => app.scroll_keymap_help(-1),
+        (KeyCode::Down, _) | (_, Some(Action::MoveDown)) => app.scroll_keymap_help(1),
+        (KeyCode::PageUp, _) => app.scroll_keymap_help(-10),
+        (KeyCode::PageDown, _) => app.scroll_keymap_help(10),
+        (KeyCode::Home, _) => app.scroll_keymap_help(-(ACTIONS.len() as i64)),
+        (KeyCode::End, _) => app.scroll_keymap_help(ACTIONS.len() as i64),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle keys owned by the open agenda panel, returning whether the key was used
+fn handle_agenda_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => app.close_agenda(),
+        KeyCode::Enter => app.confirm_agenda(),
+        KeyCode::Up => app.move_agenda(-1),
+        KeyCode::Down => app.move_agenda(1),
+        KeyCode::PageUp => app.move_agenda(-10),
+        KeyCode::PageDown => app.move_agenda(10),
+        _ => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use chrono::NaiveDate;
+    use crossterm::event::KeyEvent;
+    use ratatui::{backend::TestBackend, buffer::Buffer};
+
+    use super::*;
+    use crate::{app::test_app, config::AppConfig};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn picker() -> App {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_pick();
+        app
+    }
+
+    /// Run a key through the default bindings; returns true when it ends the session
+    fn press(app: &mut App, code: KeyCode) -> bool {
+        let bindings = KeyBindings::default();
+        let mut resolver = BindingResolver::new(app.sequence_timeout());
+        let key = KeyEvent::new(code, KeyModifiers::NONE);
+        let action = resolver.process(&bindings, key).and_then(Bound::action);
+        handle_action(app, action.expect("bound key"), 1) == Handled::Quit
+    }
+
+    #[test]
+    fn failed_reload_keeps_the_current_bindings() {
+        let dir = env::temp_dir().join(format!("moli-reload-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let labels = |bindings: &KeyBindings| -> Vec<Vec<String>> {
+            ACTIONS
+                .iter()
+                .map(|&(action, _, _)| bindings.labels_for(action))
+                .collect()
+        };
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let mut bindings = KeyBindings::default();
+        let custom = write("custom.ron", r#"(quit: ["x"])"#);
+        apply_reloaded_config(
+            &mut app,
+            &mut bindings,
+            config::reload_config_files(None, Some(&custom)),
+        );
+        assert_eq!(bindings.labels_for(Action::Quit), ["x"]);
+        assert_eq!(app.message().unwrap().level, MessageLevel::Info);
+        let loaded = labels(&bindings);
+
+        for (name, content, reason) in [
+            ("malformed.ron", r#"(quit: ["q""#, "malformed.ron"),
+            ("no-quit.ron", "(quit: [])", "quit"),
+        ] {
+            let path = write(name, content);
+            apply_reloaded_config(
+                &mut app,
+                &mut bindings,
+                config::reload_config_files(None, Some(&path)),
+            );
+            assert_eq!(labels(&bindings), loaded, "{name}");
+            let message = app.message().unwrap();
+            assert_eq!(message.level, MessageLevel::Error, "{name}");
+            assert!(message.text.contains(reason), "{}", message.text);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn confirmed_pick_prints_the_date() {
+        let mut app = picker();
+        assert!(!press(&mut app, KeyCode::Char('l')));
+        assert!(press(&mut app, KeyCode::Enter));
+        assert_eq!(pick_output(&app, None).as_deref(), Some("2025-06-19"));
+        assert_eq!(
+            pick_output(&app, Some("%Y/%m/%d %a")).as_deref(),
+            Some("2025/06/19 Thu")
+        );
+    }
+
+    #[test]
+    fn leaving_the_picker_prints_nothing() {
+        for code in [KeyCode::Esc, KeyCode::Char('q')] {
+            let mut app = picker();
+            assert!(press(&mut app, code));
+            assert_eq!(pick_output(&app, None), None);
+        }
+    }
+
+    #[test]
+    fn yank_writes_to_the_given_stream() {
+        let mut app = picker();
+        let mut out = Vec::new();
+        yank_date(&mut app, &mut out);
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.starts_with("\x1b]52;c;") || written.starts_with("\x1bPtmux;"));
+    }
+
+    #[test]
+    fn only_keys_that_change_something_redraw() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 36)).unwrap();
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let mut bindings = KeyBindings::default();
+        let mut resolver = BindingResolver::new(None);
+        let screen = Screen {
+            alt_screen: false,
+            inline: None,
+            stderr: false,
+            keyboard_enhancement: false,
+        };
+        let mut key_dirties = |app: &mut App, code: KeyCode| {
+            app.take_dirty();
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            let quit = handle_key(
+                &mut terminal,
+                app,
+                &mut bindings,
+                None,
+                screen,
+                &mut resolver,
+                key,
+            )
+            .unwrap();
+            assert!(!quit);
+            app.take_dirty()
+        };
+        // Unbound keys leave the screen alone
+        assert!(!key_dirties(&mut app, KeyCode::F(12)));
+        assert!(!key_dirties(&mut app, KeyCode::Char('Z')));
+        // Enter confirms nothing outside pick mode
+        assert!(!key_dirties(&mut app, KeyCode::Enter));
+        assert!(key_dirties(&mut app, KeyCode::Char('l')));
+        assert_eq!(app.selected_date(), date(2025, 6, 19));
+        // A count prefix shows, and an unbound key then drops it from the screen
+        assert!(key_dirties(&mut app, KeyCode::Char('3')));
+        assert!(key_dirties(&mut app, KeyCode::F(12)));
+        // Starting and abandoning a sequence shows and hides its hints
+        assert!(key_dirties(&mut app, KeyCode::Char('g')));
+        assert!(key_dirties(&mut app, KeyCode::F(12)));
+        // Inside the jump prompt only keys the prompt uses redraw
+        app.start_jump_prompt();
+        assert!(key_dirties(&mut app, KeyCode::Char('1')));
+        assert!(!key_dirties(&mut app, KeyCode::F(12)));
+        // Paste outside a text input is dropped
+        assert!(!handle_paste(
+            &mut test_app(date(2025, 6, 18), AppConfig::default()),
+            "2025"
+        ));
+        assert!(handle_paste(&mut app, "2025"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn suspending_restores_before_stopping_and_redraws_on_resume() {
+        use std::cell::RefCell;
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 36)).unwrap();
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let bindings = KeyBindings::default();
+        terminal
+            .draw(|frame| ui::draw(frame, &mut app, &bindings, &[]))
+            .unwrap();
+        let steps = RefCell::new(Vec::new());
+        let step = |name| {
+            steps.borrow_mut().push(name);
+            Ok(())
+        };
+        hand_over(
+            &mut terminal,
+            || step("restore"),
+            || steps.borrow_mut().push("stop"),
+            || step("enter"),
+        )
+        .unwrap();
+        assert_eq!(*steps.borrow(), ["restore", "stop", "enter"]);
+        // The next frame is drawn in full over whatever the shell left
+        let area = terminal.backend().buffer().area;
+        assert_eq!(*terminal.backend().buffer(), Buffer::empty(area));
+
+        // Without a restored terminal moli doesn't stop at all
+        steps.borrow_mut().clear();
+        let failed = hand_over(
+            &mut terminal,
+            || Err(io::Error::other("no tty")),
+            || steps.borrow_mut().push("stop"),
+            || step("enter"),
+        );
+        assert!(failed.is_err());
+        assert!(steps.borrow().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn termination_signals_end_the_event_loop() {
+        use std::io::Read;
+
+        let screen = Screen {
+            alt_screen: false,
+            inline: None,
+            stderr: false,
+            keyboard_enhancement: false,
+        };
+        for signal in [libc::SIGTERM, libc::SIGHUP] {
+            let waiter = EventWaiter::with_tty(None).unwrap();
+            assert!(!waiter.shutdown_requested());
+            // SAFETY: raise only sends a signal, which the waiter has a handler for
+            assert_eq!(unsafe { libc::raise(signal) }, 0);
+            assert!(waiter.shutdown_requested());
+            // The wake end turns readable, cutting a sleeping wait short
+            let mut byte = [0];
+            (&waiter.wake).read_exact(&mut byte).unwrap();
+            // The loop returns before drawing, so main goes on to restore the terminal
+            let mut terminal = Terminal::new(TestBackend::new(100, 36)).unwrap();
+            let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+            let bindings = KeyBindings::default();
+            run_app(&mut terminal, &mut app, bindings, None, screen, &waiter).unwrap();
+            let area = terminal.backend().buffer().area;
+            assert_eq!(*terminal.backend().buffer(), Buffer::empty(area));
+        }
+    }
+
+    #[test]
+    fn keyboard_flags_and_screens_are_switched_in_pairs() {
+        let escape = |command: &dyn Fn(&mut Vec<u8>) -> io::Result<()>| {
+            let mut out = Vec::new();
+            command(&mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let push = escape(&|out| {
+            execute!(
+                out,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            )
+        });
+        let pop = escape(&|out| execute!(out, PopKeyboardEnhancementFlags));
+        let enter_alt = escape(&|out| execute!(out, EnterAlternateScreen));
+        let leave_alt = escape(&|out| execute!(out, LeaveAlternateScreen));
+        for (alt_screen, keyboard_enhancement) in
+            [(false, false), (false, true), (true, false), (true, true)]
+        {
+            let screen = Screen {
+                alt_screen,
+                inline: None,
+                stderr: false,
+                keyboard_enhancement,
+            };
+            let enter = escape(&|out| write_enter(out, screen));
+            let restore = escape(&|out| write_restore(out, screen));
+            assert_eq!(
+                enter.matches(&push).count(),
+                usize::from(keyboard_enhancement)
+            );
+            assert_eq!(
+                restore.matches(&pop).count(),
+                usize::from(keyboard_enhancement)
+            );
+            assert!(!enter.contains(&pop) && !restore.contains(&push));
+            assert_eq!(enter.matches(&enter_alt).count(), usize::from(alt_screen));
+            assert_eq!(restore.matches(&leave_alt).count(), usize::from(alt_screen));
+            // The flags are pushed on the alternate screen's stack and popped from it
+            if alt_screen && keyboard_enhancement {
+                assert!(enter.find(&enter_alt) < enter.find(&push));
+                assert!(restore.find(&pop) < restore.find(&leave_alt));
+            }
+        }
+    }
+
+    #[test]
+    fn inline_mode_draws_on_the_normal_screen_and_leaves_it_there() {
+        let screen = |args: &[&str]| match cli::parse_args(args.iter().map(|arg| arg.to_string())) {
+            Ok(Command::Tui(options)) => Screen::new(&options, false),
+            _ => panic!("{args:?}"),
+        };
+        let fullscreen = screen(&[]);
+        assert!(fullscreen.alt_screen);
+        assert_eq!(fullscreen.viewport(), Viewport::Fullscreen);
+        let inline = screen(&["--inline=30"]);
+        assert!(!inline.alt_screen && !inline.stderr);
+        assert_eq!(inline.viewport(), Viewport::Inline(30));
+        assert_eq!(screen(&["--inline"]).viewport(), Viewport::Inline(24));
+
+        // Cleanup keeps to the normal screen and brings the cursor back
+        let mut out = Vec::new();
+        write_restore(&mut out, inline).unwrap();
+        let mut expected = Vec::new();
+        execute!(expected, DisableBracketedPaste, DisableMouseCapture, Show).unwrap();
+        assert_eq!(out, expected);
+        // The prompt returns on the line below the last frame
+        #[derive(Clone, Default)]
+        struct Shared(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let out = Shared::default();
+        let area = ratatui::layout::Rect::new(0, 10, 80, 30);
+        let backend = CrosstermBackend::new(out.clone());
+        let viewport = Viewport::Fixed(area);
+        let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport }).unwrap();
+        leave_inline_frame(&mut terminal).unwrap();
+        let written = String::from_utf8(out.0.borrow().clone()).unwrap();
+        assert!(written.ends_with("\x1b[40;1H\r\n"), "{written:?}");
+    }
+
+    #[test]
+    fn count_prefixes_saturate_carry_into_sequences_and_reset_on_esc() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 36)).unwrap();
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let mut bindings = KeyBindings::default();
+        let mut resolver = BindingResolver::new(None);
+        let screen = Screen {
+            alt_screen: false,
+            inline: None,
+            stderr: false,
+            keyboard_enhancement: false,
+        };
+        let mut press = |app: &mut App, keys: &[KeyCode]| {
+            for &code in keys {
+                let key = KeyEvent::new(code, KeyModifiers::NONE);
+                let quit = handle_key(
+                    &mut terminal,
+                    app,
+                    &mut bindings,
+                    None,
+                    screen,
+                    &mut resolver,
+                    key,
+                )
+                .unwrap();
+                assert!(!quit, "{code:?}");
+            }
+        };
+        let digits = |text: &str| text.chars().map(KeyCode::Char).collect::<Vec<_>>();
+        // A leading zero is no count; further digits build on the count
+        press(&mut app, &digits("0"));
+        assert_eq!(app.pending_count(), None);
+        press(&mut app, &digits("12"));
+        assert_eq!(app.pending_count(), Some(12));
+        // Typing past the limit saturates instead of wrapping
+        press(&mut app, &digits("9999999999"));
+        assert_eq!(app.pending_count(), Some(99_999));
+        // Esc drops the count without leaving moli, and the next motion moves once
+        press(&mut app, &[KeyCode::Esc]);
+        assert_eq!(app.pending_count(), None);
+        press(&mut app, &digits("l"));
+        assert_eq!(app.selected_date(), date(2025, 6, 19));
+        press(&mut app, &digits("3l"));
+        assert_eq!(app.selected_date(), date(2025, 6, 22));
+
+        // The count waits through a sequence and is used up by the action it ends in
+        press(&mut app, &digits("3g"));
+        assert_eq!(app.pending_count(), Some(3));
+        press(&mut app, &digits("h"));
+        assert!(app.holiday_list_active());
+        assert_eq!(app.pending_count(), None);
+        press(&mut app, &[KeyCode::Esc]);
+        // A sequence that goes nowhere drops the count with it
+        press(&mut app, &digits("4g"));
+        press(&mut app, &[KeyCode::F(12)]);
+        assert_eq!(app.pending_count(), None);
+        press(&mut app, &digits("l"));
+        assert_eq!(app.selected_date(), date(2025, 6, 23));
+    }
+
+    #[test]
+    fn pastes_drop_control_characters_and_stop_at_each_inputs_cap() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        // Line breaks and tabs from a copied line never reach the prompt
+        app.start_jump_prompt();
+        assert!(handle_paste(&mut app, "2025-\t06-01\r\n"));
+        assert_eq!(app.jump_prompt_view().unwrap().input, "2025-06-01");
+        // The jump prompt holds 16 characters; the rest of a paste is dropped silently
+        assert!(handle_paste(&mut app, " 0123456789"));
+        assert_eq!(app.jump_prompt_view().unwrap().input, "2025-06-01 01234");
+        app.cancel_jump_prompt();
+
+        // Pastes go to the end date, the field the calculator opens on, and keep only
+        // the first of several lines
+        app.start_date_diff();
+        handle_paste(&mut app, "2025-06-01\n2025-07-01");
+        let view = app.date_diff_view().unwrap();
+        assert_eq!(view.inputs, ["2025-06-18", "2025-06-01"]);
+        app.cancel_date_diff();
+
+        app.start_note_prompt();
+        handle_paste(&mut app, &"买菜\n".repeat(150));
+        let note = app.note_prompt_input().unwrap();
+        assert_eq!(note.chars().count(), 200);
+        assert!(!note.contains('\n'));
+        app.cancel_note_prompt();
+
+        app.start_note_search();
+        handle_paste(&mut app, &"a\x1b".repeat(300));
+        assert_eq!(app.note_search_view().unwrap().query, "a".repeat(200));
+        app.cancel_note_search();
+
+        app.start_festival_search();
+        handle_paste(&mut app, &format!("中秋\u{7}{}", "节".repeat(300)));
+        let query = app.festival_search_view().unwrap().query;
+        assert!(query.starts_with("中秋节") && query.chars().count() == 200);
+        app.cancel_festival_search();
+        app.start_festival_search();
+        handle_paste(&mut app, "中秋\r\n国庆\n");
+        assert_eq!(app.festival_search_view().unwrap().query, "中秋");
     }
 }
@@ -0,0 +1,195 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+
+use crate::fsutil::write_atomically;
+
+const NOTES_FILE_ENV: &str = "MOLI_NOTES_FILE";
+const NOTES_FILE_NAME: &str = "notes.json";
+const DATA_DIR_NAME: &str = "moli";
+
+/// Short per-date notes, saved as a JSON object keyed by ISO date
+#[derive(Clone, Debug, Default)]
+pub struct NoteStore {
+    /// Where edits are written; None keeps notes in memory only
+    path: Option<PathBuf>,
+    notes: BTreeMap<NaiveDate, String>,
+}
+
+impl NoteStore {
+    pub fn get(&self, date: NaiveDate) -> Option<&str> {
+        self.notes.get(&date).map(String::as_str)
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.notes.contains_key(&date)
+    }
+
+    /// Notes containing the query, case-insensitively, sorted by date
+    /// Case folds beyond ASCII too, like the festival search; an empty query lists every note
+    pub fn search(&self, query: &str) -> Vec<(NaiveDate, String)> {
+        let query = query.to_lowercase();
+        self.notes
+            .iter()
+            .filter(|(_, text)| text.to_lowercase().contains(&query))
+            .map(|(date, text)| (*date, text.clone()))
+            .collect()
+    }
+
+    /// Replace the note of a date, removing it when the text is blank, and save the file
+    pub fn set(&mut self, date: NaiveDate, text: &str) -> io::Result<()> {
+        let text = text.trim();
+        if text.is_empty() {
+            self.notes.remove(&date);
+        } else {
+            self.notes.insert(date, text.to_string());
+        }
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let content = serde_json::to_string_pretty(&self.notes).map_err(io::Error::other)?;
+        write_atomically(path, &content)
+    }
+}
+
+/// Load notes from the user's data directory
+/// A missing file starts an empty store; unreadable files are reported in the returned
+/// warnings and left untouched
+pub fn load_notes() -> (NoteStore, Vec<String>) {
+    let Some(path) = env::var_os(NOTES_FILE_ENV)
+        .map(PathBuf::from)
+        .or_else(default_notes_path)
+    else {
+        return (NoteStore::default(), Vec::new());
+    };
+    load_store(path)
+}
+
+/// The store saving to `path`, holding the notes already there
+fn load_store(path: PathBuf) -> (NoteStore, Vec<String>) {
+    if !path.exists() {
+        let store = NoteStore {
+            path: Some(path),
+            notes: BTreeMap::new(),
+        };
+        return (store, Vec::new());
+    }
+    match load_from_path(&path) {
+        Ok(notes) => {
+            let store = NoteStore {
+                path: Some(path),
+                notes,
+            };
+            (store, Vec::new())
+        }
+        // Keep edits in memory so a broken file is not overwritten
+        Err(warning) => (NoteStore::default(), vec![warning]),
+    }
+}
+
+fn load_from_path(path: &Path) -> Result<BTreeMap<NaiveDate, String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read notes file {}: {err}", path.display()))?;
+    serde_json::from_str(&content).map_err(|err| {
+        format!(
+            "failed to parse notes file {}: {err}; notes will not be saved",
+            path.display()
+        )
+    })
+}
+
+fn default_notes_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|mut dir| {
+        dir.push(DATA_DIR_NAME);
+        dir.push(NOTES_FILE_NAME);
+        dir
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn scratch_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("moli-notes-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(NOTES_FILE_NAME)
+    }
+
+    #[test]
+    fn notes_are_trimmed_and_blank_ones_deleted() {
+        let path = scratch_path("set");
+        let (mut store, warnings) = load_store(path.clone());
+        assert!(warnings.is_empty());
+        store.set(date(2025, 6, 18), "  买菜\n").unwrap();
+        store.set(date(2025, 6, 19), "开会").unwrap();
+        assert_eq!(store.get(date(2025, 6, 18)), Some("买菜"));
+        // The file holds what the store holds
+        assert_eq!(load_from_path(&path).unwrap(), store.notes);
+        store.set(date(2025, 6, 18), " \t ").unwrap();
+        assert!(!store.contains(date(2025, 6, 18)));
+        store.set(date(2025, 6, 19), "").unwrap();
+        assert!(load_from_path(&path).unwrap().is_empty());
+        let (reloaded, _) = load_store(path.clone());
+        assert_eq!(reloaded.get(date(2025, 6, 19)), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_is_reported_and_left_alone() {
+        let path = scratch_path("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{\"2025-06-18\": ").unwrap();
+        let (mut store, warnings) = load_store(path.clone());
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("notes will not be saved"),
+            "{warnings:?}"
+        );
+        // Edits still work, in memory only
+        store.set(date(2025, 6, 18), "买菜").unwrap();
+        assert_eq!(store.get(date(2025, 6, 18)), Some("买菜"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"2025-06-18\": ");
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn search_folds_case_and_matches_cjk_substrings() {
+        let mut store = NoteStore::default();
+        store.set(date(2025, 6, 19), "Call Mom 买菜").unwrap();
+        store.set(date(2025, 6, 18), "Café with Ölaf").unwrap();
+        store.set(date(2024, 12, 31), "跨年 party").unwrap();
+        let dates = |query| {
+            store
+                .search(query)
+                .into_iter()
+                .map(|(date, _)| date)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(dates("call mom"), [date(2025, 6, 19)]);
+        assert_eq!(dates("PARTY"), [date(2024, 12, 31)]);
+        assert_eq!(dates("菜"), [date(2025, 6, 19)]);
+        assert_eq!(dates("跨年"), [date(2024, 12, 31)]);
+        // Letters outside ASCII fold too
+        assert_eq!(dates("CAFÉ WITH ölaf"), [date(2025, 6, 18)]);
+        assert!(dates("买 菜").is_empty());
+        // An empty query lists every note by date, with its text
+        assert_eq!(
+            dates(""),
+            [date(2024, 12, 31), date(2025, 6, 18), date(2025, 6, 19)]
+        );
+        assert_eq!(store.search("mom")[0].1, "Call Mom 买菜");
+    }
+}
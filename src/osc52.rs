@@ -0,0 +1,82 @@
+use std::{
+    env,
+    io::{self, Write},
+};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Ask the terminal to put `text` on the system clipboard via the OSC 52 escape
+/// Inside tmux the sequence is wrapped in a passthrough so it reaches the outer terminal
+pub fn copy_to_clipboard(out: &mut impl Write, text: &str) -> io::Result<()> {
+    let sequence = clipboard_sequence(text, env::var_os("TMUX").is_some());
+    out.write_all(sequence.as_bytes())?;
+    out.flush()
+}
+
+/// The OSC 52 escape for `text`, wrapped for tmux when asked
+fn clipboard_sequence(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()));
+    if tmux {
+        // tmux passthrough requires every ESC inside the payload to be doubled
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Standard base64 with padding
+fn base64_encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let group = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - index * 6)) & 0x3f;
+                output.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_rfc_4648_vectors() {
+        let cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(base64_encode(input.as_bytes()), expected, "{input:?}");
+        }
+        assert_eq!(base64_encode("中秋节".as_bytes()), "5Lit56eL6IqC");
+    }
+
+    #[test]
+    fn clipboard_sequence_with_and_without_tmux() {
+        assert_eq!(
+            clipboard_sequence("2025-10-06", false),
+            "\x1b]52;c;MjAyNS0xMC0wNg==\x07"
+        );
+        assert_eq!(
+            clipboard_sequence("2025-10-06", true),
+            "\x1bPtmux;\x1b\x1b]52;c;MjAyNS0xMC0wNg==\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::HolidayInfo,
+    config,
+    i18n::{self, Locale},
+};
+
+const HOLIDAY_CONFIG_ENV: &str = "MOLI_HOLIDAY_CONFIG";
+const HOLIDAY_FILE_NAME: &str = "holidays.json";
+/// Official arrangements shipped with MoLi, in the same format as the user file
+const BUNDLED_HOLIDAYS: &str = include_str!("../data/holidays.json");
+
+/// How a date is arranged by the official holiday schedule
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayArrangement {
+    /// Day off (休)
+    Rest,
+    /// Makeup workday (班)
+    Work,
+}
+
+impl DayArrangement {
+    pub fn badge(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (DayArrangement::Rest, Locale::Zh | Locale::ZhHant) => "休",
+            (DayArrangement::Work, Locale::Zh | Locale::ZhHant) => "班",
+            (DayArrangement::Rest, Locale::En) => "R",
+            (DayArrangement::Work, Locale::En) => "W",
+        }
+    }
+}
+
+/// A continuous day-off span for one holiday
+#[derive(Clone, Debug, Deserialize)]
+struct HolidaySpan {
+    name: String,
+    start: NaiveDate,
+    end: NaiveDate,
+    /// Replaces the generated arrangement text when present
+    #[serde(default)]
+    note: Option<String>,
+}
+
+impl HolidaySpan {
+    fn contains(&self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+
+    /// Describe the span, e.g. "5月1日至5日放假，共5天"
+    fn arrangement_text(&self) -> String {
+        if let Some(note) = &self.note {
+            return note.clone();
+        }
+        let days = (self.end - self.start).num_days() + 1;
+        let start = format!("{}月{}日", self.start.month(), self.start.day());
+        if self.start == self.end {
+            return format!("{start}放假，共1天");
+        }
+        let end = if self.start.month() == self.end.month() {
+            format!("{}日", self.end.day())
+        } else {
+            format!("{}月{}日", self.end.month(), self.end.day())
+        };
+        format!("{start}至{end}放假，共{days}天")
+    }
+}
+
+/// Holiday spans and makeup workdays of a single year
+#[derive(Clone, Debug, Default)]
+struct YearArrangement {
+    spans: Vec<HolidaySpan>,
+    workdays: Vec<NaiveDate>,
+}
+
+/// Per-year official holiday arrangements
+/// Bundled data is merged with the optional user file, where the file wins
+#[derive(Clone, Debug, Default)]
+pub struct HolidaySchedule {
+    years: BTreeMap<i32, YearArrangement>,
+}
+
+impl HolidaySchedule {
+    /// Arrangements shipped with MoLi
+    pub fn bundled() -> Self {
+        let file: ScheduleFile =
+            serde_json::from_str(BUNDLED_HOLIDAYS).expect("bundled holiday data is valid JSON");
+        let mut schedule = HolidaySchedule::default();
+        schedule.merge(file);
+        schedule
+    }
+
+    /// Whether the date is an official day off or a makeup workday
+    pub fn arrangement_for(&self, date: NaiveDate) -> Option<DayArrangement> {
+        // Spans can start in the previous year (元旦 often begins on Dec 30/31)
+        let candidates = [date.year(), date.year() + 1];
+        let years = candidates.iter().filter_map(|year| self.years.get(year));
+        for arrangement in years {
+            if arrangement.workdays.contains(&date) {
+                return Some(DayArrangement::Work);
+            }
+            if arrangement.spans.iter().any(|span| span.contains(date)) {
+                return Some(DayArrangement::Rest);
+            }
+        }
+        None
+    }
+
+    /// Whether the year's official arrangement is known
+    pub fn has_year(&self, year: i32) -> bool {
+        self.years.contains_key(&year)
+    }
+
+    /// Describe a holiday for the given year
+    /// Appends that year's official arrangement when known, otherwise returns the generic note
+    /// The generated arrangement reads the same in both scripts; notes from the file are kept as written
+    pub fn note(&self, info: &HolidayInfo, year: i32, locale: Locale) -> Cow<'static, str> {
+        let note = i18n::holiday_note(info.note, locale);
+        self.years
+            .get(&year)
+            .and_then(|arrangement| arrangement.spans.iter().find(|span| span.name == info.name))
+            .map(|span| Cow::Owned(format!("{} · {}", note, span.arrangement_text())))
+            .unwrap_or(Cow::Borrowed(note))
+    }
+
+    /// Merge a parsed file over the current data
+    /// Spans replace existing ones with the same holiday name; a workday list replaces the year's list
+    fn merge(&mut self, file: ScheduleFile) {
+        for (year, entry) in file.years {
+            let arrangement = self.years.entry(year).or_default();
+            for span in entry.holidays {
+                arrangement
+                    .spans
+                    .retain(|existing| existing.name != span.name);
+                arrangement.spans.push(span);
+            }
+            if let Some(workdays) = entry.workdays {
+                arrangement.workdays = workdays;
+            }
+        }
+    }
+}
+
+/// Load the bundled schedule and merge the user's holiday file over it
+/// Problems with the file come back as warnings, for the caller to show where they can be read
+pub fn load_holiday_schedule() -> (HolidaySchedule, Vec<String>) {
+    // An explicit path must exist, while the default file is optional
+    let path = match env::var_os(HOLIDAY_CONFIG_ENV) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => default_holiday_path().filter(|path| path.exists()),
+    };
+    schedule_with_file(path.as_deref())
+}
+
+/// The bundled schedule with the holiday file at `path` merged over it
+/// A file that can't be read or parsed is reported and leaves the bundled data as is
+pub fn schedule_with_file(path: Option<&Path>) -> (HolidaySchedule, Vec<String>) {
+    let mut schedule = HolidaySchedule::bundled();
+    let Some(path) = path else {
+        return (schedule, Vec::new());
+    };
+    let file = match load_from_path(path) {
+        Ok(file) => file,
+        Err(warning) => {
+            log::warn!("{warning}");
+            return (schedule, vec![warning]);
+        }
+    };
+    log::info!("merging holiday file {path:?} over the bundled data");
+    for (year, entry) in &file.years {
+        let known = schedule.years.get(year);
+        for span in &entry.holidays {
+            let replaces = known.is_some_and(|arrangement| {
+                arrangement
+                    .spans
+                    .iter()
+                    .any(|existing| existing.name == span.name)
+            });
+            let verb = if replaces { "replaces" } else { "adds" };
+            log::debug!("{year}: {verb} {}", span.name);
+        }
+        if let Some(workdays) = &entry.workdays {
+            log::debug!("{year}: sets {} make-up workdays", workdays.len());
+        }
+    }
+    schedule.merge(file);
+    (schedule, Vec::new())
+}
+
+/// Load holiday adjustments from the specified path
+fn load_from_path(path: &Path) -> Result<ScheduleFile, String> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        format!("failed to read holiday file {path:?}: {err}; using bundled data")
+    })?;
+    serde_json::from_str(&content)
+        .map_err(|err| format!("failed to parse holiday file {path:?}: {err}; using bundled data"))
+}
+
+fn default_holiday_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(HOLIDAY_FILE_NAME))
+}
+
+/// Holiday adjustment file, keyed by year
+#[derive(Debug, Deserialize)]
+struct ScheduleFile {
+    #[serde(default)]
+    years: BTreeMap<i32, YearEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YearEntry {
+    #[serde(default)]
+    holidays: Vec<HolidaySpan>,
+    workdays: Option<Vec<NaiveDate>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::app;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn labor_day_note(schedule: &HolidaySchedule) -> String {
+        let info = app::holidays_on(date(2025, 5, 1))[0];
+        schedule.note(&info, 2025, Locale::Zh).into_owned()
+    }
+
+    #[test]
+    fn file_overrides_bundled_data() {
+        let (schedule, warnings) = schedule_with_file(Some(&fixture("holidays-2025.json")));
+        assert!(warnings.is_empty());
+        assert_eq!(
+            schedule.arrangement_for(date(2025, 5, 3)),
+            Some(DayArrangement::Rest)
+        );
+        assert_eq!(schedule.arrangement_for(date(2025, 5, 4)), None);
+        assert!(
+            labor_day_note(&schedule).ends_with("5月1日至3日放假，共3天; 4月27日上班, 以通知为准")
+        );
+        // The file's workday list replaces the bundled one for that year
+        assert_eq!(
+            schedule.arrangement_for(date(2025, 4, 27)),
+            Some(DayArrangement::Work)
+        );
+        assert_eq!(schedule.arrangement_for(date(2025, 1, 26)), None);
+        // Holidays the file leaves out keep their bundled spans
+        assert_eq!(
+            schedule.arrangement_for(date(2025, 1, 28)),
+            Some(DayArrangement::Rest)
+        );
+    }
+
+    #[test]
+    fn partial_file_keeps_other_years() {
+        let (schedule, _) = schedule_with_file(Some(&fixture("holidays-2025.json")));
+        let bundled = HolidaySchedule::bundled();
+        for year in [2023, 2024, 2026] {
+            assert!(schedule.has_year(year));
+        }
+        for day in [date(2024, 10, 1), date(2024, 9, 29), date(2026, 2, 15)] {
+            assert_eq!(schedule.arrangement_for(day), bundled.arrangement_for(day));
+        }
+    }
+
+    #[test]
+    fn malformed_file_falls_back_to_bundled_data() {
+        let (schedule, warnings) = schedule_with_file(Some(&fixture("holidays-malformed.json")));
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with("failed to parse holiday file"),
+            "{warnings:?}"
+        );
+        assert_eq!(
+            schedule.arrangement_for(date(2025, 5, 4)),
+            Some(DayArrangement::Rest)
+        );
+        assert_eq!(
+            labor_day_note(&schedule),
+            labor_day_note(&HolidaySchedule::bundled())
+        );
+        let (missing, warnings) = schedule_with_file(Some(&fixture("no-such-file.json")));
+        assert!(
+            warnings[0].starts_with("failed to read holiday file"),
+            "{warnings:?}"
+        );
+        assert_eq!(
+            missing.arrangement_for(date(2025, 1, 26)),
+            Some(DayArrangement::Work)
+        );
+    }
+}
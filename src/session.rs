@@ -0,0 +1,131 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{app::ViewMode, fsutil::write_atomically};
+
+const STATE_FILE_ENV: &str = "MOLI_STATE_FILE";
+const STATE_FILE_NAME: &str = "state.ron";
+const STATE_DIR_NAME: &str = "moli";
+
+/// What was on screen when moli last quit, restored on the next start with `restore_session`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Session {
+    /// The selected date, which also decides the viewed month
+    pub selected: NaiveDate,
+    pub view: ViewMode,
+    pub show_help_bar: bool,
+    pub show_details: bool,
+    pub show_cell_labels: bool,
+    pub tri_month: bool,
+}
+
+/// Read the saved session; a missing, unreadable or corrupt file gives None
+/// Problems are logged rather than shown, since the calendar simply opens on today instead
+pub fn load_session() -> Option<Session> {
+    read_session(&session_path()?)
+}
+
+fn read_session(path: &Path) -> Option<Session> {
+    if !path.exists() {
+        return None;
+    }
+    let content = fs::read_to_string(path)
+        .inspect_err(|err| log::warn!("failed to read session file {path:?}: {err}"))
+        .ok()?;
+    ron::from_str(&content)
+        .inspect_err(|err| log::warn!("ignoring corrupt session file {path:?}: {err}"))
+        .ok()
+}
+
+/// Save the session for the next start; a failure is logged and otherwise ignored
+pub fn save_session(session: &Session) {
+    let Some(path) = session_path() else {
+        return;
+    };
+    if let Err(err) = write_session(&path, session) {
+        log::warn!("failed to save session file {path:?}: {err}");
+    }
+}
+
+fn write_session(path: &Path, session: &Session) -> io::Result<()> {
+    let content = ron::ser::to_string_pretty(session, ron::ser::PrettyConfig::default())
+        .map_err(io::Error::other)?;
+    write_atomically(path, &content)
+}
+
+/// `MOLI_STATE_FILE`, or `state.ron` in the user's state directory (the data directory where
+/// the platform has none)
+fn session_path() -> Option<PathBuf> {
+    env::var_os(STATE_FILE_ENV).map(PathBuf::from).or_else(|| {
+        dirs::state_dir().or_else(dirs::data_dir).map(|mut dir| {
+            dir.push(STATE_DIR_NAME);
+            dir.push(STATE_FILE_NAME);
+            dir
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::test_app, config::AppConfig, lunar};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn session(selected: NaiveDate) -> Session {
+        Session {
+            selected,
+            view: ViewMode::Year,
+            show_help_bar: false,
+            show_details: true,
+            show_cell_labels: false,
+            tri_month: true,
+        }
+    }
+
+    /// A fresh path under the system temp directory, unique to the test
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("moli-session-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(STATE_FILE_NAME)
+    }
+
+    #[test]
+    fn session_round_trips_through_ron() {
+        let path = temp_path("round-trip");
+        let saved = session(date(2025, 6, 18));
+        write_session(&path, &saved).unwrap();
+        assert_eq!(read_session(&path), Some(saved));
+        // The temporary file is renamed away
+        assert!(!path.with_extension("ron.tmp").exists());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_or_corrupt_file_gives_none() {
+        let path = temp_path("corrupt");
+        assert_eq!(read_session(&path), None);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "(selected: \"not a date\")").unwrap();
+        assert_eq!(read_session(&path), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn restored_date_is_clamped_to_the_supported_range() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.restore_session(session(date(1800, 3, 1)));
+        assert_eq!(app.selected_date(), date(lunar::MIN_YEAR, 1, 1));
+        app.restore_session(session(date(2500, 3, 1)));
+        let last = date(lunar::max_supported_year(), 12, 31);
+        assert_eq!(app.selected_date(), last);
+        assert!(!app.show_help_bar());
+    }
+}
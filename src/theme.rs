@@ -0,0 +1,594 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use chrono::Weekday;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::border,
+    widgets::Block,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::{app::HolidayCategory, schedule::DayArrangement};
+
+/// Colors of the interface, set by the `theme` section of `config.ron`, and the glyphs
+/// they are drawn with
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub today_fg: Color,
+    /// Weekday column headers
+    pub header_fg: Color,
+    pub holiday_statutory: Color,
+    pub holiday_traditional: Color,
+    pub holiday_other: Color,
+    pub saturday: Color,
+    pub sunday: Color,
+    /// Days of the neighbouring months and the week number column
+    pub out_of_month: Color,
+    pub border: Color,
+    pub error: Color,
+    /// Background of the visual range
+    pub range_bg: Color,
+    pub solar_term: Color,
+    pub note: Color,
+    pub event: Color,
+    pub birthday: Color,
+    /// 休 badges
+    pub rest_day: Color,
+    /// 班 badges
+    pub work_day: Color,
+    /// Detail labels and key hints
+    pub hint: Color,
+    /// Transient status line messages
+    pub message: Color,
+    /// Pending marks, counts and out-of-range previews
+    pub warning: Color,
+    /// Borders and symbols, swapped for ASCII ones by `ascii_mode`
+    pub glyphs: Glyphs,
+}
+
+/// Border lines and decorative symbols of the interface
+#[derive(Clone, Copy, Debug)]
+pub struct Glyphs {
+    pub border: border::Set,
+    pub up: &'static str,
+    pub down: &'static str,
+    /// Leads the jump prompt's preview, e.g. "→ 2025-10-06"
+    pub arrow: &'static str,
+    /// Marks the focused field
+    pub pointer: &'static str,
+    pub bullet: &'static str,
+    /// Clickable header arrows that step the year or month back and forth
+    pub prev: &'static str,
+    pub next: &'static str,
+    /// Color sample in the legend
+    pub swatch: &'static str,
+    /// Day cell badge for a date with a note
+    pub note: &'static str,
+    /// Day cell badge for a date with a configured anniversary
+    pub anniversary: &'static str,
+    /// Day cell badge for a countdown's target date
+    pub countdown: &'static str,
+    /// End of cut-off text; one column wide in both sets
+    pub ellipsis: &'static str,
+    pub times: &'static str,
+    /// Filled and empty cells of a progress bar
+    pub bar_full: &'static str,
+    pub bar_empty: &'static str,
+    /// Whether the terminal draws East Asian ambiguous characters such as "·" two columns wide
+    pub wide_ambiguous: bool,
+}
+
+/// Rounded box drawing and Unicode symbols
+pub const UNICODE_GLYPHS: Glyphs = Glyphs {
+    border: border::ROUNDED,
+    up: "↑",
+    down: "↓",
+    arrow: "→",
+    pointer: "▶",
+    bullet: "•",
+    prev: "◀",
+    next: "▶",
+    swatch: "■",
+    note: "·",
+    anniversary: "♥",
+    countdown: "◎",
+    ellipsis: "…",
+    times: "×",
+    bar_full: "▓",
+    bar_empty: "░",
+    wide_ambiguous: false,
+};
+
+/// `+-|` borders and ASCII stand-ins for terminals or fonts without the Unicode symbols
+pub const ASCII_GLYPHS: Glyphs = Glyphs {
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+    up: "^",
+    down: "v",
+    arrow: "->",
+    pointer: ">",
+    bullet: "-",
+    prev: "<",
+    next: ">",
+    swatch: "#",
+    note: ".",
+    anniversary: "&",
+    countdown: "@",
+    ellipsis: "~",
+    times: "x",
+    bar_full: "#",
+    bar_empty: "-",
+    wide_ambiguous: false,
+};
+
+impl Default for Theme {
+    fn default() -> Self {
+        DEFAULT_THEME
+    }
+}
+
+/// The built-in look, using only the standard named colors
+const DEFAULT_THEME: Theme = Theme {
+    selected_bg: Color::Green,
+    selected_fg: Color::Black,
+    today_fg: Color::Yellow,
+    header_fg: Color::Cyan,
+    holiday_statutory: Color::Red,
+    holiday_traditional: Color::Yellow,
+    holiday_other: Color::Magenta,
+    saturday: Color::LightBlue,
+    sunday: Color::LightRed,
+    out_of_month: Color::DarkGray,
+    border: Color::Reset,
+    error: Color::Red,
+    range_bg: Color::Blue,
+    solar_term: Color::Green,
+    note: Color::Cyan,
+    event: Color::Magenta,
+    birthday: Color::LightYellow,
+    rest_day: Color::Red,
+    work_day: Color::Gray,
+    hint: Color::Gray,
+    message: Color::Green,
+    warning: Color::Yellow,
+    glyphs: UNICODE_GLYPHS,
+};
+
+/// Darker 256-color tones that stay readable on a light background
+const LIGHT_THEME: Theme = Theme {
+    selected_bg: Color::Indexed(25),
+    selected_fg: Color::Indexed(231),
+    today_fg: Color::Indexed(166),
+    header_fg: Color::Indexed(24),
+    holiday_statutory: Color::Indexed(160),
+    holiday_traditional: Color::Indexed(130),
+    holiday_other: Color::Indexed(90),
+    saturday: Color::Indexed(26),
+    sunday: Color::Indexed(124),
+    out_of_month: Color::Indexed(247),
+    border: Color::Reset,
+    error: Color::Indexed(160),
+    range_bg: Color::Indexed(153),
+    solar_term: Color::Indexed(28),
+    note: Color::Indexed(30),
+    event: Color::Indexed(90),
+    birthday: Color::Indexed(136),
+    rest_day: Color::Indexed(160),
+    work_day: Color::Indexed(240),
+    hint: Color::Indexed(240),
+    message: Color::Indexed(28),
+    warning: Color::Indexed(166),
+    glyphs: UNICODE_GLYPHS,
+};
+
+/// Only the eight normal ANSI colors, which every terminal palette defines
+const ANSI16_THEME: Theme = Theme {
+    selected_bg: Color::Cyan,
+    selected_fg: Color::Black,
+    today_fg: Color::Yellow,
+    header_fg: Color::Cyan,
+    holiday_statutory: Color::Red,
+    holiday_traditional: Color::Yellow,
+    holiday_other: Color::Magenta,
+    saturday: Color::Blue,
+    sunday: Color::Red,
+    out_of_month: Color::Gray,
+    border: Color::Reset,
+    error: Color::Red,
+    range_bg: Color::Blue,
+    solar_term: Color::Green,
+    note: Color::Cyan,
+    event: Color::Magenta,
+    birthday: Color::Yellow,
+    rest_day: Color::Red,
+    work_day: Color::Gray,
+    hint: Color::Gray,
+    message: Color::Green,
+    warning: Color::Yellow,
+    glyphs: UNICODE_GLYPHS,
+};
+
+/// Bright colors and a white selection for dim or low-quality displays
+const HIGH_CONTRAST_THEME: Theme = Theme {
+    selected_bg: Color::White,
+    selected_fg: Color::Black,
+    today_fg: Color::LightYellow,
+    header_fg: Color::LightCyan,
+    holiday_statutory: Color::LightRed,
+    holiday_traditional: Color::LightYellow,
+    holiday_other: Color::LightMagenta,
+    saturday: Color::LightCyan,
+    sunday: Color::LightRed,
+    out_of_month: Color::Gray,
+    border: Color::White,
+    error: Color::LightRed,
+    range_bg: Color::Blue,
+    solar_term: Color::LightGreen,
+    note: Color::LightCyan,
+    event: Color::LightMagenta,
+    birthday: Color::LightYellow,
+    rest_day: Color::LightRed,
+    work_day: Color::White,
+    hint: Color::White,
+    message: Color::LightGreen,
+    warning: Color::LightYellow,
+    glyphs: UNICODE_GLYPHS,
+};
+
+/// The Okabe-Ito palette, which avoids red/green pairs
+const COLORBLIND_THEME: Theme = Theme {
+    selected_bg: Color::Rgb(0, 114, 178),
+    selected_fg: Color::White,
+    today_fg: Color::Rgb(240, 228, 66),
+    header_fg: Color::Rgb(86, 180, 233),
+    holiday_statutory: Color::Rgb(213, 94, 0),
+    holiday_traditional: Color::Rgb(230, 159, 0),
+    holiday_other: Color::Rgb(204, 121, 167),
+    saturday: Color::Rgb(86, 180, 233),
+    sunday: Color::Rgb(213, 94, 0),
+    out_of_month: Color::DarkGray,
+    border: Color::Reset,
+    error: Color::Rgb(213, 94, 0),
+    range_bg: Color::Rgb(0, 73, 115),
+    solar_term: Color::Rgb(0, 158, 115),
+    note: Color::Rgb(86, 180, 233),
+    event: Color::Rgb(204, 121, 167),
+    birthday: Color::Rgb(240, 228, 66),
+    rest_day: Color::Rgb(213, 94, 0),
+    work_day: Color::Gray,
+    hint: Color::Gray,
+    message: Color::Rgb(86, 180, 233),
+    warning: Color::Rgb(230, 159, 0),
+    glyphs: UNICODE_GLYPHS,
+};
+
+/// Built-in themes selectable by name
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThemePreset {
+    #[default]
+    Default,
+    Light,
+    Ansi16,
+    HighContrast,
+    Colorblind,
+}
+
+impl ThemePreset {
+    const ALL: [ThemePreset; 5] = [
+        ThemePreset::Default,
+        ThemePreset::Light,
+        ThemePreset::Ansi16,
+        ThemePreset::HighContrast,
+        ThemePreset::Colorblind,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemePreset::Default => "default",
+            ThemePreset::Light => "light",
+            ThemePreset::Ansi16 => "ansi16",
+            ThemePreset::HighContrast => "high-contrast",
+            ThemePreset::Colorblind => "colorblind",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The following preset, wrapping around after the last one
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|preset| *preset == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            ThemePreset::Default => DEFAULT_THEME,
+            ThemePreset::Light => LIGHT_THEME,
+            ThemePreset::Ansi16 => ANSI16_THEME,
+            ThemePreset::HighContrast => HIGH_CONTRAST_THEME,
+            ThemePreset::Colorblind => COLORBLIND_THEME,
+        }
+    }
+}
+
+impl Theme {
+    /// The bordered block every panel and popup is drawn in
+    pub fn block(&self) -> Block<'static> {
+        Block::default()
+            .border_set(self.glyphs.border)
+            .border_style(self.border_style())
+    }
+
+    /// Highlight of the selected day and of list selections
+    pub fn selected(&self) -> Style {
+        Style::default()
+            .bg(self.selected_bg)
+            .fg(self.selected_fg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn border_style(&self) -> Style {
+        Style::default().fg(self.border)
+    }
+
+    /// Foreground for weekend days and their column headers
+    /// Saturday keeps its own color; any other configured weekend day shares Sunday's
+    pub fn weekend_color(&self, weekday: Weekday) -> Color {
+        match weekday {
+            Weekday::Sat => self.saturday,
+            _ => self.sunday,
+        }
+    }
+
+    pub fn holiday(&self, category: HolidayCategory) -> Color {
+        match category {
+            HolidayCategory::Statutory => self.holiday_statutory,
+            HolidayCategory::Traditional => self.holiday_traditional,
+            HolidayCategory::OtherTraditional => self.holiday_other,
+        }
+    }
+
+    pub fn arrangement(&self, arrangement: DayArrangement) -> Color {
+        match arrangement {
+            DayArrangement::Rest => self.rest_day,
+            DayArrangement::Work => self.work_day,
+        }
+    }
+}
+
+/// The `theme` setting: a preset name, or a table of colors with an optional `preset` key
+#[derive(Clone, Debug, Default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    /// Validated colors applied over the preset, in application order
+    overrides: Vec<(String, Color)>,
+    /// Problems found while reading the section, reported by the config loader
+    warnings: Vec<String>,
+}
+
+impl ThemeConfig {
+    /// The chosen preset with the user's colors on top
+    pub fn theme(&self) -> Theme {
+        let mut theme = self.preset.theme();
+        for (slot, color) in &self.overrides {
+            apply_override(&mut theme, slot, *color);
+        }
+        theme
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+/// A color written as a name such as `"lightblue"`, a `"#rrggbb"` string, or a 0-255 palette index
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Index(u8),
+    Text(String),
+}
+
+impl RawColor {
+    fn parse(&self) -> Option<Color> {
+        match self {
+            RawColor::Index(index) => Some(Color::Indexed(*index)),
+            RawColor::Text(text) => Color::from_str(text.trim()).ok(),
+        }
+    }
+}
+
+impl fmt::Display for RawColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawColor::Index(index) => write!(f, "{index}"),
+            RawColor::Text(text) => write!(f, "{text:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawThemeConfig {
+    Preset(String),
+    Table(BTreeMap<String, RawColor>),
+}
+
+/// Unknown presets, unknown slots and invalid colors are reported and skipped instead of rejecting the theme
+impl<'de> Deserialize<'de> for ThemeConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut warnings = Vec::new();
+        let mut table = match RawThemeConfig::deserialize(deserializer)? {
+            RawThemeConfig::Preset(name) => {
+                return Ok(ThemeConfig {
+                    preset: parse_preset(&name, &mut warnings),
+                    overrides: Vec::new(),
+                    warnings,
+                });
+            }
+            RawThemeConfig::Table(table) => table,
+        };
+        let preset = match table.remove("preset") {
+            Some(RawColor::Text(name)) => parse_preset(&name, &mut warnings),
+            Some(value) => {
+                warnings.push(format!("invalid theme preset {value}, using the default"));
+                ThemePreset::Default
+            }
+            None => ThemePreset::Default,
+        };
+        // The shorthand applies first so the individual days can override it
+        let weekend = table.remove_entry("weekend");
+        let mut overrides = Vec::new();
+        for (slot, value) in weekend.into_iter().chain(table) {
+            if !apply_override(&mut Theme::default(), &slot, Color::Reset) {
+                warnings.push(format!("unknown theme color theme.{slot}, ignoring it"));
+                continue;
+            }
+            match value.parse() {
+                Some(color) => overrides.push((slot, color)),
+                None => warnings.push(format!(
+                    "invalid color {value} for theme.{slot}, keeping the preset color"
+                )),
+            }
+        }
+        Ok(ThemeConfig {
+            preset,
+            overrides,
+            warnings,
+        })
+    }
+}
+
+fn parse_preset(name: &str, warnings: &mut Vec<String>) -> ThemePreset {
+    ThemePreset::from_name(name).unwrap_or_else(|| {
+        warnings.push(format!("unknown theme preset {name:?}, using the default"));
+        ThemePreset::Default
+    })
+}
+
+/// Set the named slot, returning false when no slot has that name
+fn apply_override(theme: &mut Theme, slot: &str, color: Color) -> bool {
+    let field = match slot {
+        "weekend" => {
+            theme.saturday = color;
+            &mut theme.sunday
+        }
+        "selected_bg" => &mut theme.selected_bg,
+        "selected_fg" => &mut theme.selected_fg,
+        "today_fg" => &mut theme.today_fg,
+        "header_fg" => &mut theme.header_fg,
+        "holiday_statutory" => &mut theme.holiday_statutory,
+        "holiday_traditional" => &mut theme.holiday_traditional,
+        "holiday_other" => &mut theme.holiday_other,
+        "saturday" => &mut theme.saturday,
+        "sunday" => &mut theme.sunday,
+        "out_of_month" => &mut theme.out_of_month,
+        "border" => &mut theme.border,
+        "error" => &mut theme.error,
+        "range_bg" => &mut theme.range_bg,
+        "solar_term" => &mut theme.solar_term,
+        "note" => &mut theme.note,
+        "event" => &mut theme.event,
+        "birthday" => &mut theme.birthday,
+        "rest_day" => &mut theme.rest_day,
+        "work_day" => &mut theme.work_day,
+        "hint" => &mut theme.hint,
+        "message" => &mut theme.message,
+        "warning" => &mut theme.warning,
+        _ => return false,
+    };
+    *field = color;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(ron: &str) -> ThemeConfig {
+        ron::from_str(ron).unwrap()
+    }
+
+    #[test]
+    fn overrides_apply_over_the_preset() {
+        let mut config = parse(
+            r##"{"preset": "light", "weekend": "#ff0000", "sunday": 33, "bogus": "red", "today_fg": "notacolor"}"##,
+        );
+        assert_eq!(config.preset, ThemePreset::Light);
+        let theme = config.theme();
+        // The weekend shorthand goes first, so a single day still overrides it
+        assert_eq!(theme.saturday, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.sunday, Color::Indexed(33));
+        assert_eq!(theme.today_fg, LIGHT_THEME.today_fg);
+        assert_eq!(
+            config.take_warnings(),
+            [
+                "unknown theme color theme.bogus, ignoring it",
+                "invalid color \"notacolor\" for theme.today_fg, keeping the preset color",
+            ]
+        );
+        assert!(config.take_warnings().is_empty());
+
+        #[derive(Deserialize)]
+        struct File {
+            theme: ThemeConfig,
+        }
+        let file: File = toml::from_str("[theme]\nborder = \"lightblue\"\n").unwrap();
+        let mut config = file.theme;
+        assert_eq!(config.preset, ThemePreset::Default);
+        assert_eq!(config.theme().border, Color::LightBlue);
+        assert!(config.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn unknown_presets_fall_back_to_the_default() {
+        let mut config = parse(r#""High-Contrast""#);
+        assert_eq!(config.preset, ThemePreset::HighContrast);
+        assert!(config.take_warnings().is_empty());
+        let mut config = parse(r#""neon""#);
+        assert_eq!(config.preset, ThemePreset::Default);
+        assert_eq!(
+            config.take_warnings(),
+            ["unknown theme preset \"neon\", using the default"]
+        );
+        let mut config = parse(r#"{"preset": 5}"#);
+        assert_eq!(config.preset, ThemePreset::Default);
+        assert_eq!(
+            config.take_warnings(),
+            ["invalid theme preset 5, using the default"]
+        );
+    }
+
+    #[test]
+    fn every_preset_resolves_by_name() {
+        for preset in ThemePreset::ALL {
+            assert_eq!(ThemePreset::from_name(preset.name()), Some(preset));
+            let mut config = parse(&format!("{:?}", preset.name()));
+            assert_eq!(config.preset, preset);
+            assert!(config.take_warnings().is_empty(), "{}", preset.name());
+        }
+        // Presets differ from one another
+        let selected: Vec<Color> = ThemePreset::ALL
+            .iter()
+            .map(|preset| preset.theme().selected_bg)
+            .collect();
+        assert!(selected.iter().skip(1).any(|color| *color != selected[0]));
+    }
+}
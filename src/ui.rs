@@ -1,265 +1,2492 @@
-use chrono::Datelike;
+use std::iter;
+
+use chrono::{Datelike, NaiveDate, Weekday};
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
+    widgets::{Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, Wrap},
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::{
-    app::{App, DayCell, JumpPromptView},
-    config::{Action, KeyBindings},
+    almanac,
+    app::{
+        self, AgendaKind, App, BaziView, ClickTarget, DateDiffView, DayCell, FestivalSearchView,
+        HitZone, HolidayCategory, HolidayListView, JumpPromptView, LabelSource, MarkOperation,
+        MessageLevel, MonthStats, NoteSearchView, StatusMessage, ViewMode,
+    },
+    bazi,
+    config::{
+        ACTIONS, Action, ActionGroup, CellDetail, KeyBindings, LabelPriority, MarkerPosition,
+        PendingHint, TodayMarker, WeekStart, WeekendDays,
+    },
+    i18n::{self, Locale, Msg},
     lunar,
+    schedule::DayArrangement,
+    theme::{Glyphs, Theme},
 };
 
 /// Main entry point for rendering the UI
-pub fn draw(frame: &mut Frame, app: &App, bindings: &KeyBindings) {
-    let (help_widget, help_height) = help_bar(bindings);
+pub fn draw(frame: &mut Frame, app: &mut App, bindings: &KeyBindings, pending: &[PendingHint]) {
+    let theme = *app.theme();
+    let locale = app.locale();
+    // Below this size the layout collapses into border fragments, so explain instead
+    let size = frame.size();
+    if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
+        draw_too_small(frame, &theme, locale);
+        widen_ambiguous(frame.buffer_mut(), &theme.glyphs);
+        app.set_hit_zones(Vec::new());
+        return;
+    }
+    let (compact_width, compact_height) = app.compact_thresholds();
+    let compact = is_compact(frame.size(), compact_width, compact_height);
+    // A hidden help bar gives its rows to the calendar and leaves a hint in the header;
+    // the compact layout always hides it
+    let help = (app.show_help_bar() && !compact).then(|| {
+        help_bar(
+            bindings,
+            app.show_unbound_actions(),
+            app.pick_mode(),
+            frame.size().width,
+            app.theme_name(),
+            &theme,
+            locale,
+        )
+    });
+    let help_height = help.as_ref().map_or(0, |(_, height)| *height);
+    // The status line only takes a row while a message is showing, the countdowns while
+    // there are any
+    let status_height = u16::from(app.message().is_some());
+    let countdown_line = countdown_line(app, frame.size().width);
+    let countdown_height = u16::from(countdown_line.is_some());
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(10),
+            Constraint::Length(countdown_height),
+            Constraint::Length(status_height),
             Constraint::Length(help_height),
         ])
         .split(frame.size());
 
-    frame.render_widget(header(app), chunks[0]);
+    let help_hint = if help.is_some() {
+        None
+    } else {
+        [
+            (Action::ShowHelp, Msg::Help),
+            (Action::ToggleHelpBar, Msg::HelpBar),
+        ]
+        .into_iter()
+        .find_map(|(action, label)| {
+            let keys = bindings.labels_for(action);
+            keys.first()
+                .map(|key| format!("{} {}", key, label.text(locale)))
+        })
+    };
+    // Click targets of this frame, handed to the app once everything is drawn
+    let mut zones = Vec::new();
+    frame.render_widget(header(app, help_hint, chunks[0], &mut zones), chunks[0]);
 
-    let body = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-        .split(chunks[1]);
+    // Compact rows drop the spare line, unless every cell is asked to show two labels
+    // Without labels only the day numbers are left
+    let row_height = if !app.show_cell_labels() {
+        1
+    } else if compact && app.cell_detail() != CellDetail::Full {
+        2
+    } else {
+        3
+    };
+    // The side panels stay while the agenda is open, since the agenda lives there
+    let wants_side = app.show_details() || app.agenda_active();
+    let (calendar_area, side_area) = if compact {
+        stacked_body(app, chunks[1], row_height, wants_side)
+    } else {
+        side_by_side_body(app, chunks[1], wants_side)
+    };
 
-    frame.render_widget(calendar(app), body[0]);
-    frame.render_widget(details(app), body[1]);
-    frame.render_widget(help_widget, chunks[2]);
+    match app.view_mode() {
+        // The legend would crowd the shortened compact grid, so it only shows in the full layout
+        ViewMode::Month => draw_month(
+            frame,
+            app,
+            calendar_area,
+            row_height,
+            !compact && app.show_legend(),
+            &mut zones,
+        ),
+        ViewMode::Week => draw_week(frame, app, calendar_area, &mut zones),
+        ViewMode::Year => draw_year(frame, app, calendar_area),
+    }
+    if let Some(side_area) = side_area {
+        if compact {
+            // Details and agenda sit side by side under the calendar; upcoming holidays are left out
+            let side = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(if app.agenda_active() {
+                    vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+                } else {
+                    vec![Constraint::Percentage(100)]
+                })
+                .split(side_area);
+            draw_details(frame, app, side[0]);
+            if app.agenda_active() {
+                draw_agenda(frame, app, side[1]);
+            }
+        } else {
+            let upcoming_count = app.upcoming_holidays().len()
+                + app.upcoming_birthdays().len()
+                + app.upcoming_anniversaries().len();
+            let upcoming_height = upcoming_count.max(1) as u16 + 2;
+            // The agenda splits the details column when open
+            let side_constraints = if app.agenda_active() {
+                vec![
+                    Constraint::Min(8),
+                    Constraint::Percentage(45),
+                    Constraint::Length(upcoming_height),
+                ]
+            } else {
+                vec![Constraint::Min(8), Constraint::Length(upcoming_height)]
+            };
+            let side = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(side_constraints)
+                .split(side_area);
+            draw_details(frame, app, side[0]);
+            if app.agenda_active() {
+                draw_agenda(frame, app, side[1]);
+            }
+            frame.render_widget(upcoming(app), side[side.len() - 1]);
+        }
+    }
+    if let Some(line) = countdown_line {
+        frame.render_widget(line, chunks[2]);
+    }
+    if let Some(message) = app.message() {
+        frame.render_widget(status_line(message, &theme), chunks[3]);
+    }
+    if let Some((help_widget, _)) = help {
+        frame.render_widget(help_widget, chunks[4]);
+    }
+    // Possible completions of a half-typed key sequence
+    if !pending.is_empty() {
+        draw_pending_hints(frame, pending, bindings, chunks[1], &theme, locale);
+    }
     // Render the jump prompt overlay
     if let Some(prompt) = app.jump_prompt_view() {
-        draw_jump_prompt(frame, prompt);
+        draw_jump_prompt(frame, prompt, app.date_format(), &theme, locale);
+    }
+    // Render the note editor overlay
+    if let Some(input) = app.note_prompt_input() {
+        draw_note_prompt(frame, app.selected_date(), input, &theme, locale);
+    }
+    // Render the note search overlay
+    if let Some(search) = app.note_search_view() {
+        draw_note_search(frame, search, &theme, locale);
+    }
+    // Render the festival search overlay
+    if let Some(search) = app.festival_search_view() {
+        draw_festival_search(frame, search, &theme, locale);
+    }
+    // Render the date difference overlay
+    if let Some(diff) = app.date_diff_view() {
+        draw_date_diff(frame, diff, &theme, locale);
+    }
+    // Render the four pillars overlay
+    if let Some(view) = app.bazi_view() {
+        draw_bazi(frame, view, &theme, locale);
     }
+    // Render the year holiday list overlay
+    if let Some(list) = app.holiday_list_view() {
+        draw_holiday_list(frame, list, &theme, locale);
+    }
+    // Render the full keymap overlay
+    if app.keymap_help().is_some() {
+        draw_keymap_help(frame, app, bindings, &theme);
+    }
+    // Config problems found at startup or on reload sit above everything else
+    if !app.config_warnings().is_empty() {
+        draw_config_warnings(frame, app.config_warnings(), &theme, locale);
+    }
+    widen_ambiguous(frame.buffer_mut(), &theme.glyphs);
+    app.set_hit_zones(zones);
 }
 
-fn header(app: &App) -> Paragraph<'_> {
-    let solar = format!("公历：{} 年 {:02} 月", app.view_year(), app.view_month());
-    let today_text = format!("今天：{}", app.today().format("%Y-%m-%d"));
-    let lunar_text = if let Some(info) = app.month_anchor_lunar() {
-        let gz = lunar::gan_zhi_year(info.date.year);
-        let zodiac = lunar::zodiac_animal(info.date.year);
-        format!("农历：{}年 · {}年", gz, zodiac)
+/// Make room for symbols such as "·" and "…" on terminals that draw them two columns wide
+/// ratatui lays them out in one column, so each one pushes the rest of its text run right
+/// into the run's trailing padding, keeping borders in place; a run without padding loses
+/// its last cell instead
+fn widen_ambiguous(buffer: &mut Buffer, glyphs: &Glyphs) {
+    if !glyphs.wide_ambiguous {
+        return;
+    }
+    let area = buffer.area;
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x + 1 < area.right() {
+            let symbol = buffer.get(x, y).symbol();
+            if symbol.width() != 1 || symbol.width_cjk() != 2 || is_box_drawing(symbol) {
+                x += symbol.width().max(1) as u16;
+                continue;
+            }
+            // The cell the widened symbol takes over from the end of the run
+            let mut spare = x + 1;
+            let mut overflow = false;
+            loop {
+                let symbol = buffer.get(spare, y).symbol();
+                if symbol == glyphs.border.horizontal_top
+                    || symbol == glyphs.border.horizontal_bottom
+                {
+                    break;
+                }
+                if is_border_stop(symbol, glyphs) {
+                    spare -= 1;
+                    overflow = true;
+                    break;
+                }
+                let step = symbol.width().max(1) as u16;
+                let next = spare + step;
+                if symbol == " "
+                    && (next >= area.right() || {
+                        let after = buffer.get(next, y).symbol();
+                        after == " " || is_border_stop(after, glyphs)
+                    })
+                {
+                    break;
+                }
+                if next >= area.right() {
+                    spare = area.right() - 1;
+                    overflow = true;
+                    break;
+                }
+                spare = next;
+            }
+            if spare <= x {
+                x += 1;
+                continue;
+            }
+            for column in (x + 2..=spare).rev() {
+                let cell = buffer.get(column - 1, y).clone();
+                *buffer.get_mut(column, y) = cell;
+            }
+            // A wide character pushed against the stop would spill over it
+            if overflow && buffer.get(spare, y).symbol().width() > 1 {
+                buffer.get_mut(spare, y).set_symbol(" ");
+            }
+            let style = buffer.get(x, y).style();
+            buffer
+                .get_mut(x + 1, y)
+                .set_symbol(" ")
+                .set_style(style)
+                .set_skip(true);
+            x += 2;
+        }
+    }
+}
+
+/// Box drawing characters, which terminals draw one column wide whatever their width setting
+fn is_box_drawing(symbol: &str) -> bool {
+    symbol
+        .chars()
+        .next()
+        .is_some_and(|ch| ('\u{2500}'..='\u{257F}').contains(&ch))
+}
+
+/// Cells a widened run must not push into: vertical borders and corners
+fn is_border_stop(symbol: &str, glyphs: &Glyphs) -> bool {
+    let border = glyphs.border;
+    (is_box_drawing(symbol)
+        && symbol != border.horizontal_top
+        && symbol != border.horizontal_bottom)
+        || [
+            border.vertical_left,
+            border.vertical_right,
+            border.top_left,
+            border.top_right,
+            border.bottom_left,
+            border.bottom_right,
+        ]
+        .contains(&symbol)
+}
+
+/// One-line transient message, colored by its level
+fn status_line(message: &StatusMessage, theme: &Theme) -> Paragraph<'static> {
+    let color = match message.level {
+        MessageLevel::Info => theme.message,
+        MessageLevel::Warn => theme.warning,
+        MessageLevel::Error => theme.error,
+    };
+    Paragraph::new(format!(" {}", message.text)).style(Style::default().fg(color))
+}
+
+/// Every configured and pinned countdown on one line, e.g. " 距高考还有 87 天 · 距2025-09-30还有 3 天"
+/// None when there are none, so the line takes no room
+fn countdown_line(app: &App, width: u16) -> Option<Paragraph<'static>> {
+    let theme = app.theme();
+    let locale = app.locale();
+    let today = app.today();
+    let text = app
+        .countdowns()
+        .map(|countdown| match countdown.days_from(today) {
+            0 => Msg::TargetToday.fill(locale, &[&countdown.name]),
+            days @ 1.. => Msg::TargetDaysLeft.fill(locale, &[&countdown.name, &days]),
+            days => Msg::TargetDaysAgo.fill(locale, &[&countdown.name, &-days]),
+        })
+        .collect::<Vec<_>>()
+        .join(" · ");
+    if text.is_empty() {
+        return None;
+    }
+    let text = truncate_to_width(&text, width.saturating_sub(1) as usize, &theme.glyphs);
+    Some(Paragraph::new(format!(" {text}")).style(Style::default().fg(theme.warning)))
+}
+
+/// Smallest terminal the normal interface is drawn in
+const MIN_TERMINAL_WIDTH: u16 = 40;
+pub const MIN_TERMINAL_HEIGHT: u16 = 12;
+
+/// Vertically centered notice shown instead of the interface on a tiny terminal
+fn draw_too_small(frame: &mut Frame, theme: &Theme, locale: Locale) {
+    let size = frame.size();
+    let times = theme.glyphs.times;
+    let line = Line::from(Msg::TooSmall.fill(
+        locale,
+        &[
+            &format!("{MIN_TERMINAL_WIDTH}{times}{MIN_TERMINAL_HEIGHT}"),
+            &format!("{}{times}{}", size.width, size.height),
+        ],
+    ))
+    .style(Style::default().fg(theme.warning));
+    let height = wrapped_height(std::slice::from_ref(&line), size.width).min(size.height);
+    let area = Rect {
+        y: size.y + (size.height - height) / 2,
+        height,
+        ..size
+    };
+    let paragraph = Paragraph::new(line)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+/// Whether a terminal of this size gets the compact layout; a zero threshold never triggers
+fn is_compact(size: Rect, min_width: u16, min_height: u16) -> bool {
+    size.width < min_width || size.height < min_height
+}
+
+/// Calendar on the left and the side panels on the right
+/// The side column never squeezes the month grid below its legible width
+fn side_by_side_body(app: &App, area: Rect, wants_side: bool) -> (Rect, Option<Rect>) {
+    let side_width = if wants_side {
+        let wanted = area.width * app.details_width_percent() / 100;
+        let spare = area
+            .width
+            .saturating_sub(min_calendar_width(app.show_week_numbers()));
+        wanted.min(spare)
     } else {
-        "农历：暂不可用".to_string()
+        0
     };
-    Paragraph::new(Line::from(Span::raw(format!(
-        "{} | {} | {}",
-        solar, today_text, lunar_text
-    ))))
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .border_type(BorderType::Rounded)
-            .borders(Borders::ALL)
-            .title("MoLi"),
-    )
+    // A sliver too narrow to read is dropped and its width given back to the grid
+    if side_width < MIN_SIDE_WIDTH {
+        return (area, None);
+    }
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(side_width)])
+        .split(area);
+    (body[0], Some(body[1]))
+}
+
+/// Calendar on top and the side panels in whatever height is left below it
+fn stacked_body(app: &App, area: Rect, row_height: u16, wants_side: bool) -> (Rect, Option<Rect>) {
+    if !wants_side {
+        return (area, None);
+    }
+    // Weeks plus the weekday header and borders; other views take three fifths
+    let calendar_height = match app.view_mode() {
+        ViewMode::Month => app.month_rows().len() as u16 * row_height + 3,
+        ViewMode::Week | ViewMode::Year => area.height * 3 / 5,
+    };
+    let side_height = area.height.saturating_sub(calendar_height);
+    if side_height < MIN_STACKED_SIDE_HEIGHT {
+        return (area, None);
+    }
+    let body = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(side_height)])
+        .split(area);
+    (body[0], Some(body[1]))
+}
+
+/// Shortest details panel worth drawing below the calendar: borders around two lines
+const MIN_STACKED_SIDE_HEIGHT: u16 = 4;
+
+fn header(
+    app: &App,
+    help_hint: Option<String>,
+    area: Rect,
+    zones: &mut Vec<HitZone>,
+) -> Paragraph<'static> {
+    let theme = app.theme();
+    let locale = app.locale();
+    let today_text = Msg::HeaderToday.fill(locale, &[&app.format_date(app.today())]);
+    let lunar_text = if let Some(info) = app.header_lunar() {
+        let gz = i18n::gan_zhi(&lunar::gan_zhi_year(info.date.year), locale);
+        let zodiac = i18n::zodiac(lunar::zodiac_animal(info.date.year), locale);
+        Msg::HeaderLunar.fill(locale, &[&gz, &zodiac])
+    } else {
+        Msg::LunarUnavailable.text(locale).to_string()
+    };
+    let mut line = HeaderLine::new(theme.glyphs);
+    // The template's two slots take the year and the month, each between its step arrows
+    // A custom month_format can't be split that way, so the whole month gets the month arrows
+    let arrow_style = Style::default().fg(theme.hint);
+    let steppers = match app.format_month(app.view_year(), app.view_month()) {
+        Some(month) => {
+            let solar = Msg::HeaderMonthFormatted.fill(locale, &[&'\0']);
+            let mut pieces = solar.split('\0');
+            line.push(Span::raw(pieces.next().unwrap_or_default().to_string()));
+            vec![(
+                month,
+                Action::PrevMonth,
+                Action::NextMonth,
+                pieces.next().unwrap_or_default().to_string(),
+            )]
+        }
+        None => {
+            let solar = Msg::HeaderMonth.fill(locale, &[&'\0', &'\0']);
+            let mut pieces = solar.split('\0');
+            line.push(Span::raw(pieces.next().unwrap_or_default().to_string()));
+            vec![
+                (
+                    app.view_year().to_string(),
+                    Action::PrevYear,
+                    Action::NextYear,
+                    pieces.next().unwrap_or_default().to_string(),
+                ),
+                (
+                    format!("{:02}", app.view_month()),
+                    Action::PrevMonth,
+                    Action::NextMonth,
+                    pieces.next().unwrap_or_default().to_string(),
+                ),
+            ]
+        }
+    };
+    for (value, prev, next, piece) in steppers {
+        line.push_zone(Span::styled(theme.glyphs.prev, arrow_style), prev);
+        line.push(Span::raw(format!(" {} ", value)));
+        line.push_zone(Span::styled(theme.glyphs.next, arrow_style), next);
+        line.push(Span::raw(piece));
+    }
+    line.push(Span::raw(" | "));
+    line.push_zone(Span::raw(today_text), Action::BackToToday);
+    line.push(Span::raw(format!(" | {}", lunar_text)));
+    // Without the details column, keep the selected day's lunar date and holiday in view
+    if !app.show_details() {
+        let selected = app.selected_date();
+        let mut text = Msg::HeaderSelected.fill(locale, &[&selected.format("%m-%d")]);
+        if let Some(info) = app.selected_lunar() {
+            text.push_str(&format!(" {}", i18n::lunar_date(&info, locale)));
+        }
+        for info in app.selected_holidays() {
+            text.push_str(&format!(" · {}", i18n::name(info.name, locale)));
+        }
+        line.push(Span::styled(text, Style::default().fg(theme.header_fg)));
+    }
+    if let Some(operation) = app.mark_pending() {
+        let hint = match operation {
+            MarkOperation::Set => Msg::SetMarkHint,
+            MarkOperation::Jump => Msg::JumpMarkHint,
+        };
+        line.push(Span::styled(
+            hint.text(locale),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    if app.visual_active() {
+        line.push(Span::styled(
+            Msg::VisualHint.text(locale),
+            Style::default().fg(theme.range_bg),
+        ));
+    }
+    if let Some(count) = app.pending_count() {
+        line.push(Span::styled(
+            Msg::CountHint.fill(locale, &[&count]),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    if let Some(hint) = help_hint {
+        line.push(Span::styled(
+            format!(" | {}", hint),
+            Style::default().fg(theme.hint),
+        ));
+    }
+    let block = theme.block().borders(Borders::ALL).title("MoLi");
+    let inner = block.inner(area);
+    if app.show_year_progress() {
+        push_year_progress(&mut line, app.today(), inner.width, theme);
+    }
+    line.record_zones(inner, zones);
+    Paragraph::new(Line::from(line.spans))
+        .alignment(Alignment::Center)
+        .block(block)
+}
+
+/// Widest and narrowest year progress bar worth drawing in the header
+const YEAR_PROGRESS_BAR_MAX: usize = 10;
+const YEAR_PROGRESS_BAR_MIN: usize = 5;
+
+/// End the header with today's share of the year, e.g. " | 2025 ▓▓▓▓▓░░░░░ 47%"
+/// The bar takes what room is left, down to `YEAR_PROGRESS_BAR_MIN` cells; below that only the
+/// percentage is shown, and nothing when even that would not fit
+fn push_year_progress(line: &mut HeaderLine, today: NaiveDate, width: u16, theme: &Theme) {
+    let progress = app::year_progress(today);
+    let percent = (progress * 100.0).round();
+    let candidates = (YEAR_PROGRESS_BAR_MIN..=YEAR_PROGRESS_BAR_MAX)
+        .rev()
+        .map(|bar_width| {
+            let bar = progress_bar(progress, bar_width, &theme.glyphs);
+            format!(" | {} {bar} {percent}%", today.year())
+        })
+        .chain(iter::once(format!(" | {} {percent}%", today.year())));
+    for text in candidates {
+        if line.fits(&text, width) {
+            line.push(Span::styled(text, Style::default().fg(theme.hint)));
+            return;
+        }
+    }
+}
+
+/// `width` bar cells, the filled share rounded to the nearest cell
+fn progress_bar(progress: f64, width: usize, glyphs: &Glyphs) -> String {
+    let filled = ((progress.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    glyphs.bar_full.repeat(filled) + &glyphs.bar_empty.repeat(width - filled)
+}
+
+/// Header spans under construction, with the line columns of the clickable ones
+/// `width` is what ratatui lays the line out with, `columns` what the terminal finally shows
+/// once ambiguous-width symbols are widened
+struct HeaderLine {
+    spans: Vec<Span<'static>>,
+    glyphs: Glyphs,
+    width: u16,
+    columns: u16,
+    clickable: Vec<(u16, u16, Action)>,
+}
+
+impl HeaderLine {
+    fn new(glyphs: Glyphs) -> Self {
+        HeaderLine {
+            spans: Vec::new(),
+            glyphs,
+            width: 0,
+            columns: 0,
+            clickable: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, span: Span<'static>) {
+        self.width = self.width.saturating_add(span.width() as u16);
+        self.columns = self
+            .columns
+            .saturating_add(text_width(&span.content, &self.glyphs) as u16);
+        self.spans.push(span);
+    }
+
+    /// Whether `text` still fits on a centered line `width` columns wide, leaving enough
+    /// trailing room for the ambiguous-width symbols to widen into
+    fn fits(&self, text: &str, width: u16) -> bool {
+        let laid_out = self.width as usize + text.width();
+        let shown = self.columns as usize + text_width(text, &self.glyphs);
+        laid_out <= width as usize && shown - laid_out <= (width as usize - laid_out).div_ceil(2)
+    }
+
+    fn push_zone(&mut self, span: Span<'static>, action: Action) {
+        let width = text_width(&span.content, &self.glyphs) as u16;
+        self.clickable.push((self.columns, width, action));
+        self.push(span);
+    }
+
+    /// Place the clickable spans on screen the way a centered paragraph line lands in `inner`,
+    /// dropping whatever the line's cut-off end hides
+    fn record_zones(&self, inner: Rect, zones: &mut Vec<HitZone>) {
+        let offset = (inner.width / 2).saturating_sub(self.width.min(inner.width) / 2);
+        let shown = inner.width - offset;
+        for &(start, width, action) in &self.clickable {
+            let end = start.saturating_add(width).min(shown);
+            if start >= end || inner.height == 0 {
+                continue;
+            }
+            zones.push(HitZone {
+                area: Rect::new(inner.x + offset + start, inner.y, end - start, 1),
+                target: ClickTarget::Action(action),
+            });
+        }
+    }
+}
+
+/// Weekday header cells, starting from the configured first day of the week
+/// Each label carries its own weekday's style, so weekend days stay highlighted in any column order
+fn weekday_headers<'a>(
+    week_start: WeekStart,
+    weekend: &'a WeekendDays,
+    theme: &'a Theme,
+    locale: Locale,
+) -> impl Iterator<Item = Cell<'static>> + 'a {
+    iter::successors(Some(week_start.weekday()), |day| Some(day.succ()))
+        .take(7)
+        .map(move |weekday| {
+            let line =
+                Line::from(i18n::weekday_short(weekday, locale)).alignment(Alignment::Center);
+            let color = if weekend.contains(weekday) {
+                theme.weekend_color(weekday)
+            } else {
+                theme.header_fg
+            };
+            Cell::from(line).style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+        })
 }
 
 /// Calendar display
-fn calendar(app: &App) -> Table<'_> {
-    let headers = ["一", "二", "三", "四", "五", "六", "日"]
-        .into_iter()
-        .map(|label| {
-            let line = Line::from(label).alignment(Alignment::Center);
-            Cell::from(line).style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
-        });
+/// Each week row is `row_height` lines: the date, with 2 lines its main label and, with 3 lines,
+/// the lunar day when the main label is something else
+/// Labels are cut to the column width that `area_width` leaves each day
+fn calendar(
+    app: &App,
+    rows: Vec<Vec<DayCell>>,
+    title: String,
+    row_height: u16,
+    area_width: u16,
+    legend: Option<Line<'static>>,
+) -> Table<'static> {
+    let theme = app.theme();
+    let week_start = app.week_start();
+    let show_week_numbers = app.show_week_numbers();
+    let label_width = day_column_width(area_width, show_week_numbers);
+    let label_lines = match app.cell_detail() {
+        CellDetail::Single => row_height.saturating_sub(1).min(1),
+        _ => row_height.saturating_sub(1),
+    };
+    let today_marker = app.today_marker();
+    let label_priority = app.label_priority();
+    let show_adjacent_days = app.show_adjacent_days();
+    let locale = app.locale();
+    let headers = weekday_headers(week_start, app.weekend(), theme, locale);
+    let week_header = show_week_numbers.then(|| {
+        Cell::from(Line::from(Msg::WeekColumn.text(locale)).alignment(Alignment::Center))
+            .style(week_number_style(theme))
+    });
 
-    let header_row = Row::new(headers).height(1);
-    let widths = [Constraint::Ratio(1, 7); 7];
+    let header_row = Row::new(week_header.into_iter().chain(headers)).height(1);
 
-    let rows = app
-        .month_rows()
+    let rows = rows
         .into_iter()
         .map(|week| {
-            let cells = week.into_iter().map(|cell| day_cell(cell));
-            Row::new(cells).height(3)
+            let week_number = show_week_numbers.then(|| week_number_cell(&week, theme));
+            // The selection follows its date into the viewed month, so a blank cell never hides it
+            let cells = week.into_iter().map(|cell| {
+                if !show_adjacent_days && !cell.is_current_month {
+                    return Cell::from("");
+                }
+                day_cell(
+                    cell,
+                    theme,
+                    locale,
+                    label_priority,
+                    label_lines,
+                    label_width,
+                    today_marker,
+                )
+            });
+            Row::new(week_number.into_iter().chain(cells)).height(row_height)
         })
         .collect::<Vec<_>>();
 
-    Table::new(rows, widths).header(header_row).block(
-        Block::default()
-            .border_type(BorderType::Rounded)
-            .title("月历")
-            .borders(Borders::ALL),
-    )
+    Table::new(rows, calendar_widths(show_week_numbers))
+        .header(header_row)
+        .block(
+            theme
+                .block()
+                .title(title)
+                .title_bottom(legend.unwrap_or_default())
+                .borders(Borders::ALL),
+        )
 }
 
-fn day_cell(cell: DayCell) -> Cell<'static> {
-    // Whether to show holiday/solar-term/lunar labels next to the date number
-    let has_label = cell.holiday.is_some() || cell.solar_term.is_some() || cell.lunar.is_some();
-    let mut lines =
-        vec![Line::from(format!("{:02}", cell.date.day())).alignment(Alignment::Center)];
-    if has_label {
-        // Label priority: holiday > solar term > lunar date
-        let label = cell
-            .holiday
-            .map(|info| info.name.to_string())
-            .or_else(|| cell.solar_term.map(|name| name.to_string()))
-            .or_else(|| cell.lunar.map(|info| info.display_label().to_string()))
-            .unwrap_or_else(|| "--".to_string());
-        lines.push(Line::from(label).alignment(Alignment::Center));
-    }
-    let mut style = if cell.is_current_month {
-        Style::default()
-    } else {
-        Style::default().fg(Color::DarkGray)
+/// One-line key to the grid's colors and badges, drawn in the calendar's bottom border
+/// Entries for features without data are left out, and entries that do not fit `width` are dropped
+fn legend(app: &App, width: u16) -> Line<'static> {
+    let theme = app.theme();
+    let locale = app.locale();
+    let swatch = |color, label: Msg| {
+        let label = label.text(locale);
+        vec![
+            Span::styled(theme.glyphs.swatch, Style::default().fg(color)),
+            Span::raw(format!(" {label}")),
+        ]
     };
-    if cell.is_selected {
-        style = style
-            .bg(Color::Green)
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD);
-    } else if cell.is_today {
-        style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut entries = vec![
+        swatch(
+            theme.holiday(HolidayCategory::Statutory),
+            Msg::LegendStatutory,
+        ),
+        swatch(
+            theme.holiday(HolidayCategory::Traditional),
+            Msg::LegendTraditional,
+        ),
+        swatch(
+            theme.holiday(HolidayCategory::OtherTraditional),
+            Msg::LegendFolk,
+        ),
+        swatch(theme.solar_term, Msg::LegendSolarTerm),
+    ];
+    if app.has_birthdays() {
+        entries.push(swatch(theme.birthday, Msg::LegendBirthday));
     }
-    Cell::from(lines).style(style)
+    entries.push(vec![
+        Span::styled(
+            DayArrangement::Rest.badge(locale),
+            Style::default().fg(theme.rest_day),
+        ),
+        Span::raw("/"),
+        Span::styled(
+            DayArrangement::Work.badge(locale),
+            Style::default().fg(theme.work_day),
+        ),
+        Span::raw(format!(" {}", Msg::LegendArrangement.text(locale))),
+    ]);
+    entries.push(vec![
+        Span::styled(theme.glyphs.note, Style::default().fg(theme.note)),
+        Span::raw(format!(" {}", Msg::LegendNote.text(locale))),
+    ]);
+    if app.has_anniversaries() {
+        entries.push(vec![
+            Span::styled(
+                theme.glyphs.anniversary,
+                Style::default().fg(theme.birthday),
+            ),
+            Span::raw(format!(" {}", Msg::LegendAnniversary.text(locale))),
+        ]);
+    }
+    if app.has_events() {
+        entries.push(vec![
+            Span::styled("*", Style::default().fg(theme.event)),
+            Span::raw(format!(" {}", Msg::LegendEvent.text(locale))),
+        ]);
+    }
+
+    // Plain spaces, since "·" is itself the note badge
+    let separator = "  ";
+    let mut spans = Vec::new();
+    let mut used = 0;
+    for entry in entries {
+        let entry_width = line_width(&entry, &theme.glyphs);
+        let extra = if spans.is_empty() {
+            0
+        } else {
+            separator.width()
+        };
+        if used + extra + entry_width > width as usize {
+            break;
+        }
+        if extra > 0 {
+            spans.push(Span::raw(separator));
+        }
+        spans.extend(entry);
+        used += extra + entry_width;
+    }
+    Line::from(spans)
 }
 
-/// Selected date detail panel
-fn details(app: &App) -> Paragraph<'_> {
-    let selected = app.selected_date();
-    let holiday = app.selected_holiday();
-    let holiday_suffix = holiday
-        .map(|info| format!(" · {}", info.name))
+/// Width of the optional ISO week number column
+const WEEK_NUMBER_WIDTH: u16 = 4;
+
+/// Narrowest details column worth drawing: borders around a "当前：2025-07-10" line
+const MIN_SIDE_WIDTH: u16 = 18;
+
+/// Narrowest month grid whose cells still fit their content: the widest day line
+/// ("09'·*休" with mark, note, event and arrangement badges) or a lunar month name
+/// such as "十一月", seven times over with column spacing and borders
+fn min_calendar_width(show_week_numbers: bool) -> u16 {
+    let day_line = "09'·*".width() + "休".width();
+    let label = "十一月".width();
+    let cell = day_line.max(label) as u16;
+    let mut width = cell * 7 + 6 + 2;
+    if show_week_numbers {
+        width += WEEK_NUMBER_WIDTH + 1;
+    }
+    width
+}
+
+/// Narrowest day column a grid of this outer width gets, the reverse of `min_calendar_width`
+/// Ratio constraints hand the leftover cells to some columns, so the others get the floor
+fn day_column_width(area_width: u16, show_week_numbers: bool) -> usize {
+    let mut inner = area_width.saturating_sub(2 + 6);
+    if show_week_numbers {
+        inner = inner.saturating_sub(WEEK_NUMBER_WIDTH + 1);
+    }
+    (inner / 7) as usize
+}
+
+/// ISO week number of a calendar row, taken from its Monday
+fn week_number_cell(week: &[DayCell], theme: &Theme) -> Cell<'static> {
+    let number = week
+        .iter()
+        .find(|cell| cell.date.weekday() == Weekday::Mon)
+        .map(|cell| cell.date.iso_week().week().to_string())
         .unwrap_or_default();
-    let current_line = format!(
-        "当前：{} ({:?}){}",
-        selected.format("%Y-%m-%d"),
-        selected.weekday(),
-        holiday_suffix,
-    );
-    let mut lines = vec![Line::from(current_line)];
-    let term_line = app
-        .selected_solar_term()
-        .map(|name| format!("节气：{}", name))
-        .unwrap_or_else(|| "节气: -".to_string());
-    lines.push(Line::from(term_line));
-    if let Some(info) = holiday {
-        lines.push(Line::from(format!(
-            "{}：{} - {}",
-            info.category.label(),
-            info.name,
-            info.note
-        )));
+    Cell::from(Line::from(number).alignment(Alignment::Center)).style(week_number_style(theme))
+}
+
+fn week_number_style(theme: &Theme) -> Style {
+    Style::default().fg(theme.out_of_month)
+}
+
+/// Terminal width from which the previous and next months are shown beside the current one
+const TRI_MONTH_AUTO_WIDTH: u16 = 200;
+
+/// Column constraints of the month table, with the optional week number column first
+fn calendar_widths(show_week_numbers: bool) -> Vec<Constraint> {
+    let mut widths = vec![Constraint::Ratio(1, 7); 7];
+    if show_week_numbers {
+        widths.insert(0, Constraint::Length(WEEK_NUMBER_WIDTH));
     }
+    widths
+}
 
-    if let Some(info) = app.selected_lunar() {
-        let gz = lunar::gan_zhi_year(info.date.year);
-        let zodiac = lunar::zodiac_animal(info.date.year);
-        let lunar_line = format!(
-            "农历：{}年 {} {}",
-            gz,
-            info.month_label(),
-            info.display_label()
+/// Record each visible day of a month table drawn in `area` as a click target
+/// Mirrors the table's own layout: a border, one header row, `row_height` lines per week
+/// and columns split like `Table` does, starting at the left with one column of spacing
+fn day_zones(
+    app: &App,
+    rows: &[Vec<DayCell>],
+    area: Rect,
+    row_height: u16,
+    zones: &mut Vec<HitZone>,
+) {
+    let inner = app.theme().block().borders(Borders::ALL).inner(area);
+    let columns = Layout::horizontal(calendar_widths(app.show_week_numbers()))
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(inner);
+    // The week number column is not a day
+    let day_columns = &columns[columns.len() - 7..];
+    let mut top = inner.top().saturating_add(1);
+    for week in rows {
+        if top >= inner.bottom() {
+            break;
+        }
+        let height = row_height.min(inner.bottom() - top);
+        for (cell, column) in week.iter().zip(day_columns) {
+            if !app.show_adjacent_days() && !cell.is_current_month {
+                continue;
+            }
+            zones.push(HitZone {
+                area: Rect {
+                    y: top,
+                    height,
+                    ..*column
+                },
+                target: ClickTarget::Day(cell.date),
+            });
+        }
+        top = top.saturating_add(row_height);
+    }
+}
+
+/// Month view: the viewed month alone, or flanked by its neighbours on wide terminals
+fn draw_month(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    row_height: u16,
+    show_legend: bool,
+    zones: &mut Vec<HitZone>,
+) {
+    let wide = frame.size().width >= TRI_MONTH_AUTO_WIDTH;
+    if wide == app.tri_month_toggled() {
+        let legend = show_legend.then(|| legend(app, area.width.saturating_sub(4)));
+        let rows = app.month_rows();
+        day_zones(app, &rows, area, row_height, zones);
+        frame.render_widget(
+            calendar(
+                app,
+                rows,
+                month_title(app, app.view_year(), app.view_month(), area.width),
+                row_height,
+                area.width,
+                legend,
+            ),
+            area,
         );
-        lines.push(Line::from(lunar_line));
-        lines.push(Line::from(format!("生肖：{}", zodiac)));
-        let festival_text = info.festival.unwrap_or("-");
-        lines.push(Line::from(format!("节日：{}", festival_text)));
-    } else {
-        lines.push(Line::from("农历：超出支持范围"));
+        return;
+    }
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(area);
+    for (delta, column) in (-1..=1).zip(columns.iter()) {
+        let (year, month) = app::shift_month(app.view_year(), app.view_month(), delta);
+        let mut rows = app.month_rows_for(year, month);
+        // Only the centre month carries the selection, and the legend
+        if delta != 0 {
+            rows.iter_mut()
+                .flatten()
+                .for_each(|cell| cell.is_selected = false);
+        }
+        let legend =
+            (show_legend && delta == 0).then(|| legend(app, column.width.saturating_sub(4)));
+        day_zones(app, &rows, *column, row_height, zones);
+        frame.render_widget(
+            calendar(
+                app,
+                rows,
+                month_title(app, year, month, column.width),
+                row_height,
+                column.width,
+                legend,
+            ),
+            *column,
+        );
+    }
+}
+
+/// `App::month_title` cut to the block's top border, which leaves two columns for the corners
+fn month_title(app: &App, year: i32, month: u32, area_width: u16) -> String {
+    truncate_to_width(
+        &app.month_title(year, month),
+        area_width.saturating_sub(2) as usize,
+        &app.theme().glyphs,
+    )
+}
+
+/// Single-week view with a tall bordered cell per day
+fn draw_week(frame: &mut Frame, app: &App, area: Rect, zones: &mut Vec<HitZone>) {
+    let theme = app.theme();
+    let locale = app.locale();
+    let cells = app.week_cells();
+    let title = cells
+        .first()
+        .map(|cell| {
+            let week = cell.date.iso_week();
+            Msg::WeekTitle.fill(locale, &[&week.year(), &week.week()])
+        })
+        .unwrap_or_else(|| Msg::WeekView.text(locale).to_string());
+    let block = theme.block().title(title).borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 7); 7])
+        .split(inner);
+    for (cell, column) in cells.into_iter().zip(columns.iter()) {
+        zones.push(HitZone {
+            area: *column,
+            target: ClickTarget::Day(cell.date),
+        });
+        frame.render_widget(week_day(cell, theme, locale), *column);
     }
+}
 
+fn week_day(cell: DayCell, theme: &Theme, locale: Locale) -> Paragraph<'static> {
+    let weekday = i18n::weekday_abbr(cell.date.weekday(), locale);
+    let date = month_day(cell.date, locale);
+    let mut date_line = vec![Span::styled(
+        date,
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if let Some(arrangement) = cell.arrangement {
+        date_line.push(Span::raw(" "));
+        date_line.push(Span::styled(
+            arrangement.badge(locale),
+            Style::default().fg(theme.arrangement(arrangement)),
+        ));
+    }
+    let mut lines = vec![Line::from(date_line)];
+    if let Some(info) = cell.lunar {
+        lines.push(Line::from(i18n::lunar_date(&info, locale)));
+    }
+    for info in &cell.holidays {
+        lines.push(Line::from(Span::styled(
+            i18n::name(info.name, locale).to_string(),
+            Style::default().fg(theme.holiday(info.category)),
+        )));
+    }
+    for name in cell.birthdays.into_iter().chain(cell.anniversaries) {
+        lines.push(Line::from(Span::styled(
+            name,
+            Style::default().fg(theme.birthday),
+        )));
+    }
+    if let Some(term) = cell.solar_term {
+        lines.push(Line::from(Span::styled(
+            i18n::name(term, locale).to_string(),
+            Style::default().fg(theme.solar_term),
+        )));
+    }
+    let border_style = if cell.is_selected {
+        Style::default()
+            .fg(theme.selected_bg)
+            .add_modifier(Modifier::BOLD)
+    } else if cell.is_today {
+        Style::default().fg(theme.today_fg)
+    } else {
+        theme.border_style()
+    };
     Paragraph::new(lines)
         .block(
-            Block::default()
-                .border_type(BorderType::Rounded)
-                .title("详情")
-                .borders(Borders::ALL),
+            theme
+                .block()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title(weekday),
         )
         .wrap(Wrap { trim: true })
 }
 
-fn help_bar(bindings: &KeyBindings) -> (Paragraph<'static>, u16) {
-    let prev_month = format_actions(bindings, Action::PrevMonth);
-    let next_month = format_actions(bindings, Action::NextMonth);
-    let prev_year = format_actions(bindings, Action::PrevYear);
-    let next_year = format_actions(bindings, Action::NextYear);
-    let move_left = format_actions(bindings, Action::MoveLeft);
-    let move_right = format_actions(bindings, Action::MoveRight);
-    let move_up = format_actions(bindings, Action::MoveUp);
-    let move_down = format_actions(bindings, Action::MoveDown);
-    let back_today = format_actions(bindings, Action::BackToToday);
-    let quit = format_actions(bindings, Action::Quit);
-    let jump_to = format_actions(bindings, Action::OpenJumpPrompt);
-    let lines = vec![
-        Line::from(format!(
-            "左:{} 右:{} 上:{} 下:{} · {} / {} 切换月份 · {} / {} 切换年份",
-            move_left, move_right, move_up, move_down, prev_month, next_month, prev_year, next_year
-        )),
-        Line::from(format!(
-            "{} 回到今天 · {} 跳转日期 · {} 退出 · 配置：~/.config/moli/key_bindings.ron",
-            back_today, jump_to, quit
-        )),
-    ];
-    let height = lines.len() as u16 + 2;
-    let paragraph = Paragraph::new(lines)
+/// Minimum width of one mini month: 7 columns of "dd " plus borders
+const MINI_MONTH_WIDTH: u16 = 23;
+
+/// Whole-year overview with twelve mini month grids
+fn draw_year(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let block = theme
+        .block()
+        .title(Msg::YearTitle.fill(app.locale(), &[&app.view_year()]))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    // Prefer four columns when there is room, otherwise fall back to three
+    let columns: u16 = if inner.width >= MINI_MONTH_WIDTH * 4 {
+        4
+    } else {
+        3
+    };
+    let rows = 12 / columns;
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, rows as u32); rows as usize])
+        .split(inner);
+    for (index, month) in app.year_cells().into_iter().enumerate() {
+        let row = index as u16 / columns;
+        let column = index as u16 % columns;
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, columns as u32); columns as usize])
+            .split(row_areas[row as usize]);
+        let is_view_month = index as u32 + 1 == app.view_month();
+        frame.render_widget(
+            mini_month(
+                index as u32 + 1,
+                month,
+                is_view_month,
+                app.week_start(),
+                app.weekend(),
+                theme,
+                app.locale(),
+            ),
+            column_areas[column as usize],
+        );
+    }
+}
+
+fn mini_month(
+    month: u32,
+    rows: Vec<Vec<DayCell>>,
+    is_view_month: bool,
+    week_start: WeekStart,
+    weekend: &WeekendDays,
+    theme: &Theme,
+    locale: Locale,
+) -> Table<'static> {
+    let header = Row::new(weekday_headers(week_start, weekend, theme, locale));
+    let rows = rows
+        .into_iter()
+        .map(|week| Row::new(week.into_iter().map(|cell| mini_day_cell(cell, theme))))
+        .collect::<Vec<_>>();
+    let title_style = if is_view_month {
+        Style::default()
+            .fg(theme.selected_bg)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    Table::new(rows, [Constraint::Length(2); 7])
+        .header(header)
+        .column_spacing(1)
         .block(
-            Block::default()
-                .border_type(BorderType::Rounded)
+            theme
+                .block()
                 .borders(Borders::ALL)
-                .title("快捷键"),
+                .title(Span::styled(i18n::month_name(month, locale), title_style)),
         )
-        .wrap(Wrap { trim: true });
-    (paragraph, height)
 }
 
-fn format_actions(bindings: &KeyBindings, action: Action) -> String {
-    let labels = bindings.labels_for(action);
-    if labels.is_empty() {
-        "未绑定".into()
+/// Day cell for the year overview: only the day number, tinted by holiday
+fn mini_day_cell(cell: DayCell, theme: &Theme) -> Cell<'static> {
+    // Adjacent-month days stay blank so each date appears once in the year
+    if !cell.is_current_month {
+        return Cell::from("");
+    }
+    let mut style = match cell.holidays.first() {
+        Some(info) => Style::default().fg(theme.holiday(info.category)),
+        None => Style::default(),
+    };
+    if cell.is_selected {
+        style = style.patch(theme.selected());
+    } else if cell.is_today {
+        style = style.fg(theme.today_fg).add_modifier(Modifier::BOLD);
+    }
+    if cell.in_range && !cell.is_selected {
+        style = style.bg(theme.range_bg);
+    }
+    Cell::from(format!("{:>2}", cell.date.day())).style(style)
+}
+
+/// Underline today's number and put the configured symbol beside it
+/// A selected today keeps the symbol but takes the selection colors, so it stays legible
+fn add_today_marker(
+    day_line: &mut Vec<Span<'static>>,
+    cell: &DayCell,
+    theme: &Theme,
+    marker: &TodayMarker,
+) {
+    if marker.underline {
+        day_line[0] = day_line[0]
+            .clone()
+            .style(Style::default().add_modifier(Modifier::UNDERLINED));
+    }
+    if marker.symbol.is_empty() {
+        return;
+    }
+    let style = if cell.is_selected {
+        Style::default()
     } else {
-        labels.join("/")
+        Style::default()
+            .fg(theme.today_fg)
+            .add_modifier(Modifier::BOLD)
+    };
+    let symbol = Span::styled(marker.symbol.clone(), style);
+    match marker.position {
+        MarkerPosition::Before => day_line.insert(0, symbol),
+        MarkerPosition::After => day_line.insert(1, symbol),
     }
 }
 
-fn draw_jump_prompt(frame: &mut Frame, prompt: JumpPromptView<'_>) {
-    // Center a 40x15 window on the screen
-    let area = centered_rect(40, 15, frame.size());
-    // Clear the window area
-    frame.render_widget(Clear, area);
-    // Build prompt lines
-    let mut lines = vec![
-        Line::from(format!("目标日期 (YYYY-MM-DD)：{}", prompt.input)).alignment(Alignment::Left),
-        Line::from("Enter 确认 · Esc 取消").style(Style::default().fg(Color::Gray)),
-    ];
-    if let Some(err) = prompt.error {
-        lines.push(Line::from(err).style(Style::default().fg(Color::Red)));
+/// Label cut to a day column; a lone ellipsis in every cell would only be noise
+/// Widened symbols get matching trailing padding, so the centered label stays centered
+/// once the terminal draws them two columns wide
+fn cell_text(text: &str, width: usize, glyphs: &Glyphs) -> String {
+    let mut text = truncate_to_width(text, width, glyphs);
+    if text == glyphs.ellipsis {
+        return String::new();
     }
-    // Build the paragraph widget
+    let padding = text_width(&text, glyphs) - text.width();
+    text.extend(iter::repeat_n(' ', padding));
+    text
+}
+
+impl LabelSource {
+    /// How a label from this source is drawn in the grid, also used by `--print`
+    pub fn style(self, theme: &Theme) -> Style {
+        match self {
+            LabelSource::Holiday(HolidayCategory::Statutory) => Style::default()
+                .fg(theme.holiday(HolidayCategory::Statutory))
+                .add_modifier(Modifier::BOLD),
+            LabelSource::Holiday(category) => Style::default().fg(theme.holiday(category)),
+            LabelSource::Birthday => Style::default().fg(theme.birthday),
+            LabelSource::SolarTerm => Style::default().fg(theme.solar_term),
+            LabelSource::Festival => {
+                Style::default().fg(theme.holiday(HolidayCategory::OtherTraditional))
+            }
+            LabelSource::Lunar => Style::default().add_modifier(Modifier::DIM),
+        }
+    }
+}
+
+fn day_cell(
+    cell: DayCell,
+    theme: &Theme,
+    locale: Locale,
+    label_priority: &LabelPriority,
+    label_lines: u16,
+    label_width: usize,
+    today_marker: &TodayMarker,
+) -> Cell<'static> {
+    // Whether to show holiday/solar-term/lunar labels under the date number
+    let has_label = label_lines >= 1
+        && (!cell.holidays.is_empty()
+            || !cell.birthdays.is_empty()
+            || cell.solar_term.is_some()
+            || cell.festival.is_some()
+            || cell.lunar.is_some());
+    let mut day_line = vec![Span::raw(format!("{:02}", cell.date.day()))];
+    if cell.is_today {
+        add_today_marker(&mut day_line, &cell, theme, today_marker);
+    }
+    if cell.is_marked {
+        day_line.push(Span::raw("'"));
+    }
+    if cell.has_note {
+        day_line.push(Span::styled(
+            theme.glyphs.note,
+            Style::default().fg(theme.note),
+        ));
+    }
+    if !cell.anniversaries.is_empty() {
+        day_line.push(Span::styled(
+            theme.glyphs.anniversary,
+            Style::default().fg(theme.birthday),
+        ));
+    }
+    if cell.is_countdown {
+        day_line.push(Span::styled(
+            theme.glyphs.countdown,
+            Style::default().fg(theme.warning),
+        ));
+    }
+    // Several events show their count in place of the marker, capped at 9 to keep the width
+    match cell.events.len() {
+        0 => {}
+        1 => day_line.push(Span::styled("*", Style::default().fg(theme.event))),
+        count => day_line.push(Span::styled(
+            count.min(9).to_string(),
+            Style::default()
+                .fg(theme.event)
+                .add_modifier(Modifier::BOLD),
+        )),
+    }
+    if let Some(arrangement) = cell.arrangement {
+        day_line.push(Span::styled(
+            arrangement.badge(locale),
+            Style::default()
+                .fg(theme.arrangement(arrangement))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    let mut lines = vec![Line::from(day_line).alignment(Alignment::Center)];
+    if has_label {
+        let (label, source) = cell
+            .label(label_priority, locale)
+            .unwrap_or_else(|| ("--".to_string(), LabelSource::Lunar));
+        // Selected and out-of-month cells keep their own colors so the label stays legible
+        // A lunar date ranked above a holiday still takes the holiday's color
+        let label_style = if cell.is_selected || !cell.is_current_month {
+            Style::default()
+        } else if let LabelSource::Lunar = source
+            && let Some(info) = cell.holidays.first()
+        {
+            LabelSource::Holiday(info.category).style(theme)
+        } else {
+            source.style(theme)
+        };
+        lines.push(
+            Line::from(Span::styled(
+                cell_text(&label, label_width, &theme.glyphs),
+                label_style,
+            ))
+            .alignment(Alignment::Center),
+        );
+        // Keep the lunar day visible under a holiday or solar term that took its place
+        if label_lines >= 2
+            && !matches!(source, LabelSource::Lunar)
+            && let Some(info) = cell.lunar
+        {
+            lines.push(
+                Line::from(Span::styled(
+                    cell_text(
+                        &i18n::lunar_cell_day(&info, locale),
+                        label_width,
+                        &theme.glyphs,
+                    ),
+                    Style::default().fg(theme.hint),
+                ))
+                .alignment(Alignment::Center),
+            );
+        }
+    }
+    let mut style = if !cell.is_current_month {
+        Style::default().fg(theme.out_of_month)
+    } else if cell.is_weekend {
+        Style::default().fg(theme.weekend_color(cell.date.weekday()))
+    } else {
+        Style::default()
+    };
+    if cell.is_selected {
+        style = style.patch(theme.selected());
+    } else if cell.in_range {
+        style = style.bg(theme.range_bg);
+        if cell.is_today {
+            style = style.fg(theme.today_fg).add_modifier(Modifier::BOLD);
+        }
+    } else if cell.is_today {
+        style = style.fg(theme.today_fg).add_modifier(Modifier::BOLD);
+    }
+    Cell::from(lines).style(style)
+}
+
+/// Selected date detail panel, scrolled by the app's offset
+/// The offset is clamped here because only rendering knows the wrapped content height
+fn draw_details(frame: &mut Frame, app: &mut App, area: Rect) {
+    let theme = *app.theme();
+    let locale = app.locale();
+    let block = theme
+        .block()
+        .title(Msg::Details.text(locale))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    let month_terms = app.month_solar_terms().to_vec();
+    let month_stats = app.month_stats();
+    let lines = details_lines(app, &month_terms, month_stats, inner.width);
+    let content_height = wrapped_height(&lines, inner.width);
+    let max_scroll = content_height.saturating_sub(inner.height);
+    app.clamp_details_scroll(max_scroll);
+    let scroll = app.details_scroll();
+    let (up, down) = (theme.glyphs.up, theme.glyphs.down);
+    let more = Msg::More.text(locale);
+    let block = match (scroll > 0, scroll < max_scroll) {
+        (true, true) => block.title_bottom(format!("{up}{down} {more}")),
+        (true, false) => block.title_bottom(format!("{up} {more}")),
+        (false, true) => block.title_bottom(format!("{down} {more}")),
+        (false, false) => block,
+    };
     let paragraph = Paragraph::new(lines)
-        .block(
-            Block::default()
-                .border_type(BorderType::Rounded)
-                .title("跳转到指定日期")
-                .borders(Borders::ALL),
-        )
-        .wrap(Wrap { trim: false });
-    // Render the paragraph
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .scroll((scroll, 0));
     frame.render_widget(paragraph, area);
 }
 
-/// Split horizontally into three parts with ratios (100 - percent_x)/2 : percent_x : (100 - percent_x)/2
-/// Take the middle part and split it vertically with ratios (100 - percent_y)/2 : percent_y : (100 - percent_y)/2
-/// Return the centered area from that middle block
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
-    let horizontal = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
+/// Rows the lines occupy once wrapped to the given width
+fn wrapped_height(lines: &[Line], width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    lines
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(width) as u16)
+        .sum()
+}
+
+fn details_lines(
+    app: &App,
+    month_terms: &[(&'static str, NaiveDate)],
+    stats: MonthStats,
+    width: u16,
+) -> Vec<Line<'static>> {
+    let theme = app.theme();
+    let locale = app.locale();
+    let selected = app.selected_date();
+    let holidays = app.selected_holidays();
+    let holiday_suffix = holidays
+        .iter()
+        .map(|info| format!(" · {}", i18n::name(info.name, locale)))
+        .collect::<String>();
+    let mut lines = vec![detail_line(
+        theme,
+        locale,
+        Msg::Current.text(locale),
+        format!(
+            "{} {}{}",
+            app.format_date(selected),
+            i18n::weekday_name(selected.weekday(), locale),
+            holiday_suffix
+        ),
+    )];
+    if let Some(summary) = app.range_summary() {
+        let holidays = if summary.holidays.is_empty() {
+            Msg::None.text(locale).to_string()
+        } else {
+            summary
+                .holidays
+                .iter()
+                .map(|name| i18n::name(name, locale))
+                .collect::<Vec<_>>()
+                .join(i18n::list_separator(locale))
+        };
+        lines.push(Line::from(vec![
+            detail_label(theme, locale, Msg::Range.text(locale)),
+            Span::styled(
+                Msg::RangeSummary.fill(locale, &[&summary.days, &summary.weekend_days, &holidays]),
+                Style::default().fg(theme.range_bg),
+            ),
+        ]));
+    }
+    if let Some(note) = app.selected_note() {
+        lines.push(Line::from(vec![
+            detail_label(theme, locale, Msg::Note.text(locale)),
+            Span::styled(note.to_string(), Style::default().fg(theme.note)),
+        ]));
+    }
+    for name in app.selected_birthdays() {
+        lines.push(Line::from(vec![
+            detail_label(theme, locale, Msg::Birthday.text(locale)),
+            Span::styled(name, Style::default().fg(theme.birthday)),
+        ]));
+    }
+    for name in app.selected_anniversaries() {
+        lines.push(Line::from(vec![
+            detail_label(theme, locale, Msg::Anniversary.text(locale)),
+            Span::styled(name, Style::default().fg(theme.birthday)),
+        ]));
+    }
+    for event in app.selected_events() {
+        lines.push(Line::from(vec![
+            detail_label(theme, locale, Msg::Events.text(locale)),
+            Span::styled(event, Style::default().fg(theme.event)),
+        ]));
+    }
+    lines.push(detail_line(
+        theme,
+        locale,
+        Msg::InYear.text(locale),
+        year_position(selected, locale),
+    ));
+    if app.show_month_stats() {
+        lines.push(detail_line(
+            theme,
+            locale,
+            &i18n::month_name(app.view_month(), locale),
+            Msg::MonthStats.fill(
+                locale,
+                &[
+                    &stats.days,
+                    &stats.workdays,
+                    &stats.weekend_days,
+                    &stats.holidays,
+                ],
+            ),
+        ));
+    }
+    if app.show_astronomical() {
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::Astronomical.text(locale),
+            Msg::AstronomicalDays.fill(
+                locale,
+                &[
+                    &app::julian_day(selected),
+                    &app::modified_julian_day(selected),
+                    &app::unix_day(selected),
+                ],
+            ),
+        ));
+    }
+    lines.push(detail_line(
+        theme,
+        locale,
+        Msg::FromToday.text(locale),
+        app::format_day_offset(app.today(), selected, locale),
+    ));
+    if let Some((name, date)) = app.countdown() {
+        let days = (date - app.today()).num_days();
+        let value = if days == 0 {
+            Msg::CountdownToday.text(locale).to_string()
+        } else {
+            Msg::Countdown.fill(locale, &[&app.format_date(date), &days])
+        };
+        lines.push(detail_line(theme, locale, i18n::name(name, locale), value));
+    }
+    lines.push(detail_line(
+        theme,
+        locale,
+        Msg::SolarTerm.text(locale),
+        app.selected_solar_term()
+            .map_or("-", |term| i18n::name(term, locale))
+            .to_string(),
+    ));
+    let terms = if month_terms.is_empty() {
+        Msg::NoSolarTermData.text(locale).to_string()
+    } else {
+        month_terms
+            .iter()
+            .map(|(name, date)| {
+                format!("{} {}", i18n::name(name, locale), month_day(*date, locale))
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    };
+    lines.push(detail_line(
+        theme,
+        locale,
+        Msg::MonthTerms.text(locale),
+        terms,
+    ));
+    for info in &holidays {
+        // Arrangement notes come from the Chinese holiday data and are only shown in Chinese
+        let value = match locale {
+            Locale::Zh | Locale::ZhHant => format!(
+                "{} - {}",
+                i18n::name(info.name, locale),
+                app.schedule().note(info, selected.year(), locale)
+            ),
+            Locale::En => i18n::name(info.name, locale).to_string(),
+        };
+        lines.push(detail_line(
+            theme,
+            locale,
+            info.category.label(locale),
+            value,
+        ));
+    }
+
+    if let Some(info) = app.selected_lunar() {
+        let gz = i18n::gan_zhi(&lunar::gan_zhi_year(info.date.year), locale);
+        let zodiac = lunar::zodiac_animal(info.date.year);
+        let date = match locale {
+            Locale::Zh | Locale::ZhHant => {
+                format!("{} {}", i18n::lunar_month(&info, locale), info.day_name())
+            }
+            Locale::En => i18n::lunar_date(&info, locale),
+        };
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::Lunar.text(locale),
+            Msg::LunarYear.fill(locale, &[&gz, &date]),
+        ));
+        // Paper calendars mark each lunar month 大 or 小 by its length
+        if let Some((first, last, length)) = lunar::lunar_month_span(&info) {
+            let size = if length == 30 {
+                Msg::LunarMonthLong
+            } else {
+                Msg::LunarMonthShort
+            };
+            let separator = match locale {
+                Locale::Zh | Locale::ZhHant => "—",
+                Locale::En => " – ",
+            };
+            lines.push(detail_line(
+                theme,
+                locale,
+                &size.fill(locale, &[&i18n::lunar_month(&info, locale)]),
+                format!(
+                    "{}{separator}{}",
+                    month_day(first, locale),
+                    month_day(last, locale)
+                ),
+            ));
+        }
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::Zodiac.text(locale),
+            i18n::zodiac(zodiac, locale),
+        ));
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::DayPillar.text(locale),
+            i18n::gan_zhi(&lunar::gan_zhi_day(selected), locale),
+        ));
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::Holiday.text(locale),
+            info.festival
+                .map_or("-", |festival| i18n::name(festival, locale))
+                .to_string(),
+        ));
+    } else {
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::Lunar.text(locale),
+            Msg::OutOfRange.text(locale).to_string(),
+        ));
+    }
+    if app.show_almanac() {
+        let almanac = almanac::almanac_for(selected);
+        for (label, items) in [(Msg::Yi, almanac.yi), (Msg::Ji, almanac.ji)] {
+            if !items.is_empty() {
+                let label = label.text(locale);
+                let label_width = label.width() + i18n::colon(locale).width();
+                let value = truncate_to_width(
+                    &items.join(" "),
+                    (width as usize).saturating_sub(label_width),
+                    &theme.glyphs,
+                );
+                lines.push(detail_line(theme, locale, label, value));
+            }
+        }
+    }
+    // The double-hour comes from the app's last tick, which redraws when it turns
+    if selected == app.today() {
+        lines.push(detail_line(
+            theme,
+            locale,
+            Msg::Now.text(locale),
+            i18n::shi_chen(app.shi_chen(), locale),
+        ));
+    }
+    lines
+}
+
+/// One-line summary of the selected date, e.g. "2025-10-06 星期一 农历八月十五 中秋节"
+pub fn date_summary(app: &App) -> String {
+    let locale = app.locale();
+    let selected = app.selected_date();
+    let mut parts = vec![
+        app.format_date(selected),
+        i18n::weekday_name(selected.weekday(), locale).to_string(),
+    ];
+    if let Some(info) = app.selected_lunar() {
+        parts.push(match locale {
+            Locale::Zh | Locale::ZhHant => format!(
+                "{}{}",
+                Msg::Lunar.text(locale),
+                i18n::lunar_month_day(&info, locale)
+            ),
+            Locale::En => format!("lunar {}", i18n::lunar_date(&info, locale)),
+        });
+    }
+    parts.extend(
+        app.selected_holidays()
+            .iter()
+            .map(|info| i18n::name(info.name, locale).to_string()),
+    );
+    parts.join(" ")
+}
+
+/// Month and day such as "6月21日" or "Jun 21"
+fn month_day(date: NaiveDate, locale: Locale) -> String {
+    match locale {
+        Locale::Zh | Locale::ZhHant => format!("{}月{}日", date.month(), date.day()),
+        Locale::En => format!("{} {}", i18n::month_name(date.month(), locale), date.day()),
+    }
+}
+
+/// A details line with a dimmed label and a plain value
+fn detail_line(theme: &Theme, locale: Locale, label: &str, value: String) -> Line<'static> {
+    Line::from(vec![detail_label(theme, locale, label), Span::raw(value)])
+}
+
+fn detail_label(theme: &Theme, locale: Locale, label: &str) -> Span<'static> {
+    Span::styled(
+        format!("{}{}", label, i18n::colon(locale)),
+        Style::default().fg(theme.hint),
+    )
+}
+
+/// Columns `text` takes on this terminal, counting ambiguous-width symbols as configured
+fn text_width(text: &str, glyphs: &Glyphs) -> usize {
+    if glyphs.wide_ambiguous {
+        text.width_cjk()
+    } else {
+        text.width()
+    }
+}
+
+/// `text_width` of a single character
+fn char_width(ch: char, glyphs: &Glyphs) -> usize {
+    if glyphs.wide_ambiguous {
+        ch.width_cjk()
+    } else {
+        ch.width()
+    }
+    .unwrap_or(0)
+}
+
+/// `text_width` of a run of spans
+fn line_width(spans: &[Span], glyphs: &Glyphs) -> usize {
+    spans
+        .iter()
+        .map(|span| text_width(&span.content, glyphs))
+        .sum()
+}
+
+/// Cut text to the given display width, marking the cut with an ellipsis
+/// A wide character is never split, and zero-width marks stay with the character before them;
+/// a width too small for the ellipsis yields an empty string
+fn truncate_to_width(text: &str, width: usize, glyphs: &Glyphs) -> String {
+    if text_width(text, glyphs) <= width {
+        return text.to_string();
+    }
+    let ellipsis = glyphs.ellipsis;
+    let ellipsis_width = text_width(ellipsis, glyphs);
+    if width < ellipsis_width {
+        return String::new();
+    }
+    let mut result = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = char_width(ch, glyphs);
+        if ch_width > 0 && used + ch_width + ellipsis_width > width {
+            break;
+        }
+        result.push(ch);
+        used += ch_width;
+    }
+    result.push_str(ellipsis);
+    result
+}
+
+/// Day of year, ISO week and days left in the year, e.g. "第 136 天 · 2026-W20 · 剩余 229 天"
+/// The ISO year is spelled out because late December can belong to week 1 of the next year
+fn year_position(date: NaiveDate, locale: Locale) -> String {
+    let last_day = NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap();
+    Msg::YearPosition.fill(
+        locale,
+        &[
+            &date.ordinal(),
+            &date.format("%G-W%V"),
+            &(last_day.ordinal() - date.ordinal()),
+        ],
+    )
+}
+
+/// Holidays, solar terms, events and notes of the viewed month, one per row
+fn draw_agenda(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let locale = app.locale();
+    let entries = app.agenda_entries();
+    let block = theme
+        .block()
+        .title(Msg::Agenda.fill(locale, &[&app.view_month()]))
+        .title_bottom(Msg::JumpClose.text(locale))
+        .borders(Borders::ALL);
+    if entries.is_empty() {
+        let empty = Paragraph::new(Msg::AgendaEmpty.text(locale))
+            .style(Style::default().fg(theme.hint))
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+    // "MM-DD (周X) " takes 12 columns, "MM-DD (Tue) " 12 as well
+    let text_width = block.inner(area).width.saturating_sub(12) as usize;
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let color = agenda_color(entry.kind, theme);
+            let weekday = i18n::weekday_abbr(entry.date.weekday(), locale);
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ({}) ", entry.date.format("%m-%d"), weekday)),
+                Span::styled(
+                    truncate_to_width(&entry.text, text_width, &theme.glyphs),
+                    Style::default().fg(color),
+                ),
+            ]))
+        })
+        .collect::<Vec<_>>();
+    let widget = List::new(items)
+        .block(block)
+        .highlight_style(theme.selected());
+    let selected = app.agenda_selected(entries.len());
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(widget, area, &mut state);
+}
+
+/// Text color of an agenda row or named day of the given kind
+fn agenda_color(kind: AgendaKind, theme: &Theme) -> Color {
+    match kind {
+        AgendaKind::Holiday(category) => theme.holiday(category),
+        AgendaKind::SolarTerm => theme.solar_term,
+        AgendaKind::Birthday | AgendaKind::Anniversary => theme.birthday,
+        AgendaKind::Event => theme.event,
+        AgendaKind::Note => theme.note,
+    }
+}
+
+/// Upcoming holidays, lunar birthdays and anniversaries counted down from today
+/// One-off anniversaries that have passed come last, counted up instead
+fn upcoming(app: &App) -> Paragraph<'_> {
+    let theme = app.theme();
+    let locale = app.locale();
+    let today = app.today();
+    let mut entries: Vec<(NaiveDate, &str, Style)> = app
+        .upcoming_holidays()
+        .iter()
+        .map(|(date, info)| (*date, i18n::name(info.name, locale), Style::default()))
+        .chain(
+            app.upcoming_birthdays()
+                .iter()
+                .map(|(date, name)| (*date, name.as_str(), Style::default().fg(theme.birthday))),
+        )
+        .chain(
+            app.upcoming_anniversaries()
+                .iter()
+                .map(|(date, name)| (*date, name.as_str(), Style::default().fg(theme.birthday))),
+        )
+        .collect();
+    entries.sort_by_key(|(date, _, _)| (*date < today, (*date - today).num_days().abs()));
+    let mut lines: Vec<Line> = entries
+        .into_iter()
+        .map(|(date, name, style)| {
+            let days = (date - today).num_days();
+            let countdown = match days {
+                0 => Msg::Today.text(locale).to_string(),
+                ..0 => Msg::DaysAgo.fill(locale, &[&-days]),
+                _ => Msg::DaysLeft.fill(locale, &[&days]),
+            };
+            let date = month_day(date, locale);
+            Line::styled(format!("{} · {} · {}", name, date, countdown), style)
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Msg::NoHolidayData.text(locale)));
+    }
+    Paragraph::new(lines)
+        .block(
+            theme
+                .block()
+                .title(Msg::Upcoming.text(locale))
+                .borders(Borders::ALL),
+        )
+        .wrap(Wrap { trim: true })
+}
+
+fn help_bar(
+    bindings: &KeyBindings,
+    show_unbound: bool,
+    pick_mode: bool,
+    width: u16,
+    theme_name: &str,
+    theme: &Theme,
+    locale: Locale,
+) -> (Paragraph<'static>, u16) {
+    let mut entries = Vec::new();
+    for group in ActionGroup::ALL {
+        let mut first = true;
+        for &(action, _, _) in ACTIONS
+            .iter()
+            .filter(|entry| entry.2 == group && (entry.0 != Action::ConfirmPick || pick_mode))
+        {
+            let label = action.label(locale);
+            let keys = bindings.labels_for(action);
+            let mut spans = Vec::new();
+            if first {
+                spans.push(Span::styled(
+                    format!("{}{}", group.label(locale), i18n::colon(locale)),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+            }
+            if keys.is_empty() {
+                if !show_unbound {
+                    continue;
+                }
+                spans.push(Span::styled(
+                    Msg::UnboundAction.fill(locale, &[&label]),
+                    Style::default().fg(theme.hint),
+                ));
+            } else {
+                spans.push(Span::raw(format!("{} {}", keys.join("/"), label)));
+            }
+            entries.push((spans, first));
+            first = false;
+        }
+        // Jump shortcuts from the key config follow the built-in jumps, named by their targets
+        if group == ActionGroup::Jump {
+            for (shortcut, keys) in bindings.jump_shortcuts() {
+                let mut spans = Vec::new();
+                if first {
+                    spans.push(Span::styled(
+                        format!("{}{}", group.label(locale), i18n::colon(locale)),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ));
+                }
+                spans.push(Span::raw(format!("{} {}", keys.join("/"), shortcut.name)));
+                entries.push((spans, first));
+                first = false;
+            }
+        }
+    }
+    let lines = wrap_entries(entries, width.saturating_sub(2), &theme.glyphs);
+    let height = lines.len() as u16 + 2;
+    let paragraph = Paragraph::new(lines).block(
+        theme
+            .block()
+            .borders(Borders::ALL)
+            .title(Msg::KeysTitle.fill(locale, &[&theme_name])),
+    );
+    (paragraph, height)
+}
+
+/// Lay out help entries left to right, starting a new line when the next one would not fit
+/// Entries are separated by " · ", and by a wider gap where a new group starts
+fn wrap_entries(
+    entries: Vec<(Vec<Span<'static>>, bool)>,
+    width: u16,
+    glyphs: &Glyphs,
+) -> Vec<Line<'static>> {
+    let width = width.max(1) as usize;
+    let mut lines = Vec::new();
+    let mut current: Vec<Span> = Vec::new();
+    let mut used = 0;
+    for (spans, starts_group) in entries {
+        let entry_width = line_width(&spans, glyphs);
+        let separator = if starts_group { "   " } else { " · " };
+        if !current.is_empty() && used + text_width(separator, glyphs) + entry_width > width {
+            lines.push(Line::from(std::mem::take(&mut current)));
+            used = 0;
+        }
+        if !current.is_empty() {
+            current.push(Span::raw(separator));
+            used += text_width(separator, glyphs);
+        }
+        used += entry_width;
+        current.extend(spans);
+    }
+    if !current.is_empty() {
+        lines.push(Line::from(current));
+    }
+    lines
+}
+
+/// Which-key style popup in the bottom-right corner of `area`, e.g. "d  跳转日期" titled "g …"
+fn draw_pending_hints(
+    frame: &mut Frame,
+    hints: &[PendingHint],
+    bindings: &KeyBindings,
+    area: Rect,
+    theme: &Theme,
+    locale: Locale,
+) {
+    let lines = hints
+        .iter()
+        .map(|hint| {
+            Line::from(vec![
+                Span::styled(
+                    hint.remaining.clone(),
+                    Style::default()
+                        .fg(theme.warning)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  "),
+                Span::raw(bindings.bound_label(hint.bound, locale).to_string()),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let title = format!("{} {}", hints[0].typed, theme.glyphs.ellipsis);
+    let content_width = lines
+        .iter()
+        .map(|line| line_width(&line.spans, &theme.glyphs))
+        .chain(iter::once(text_width(&title, &theme.glyphs)))
+        .max()
+        .unwrap_or(0) as u16;
+    let width = (content_width + 4).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.right().saturating_sub(width + 1),
+        y: area.bottom().saturating_sub(height + 1),
+        width,
+        height,
+    };
+    frame.render_widget(Clear, popup);
+    let paragraph = Paragraph::new(lines).block(theme.block().title(title).borders(Borders::ALL));
+    frame.render_widget(paragraph, popup);
+}
+
+/// Notice listing config problems, closed by any key
+fn draw_config_warnings(frame: &mut Frame, warnings: &[String], theme: &Theme, locale: Locale) {
+    let screen = frame.size();
+    let lines = warnings
+        .iter()
+        .map(|warning| {
+            Line::from(format!("{} {}", theme.glyphs.bullet, warning))
+                .style(Style::default().fg(theme.warning))
+        })
+        .collect::<Vec<_>>();
+    let width = (screen.width * 7 / 10).max(20).min(screen.width);
+    let height = (wrapped_height(&lines, width.saturating_sub(2)) + 2).min(screen.height);
+    let area = Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, area);
+    let paragraph = Paragraph::new(lines)
+        .block(
+            theme
+                .block()
+                .border_style(Style::default().fg(theme.warning))
+                .title(Msg::ConfigWarnings.text(locale))
+                .borders(Borders::ALL),
+        )
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_jump_prompt(
+    frame: &mut Frame,
+    prompt: JumpPromptView<'_>,
+    date_format: &str,
+    theme: &Theme,
+    locale: Locale,
+) {
+    // Build prompt lines
+    let preview = match prompt.preview {
+        Some(date) if (lunar::MIN_YEAR..=lunar::max_supported_year()).contains(&date.year()) => {
+            let lunar_text = lunar::solar_to_lunar(date)
+                .map(|info| Msg::PreviewLunar.fill(locale, &[&i18n::lunar_date(&info, locale)]))
+                .unwrap_or_default();
+            Line::from(format!(
+                "{} {} {}{}",
+                theme.glyphs.arrow,
+                date.format(date_format),
+                i18n::weekday_name(date.weekday(), locale),
+                lunar_text
+            ))
+            .style(Style::default().fg(theme.message))
+        }
+        Some(date) => Line::from(
+            Msg::PreviewOutOfRange.fill(locale, &[&theme.glyphs.arrow, &date.format(date_format)]),
+        )
+        .style(Style::default().fg(theme.warning)),
+        None => Line::from(Msg::KeepTyping.text(locale)).style(Style::default().fg(theme.hint)),
+    };
+    let label = Msg::JumpLabel.text(locale);
+    let mut lines = vec![
+        Line::from(format!("{}{}", label, prompt.input)).alignment(Alignment::Left),
+        preview,
+        Line::from(Msg::JumpExamples.text(locale)).style(Style::default().fg(theme.hint)),
+        Line::from(Msg::JumpKeys.fill(
+            locale,
+            &[&format!("{}{}", theme.glyphs.up, theme.glyphs.down)],
+        ))
+        .style(Style::default().fg(theme.hint)),
+    ];
+    if let Some(err) = prompt.error {
+        lines.push(Line::from(err).style(Style::default().fg(theme.error)));
+    }
+    let title = match prompt.history_position {
+        Some((position, len)) => format!("{} ({}/{})", Msg::JumpTitle.text(locale), position, len),
+        None => Msg::JumpTitle.text(locale).to_string(),
+    };
+    // Size the window to its content: the widest line, with room for the cursor after the
+    // input, and every line once wrapped to what the terminal allows
+    let input_width = text_width(label, &theme.glyphs) + text_width(prompt.input, &theme.glyphs);
+    let content_width = lines
+        .iter()
+        .map(|line| line_width(&line.spans, &theme.glyphs))
+        .chain([input_width + 1, text_width(&title, &theme.glyphs)])
+        .max()
+        .unwrap_or_default() as u16;
+    let width = content_width.saturating_add(2).min(frame.size().width);
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let area = centered_fixed_rect(width, height, frame.size());
+    // Clear the window area
+    frame.render_widget(Clear, area);
+    // Build the paragraph widget
+    let paragraph = Paragraph::new(lines)
+        .block(theme.block().title(title).borders(Borders::ALL))
+        .wrap(Wrap { trim: false });
+    // Render the paragraph
+    frame.render_widget(paragraph, area);
+    // Terminal cursor right after the typed text, measured in columns since the label is
+    // full-width; an input too long for the line keeps it on the right border
+    let inner = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(2),
+        height: area.height.saturating_sub(2),
+    };
+    if inner.width > 0 && inner.height > 0 {
+        let offset = input_width as u16;
+        frame.set_cursor((inner.x + offset).min(inner.right() - 1), inner.y);
+    }
+}
+
+fn draw_note_prompt(
+    frame: &mut Frame,
+    date: NaiveDate,
+    input: &str,
+    theme: &Theme,
+    locale: Locale,
+) {
+    let area = centered_rect(50, 15, frame.size());
+    frame.render_widget(Clear, area);
+    let lines = vec![
+        Line::from(format!("{}{}", Msg::NoteLabel.text(locale), input)),
+        Line::from(Msg::NoteKeys.text(locale)).style(Style::default().fg(theme.hint)),
+    ];
+    let paragraph = Paragraph::new(lines)
+        .block(
+            theme
+                .block()
+                .title(Msg::NoteTitle.fill(locale, &[&date.format("%Y-%m-%d")]))
+                .borders(Borders::ALL),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_date_diff(frame: &mut Frame, diff: DateDiffView<'_>, theme: &Theme, locale: Locale) {
+    let area = centered_rect(50, 40, frame.size());
+    frame.render_widget(Clear, area);
+    let mut lines: Vec<Line> = [Msg::DiffStart, Msg::DiffEnd]
+        .into_iter()
+        .enumerate()
+        .map(|(index, label)| {
+            let marker = if index == diff.active {
+                format!("{} ", theme.glyphs.pointer)
+            } else {
+                "  ".to_string()
+            };
+            let style = if index == diff.active {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Line::from(format!(
+                "{}{}{}{}",
+                marker,
+                label.text(locale),
+                i18n::colon(locale),
+                diff.inputs[index]
+            ))
+            .style(style)
+        })
+        .collect();
+    lines.push(Line::from(Msg::DiffKeys.text(locale)).style(Style::default().fg(theme.hint)));
+    if let Some(err) = diff.error {
+        lines.push(Line::from(err).style(Style::default().fg(theme.error)));
+    }
+    if let Some(result) = diff.result {
+        lines.push(Line::from(""));
+        lines.push(Line::from(
+            Msg::DiffDays.fill(locale, &[&result.days, &(result.days + 1)]),
+        ));
+        lines.push(Line::from(Msg::DiffWeeks.fill(
+            locale,
+            &[&(result.days / 7), &(result.days % 7), &result.weekend_days],
+        )));
+        for (label, date, info) in [
+            (Msg::DiffFrom, result.start, result.start_lunar),
+            (Msg::DiffTo, result.end, result.end_lunar),
+        ] {
+            let lunar_text = info
+                .map(|info| i18n::lunar_date(&info, locale))
+                .unwrap_or_else(|| Msg::OutOfRange.text(locale).to_string());
+            lines.push(Line::from(Msg::DiffDate.fill(
+                locale,
+                &[&label.text(locale), &date.format("%Y-%m-%d"), &lunar_text],
+            )));
+        }
+    }
+    let paragraph = Paragraph::new(lines)
+        .block(
+            theme
+                .block()
+                .title(Msg::DiffTitle.text(locale))
+                .borders(Borders::ALL),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// Stems, branches, phases and zodiac of the four pillars, one pillar per column
+fn draw_bazi(frame: &mut Frame, view: BaziView, theme: &Theme, locale: Locale) {
+    let area = centered_rect(50, 40, frame.size());
+    frame.render_widget(Clear, area);
+    let hour = i18n::shi_chen(&format!("{}时", lunar::branch(view.hour)), locale);
+    let block = theme
+        .block()
+        .title(Msg::BaziTitle.fill(locale, &[&view.date.format("%Y-%m-%d"), &hour]))
+        .title_bottom(Msg::BaziKeys.fill(
+            locale,
+            &[&format!("{}{}", theme.glyphs.up, theme.glyphs.down)],
+        ))
+        .borders(Borders::ALL);
+    let Some(pillars) = view.pillars else {
+        let paragraph = Paragraph::new(Msg::OutOfRange.text(locale)).block(block);
+        frame.render_widget(paragraph, area);
+        return;
+    };
+    let columns = [pillars.year, pillars.month, pillars.day, pillars.hour];
+    let label = |msg: Msg| Cell::from(msg.text(locale)).style(Style::default().fg(theme.hint));
+    let row =
+        |msg: Msg, value: &dyn Fn(bazi::Pillar) -> String| {
+            Row::new(iter::once(label(msg)).chain(
+                columns.iter().map(|pillar| {
+                    Cell::from(Line::from(value(*pillar)).alignment(Alignment::Center))
+                }),
+            ))
+        };
+    let header = Row::new(
+        iter::once(Cell::from("")).chain(
+            [Msg::BaziYear, Msg::BaziMonth, Msg::BaziDay, Msg::BaziHour]
+                .into_iter()
+                .map(|msg| Cell::from(Line::from(msg.text(locale)).alignment(Alignment::Center))),
+        ),
+    )
+    .style(
+        Style::default()
+            .fg(theme.header_fg)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows = vec![
+        row(Msg::BaziStem, &|pillar| {
+            i18n::gan_zhi(&lunar::stem(pillar.stem).to_string(), locale)
+        }),
+        row(Msg::BaziBranch, &|pillar| {
+            i18n::gan_zhi(&lunar::branch(pillar.branch).to_string(), locale)
+        }),
+        row(Msg::BaziElement, &|pillar| {
+            format!(
+                "{}/{}",
+                pillar.stem_element().label(locale),
+                pillar.branch_element().label(locale)
+            )
+        }),
+        row(Msg::Zodiac, &|pillar| i18n::zodiac(pillar.zodiac(), locale)),
+    ];
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+        ],
+    )
+    .header(header.bottom_margin(1))
+    .block(block);
+    frame.render_widget(table, area);
+}
+
+fn draw_note_search(frame: &mut Frame, search: NoteSearchView<'_>, theme: &Theme, locale: Locale) {
+    let area = centered_rect(60, 70, frame.size());
+    frame.render_widget(Clear, area);
+    let block = theme
+        .block()
+        .title(Msg::SearchTitle.text(locale))
+        .title_bottom(Msg::SearchKeys.fill(
+            locale,
+            &[&format!("{}{}", theme.glyphs.up, theme.glyphs.down)],
+        ))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(inner);
+    frame.render_widget(
+        Paragraph::new(format!("{}{}", Msg::SearchQuery.text(locale), search.query)),
+        rows[0],
+    );
+    if search.matches.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Msg::SearchEmpty.text(locale)).style(Style::default().fg(theme.hint)),
+            rows[1],
+        );
+        return;
+    }
+    // Date column plus separator take 13 columns
+    let snippet_width = rows[1].width.saturating_sub(13) as usize;
+    let items = search
+        .matches
+        .iter()
+        .map(|(date, text)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", date.format("%Y-%m-%d"))),
+                Span::styled(
+                    truncate_to_width(text, snippet_width, &theme.glyphs),
+                    Style::default().fg(theme.note),
+                ),
+            ]))
+        })
+        .collect::<Vec<_>>();
+    let widget = List::new(items).highlight_style(theme.selected());
+    let mut state = ListState::default().with_selected(Some(search.selected));
+    frame.render_stateful_widget(widget, rows[1], &mut state);
+}
+
+fn draw_festival_search(
+    frame: &mut Frame,
+    search: FestivalSearchView<'_>,
+    theme: &Theme,
+    locale: Locale,
+) {
+    let area = centered_rect(50, 70, frame.size());
+    frame.render_widget(Clear, area);
+    let block = theme
+        .block()
+        .title(Msg::FestivalSearchTitle.fill(locale, &[&search.year]))
+        .title_bottom(Msg::SearchKeys.fill(
+            locale,
+            &[&format!("{}{}", theme.glyphs.up, theme.glyphs.down)],
+        ))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(inner);
+    frame.render_widget(
+        Paragraph::new(format!("{}{}", Msg::SearchQuery.text(locale), search.query)),
+        rows[0],
+    );
+    if search.matches.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Msg::FestivalSearchEmpty.text(locale))
+                .style(Style::default().fg(theme.hint)),
+            rows[1],
+        );
+        return;
+    }
+    // The list scrolls to keep the highlight within the popup
+    let items = search
+        .matches
+        .iter()
+        .map(|day| {
+            let weekday = i18n::weekday_abbr(day.date.weekday(), locale);
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ({}) ", day.date.format("%m-%d"), weekday)),
+                Span::styled(
+                    i18n::name(day.name, locale),
+                    Style::default().fg(agenda_color(day.kind, theme)),
+                ),
+            ]))
+        })
+        .collect::<Vec<_>>();
+    let widget = List::new(items).highlight_style(theme.selected());
+    let mut state = ListState::default().with_selected(Some(search.selected));
+    frame.render_stateful_widget(widget, rows[1], &mut state);
+}
+
+fn draw_holiday_list(frame: &mut Frame, list: HolidayListView<'_>, theme: &Theme, locale: Locale) {
+    let area = centered_rect(50, 70, frame.size());
+    frame.render_widget(Clear, area);
+    let items = list
+        .entries
+        .iter()
+        .map(|(date, info)| {
+            let color = theme.holiday(info.category);
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", date.format("%m-%d"))),
+                Span::styled(i18n::name(info.name, locale), Style::default().fg(color)),
+                Span::styled(
+                    format!(" · {}", info.category.label(locale)),
+                    Style::default().fg(theme.hint),
+                ),
+            ]))
+        })
+        .collect::<Vec<_>>();
+    let widget = List::new(items)
+        .block(
+            theme
+                .block()
+                .title(Msg::HolidayListTitle.fill(locale, &[&list.year]))
+                .title_bottom(Msg::JumpClose.text(locale))
+                .borders(Borders::ALL),
+        )
+        .highlight_style(theme.selected());
+    let mut state = ListState::default().with_selected(Some(list.selected));
+    frame.render_stateful_widget(widget, area, &mut state);
+}
+
+/// Every action with its group and keys, scrolled by the offset kept on `App`
+fn draw_keymap_help(frame: &mut Frame, app: &mut App, bindings: &KeyBindings, theme: &Theme) {
+    let area = centered_rect(60, 80, frame.size());
+    frame.render_widget(Clear, area);
+    let locale = app.locale();
+    let mut rows = Vec::new();
+    for group in ActionGroup::ALL {
+        let group_cell = || Cell::from(group.label(locale)).style(Style::default().fg(theme.hint));
+        // Confirming a pick means nothing outside `--pick`
+        for &(action, _, _) in ACTIONS
+            .iter()
+            .filter(|entry| entry.2 == group && (entry.0 != Action::ConfirmPick || app.pick_mode()))
+        {
+            let keys = bindings.labels_for(action);
+            let keys = if keys.is_empty() {
+                Cell::from(Msg::Unbound.text(locale)).style(Style::default().fg(theme.hint))
+            } else {
+                Cell::from(keys.join("  "))
+            };
+            rows.push(Row::new(vec![
+                group_cell(),
+                Cell::from(action.label(locale)),
+                keys,
+            ]));
+        }
+        if group == ActionGroup::Jump {
+            for (shortcut, keys) in bindings.jump_shortcuts() {
+                rows.push(Row::new(vec![
+                    group_cell(),
+                    Cell::from(shortcut.name.clone()),
+                    Cell::from(keys.join("  ")),
+                ]));
+            }
+        }
+    }
+    // Label columns fit their widest entry, which differs between locales
+    let group_width = ActionGroup::ALL
+        .into_iter()
+        .map(|group| group.label(locale).width())
+        .max()
+        .unwrap_or(0) as u16;
+    let action_width = ACTIONS
+        .iter()
+        .map(|entry| entry.0.label(locale).width())
+        .chain(
+            bindings
+                .jump_shortcuts()
+                .map(|(shortcut, _)| shortcut.name.width()),
+        )
+        .max()
+        .unwrap_or(0) as u16;
+    // Borders and the header row take three lines
+    let visible = area.height.saturating_sub(3) as usize;
+    app.clamp_keymap_help(rows.len().saturating_sub(visible));
+    let offset = app.keymap_help().unwrap_or(0);
+    let table = Table::new(
+        rows.into_iter().skip(offset),
+        [
+            Constraint::Length(group_width),
+            Constraint::Length(action_width),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            Msg::KeymapGroup.text(locale),
+            Msg::KeymapAction.text(locale),
+            Msg::KeymapKeys.text(locale),
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(
+        theme
+            .block()
+            .title(Msg::KeymapTitle.text(locale))
+            .title_bottom(Msg::KeymapKeysHint.fill(
+                locale,
+                &[&format!("{}/{}", theme.glyphs.up, theme.glyphs.down)],
+            ))
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(table, area);
+}
+
+/// Rect of the given size centered in `area`, shrunk to fit when `area` is smaller
+/// Suited to modals sized by their content rather than by a share of the screen
+fn centered_fixed_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Split horizontally into three parts with ratios (100 - percent_x)/2 : percent_x : (100 - percent_x)/2
+/// Take the middle part and split it vertically with ratios (100 - percent_y)/2 : percent_y : (100 - percent_y)/2
+/// Return the centered area from that middle block
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
             Constraint::Percentage(percent_x),
             Constraint::Percentage((100 - percent_x) / 2),
         ])
@@ -273,3 +2500,906 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         ])
         .split(horizontal[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::{app::test_app, config::AppConfig, countdowns::Countdown};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Draw one frame of `app` at the given terminal size
+    fn render(app: &mut App, width: u16, height: u16) -> Terminal<TestBackend> {
+        render_with(app, &KeyBindings::default(), width, height)
+    }
+
+    fn render_with(
+        app: &mut App,
+        bindings: &KeyBindings,
+        width: u16,
+        height: u16,
+    ) -> Terminal<TestBackend> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| draw(frame, app, bindings, &[]))
+            .unwrap();
+        terminal
+    }
+
+    /// The screen as text, one string per row without trailing blanks
+    /// A wide character's second cell is skipped, so rows read like the terminal shows them
+    fn screen_text(terminal: &Terminal<TestBackend>) -> String {
+        let buffer = terminal.backend().buffer();
+        let area = buffer.area;
+        let mut rows = Vec::new();
+        for y in area.top()..area.bottom() {
+            let mut row = String::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let symbol = buffer.get(x, y).symbol();
+                row.push_str(symbol);
+                x += symbol.width().max(1) as u16;
+            }
+            rows.push(row.trim_end().to_string());
+        }
+        rows.join("\n") + "\n"
+    }
+
+    /// Compare the screen with `tests/fixtures/ui/<name>.txt`
+    fn assert_snapshot(terminal: &Terminal<TestBackend>, name: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/ui")
+            .join(format!("{name}.txt"));
+        let expected = fs::read_to_string(&path).unwrap_or_default();
+        assert_eq!(screen_text(terminal), expected, "{path:?}");
+    }
+
+    #[test]
+    fn month_view_snapshot() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        assert_snapshot(&render(&mut app, 100, 36), "month-2025-06");
+    }
+
+    #[test]
+    fn spring_festival_month_snapshot() {
+        let mut app = test_app(date(2025, 1, 29), AppConfig::default());
+        assert_snapshot(&render(&mut app, 100, 36), "month-2025-01");
+    }
+
+    #[test]
+    fn jump_prompt_error_snapshot() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_jump_prompt();
+        for ch in "2025-13-40".chars() {
+            app.push_jump_input(ch);
+        }
+        app.confirm_jump_prompt();
+        assert_snapshot(&render(&mut app, 100, 36), "jump-prompt-error");
+    }
+
+    #[test]
+    fn narrow_terminal_snapshot() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        assert_snapshot(&render(&mut app, 48, 24), "narrow-2025-06");
+    }
+
+    #[test]
+    fn year_position_at_year_edges() {
+        let cases = [
+            (date(2024, 1, 1), "第 1 天 · 2024-W01 · 剩余 365 天"),
+            (date(2024, 12, 31), "第 366 天 · 2025-W01 · 剩余 0 天"),
+            (date(2023, 1, 1), "第 1 天 · 2022-W52 · 剩余 364 天"),
+            (date(2023, 12, 31), "第 365 天 · 2023-W52 · 剩余 0 天"),
+            (date(2025, 12, 29), "第 363 天 · 2026-W01 · 剩余 2 天"),
+            (date(2026, 5, 16), "第 136 天 · 2026-W20 · 剩余 229 天"),
+        ];
+        for (day, expected) in cases {
+            assert_eq!(year_position(day, Locale::Zh), expected, "{day}");
+        }
+    }
+
+    #[test]
+    fn jump_preview_follows_the_input() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_jump_prompt();
+        // The preview line sits right below the input
+        let mut preview_after = |typed: &str| {
+            while !app.jump_prompt_view().unwrap().input.is_empty() {
+                app.pop_jump_input();
+            }
+            typed.chars().for_each(|ch| app.push_jump_input(ch));
+            let preview = app.jump_prompt_view().unwrap().preview;
+            let screen = screen_text(&render(&mut app, 100, 36));
+            let rows: Vec<&str> = screen.lines().collect();
+            let input_row = rows
+                .iter()
+                .position(|row| row.contains(&format!("目标日期：{typed}")))
+                .unwrap();
+            (preview, rows[input_row + 1].to_string())
+        };
+        let (preview, line) = preview_after("2");
+        assert_eq!(preview, None);
+        assert!(line.contains("继续输入"), "{line}");
+        let (preview, line) = preview_after("2025-10-1");
+        assert_eq!(preview, Some(date(2025, 10, 1)));
+        assert!(line.contains("2025-10-01 星期三 农历八月初十"), "{line}");
+        let (preview, line) = preview_after("中秋");
+        assert_eq!(preview, Some(date(2025, 10, 6)));
+        assert!(line.contains("2025-10-06 星期一"), "{line}");
+        // Out-of-range dates still preview, with a warning instead of the lunar date
+        let (preview, line) = preview_after("1800-01-01");
+        assert_eq!(preview, Some(date(1800, 1, 1)));
+        assert!(line.contains("1800-01-01 超出支持范围"), "{line}");
+    }
+
+    #[test]
+    fn too_small_terminal_shows_the_notice() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        render(&mut app, 100, 36);
+        for (width, height) in [(39, 20), (60, 11), (20, 5)] {
+            let screen = screen_text(&render(&mut app, width, height));
+            let text: String = screen.split_whitespace().collect();
+            assert!(text.contains("终端太小"), "{width}x{height}:\n{screen}");
+            assert!(text.contains(&format!("当前{width}")), "{screen}");
+            assert!(
+                !screen.contains("MoLi") && !screen.contains("初六"),
+                "{screen}"
+            );
+            // Zones of an earlier full frame must not answer clicks on the notice
+            let clickable = (0..width).any(|x| (0..height).any(|y| app.target_at(x, y).is_some()));
+            assert!(!clickable, "{width}x{height}");
+            render(&mut app, 100, 36);
+        }
+        let screen = screen_text(&render(&mut app, 40, 12));
+        assert!(!screen.contains("终端太小"), "{screen}");
+    }
+
+    #[test]
+    fn compact_below_either_threshold() {
+        let size = |width, height| Rect::new(0, 0, width, height);
+        assert!(!is_compact(size(80, 30), 80, 30));
+        assert!(is_compact(size(79, 30), 80, 30));
+        assert!(is_compact(size(80, 29), 80, 30));
+        assert!(is_compact(size(200, 12), 80, 30));
+        // A zero threshold never triggers on its own axis
+        assert!(!is_compact(size(40, 12), 0, 0));
+        assert!(!is_compact(size(40, 100), 0, 30));
+        assert!(is_compact(size(40, 29), 0, 30));
+    }
+
+    #[test]
+    fn compact_layout_hides_the_help_bar() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        assert!(screen_text(&render(&mut app, 100, 36)).contains("切换快捷键栏"));
+        assert!(!screen_text(&render(&mut app, 100, 29)).contains("切换快捷键栏"));
+        assert!(!screen_text(&render(&mut app, 79, 36)).contains("切换快捷键栏"));
+        // With the thresholds off the same sizes keep the full layout
+        let config = AppConfig {
+            compact_width: 0,
+            compact_height: 0,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 18), config);
+        assert!(screen_text(&render(&mut app, 100, 29)).contains("切换快捷键栏"));
+    }
+
+    #[test]
+    fn fixed_rects_center_and_clamp_to_the_area() {
+        let area = Rect::new(2, 1, 40, 12);
+        assert_eq!(centered_fixed_rect(20, 6, area), Rect::new(12, 4, 20, 6));
+        // Odd leftovers put the extra column and row after the rect
+        assert_eq!(centered_fixed_rect(19, 5, area), Rect::new(12, 4, 19, 5));
+        // Larger requests shrink to the area instead of spilling over its edges
+        assert_eq!(centered_fixed_rect(60, 6, area), Rect::new(2, 4, 40, 6));
+        assert_eq!(centered_fixed_rect(20, 30, area), Rect::new(12, 1, 20, 12));
+        assert_eq!(centered_fixed_rect(0, 0, area), Rect::new(22, 7, 0, 0));
+    }
+
+    #[test]
+    fn jump_prompt_fits_its_content() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_jump_prompt();
+        for ch in "2025-13-40".chars() {
+            app.push_jump_input(ch);
+        }
+        app.confirm_jump_prompt();
+        let error = app.jump_prompt_view().unwrap().error.unwrap().to_string();
+        // Tall terminals get a box of the content's height, not a share of the screen
+        let screen = screen_text(&render(&mut app, 100, 60));
+        let rows: Vec<&str> = screen.lines().collect();
+        let top = rows
+            .iter()
+            .position(|row| row.contains("跳转到指定日期"))
+            .unwrap();
+        let bottom = top
+            + rows[top..]
+                .iter()
+                .position(|row| row.contains('╰'))
+                .unwrap();
+        assert!(rows[bottom - 1].contains(&error), "{screen}");
+        assert!(bottom - top <= 7, "{screen}");
+        // On a narrow frame the box wraps its lines and still shows the error in full
+        let terminal = render(&mut app, 40, 24);
+        let text: String = screen_text(&terminal).split_whitespace().collect();
+        let error: String = error.split_whitespace().collect();
+        assert!(text.contains(&error), "{text}");
+    }
+
+    #[test]
+    fn jump_cursor_follows_the_typed_text() {
+        use ratatui::backend::Backend;
+
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.start_jump_prompt();
+        "2025-06".chars().for_each(|ch| app.push_jump_input(ch));
+        let mut terminal = render(&mut app, 100, 36);
+        let buffer = terminal.backend().buffer().clone();
+        let (x, y) = (0..36)
+            .flat_map(|y| (0..100).map(move |x| (x, y)))
+            .find(|&(x, y)| buffer.get(x, y).symbol() == "目")
+            .unwrap();
+        // The full-width label takes two columns per character
+        let label = text_width(Msg::JumpLabel.text(Locale::Zh), &glyphs(false)) as u16;
+        assert_eq!(label, 10);
+        assert_eq!(
+            terminal.backend_mut().get_cursor().unwrap(),
+            (x + label + 7, y)
+        );
+        // Input wider than the box keeps the cursor on its last inner column
+        (0..7).for_each(|_| app.pop_jump_input());
+        "中秋节"
+            .repeat(6)
+            .chars()
+            .for_each(|ch| app.push_jump_input(ch));
+        let mut terminal = render(&mut app, 40, 24);
+        let (column, row) = terminal.backend_mut().get_cursor().unwrap();
+        assert_eq!(column, 38);
+        assert_eq!(terminal.backend().buffer().get(39, row).symbol(), "│");
+    }
+
+    #[test]
+    fn ascii_mode_draws_only_ascii() {
+        let config = AppConfig {
+            ascii_mode: true,
+            locale: Locale::En,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 18), config);
+        let bindings = KeyBindings::default().with_ascii_labels(true);
+        let screen = screen_text(&render_with(&mut app, &bindings, 100, 36));
+        assert!(screen.starts_with('+'), "{screen}");
+        assert!(screen.contains("Left/WheelUp prev month"), "{screen}");
+        // Text punctuation stays; borders, arrows and badges are the ones fonts lack
+        let stray: String = screen
+            .chars()
+            .filter(|ch| !ch.is_ascii() && !matches!(ch, '·' | '–'))
+            .collect();
+        assert_eq!(stray, "", "{screen}");
+    }
+
+    /// Color of each weekday header label, in column order
+    fn header_colors(terminal: &Terminal<TestBackend>) -> Vec<(String, Color)> {
+        let buffer = terminal.backend().buffer();
+        let labels = ["一", "二", "三", "四", "五", "六", "日"];
+        let y = (0..buffer.area.height)
+            .find(|&y| (0..buffer.area.width).any(|x| buffer.get(x, y).symbol() == "日"))
+            .unwrap();
+        (0..buffer.area.width)
+            .map(|x| buffer.get(x, y))
+            .filter(|cell| labels.contains(&cell.symbol()))
+            .take(7)
+            .map(|cell| (cell.symbol().to_string(), cell.fg))
+            .collect()
+    }
+
+    #[test]
+    fn weekend_headers_keep_their_style_in_any_order() {
+        let theme = *test_app(date(2025, 6, 18), AppConfig::default()).theme();
+        let plain = theme.header_fg;
+        let (sat, sun) = (theme.saturday, theme.sunday);
+        assert_ne!(plain, sat);
+        assert_ne!(plain, sun);
+        let colors = |config: AppConfig| {
+            let mut app = test_app(date(2025, 6, 18), config);
+            header_colors(&render(&mut app, 100, 36))
+        };
+        let expected = |labels: &str, styles: [Color; 7]| {
+            labels
+                .chars()
+                .map(String::from)
+                .zip(styles)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            colors(AppConfig::default()),
+            expected(
+                "一二三四五六日",
+                [plain, plain, plain, plain, plain, sat, sun]
+            )
+        );
+        let sunday_first = AppConfig {
+            week_start: WeekStart::Sunday,
+            ..AppConfig::default()
+        };
+        assert_eq!(
+            colors(sunday_first),
+            expected(
+                "日一二三四五六",
+                [sun, plain, plain, plain, plain, plain, sat]
+            )
+        );
+        // A configured weekend moves the style to its own days
+        let friday_saturday = AppConfig {
+            weekend: ron::from_str("[\"fri\", \"sat\"]").unwrap(),
+            ..AppConfig::default()
+        };
+        assert_eq!(
+            colors(friday_saturday),
+            expected(
+                "一二三四五六日",
+                [plain, plain, plain, plain, sun, sat, plain]
+            )
+        );
+    }
+
+    /// Week numbers shown in the calendar's week column, top to bottom
+    fn week_column(today: NaiveDate, week_start: WeekStart) -> Vec<String> {
+        let config = AppConfig {
+            show_week_numbers: true,
+            week_start,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(today, config);
+        let terminal = render(&mut app, 100, 36);
+        let (x, y) = locate(&terminal, Msg::WeekColumn.text(app.locale())).unwrap();
+        let buffer = terminal.backend().buffer();
+        // The header is centred in the column, one cell in from its left edge
+        let left = x - 1;
+        (y + 1..buffer.area.height)
+            .take_while(|&y| buffer.get(left - 1, y).symbol() == "│")
+            .map(|y| {
+                (left..left + WEEK_NUMBER_WIDTH)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .filter(|cell| !cell.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn week_column_numbers_rows_by_their_monday() {
+        assert_eq!(
+            week_column(date(2020, 12, 15), WeekStart::Monday),
+            ["49", "50", "51", "52", "53"]
+        );
+        assert_eq!(
+            week_column(date(2021, 1, 15), WeekStart::Monday),
+            ["53", "1", "2", "3", "4"]
+        );
+        // Rows starting on Sunday take the number of the Monday after
+        assert_eq!(
+            week_column(date(2021, 1, 15), WeekStart::Sunday),
+            ["53", "1", "2", "3", "4", "5"]
+        );
+    }
+
+    /// Screen position of the first cell of `text` where it first appears
+    fn locate(terminal: &Terminal<TestBackend>, text: &str) -> Option<(u16, u16)> {
+        let buffer = terminal.backend().buffer();
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                let mut shown = String::new();
+                let mut x = x;
+                while x < area.right() && shown.len() < text.len() {
+                    let symbol = buffer.get(x, y).symbol();
+                    shown.push_str(symbol);
+                    x += symbol.width().max(1) as u16;
+                }
+                shown.starts_with(text)
+            })
+    }
+
+    /// Cells of `text` where it first appears on screen, one per character
+    fn find_cells(
+        terminal: &Terminal<TestBackend>,
+        text: &str,
+    ) -> Option<Vec<ratatui::buffer::Cell>> {
+        let (x, y) = locate(terminal, text)?;
+        let buffer = terminal.backend().buffer();
+        let mut cells = Vec::new();
+        let mut x = x;
+        for _ in text.chars() {
+            let cell = buffer.get(x, y).clone();
+            x += cell.symbol().width().max(1) as u16;
+            cells.push(cell);
+        }
+        Some(cells)
+    }
+
+    #[test]
+    fn today_marker_styles_compose_with_selection() {
+        let marker = |symbol: &str, position| AppConfig {
+            today_marker: TodayMarker {
+                symbol: symbol.to_string(),
+                position,
+                underline: true,
+            },
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 18), marker("今", MarkerPosition::After));
+        let theme = *app.theme();
+        // Selected, the symbol stays and takes the selection colors
+        let cells = find_cells(&render(&mut app, 100, 36), "18今").unwrap();
+        assert!(cells[0].modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(
+            (cells[2].fg, cells[2].bg),
+            (theme.selected_fg, theme.selected_bg)
+        );
+        app.move_selection(1);
+        let cells = find_cells(&render(&mut app, 100, 36), "18今").unwrap();
+        assert_eq!(cells[2].fg, theme.today_fg);
+        assert!(cells[2].modifier.contains(Modifier::BOLD));
+        assert!(cells[0].modifier.contains(Modifier::UNDERLINED));
+
+        let mut app = test_app(date(2025, 6, 18), marker("今", MarkerPosition::Before));
+        let screen = screen_text(&render(&mut app, 100, 36));
+        assert!(screen.contains("今18"), "{screen}");
+        // Today still shows among the gray days of the neighbouring month
+        let mut app = test_app(date(2025, 6, 30), marker("今", MarkerPosition::After));
+        app.move_selection(10);
+        let cells = find_cells(&render(&mut app, 100, 36), "30今").unwrap();
+        assert_eq!(cells[2].fg, theme.today_fg);
+    }
+
+    #[test]
+    fn month_title_fits_the_top_border() {
+        let app = test_app(date(2025, 6, 18), AppConfig::default());
+        let glyphs = app.theme().glyphs;
+        let full = app.month_title(2025, 6);
+        assert_eq!(month_title(&app, 2025, 6, 60), full);
+        for width in [30, 20, 12, 2] {
+            let title = month_title(&app, 2025, 6, width);
+            assert!(text_width(&title, &glyphs) <= width as usize - 2, "{title}");
+            assert!(
+                title.is_empty() || title.ends_with(glyphs.ellipsis),
+                "{title}"
+            );
+        }
+        assert_eq!(month_title(&app, 2025, 6, 20), "2025年6月 · 五…");
+    }
+
+    #[test]
+    fn hidden_adjacent_days_leave_blanks_and_the_view_follows_the_selection() {
+        let config = AppConfig {
+            show_adjacent_days: false,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 1), config);
+        let screen = screen_text(&render(&mut app, 100, 36));
+        let first_week = screen.lines().find(|row| row.contains("01休")).unwrap();
+        assert!(
+            !first_week.contains("26") && !first_week.contains("31"),
+            "{first_week}"
+        );
+        // The blank cells are not click targets either
+        let days = (0..100)
+            .flat_map(|x| (0..36).map(move |y| (x, y)))
+            .filter_map(|(x, y)| match app.target_at(x, y) {
+                Some(ClickTarget::Day(day)) => Some(day),
+                _ => None,
+            })
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(days.first(), Some(&date(2025, 6, 1)));
+        assert_eq!(days.last(), Some(&date(2025, 6, 30)));
+
+        app.move_selection(-1);
+        assert_eq!(app.selected_date(), date(2025, 5, 31));
+        assert_eq!((app.view_year(), app.view_month()), (2025, 5));
+        let theme = *app.theme();
+        let cells = find_cells(&render(&mut app, 100, 36), "31休").unwrap();
+        assert_eq!(cells[0].bg, theme.selected_bg);
+    }
+
+    #[test]
+    fn clicks_land_on_the_day_under_the_pointer() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let terminal = render(&mut app, 100, 36);
+        // Anywhere in a day's cell counts, including the label rows below the number
+        let (x, y) = locate(&terminal, " 24 ").unwrap();
+        assert_eq!(app.click(x + 1, y), None);
+        assert_eq!(app.selected_date(), date(2025, 6, 24));
+        assert_eq!(app.click(x + 4, y + 2), None);
+        assert_eq!(app.selected_date(), date(2025, 6, 24));
+        // A neighbouring month's day pages the view to it
+        let (x, y) = locate(&terminal, "26").unwrap();
+        assert_eq!(app.click(x, y), None);
+        assert_eq!(app.selected_date(), date(2025, 5, 26));
+        assert_eq!(app.view_month(), 5);
+
+        // Borders, headers and the details panel are not days
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let terminal = render(&mut app, 100, 36);
+        let (x, y) = locate(&terminal, "一").unwrap();
+        let (details_x, details_y) = locate(&terminal, "详情").unwrap();
+        for (x, y) in [(x, y), (0, y + 1), (details_x, details_y + 2)] {
+            assert_eq!(app.click(x, y), None, "{x},{y}");
+            assert_eq!(app.selected_date(), date(2025, 6, 18));
+        }
+        // The header arrows hand their action back to the caller
+        let (x, y) = locate(&terminal, "◀").unwrap();
+        assert_eq!(
+            app.target_at(x, y),
+            Some(ClickTarget::Action(Action::PrevYear))
+        );
+        assert_eq!(app.click(x, y), Some(Action::PrevYear));
+        assert_eq!(app.selected_date(), date(2025, 6, 18));
+    }
+
+    #[test]
+    fn resizing_lays_the_next_frame_out_for_the_new_size() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let bindings = KeyBindings::default();
+        let mut terminal = Terminal::new(TestBackend::new(100, 36)).unwrap();
+        let mut draw_at = |app: &mut App, width, height| {
+            terminal.backend_mut().resize(width, height);
+            terminal
+                .draw(|frame| draw(frame, app, &bindings, &[]))
+                .unwrap();
+            screen_text(&terminal)
+        };
+        // The details scroll shrinks with the content that no longer overflows
+        app.scroll_details(100);
+        draw_at(&mut app, 100, 30);
+        let short = app.details_scroll();
+        assert!(short > 0);
+        draw_at(&mut app, 100, 60);
+        assert!(app.details_scroll() < short);
+        // An open prompt is centered in the new size, whatever it was drawn at before
+        app.start_jump_prompt();
+        for (width, height) in [(100, 36), (50, 20), (120, 40)] {
+            let screen = draw_at(&mut app, width, height);
+            let rows: Vec<&str> = screen.lines().collect();
+            let top = rows
+                .iter()
+                .position(|row| row.contains("跳转到指定日期"))
+                .unwrap();
+            let bottom = top
+                + rows[top..]
+                    .iter()
+                    .position(|row| row.contains('╰'))
+                    .unwrap();
+            let (above, below) = (top, height as usize - 1 - bottom);
+            assert!(above.abs_diff(below) <= 1, "{width}x{height}:\n{screen}");
+        }
+        // Shrinking below the minimum switches screens on the very next frame, and back
+        assert!(draw_at(&mut app, 30, 10).contains("终端太小"));
+        assert!(draw_at(&mut app, 100, 36).contains("跳转到指定日期"));
+    }
+
+    #[test]
+    fn status_line_takes_a_row_only_while_a_message_shows() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let quiet = render(&mut app, 100, 36);
+        app.notify("已复制 2025-06-18", MessageLevel::Warn);
+        let terminal = render(&mut app, 100, 36);
+        let cells = find_cells(&terminal, "已复制").unwrap();
+        assert_eq!(cells[0].fg, app.theme().warning);
+        assert!(locate(&quiet, "已复制").is_none());
+        // The line sits above the help bar and takes its row from the calendar
+        let row = |terminal: &Terminal<TestBackend>, text: &str| locate(terminal, text).unwrap().1;
+        assert_eq!(row(&terminal, "╭快捷键"), row(&quiet, "╭快捷键"));
+        assert_eq!(row(&terminal, "已复制"), row(&terminal, "╭快捷键") - 1);
+        assert_eq!(row(&terminal, "╰■"), row(&quiet, "╰■") - 1);
+    }
+
+    #[test]
+    fn leap_month_starts_are_labelled_in_cells() {
+        let mut app = test_app(date(2023, 3, 1), AppConfig::default());
+        let screen = screen_text(&render(&mut app, 100, 36));
+        let rows: Vec<&str> = screen.lines().collect();
+        let week = rows.iter().position(|row| row.contains(" 22 ")).unwrap();
+        assert!(rows[week + 1].contains("闰二月"), "{screen}");
+        // A festival on a first day still wins, with the month on the line below
+        let mut app = test_app(date(2025, 1, 29), AppConfig::default());
+        let screen = screen_text(&render(&mut app, 100, 36));
+        // Only the calendar, left of the details panel
+        let rows: Vec<&str> = screen
+            .lines()
+            .map(|row| row.split("││").next().unwrap())
+            .collect();
+        let week = rows.iter().position(|row| row.contains("春节")).unwrap();
+        assert!(rows[week + 1].contains("正月"), "{screen}");
+        assert!(!rows[week].contains("正月"), "{screen}");
+    }
+
+    #[test]
+    fn countdown_line_reads_today_on_the_festival() {
+        let details = |today| {
+            let mut app = test_app(today, AppConfig::default());
+            let screen = screen_text(&render(&mut app, 140, 36));
+            // The details panel's text with wrapping and spacing taken out
+            screen
+                .lines()
+                .filter_map(|row| row.split("││").nth(1))
+                .flat_map(|row| row.chars().filter(|ch| !ch.is_whitespace() && *ch != '│'))
+                .collect::<String>()
+        };
+        assert!(details(date(2025, 1, 29)).contains("春节：就是今天！"));
+        assert!(details(date(2025, 1, 28)).contains("春节：2025-01-29·还有1天"));
+        assert!(details(date(2025, 1, 30)).contains("春节：2026-02-17·还有383天"));
+    }
+
+    #[test]
+    fn month_terms_line_lists_both_or_says_none() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        let stats = app.month_stats();
+        let terms = app.month_solar_terms().to_vec();
+        let text = |terms: &[(&'static str, NaiveDate)]| {
+            details_lines(&app, terms, stats, 200)
+                .iter()
+                .map(|line| {
+                    line.spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect::<String>()
+                })
+                .find(|line| line.starts_with("本月节气"))
+                .unwrap()
+        };
+        assert_eq!(text(&terms), "本月节气：芒种 6月5日 · 夏至 6月21日");
+        assert_eq!(text(&[]), "本月节气：节气数据不可用");
+    }
+
+    #[test]
+    fn year_progress_shrinks_its_bar_to_the_room_left() {
+        let theme = *test_app(date(2025, 7, 2), AppConfig::default()).theme();
+        let glyphs = glyphs(false);
+        assert_eq!(progress_bar(0.0, 5, &glyphs), "░░░░░");
+        assert_eq!(progress_bar(0.501, 10, &glyphs), "▓▓▓▓▓░░░░░");
+        assert_eq!(progress_bar(1.0, 5, &glyphs), "▓▓▓▓▓");
+        // After a 20-column header, what each width leaves room for
+        let progress = |width: u16| {
+            let mut line = HeaderLine::new(glyphs);
+            line.push(Span::raw("x".repeat(20)));
+            push_year_progress(&mut line, date(2025, 7, 2), width, &theme);
+            line.spans.get(1).map(|span| span.content.to_string())
+        };
+        assert_eq!(progress(60).as_deref(), Some(" | 2025 ▓▓▓▓▓░░░░░ 50%"));
+        assert_eq!(progress(40).as_deref(), Some(" | 2025 ▓▓▓▓░░░░ 50%"));
+        assert_eq!(progress(34).as_deref(), Some(" | 2025 50%"));
+        assert_eq!(progress(30), None);
+    }
+
+    #[test]
+    fn header_names_the_selected_dates_lunar_year() {
+        let mut app = test_app(date(2026, 2, 16), AppConfig::default());
+        let header = |app: &mut App| {
+            screen_text(&render(app, 100, 36))
+                .lines()
+                .nth(1)
+                .unwrap()
+                .to_string()
+        };
+        assert!(
+            header(&mut app).contains("农历：乙巳年 ·  蛇年"),
+            "{}",
+            header(&mut app)
+        );
+        app.move_selection(1);
+        assert!(
+            header(&mut app).contains("农历：丙午年 ·  马年"),
+            "{}",
+            header(&mut app)
+        );
+    }
+
+    #[test]
+    fn hidden_cell_labels_snapshot() {
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        app.toggle_cell_labels();
+        // One line per week, numbers and badges only
+        assert_snapshot(&render(&mut app, 100, 36), "month-2025-06-no-labels");
+        app.toggle_cell_labels();
+        assert_snapshot(&render(&mut app, 100, 36), "month-2025-06");
+        // The same from the config default
+        let config = AppConfig {
+            show_cell_labels: false,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 18), config);
+        assert_snapshot(&render(&mut app, 100, 36), "month-2025-06-no-labels");
+    }
+
+    #[test]
+    fn countdown_line_counts_down_then_up() {
+        let target = |name: &str, date| Countdown {
+            name: name.to_string(),
+            date,
+        };
+        let config = AppConfig {
+            countdowns: vec![
+                target("高考", date(2026, 6, 7)),
+                target("开学", date(2025, 9, 1)),
+                target("截止", date(2025, 6, 18)),
+            ],
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2025, 6, 18), config);
+        let screen = screen_text(&render(&mut app, 120, 36));
+        let line = screen.lines().find(|row| row.contains("距高考")).unwrap();
+        assert_eq!(line, " 距高考还有 354 天 ·  距开学还有 75 天 ·  今天是截止");
+        // Without countdowns none of them show
+        let mut app = test_app(date(2025, 6, 18), AppConfig::default());
+        assert!(!screen_text(&render(&mut app, 120, 36)).contains("高考"));
+    }
+
+    #[test]
+    fn astronomical_line_shows_only_when_enabled() {
+        let config = AppConfig {
+            show_astronomical: true,
+            ..AppConfig::default()
+        };
+        let mut app = test_app(date(2000, 1, 1), config);
+        let screen: String = screen_text(&render(&mut app, 120, 36))
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '│')
+            .collect();
+        // The line wraps inside the details panel
+        assert!(screen.contains("天文：JDN2451545·MJD51544·"), "{screen}");
+        assert!(screen.contains("Unix日10957"), "{screen}");
+        let mut app = test_app(date(2000, 1, 1), AppConfig::default());
+        assert!(!screen_text(&render(&mut app, 120, 36)).contains("JDN"));
+    }
+
+    #[test]
+    fn details_give_the_lunar_months_size_and_span() {
+        let details = |today| {
+            let mut app = test_app(today, AppConfig::default());
+            screen_text(&render(&mut app, 140, 40))
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != '│')
+                .collect::<String>()
+        };
+        assert!(details(date(2025, 6, 18)).contains("农历五月（小）：5月27日—6月24日"));
+        assert!(details(date(2023, 3, 1)).contains("农历二月（大）：2月20日—3月21日"));
+        assert!(details(date(2023, 4, 1)).contains("农历闰二月（小）：3月22日—4月19日"));
+    }
+
+    #[test]
+    fn help_bar_follows_the_action_groups_and_registry_order() {
+        let bindings = KeyBindings::default();
+        let theme = Theme::default();
+        let help_text = |pick_mode| {
+            let (paragraph, height) = help_bar(
+                &bindings,
+                false,
+                pick_mode,
+                1000,
+                "默认",
+                &theme,
+                Locale::Zh,
+            );
+            let mut terminal = Terminal::new(TestBackend::new(1000, height)).unwrap();
+            terminal
+                .draw(|frame| frame.render_widget(paragraph, frame.size()))
+                .unwrap();
+            screen_text(&terminal)
+        };
+        let text = help_text(false);
+        // Groups come in their display order, each named once and set apart by a wider gap
+        let mut from = 0;
+        for (index, group) in ActionGroup::ALL.into_iter().enumerate() {
+            let label = format!("{}：", group.label(Locale::Zh));
+            assert_eq!(text.matches(&label).count(), 1, "{label}");
+            let start = from + text[from..].find(&label).expect(&label);
+            if index > 0 {
+                assert!(text[..start].ends_with("   "), "{label}");
+            }
+            // Within a group, entries keep the order of ACTIONS
+            from = start;
+            for &(action, _, _) in ACTIONS.iter().filter(|entry| entry.2 == group) {
+                let keys = bindings.labels_for(action);
+                if keys.is_empty() || action == Action::ConfirmPick {
+                    continue;
+                }
+                let entry = format!("{} {}", keys.join("/"), action.label(Locale::Zh));
+                from += text[from..].find(&entry).expect(&entry) + entry.len();
+            }
+        }
+        // Confirming a pick is only offered to the picker, among the app actions
+        assert!(!text.contains("选定日期"));
+        let text = help_text(true);
+        let app_group = text.find("应用：").unwrap();
+        assert!(text.find("选定日期").unwrap() > app_group);
+    }
+
+    fn glyphs(wide_ambiguous: bool) -> Glyphs {
+        Glyphs {
+            wide_ambiguous,
+            ..crate::theme::UNICODE_GLYPHS
+        }
+    }
+
+    #[test]
+    fn truncation_keeps_wide_characters_and_marks_whole() {
+        let narrow = glyphs(false);
+        let wide = glyphs(true);
+        assert_eq!(truncate_to_width("中秋节", 6, &narrow), "中秋节");
+        assert_eq!(truncate_to_width("中秋节", 5, &narrow), "中秋…");
+        assert_eq!(truncate_to_width("中秋节", 4, &narrow), "中…");
+        // The ellipsis takes two columns where ambiguous symbols are wide
+        assert_eq!(truncate_to_width("中秋节", 5, &wide), "中…");
+        assert_eq!(truncate_to_width("abcdef", 4, &narrow), "abc…");
+        // A combining mark stays with its base character
+        assert_eq!(
+            truncate_to_width("e\u{301}e\u{301}e\u{301}", 3, &narrow),
+            "e\u{301}e\u{301}e\u{301}"
+        );
+        assert_eq!(
+            truncate_to_width("e\u{301}e\u{301}e\u{301}", 2, &narrow),
+            "e\u{301}…"
+        );
+        // Too narrow for even the ellipsis
+        assert_eq!(truncate_to_width("中秋节", 0, &narrow), "");
+        assert_eq!(truncate_to_width("中秋节", 1, &narrow), "…");
+        assert_eq!(truncate_to_width("中秋节", 1, &wide), "");
+        for width in 0..8 {
+            for glyphs in [&narrow, &wide] {
+                let cut = truncate_to_width("国庆节·中秋节", width, glyphs);
+                assert!(text_width(&cut, glyphs) <= width, "{width}: {cut}");
+            }
+        }
+    }
+
+    #[test]
+    fn cell_text_drops_a_lone_ellipsis_and_pads_wide_symbols() {
+        let narrow = glyphs(false);
+        let wide = glyphs(true);
+        assert_eq!(cell_text("中秋节", 1, &narrow), "");
+        assert_eq!(cell_text("中秋节", 4, &narrow), "中…");
+        // The widened ellipsis gets a trailing space to stay centered
+        assert_eq!(cell_text("中秋节", 4, &wide), "中… ");
+        assert_eq!(cell_text("a·b", 4, &wide), "a·b ");
+        assert_eq!(cell_text("初八", 4, &wide), "初八");
+    }
+
+    /// One buffer row after `widen_ambiguous`, a skipped cell shown as `~`
+    fn widened(row: &str, wide_ambiguous: bool) -> String {
+        let width = row.width() as u16;
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, 1));
+        buffer.set_string(0, 0, row, Style::default());
+        widen_ambiguous(&mut buffer, &glyphs(wide_ambiguous));
+        let mut text = String::new();
+        let mut x = 0;
+        while x < width {
+            let cell = buffer.get(x, 0);
+            text.push_str(if cell.skip { "~" } else { cell.symbol() });
+            x += cell.symbol().width().max(1) as u16;
+        }
+        text
+    }
+
+    #[test]
+    fn ambiguous_symbols_count_as_configured() {
+        assert_eq!(text_width("a·b…", &glyphs(false)), 4);
+        assert_eq!(text_width("a·b…", &glyphs(true)), 6);
+        assert_eq!(text_width("中秋", &glyphs(true)), 4);
+        // Box drawing keeps one column either way
+        assert_eq!(widened("│a·b  │", true), "│a·~b │");
+        assert_eq!(widened("│a·b  │", false), "│a·b  │");
+        // Without padding the run loses its last cell instead of moving the border
+        assert_eq!(widened("│a·bc│", true), "│a·~b│");
+        // A title in a top border takes the next line cell
+        assert_eq!(widened("╭─·─╮", true), "╭─·~╮");
+    }
+}